@@ -12,6 +12,7 @@ pub(crate) mod args;
 pub(crate) mod function_id;
 
 use eth::Flags;
+use move_executor::gas::GasSchedule;
 use move_executor::load::LoadRemoteData;
 use move_executor::profile::ProfileConfig;
 use move_executor::solidity::FromSolidity;
@@ -98,6 +99,39 @@ pub struct CmdCall {
     #[clap(long = "profile", default_value = "default")]
     profile_name: String,
 
+    /// Charge the local call by the standard Aptos gas schedule and print the gas used.
+    /// The call fails if it needs more than `--max-gas` gas units
+    #[clap(long = "metered", display_order = 13)]
+    metered: bool,
+
+    /// [vm] - Keep the local state in the file between the calls.
+    /// The first call deploys the contract and saves the state, the next calls start from it
+    #[clap(long = "state", value_name = "FILE", display_order = 14)]
+    state: Option<PathBuf>,
+
+    /// [vm] - Height of the current block, `block.number`
+    #[clap(long = "block-height", value_name = "HEIGHT", display_order = 15)]
+    block_height: Option<u64>,
+
+    /// [vm] - Time of the current block in seconds since the unix epoch, `block.timestamp`
+    #[clap(long = "timestamp", value_name = "SECONDS", display_order = 16)]
+    timestamp: Option<u64>,
+
+    /// [vm] - Id of the chain
+    #[clap(long = "chain-id", value_name = "ID", display_order = 17)]
+    chain_id: Option<u8>,
+
+    /// [vm] - AptosCoin balance of the account, `address.balance`. Can be repeated
+    ///
+    /// Example: `--balance 0x42:1000000`
+    #[clap(
+        long = "balance",
+        value_name = "ADDRESS:AMOUNT",
+        value_parser = parse_balance,
+        display_order = 18
+    )]
+    balances: Vec<(String, u64)>,
+
     #[clap(flatten)]
     transaction_flags: crate::txflags::TransactionFlags,
 }
@@ -163,6 +197,15 @@ impl CmdCall {
 
         dbg!(&self.function_id);
 
+        ensure!(
+            self.state.is_none() || matches!(self.how_to_call, HowToCall::VM),
+            "`--state` is only supported by `--how vm`"
+        );
+        ensure!(
+            !self.has_env() || matches!(self.how_to_call, HowToCall::VM),
+            "`--block-height`, `--timestamp`, `--chain-id` and `--balance` are only supported by `--how vm`"
+        );
+
         let path = self.path_to_convert.as_deref().ok_or_else(|| {
             anyhow!("Specify the path to the converted project or sol file. `--path <PATH/TO>`")
         })?;
@@ -249,30 +292,77 @@ impl CmdCall {
             }
             // [vm] - Call a local contract and display the return value
             HowToCall::VM => {
-                let fn_constructor = format!(
-                    "{}::{}::constructor",
-                    self.function_id.address, self.function_id.module
-                );
-
-                println!("VM RUN: {fn_constructor}");
-                vm.run(&fn_constructor, &signer_address_hex, None).unwrap();
+                let state = self.state.as_deref().filter(|state| state.exists());
+                if let Some(state) = state {
+                    println!("VM STATE: {state:?}");
+                    vm.load_state(state)?;
+                }
+                // The constructor sees the environment too.
+                self.set_env(&mut vm)?;
+                if state.is_none() {
+                    let fn_constructor = format!(
+                        "{}::{}::constructor",
+                        self.function_id.address, self.function_id.module
+                    );
+
+                    println!("VM RUN: {fn_constructor}");
+                    vm.run(&fn_constructor, &signer_address_hex, None).unwrap();
+                }
             }
             HowToCall::Node => unreachable!(),
         }
 
+        if self.metered {
+            vm.set_gas_schedule(
+                GasSchedule::Standard,
+                u64::from(self.transaction_flags.max_gas),
+            );
+        }
+
         let fn_string = self.function_id.to_string();
         let args = FunctionArgs::from(&self.args);
 
         println!("LOCAL RUN: {fn_string}({})", args.value().join(", ").trim());
-        let res = vm
-            .run(
-                &fn_string,
-                &signer_address_hex,
-                Some(args.value().join(",").as_str()),
-            )?
-            .to_result_str();
-
-        Ok(res)
+        let res = vm.run(
+            &fn_string,
+            &signer_address_hex,
+            Some(args.value().join(",").as_str()),
+        );
+        // The deployment is kept even if the call fails.
+        if let Some(state) = &self.state {
+            vm.save_state(state)?;
+        }
+        let res = res?;
+
+        if self.metered {
+            Ok(format!("{}\nGas used: {}", res.to_result_str(), res.gas))
+        } else {
+            Ok(res.to_result_str())
+        }
+    }
+
+    fn has_env(&self) -> bool {
+        self.block_height.is_some()
+            || self.timestamp.is_some()
+            || self.chain_id.is_some()
+            || !self.balances.is_empty()
+    }
+
+    /// Sets the block, the time and the balances of the local call.
+    fn set_env(&self, vm: &mut MoveExecutor) -> Result<()> {
+        if let Some(height) = self.block_height {
+            vm.set_block_height(height)?;
+        }
+        if let Some(seconds) = self.timestamp {
+            vm.set_timestamp(seconds)?;
+        }
+        if let Some(id) = self.chain_id {
+            vm.set_chain_id(id)?;
+        }
+        for (addr, amount) in &self.balances {
+            vm.set_balance(addr, *amount)?;
+        }
+        Ok(())
     }
 }
 
@@ -288,6 +378,14 @@ enum HowToCall {
     VM,
 }
 
+fn parse_balance(val: &str) -> Result<(String, u64)> {
+    let (addr, amount) = val
+        .split_once(':')
+        .ok_or_else(|| anyhow!("Expected `ADDRESS:AMOUNT`, got {val:?}"))?;
+    AccountAddress::from_hex_literal(addr)?;
+    Ok((addr.to_string(), amount.parse()?))
+}
+
 fn ext(path: &Path) -> Option<String> {
     Some(path.extension()?.to_string_lossy().to_string())
 }
@@ -46,11 +46,14 @@ impl CmdConvert {
             ])
             .map_err(|err| anyhow!("Invalid profile parameter. {err}"))?;
 
-        let binarycode = fs::read(&result_convert.binary_code_path)?;
+        let compiled_units = result_convert
+            .binary_code_paths
+            .iter()
+            .map(fs::read)
+            .collect::<Result<Vec<_>, _>>()?;
 
-        // Send the compiled module and metadata using the code::publish_package_txn.
-        let metadata = gen_meta(result_convert, &binarycode)?;
-        let compiled_units = vec![binarycode];
+        // Send the compiled modules and metadata using the code::publish_package_txn.
+        let metadata = gen_meta(result_convert, &compiled_units)?;
 
         let payload = aptos_cached_packages::aptos_stdlib::code_publish_package_txn(
             bcs::to_bytes(&metadata).expect("PackageMetadata has BCS"),
@@ -69,20 +72,25 @@ impl CmdConvert {
     }
 }
 
-fn gen_meta(result_convert: &ResultConvert, binarycode: &[u8]) -> Result<PackageMetadata> {
-    let module_name = module_name(binarycode)?;
+fn gen_meta(result_convert: &ResultConvert, binarycodes: &[Vec<u8>]) -> Result<PackageMetadata> {
+    let package_name = result_convert.package_name.clone();
 
     let move_toml_string = TEMPLATE_MOVE_TOML
-        .replace("###NAME###", &module_name)
+        .replace("###NAME###", &package_name)
         .replace("###ADDRESS###", &result_convert.address.to_string());
     let manifest = zip_metadata_str(&move_toml_string)?;
 
-    let modules = vec![ModuleMetadata {
-        name: module_name.clone(),
-        source: Vec::new(),
-        source_map: Vec::new(),
-        extension: MoveOption::default(),
-    }];
+    let modules = binarycodes
+        .iter()
+        .map(|binarycode| {
+            Ok(ModuleMetadata {
+                name: module_name(binarycode)?,
+                source: Vec::new(),
+                source_map: Vec::new(),
+                extension: MoveOption::default(),
+            })
+        })
+        .collect::<Result<Vec<ModuleMetadata>>>()?;
 
     let deps = ["AptosFramework", "AptosStdlib", "MoveStdlib"]
         .into_iter()
@@ -93,10 +101,10 @@ fn gen_meta(result_convert: &ResultConvert, binarycode: &[u8]) -> Result<Package
             })
         })
         .collect::<Result<Vec<PackageDep>>>()?;
-    let source_digest = source_digest(binarycode)?.to_uppercase();
+    let source_digest = source_digest(binarycodes)?.to_uppercase();
 
     Ok(PackageMetadata {
-        name: module_name,
+        name: package_name,
         upgrade_policy: UpgradePolicy::compat(),
         upgrade_number: 0,
         source_digest,
@@ -117,12 +125,14 @@ fn module_name(binarycode: &[u8]) -> Result<String> {
         .to_string())
 }
 
-fn source_digest(binarycode: &[u8]) -> Result<String> {
+fn source_digest(binarycodes: &[Vec<u8>]) -> Result<String> {
     use sha2::{Digest, Sha256};
 
     // create a Sha256 object
     let mut hasher = Sha256::new();
-    hasher.update(binarycode);
+    for binarycode in binarycodes {
+        hasher.update(binarycode);
+    }
     let result = hasher.finalize().to_vec();
     Ok(hex::encode(result))
 }
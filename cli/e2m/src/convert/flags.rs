@@ -19,6 +19,14 @@ pub struct ConvertFlags {
     /// Use u128 instead of u256
     #[clap(long)]
     pub u128_io: bool,
+
+    /// Keep mapping entries by their native keys
+    #[clap(long)]
+    pub native_mappings: bool,
+
+    /// Build the functions from the Yul IR of the contract instead of the bytecode
+    #[clap(long)]
+    pub yul: bool,
 }
 
 impl ConvertFlags {
@@ -42,6 +50,7 @@ impl From<ConvertFlags> for Flags {
             native_output: fl.native_output,
             hidden_output: fl.hidden_output,
             u128_io: fl.u128_io,
+            native_mappings: fl.native_mappings,
         }
     }
 }
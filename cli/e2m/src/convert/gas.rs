@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use anyhow::Result;
+use ethabi::ethereum_types::{H160, U256};
+use ethabi::{Contract, Param, ParamType, Token};
+use itertools::Itertools;
+
+use eth::compile::EvmPack;
+use test_infra::revm::REvm;
+use translator::GasReport;
+
+/// Formats the static gas estimation of the module next to the EVM gas of the original contract.
+/// The EVM functions are called with zero arguments on empty storage.
+pub(crate) fn gas_report(pack: &EvmPack, report: &GasReport) -> Result<String> {
+    let evm = evm_gas(pack)?;
+
+    let mut buff = String::new();
+    writeln!(
+        buff,
+        "{:<32} {:>12} {:>12} {:>24} {:>12}",
+        "Function", "Worst", "Typical", "Per loop iteration", "EVM"
+    )?;
+    for fun in &report.functions {
+        let loops = if fun.loops.is_empty() {
+            "-".to_string()
+        } else {
+            fun.loops.iter().map(|lp| lp.per_iteration).join(", ")
+        };
+        let evm = evm
+            .get(&fun.name)
+            .map(|gas| gas.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        writeln!(
+            buff,
+            "{:<32} {:>12} {:>12} {:>24} {:>12}",
+            fun.name, fun.worst, fun.typical, loops, evm
+        )?;
+    }
+    writeln!(
+        buff,
+        "Move costs are in internal gas units. Each loop iteration adds its per-iteration cost."
+    )?;
+    Ok(buff)
+}
+
+/// EVM gas of the constructor and the functions of the contract.
+/// The functions which revert with the zero arguments are skipped.
+fn evm_gas(pack: &EvmPack) -> Result<HashMap<String, u64>> {
+    let abi: Contract = pack.abi()?;
+    let mut code = pack.code_evm()?;
+    if let Some(constructor) = abi.constructor() {
+        code = constructor.encode_input(code, &default_tokens(&constructor.inputs))?;
+    }
+
+    let mut gas = HashMap::new();
+    let mut vm = REvm::try_from(code)?;
+    match vm.construct(vec![]) {
+        Ok(used) => {
+            gas.insert("constructor".to_string(), used);
+        }
+        Err(err) => {
+            log::warn!("Failed to deploy the contract to EVM: {err:?}");
+            return Ok(gas);
+        }
+    }
+
+    for fun in abi.functions() {
+        let call = fun.encode_input(&default_tokens(&fun.inputs))?;
+        match vm.execute(call) {
+            Ok((_, used)) => {
+                gas.insert(fun.name.clone(), used);
+            }
+            Err(err) => log::info!("EVM call of {} failed: {err:?}", fun.name),
+        }
+    }
+    Ok(gas)
+}
+
+fn default_tokens(params: &[Param]) -> Vec<Token> {
+    params
+        .iter()
+        .map(|param| default_token(&param.kind))
+        .collect()
+}
+
+fn default_token(tp: &ParamType) -> Token {
+    match tp {
+        ParamType::Address => Token::Address(H160::zero()),
+        ParamType::Bytes => Token::Bytes(vec![]),
+        ParamType::Int(_) => Token::Int(U256::zero()),
+        ParamType::Uint(_) => Token::Uint(U256::zero()),
+        ParamType::Bool => Token::Bool(false),
+        ParamType::String => Token::String(String::new()),
+        ParamType::Array(_) => Token::Array(vec![]),
+        ParamType::FixedBytes(len) => Token::FixedBytes(vec![0; *len]),
+        ParamType::FixedArray(tp, len) => Token::FixedArray(vec![default_token(tp); *len]),
+        ParamType::Tuple(items) => Token::Tuple(items.iter().map(default_token).collect()),
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::ModuleId;
+
+use eth::compile::EvmPack;
+use eth::link::library_name;
+use translator::LibraryLink;
+
+use crate::profile::ProfileValue;
+
+/// Parses the `<NAME>=<PROFILE|ADDRESS>` assignments of the libraries.
+/// The name is either the library name or its fully qualified name `<source>:<name>`.
+pub(crate) fn library_assignments(values: &[String]) -> Result<BTreeMap<String, AccountAddress>> {
+    values
+        .iter()
+        .map(|value| {
+            let (name, address) = value.split_once('=').ok_or_else(|| {
+                anyhow!("Invalid library {value}. Expected: <NAME>=<PROFILE|ADDRESS>")
+            })?;
+            let address = ProfileValue::from_str(address)?.to_address()?;
+            Ok((name.to_string(), address))
+        })
+        .collect()
+}
+
+/// Links the libraries used by the selected contract to the Move modules named after them.
+/// The assigned libraries must already be published at their addresses. The rest are linked to
+/// the modules at `address` and returned as packs to be translated along with the contract.
+pub(crate) fn link_libraries(
+    pack: &EvmPack,
+    assignments: &BTreeMap<String, AccountAddress>,
+    address: AccountAddress,
+) -> Result<(EvmPack, Vec<EvmPack>)> {
+    let mut links = Vec::new();
+    let mut unassigned = Vec::new();
+    for library in pack.used_libraries() {
+        let assigned = assignments
+            .get(&library.path)
+            .or_else(|| assignments.get(library.name()));
+        let module = ModuleId::new(
+            assigned.copied().unwrap_or(address),
+            Identifier::new(library.name())?,
+        );
+        log::info!("Library {} is linked as {module}", library.path);
+        links.push(LibraryLink::new(library.path.clone(), module));
+        if assigned.is_none() {
+            unassigned.push(library);
+        }
+    }
+    // The libraries of the abi|bin files are only known by their fully qualified names.
+    for (path, address) in assignments {
+        if path.contains(':') && links.iter().all(|link| link.path != *path) {
+            let module = ModuleId::new(*address, Identifier::new(library_name(path))?);
+            links.push(LibraryLink::new(path.clone(), module));
+        }
+    }
+
+    // The links of the pack know the functions of the libraries which call each other.
+    let pack = pack.link(&links)?;
+    let libraries = unassigned
+        .into_iter()
+        .map(|library| library.pack(pack.links()))
+        .collect::<Result<Vec<_>>>()?;
+    Ok((pack, libraries))
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::fs::read_to_string;
 use std::path::{Path, PathBuf};
@@ -8,20 +9,34 @@ use anyhow::{anyhow, bail, Result};
 use clap::Parser;
 use itertools::Itertools;
 
-use eth::compile::{Evm, EvmPack};
+use bindings::rust_bindings;
+use eth::artifact::load_artifacts;
+use eth::compile::{build_sol_with, Evm, EvmPack, SolcConfig};
+use eth::vyper::build_vy_with;
 use move_core_types::account_address::AccountAddress;
-use translator::{translate, Target};
+use translator::{
+    estimate_gas, package_client_manifest, toml_template, translate, translate_intrinsic, Client,
+    Frontend, Target,
+};
 
 mod deploy;
 pub mod flags;
+mod gas;
+pub(crate) mod link;
+pub mod solc;
 
 use crate::call::args::FunctionArgs;
+use crate::convert::link::{library_assignments, link_libraries};
 use crate::profile::ProfileValue;
 use crate::Cmd;
 
+/// File name of the shared intrinsic library.
+const INTRINSIC_NAME: &str = "intrinsic";
+
 #[derive(Parser, Debug)]
 pub struct CmdConvert {
-    /// Path to the file. Specify the path to sol file or abi|bin.
+    /// Path to the file. Specify the path to sol or vy file, abi|bin, or a Hardhat|Foundry artifact
+    /// or artifacts directory.
     #[clap(value_parser, display_order = 1)]
     path: PathBuf,
 
@@ -33,14 +48,42 @@ pub struct CmdConvert {
     #[clap(long = "module", display_order = 4, value_parser)]
     move_module_name: Option<String>,
 
+    /// The name of the contract to convert if the sol file defines several contracts
+    #[clap(
+        long = "contract",
+        display_order = 2,
+        value_parser,
+        conflicts_with = "all"
+    )]
+    contract: Option<String>,
+
+    /// Converts all the contracts of the sol file into one Move package
+    #[clap(
+        long = "all",
+        display_order = 2,
+        value_parser,
+        conflicts_with = "move_module_name"
+    )]
+    all: bool,
+
     /// Profile name or address. The address must start with "0x". Needed for the module address
     #[clap(long = "profile", display_order = 5, short = 'p', value_parser)]
     pub(crate) profile_or_address: Option<ProfileValue>,
 
+    /// Profile name or address of the shared intrinsic library. The address must start with "0x".
+    /// If specified, the intrinsics are imported from the library instead of being copied into the module
+    #[clap(long = "intrinsic", display_order = 6, value_parser)]
+    intrinsic: Option<ProfileValue>,
+
     /// Parameters for initialization
     #[clap(long = "args", short = 'a', default_value = "")]
     init_args: Vec<String>,
 
+    /// Library published at the address, in the `<NAME>=<PROFILE|ADDRESS>` form.
+    /// The libraries which are not specified are converted into the modules of the package
+    #[clap(long = "library", value_parser)]
+    libraries: Vec<String>,
+
     #[clap(flatten)]
     pub(crate) transaction_flags: crate::txflags::TransactionFlags,
 
@@ -48,8 +91,19 @@ pub struct CmdConvert {
     #[clap(long = "deploy", short = 'd', value_parser)]
     pub deploy: bool,
 
+    /// Generates Rust bindings of the module
+    #[clap(long = "rust-bindings", value_parser)]
+    rust_bindings: bool,
+
+    /// Prints the estimated gas of the module functions next to the EVM gas of the contract
+    #[clap(long = "gas-report", value_parser)]
+    gas_report: bool,
+
     #[clap(flatten)]
     convertion_flags: flags::ConvertFlags,
+
+    #[clap(flatten)]
+    solc_flags: solc::SolcFlags,
 }
 
 impl Cmd for CmdConvert {
@@ -62,7 +116,13 @@ impl Cmd for CmdConvert {
 
         self.convertion_flags.check()?;
 
-        Ok(format!("Saved in the {:?}", result.interface_dir_path))
+        match &result.gas_report {
+            Some(report) => Ok(format!(
+                "{report}\nSaved in the {:?}",
+                result.interface_dir_path
+            )),
+            None => Ok(format!("Saved in the {:?}", result.interface_dir_path)),
+        }
     }
 }
 
@@ -70,53 +130,262 @@ impl CmdConvert {
     pub fn convert(&self) -> Result<ResultConvert> {
         log::trace!("Convert: {:?}", &self.path);
 
-        let pack = path_to_abibin(&self.path)
-            .map_err(|err| anyhow!("Failed to convert file {:?}. \nError: {err:?}", &self.path))?;
-
         let address = match self.profile_or_address.as_ref() {
             None => ProfileValue::default_profile()?.to_address()?,
             Some(profile) => profile.to_address()?,
         };
         log::trace!("Address: {address:?}");
 
+        let intrinsic_address = self
+            .intrinsic
+            .as_ref()
+            .map(|profile| profile.to_address())
+            .transpose()?;
+
+        // Vyper hashes the mapping slot before the key, so the native keys can't be recognized.
+        if self.convertion_flags.native_mappings && path_to_ext(&self.path) == Some("vy") {
+            bail!("--native-mappings is not supported for the Vyper contracts");
+        }
+
+        if self.all {
+            return self.convert_all(address, intrinsic_address);
+        }
+
+        let pack = load_pack(
+            &self.path,
+            &self.solc_flags.config()?,
+            self.contract.as_deref(),
+        )?;
+        let (pack, libraries) =
+            link_libraries(&pack, &library_assignments(&self.libraries)?, address)?;
         let module_name = self
             .move_module_name
             .clone()
             .unwrap_or_else(|| pack.name().to_string());
+        let mv = translate_pack(
+            &pack,
+            module_name.clone(),
+            address,
+            &self.init_args,
+            self.convertion_flags,
+            intrinsic_address,
+        )?;
 
         let interface_dir_path = self.interface_dir(&module_name)?;
+        let mut binary_code_paths = self.convert_libraries(
+            &interface_dir_path,
+            libraries.iter(),
+            &module_name,
+            address,
+            intrinsic_address,
+        )?;
+        // the contract is saved last, so the package is named after it
+        binary_code_paths.push(self.save_module(
+            &interface_dir_path,
+            &pack,
+            &module_name,
+            &mv,
+            None,
+            address,
+        )?);
 
-        // Convert
-
-        let initialization_args = FunctionArgs::from((&address, &self.init_args))
-            .value()
-            .join(" ");
+        // save the shared intrinsic library
+        if let Some(intrinsic_address) = intrinsic_address {
+            save_intrinsic(&interface_dir_path, intrinsic_address)?;
+        }
 
-        let binary_code_path = interface_dir_path.join(&module_name).with_extension("mv");
-        let cfg = translator::Config {
-            contract_addr: address,
-            name: &module_name,
-            initialization_args: &initialization_args,
-            flags: self.convertion_flags.into(),
+        // estimate the gas
+        let gas_report = if self.gas_report {
+            let report = estimate_gas(&mv.bytecode, intrinsic_address)?;
+            Some(gas::gas_report(&pack, &report)?)
+        } else {
+            None
         };
-        let mv = translate(pack.bin_contract(), pack.abi_str(), cfg)?;
-        fs::write(&binary_code_path, &mv.bytecode)?;
 
-        // save the interface
-        save_interface(&interface_dir_path, &module_name, &mv)?;
+        Ok(ResultConvert {
+            interface_dir_path,
+            binary_code_paths,
+            package_name: module_name,
+            address,
+            gas_report,
+        })
+    }
 
-        // save the abi
-        let abi_path = interface_dir_path.join(&module_name).with_extension("abi");
-        fs::write(abi_path, pack.contract().abi.as_str())?;
+    /// Converts every contract of the file into a module of one Move package named after the file.
+    /// The Move sources and the clients are saved as separate packages of each module.
+    fn convert_all(
+        &self,
+        address: AccountAddress,
+        intrinsic_address: Option<AccountAddress>,
+    ) -> Result<ResultConvert> {
+        let pack = compile_path(&self.path, &self.solc_flags.config()?)?;
+        let package_name = path_to_filename(&self.path)?;
+        let interface_dir_path = self.interface_dir(&package_name)?;
+
+        let assignments = library_assignments(&self.libraries)?;
+        let mut libraries = BTreeMap::new();
+        let mut binary_code_paths = Vec::new();
+        let mut gas_reports = Vec::new();
+        for pack in pack.split() {
+            let (pack, used_libraries) = link_libraries(&pack, &assignments, address)?;
+            for library in used_libraries {
+                libraries.insert(library.name().to_string(), library);
+            }
+            let module_name = pack.name().to_string();
+            let mv = translate_pack(
+                &pack,
+                module_name.clone(),
+                address,
+                &self.init_args,
+                self.convertion_flags,
+                intrinsic_address,
+            )?;
+
+            binary_code_paths.push(self.save_module(
+                &interface_dir_path,
+                &pack,
+                &module_name,
+                &mv,
+                Some(&package_name),
+                address,
+            )?);
+
+            if self.gas_report {
+                let report = estimate_gas(&mv.bytecode, intrinsic_address)?;
+                gas_reports.push(format!(
+                    "{module_name}:\n{}",
+                    gas::gas_report(&pack, &report)?
+                ));
+            }
+        }
+
+        binary_code_paths.extend(self.convert_libraries(
+            &interface_dir_path,
+            libraries.values(),
+            &package_name,
+            address,
+            intrinsic_address,
+        )?);
+
+        // the modules share the manifest of the package
+        fs::write(
+            interface_dir_path.join("Move.toml"),
+            toml_template(&package_name, address),
+        )?;
+
+        // save the shared intrinsic library
+        if let Some(intrinsic_address) = intrinsic_address {
+            save_intrinsic(&interface_dir_path, intrinsic_address)?;
+        }
 
         Ok(ResultConvert {
             interface_dir_path,
-            binary_code_path,
-            module_name,
+            binary_code_paths,
+            package_name,
             address,
+            gas_report: self.gas_report.then(|| gas_reports.join("\n")),
         })
     }
 
+    /// Converts the libraries into the modules of the package `package_name`.
+    /// Returns the paths of the binary code.
+    fn convert_libraries<'a>(
+        &self,
+        interface_dir_path: &Path,
+        libraries: impl Iterator<Item = &'a EvmPack>,
+        package_name: &str,
+        address: AccountAddress,
+        intrinsic_address: Option<AccountAddress>,
+    ) -> Result<Vec<PathBuf>> {
+        libraries
+            .map(|library| {
+                let module_name = library.name().to_string();
+                let mv = translate_pack(
+                    library,
+                    module_name.clone(),
+                    address,
+                    &[],
+                    self.convertion_flags,
+                    intrinsic_address,
+                )
+                .map_err(|err| {
+                    anyhow!(
+                        "Failed to convert the library {module_name}: {err:?}\n\
+                        Publish it separately and link it with `--library {module_name}=<ADDRESS>`"
+                    )
+                })?;
+                self.save_module(
+                    interface_dir_path,
+                    library,
+                    &module_name,
+                    &mv,
+                    Some(package_name),
+                    address,
+                )
+            })
+            .collect()
+    }
+
+    /// Saves the binary code, the interface, the abi and the generated code of the module.
+    /// If the module is a part of the package `package_name`, the Move source and the client are
+    /// saved in the subdirectories named after the module.
+    /// Returns the path of the binary code.
+    fn save_module(
+        &self,
+        interface_dir_path: &Path,
+        pack: &EvmPack,
+        module_name: &str,
+        mv: &Target,
+        package_name: Option<&str>,
+        address: AccountAddress,
+    ) -> Result<PathBuf> {
+        let binary_code_path = interface_dir_path.join(module_name).with_extension("mv");
+        fs::write(&binary_code_path, &mv.bytecode)?;
+
+        // save the interface
+        save_interface(interface_dir_path, module_name, mv)?;
+
+        let package_dir = |name: &str| match package_name {
+            Some(_) => interface_dir_path.join(name).join(module_name),
+            None => interface_dir_path.join(name),
+        };
+
+        // save the move source
+        if let Some(source) = &mv.source {
+            save_source(&package_dir("source"), module_name, source, mv)?;
+        }
+
+        // save the typed move client
+        if let Some(client) = &mv.client {
+            let manifest = match package_name {
+                Some(package_name) => {
+                    package_client_manifest(module_name, package_name, "../..", address)
+                }
+                None => client.manifest.clone(),
+            };
+            save_client(&package_dir("client"), module_name, &manifest, client)?;
+        }
+
+        // save the abi
+        let abi_path = interface_dir_path.join(module_name).with_extension("abi");
+        fs::write(abi_path, pack.contract().abi.as_str())?;
+
+        // save the rust bindings
+        if self.rust_bindings {
+            let bindings = rust_bindings(
+                module_name,
+                pack.contract().abi.as_str(),
+                self.convertion_flags.into(),
+            )?;
+            fs::write(
+                interface_dir_path.join(module_name).with_extension("rs"),
+                bindings.source,
+            )?;
+        }
+
+        Ok(binary_code_path)
+    }
+
     fn interface_dir(&self, module_name: &str) -> Result<PathBuf> {
         let interface_dir = self
             .output_path
@@ -129,6 +398,71 @@ impl CmdConvert {
     }
 }
 
+/// Compiles the passed sol or abi|bin path and selects the contract to translate.
+pub(crate) fn load_pack(
+    path: &Path,
+    solc_config: &SolcConfig,
+    contract: Option<&str>,
+) -> Result<EvmPack> {
+    select_contract(path, compile_path(path, solc_config)?, contract)
+}
+
+/// Compiles the passed sol or abi|bin path.
+fn compile_path(path: &Path, solc_config: &SolcConfig) -> Result<EvmPack> {
+    path_to_abibin(path, solc_config)
+        .map_err(|err| anyhow!("Failed to convert file {:?}. \nError: {err:?}", path))
+}
+
+/// Translates the selected contract of the pack into the Move module `module_name`.
+pub(crate) fn translate_pack(
+    pack: &EvmPack,
+    module_name: String,
+    address: AccountAddress,
+    init_args: &[String],
+    convertion_flags: flags::ConvertFlags,
+    intrinsic_address: Option<AccountAddress>,
+) -> Result<Target> {
+    let initialization_args = FunctionArgs::from((&address, &init_args.to_vec()))
+        .value()
+        .join(" ");
+
+    let frontend = if convertion_flags.yul {
+        Frontend::Yul(pack.ir().ok_or_else(|| {
+            anyhow!(
+                "The Yul IR of {} was not found. Convert a sol file, \
+                or save the IR next to the abi with `solc --ir -o <DIR>`",
+                pack.name()
+            )
+        })?)
+    } else {
+        Frontend::Bytecode
+    };
+    let cfg = translator::Config {
+        initialization_args: &initialization_args,
+        flags: convertion_flags.into(),
+        intrinsic_address,
+        storage_layout: pack.storage_layout(),
+        libraries: pack.links(),
+        frontend,
+        ..translator::Config::new(address, &module_name)
+    };
+    translate(pack.bin_contract(), pack.abi_str(), cfg)
+}
+
+/// Selects the contract to translate.
+/// If the file defines several contracts and none is passed, the contract named after the file is used.
+fn select_contract(path: &Path, pack: EvmPack, contract: Option<&str>) -> Result<EvmPack> {
+    match contract {
+        Some(name) => pack.select(name),
+        None if pack.contracts().len() > 1 && pack.name() != path_to_filename(path)? => bail!(
+            "The file defines several contracts: {}.\n\
+            Select one with `--contract <NAME>` or convert all of them with `--all`",
+            pack.contract_names().join(", ")
+        ),
+        None => Ok(pack),
+    }
+}
+
 #[inline]
 fn save_interface(base_dir: &Path, module_name: &str, target: &Target) -> Result<()> {
     fs::create_dir_all(base_dir)?;
@@ -144,6 +478,60 @@ fn save_interface(base_dir: &Path, module_name: &str, target: &Target) -> Result
     Ok(())
 }
 
+/// Saves the shared intrinsic library binary and its interface.
+/// The library must be published once before the modules that use it.
+#[inline]
+fn save_intrinsic(base_dir: &Path, address: AccountAddress) -> Result<()> {
+    let library = translate_intrinsic(address)?;
+    fs::write(
+        base_dir.join(INTRINSIC_NAME).with_extension("mv"),
+        &library.bytecode,
+    )?;
+    fs::write(
+        base_dir
+            .join("sources")
+            .join(INTRINSIC_NAME)
+            .with_extension("move"),
+        &library.interface,
+    )?;
+    Ok(())
+}
+
+/// Saves the Move source of the module as a separate package next to the interface.
+#[inline]
+fn save_source(base_dir: &Path, module_name: &str, source: &str, target: &Target) -> Result<()> {
+    fs::create_dir_all(base_dir)?;
+    fs::write(base_dir.join("Move.toml"), &target.manifest)?;
+
+    let sources = base_dir.join("sources");
+    fs::create_dir_all(&sources)?;
+    fs::write(sources.join(module_name).with_extension("move"), source)?;
+    for (name, source) in &target.intrinsics {
+        fs::write(sources.join(name).with_extension("move"), source)?;
+    }
+
+    Ok(())
+}
+
+/// Saves the typed Move client as a package that depends on the interface package.
+#[inline]
+fn save_client(base_dir: &Path, module_name: &str, manifest: &str, client: &Client) -> Result<()> {
+    fs::create_dir_all(base_dir)?;
+    fs::write(base_dir.join("Move.toml"), manifest)?;
+
+    let sources = base_dir.join("sources");
+    fs::create_dir_all(&sources)?;
+    fs::write(
+        sources
+            .join(format!("{module_name}_client"))
+            .with_extension("move"),
+        &client.source,
+    )?;
+    fs::write(sources.join("eth_abi.move"), &client.eth_abi)?;
+
+    Ok(())
+}
+
 #[inline]
 fn path_to_filename(path: &Path) -> Result<String> {
     let name = path
@@ -155,34 +543,51 @@ fn path_to_filename(path: &Path) -> Result<String> {
     Ok(name)
 }
 
+fn path_to_ext(path: &Path) -> Option<&str> {
+    path.extension().and_then(|ext| ext.to_str())
+}
+
 /// Convert the passed path to "abi" and "bin" path.
 ///     sol - compiled into "bin" and "abi" and stored in a temporary directory
+///     vy - compiled by vyper into "bin" and "abi"
 ///     bin - searches next to "abi" with the same name and returns paths to them
 ///     abi - searches next to "bin" with the same name and returns paths to them
+///     json or a directory - Hardhat or Foundry build artifacts
 #[inline]
-fn path_to_abibin(path: &Path) -> Result<EvmPack> {
-    let ext = path
-        .extension()
-        .and_then(|e| e.to_str())
-        .ok_or_else(|| anyhow!("solidity file was expected {path:?}\nPath: "))?;
+fn path_to_abibin(path: &Path, solc_config: &SolcConfig) -> Result<EvmPack> {
+    if path.is_dir() {
+        return load_artifacts(path);
+    }
+    let ext =
+        path_to_ext(path).ok_or_else(|| anyhow!("solidity file was expected {path:?}\nPath: "))?;
 
     match ext {
         "sol" => {
-            if !check_solc() {
-                bail!("solc command was not found.\n\
-                    Please install solc on your computer. See: https://docs.soliditylang.org/en/develop/installing-solidity.html")
+            if !check_compiler(solc_config.solc()) {
+                bail!("solc command was not found: {:?}\n\
+                    Please install solc on your computer. See: https://docs.soliditylang.org/en/develop/installing-solidity.html", solc_config.solc())
+            }
+            build_sol_with(path, solc_config)
+        }
+        "vy" => {
+            if !check_compiler(solc_config.vyper()) {
+                bail!("vyper command was not found: {:?}\n\
+                    Please install vyper on your computer. See: https://docs.vyperlang.org/en/stable/installing-vyper.html", solc_config.vyper())
             }
-            eth::compile::build_sol(path)
+            build_vy_with(path, solc_config.vyper())
         }
         "bin" | "abi" => find_abibin(path),
-        _ => bail!("A file with the extension bin, abi or sol was expected.\nPath: {path:?}"),
+        "json" => load_artifacts(path),
+        _ => bail!(
+            "A file with the extension bin, abi, json, sol or vy was expected.\nPath: {path:?}"
+        ),
     }
 }
 
-/// Checking whether "solc" is installed on this computer
+/// Checking whether the compiler ("solc" or "vyper") is installed on this computer
 #[inline]
-fn check_solc() -> bool {
-    let output = match cli::new("solc").arg("--version").output() {
+fn check_compiler(compiler: &Path) -> bool {
+    let output = match cli::new(compiler).arg("--version").output() {
         Ok(r) => r,
         Err(err) => {
             log::error!("{err}");
@@ -233,12 +638,23 @@ fn find_abibin(path: &Path) -> Result<EvmPack> {
 
     let bin = read_to_string(&bin_path)?;
     let abi = read_to_string(&abi_path)?;
+    // `solc --ir-optimized -o` and `solc --ir -o` save the Yul IR next to the abi.
+    let ir = [format!("{filename}_opt.yul"), format!("{filename}.yul")]
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+        .map(read_to_string)
+        .transpose()?
+        .unwrap_or_default();
 
     Ok(EvmPack::from((
         Evm {
             name: Arc::new(filename),
             bin: Arc::new(bin),
             abi: Arc::new(abi),
+            storage_layout: Arc::new("".to_string()),
+            source_map: Arc::new("".to_string()),
+            ir: Arc::new(ir),
         },
         Vec::new(),
     )))
@@ -246,7 +662,10 @@ fn find_abibin(path: &Path) -> Result<EvmPack> {
 
 pub struct ResultConvert {
     pub interface_dir_path: PathBuf,
-    pub binary_code_path: PathBuf,
-    pub module_name: String,
+    /// Binary code of the modules of the package.
+    pub binary_code_paths: Vec<PathBuf>,
+    pub package_name: String,
     pub address: AccountAddress,
+    /// Formatted gas report. Only built with `--gas-report`.
+    pub gas_report: Option<String>,
 }
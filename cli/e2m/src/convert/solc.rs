@@ -0,0 +1,97 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use eth::compile::SolcConfig;
+
+#[derive(Args, Debug, Clone, Default)]
+pub struct SolcFlags {
+    /// Path to the solc binary. If not specified, `solc` is taken from the PATH
+    #[clap(long = "solc", value_parser)]
+    pub solc: Option<PathBuf>,
+
+    /// Path to the vyper binary. If not specified, `vyper` is taken from the PATH
+    #[clap(long = "vyper", value_parser)]
+    pub vyper: Option<PathBuf>,
+
+    /// Root of the source tree. If not specified, the directory of the sol file
+    #[clap(long = "base-path", value_parser)]
+    pub base_path: Option<PathBuf>,
+
+    /// Additional directory to look for the imports in
+    #[clap(long = "include-path", value_parser)]
+    pub include_paths: Vec<PathBuf>,
+
+    /// Import remapping in the `prefix=target` form. `remappings.txt` of the base path is read as well
+    #[clap(long = "remapping", value_parser)]
+    pub remappings: Vec<String>,
+
+    /// Enables the solc optimizer
+    #[clap(long = "optimize", value_parser)]
+    pub optimize: bool,
+
+    /// Number of the optimizer runs. Enables the optimizer
+    #[clap(long = "optimizer-runs", value_parser)]
+    pub optimizer_runs: Option<u32>,
+
+    /// Compiles through the Yul IR
+    #[clap(long = "via-ir", value_parser)]
+    pub via_ir: bool,
+
+    /// Path to the yaml file with the solc settings. The flags take precedence over the file
+    #[clap(long = "solc-config", value_parser)]
+    pub config: Option<PathBuf>,
+}
+
+impl SolcFlags {
+    /// Settings of the compilation. The relative paths of the file are relative to its directory.
+    pub fn config(&self) -> Result<SolcConfig> {
+        let mut config = match &self.config {
+            Some(path) => load_config(path)?,
+            None => SolcConfig::default(),
+        };
+
+        if let Some(solc) = &self.solc {
+            config.solc = Some(solc.clone());
+        }
+        if let Some(vyper) = &self.vyper {
+            config.vyper = Some(vyper.clone());
+        }
+        if let Some(base_path) = &self.base_path {
+            config.base_path = Some(base_path.clone());
+        }
+        config
+            .include_paths
+            .extend(self.include_paths.iter().cloned());
+        config.remappings.extend(self.remappings.iter().cloned());
+        config.optimize |= self.optimize;
+        if let Some(runs) = self.optimizer_runs {
+            config.optimizer_runs = Some(runs);
+        }
+        config.via_ir |= self.via_ir;
+        Ok(config)
+    }
+}
+
+fn load_config(path: &Path) -> Result<SolcConfig> {
+    let mut config: SolcConfig = serde_yaml::from_str(&fs::read_to_string(path)?)
+        .map_err(|err| anyhow!("Invalid solc config {path:?}. {err}"))?;
+
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    // A bare name of the binary is looked up in the PATH.
+    for binary in [&mut config.solc, &mut config.vyper] {
+        if let Some(path) = binary {
+            if path.components().count() > 1 {
+                *path = dir.join(path.as_path());
+            }
+        }
+    }
+    config.base_path = config.base_path.map(|base_path| dir.join(base_path));
+    config.include_paths = config
+        .include_paths
+        .iter()
+        .map(|include_path| dir.join(include_path))
+        .collect();
+    Ok(config)
+}
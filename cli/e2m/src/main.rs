@@ -15,6 +15,7 @@ pub mod profile;
 pub mod call;
 pub mod resources;
 pub mod txflags;
+pub mod verify;
 
 pub trait Cmd {
     fn execute(&mut self) -> Result<String>;
@@ -31,6 +32,9 @@ pub enum Args {
 
     /// Command to list resources, modules, or other items owned by an address
     Resources(crate::resources::CmdResources),
+
+    /// Re-translate a sol script and compare it with the deployed module
+    VerifyBuild(crate::verify::CmdVerifyBuild),
 }
 
 impl Cmd for Args {
@@ -39,6 +43,7 @@ impl Cmd for Args {
             Args::Convert(data) => data.execute(),
             Args::Call(data) => data.execute(),
             Args::Resources(data) => data.execute(),
+            Args::VerifyBuild(data) => data.execute(),
         }
     }
 }
@@ -0,0 +1,134 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, Result};
+use clap::Parser;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::ModuleId;
+
+use move_executor::load::fixture::Remote;
+use move_executor::load::load_module;
+use move_executor::profile::{load_profile, ProfileConfig};
+
+use crate::convert::flags::ConvertFlags;
+use crate::convert::link::{library_assignments, link_libraries};
+use crate::convert::solc::SolcFlags;
+use crate::convert::{load_pack, translate_pack};
+use crate::profile::ProfileValue;
+use crate::Cmd;
+
+#[derive(Parser, Debug)]
+pub struct CmdVerifyBuild {
+    /// Path to the file. Specify the path to sol file, abi|bin, or a Hardhat|Foundry artifact or
+    /// artifacts directory.
+    #[clap(value_parser, display_order = 1)]
+    path: PathBuf,
+
+    /// The name of the Move module. If not specified, the name will be taken from the abi path
+    #[clap(long = "module", display_order = 2, value_parser)]
+    move_module_name: Option<String>,
+
+    /// The name of the contract to verify if the sol file defines several contracts
+    #[clap(long = "contract", display_order = 2, value_parser)]
+    contract: Option<String>,
+
+    /// Profile name or address of the deployed module. The address must start with "0x".
+    #[clap(long = "profile", display_order = 3, short = 'p', value_parser)]
+    profile_or_address: Option<ProfileValue>,
+
+    /// URL for the Aptos rest endpoint. If not specified, it will be taken from the profile
+    #[clap(long = "url", display_order = 4, value_parser)]
+    url: Option<String>,
+
+    /// Profile name or address of the shared intrinsic library that was used for the conversion
+    #[clap(long = "intrinsic", display_order = 5, value_parser)]
+    intrinsic: Option<ProfileValue>,
+
+    /// Parameters for initialization
+    #[clap(long = "args", short = 'a', default_value = "")]
+    init_args: Vec<String>,
+
+    /// Library the module was linked with, in the `<NAME>=<PROFILE|ADDRESS>` form
+    #[clap(long = "library", value_parser)]
+    libraries: Vec<String>,
+
+    #[clap(flatten)]
+    convertion_flags: ConvertFlags,
+
+    #[clap(flatten)]
+    solc_flags: SolcFlags,
+}
+
+impl Cmd for CmdVerifyBuild {
+    fn execute(&mut self) -> Result<String> {
+        self.convertion_flags.check()?;
+
+        let profile = match self.profile_or_address.clone() {
+            None => ProfileValue::default_profile()?,
+            Some(profile) => profile,
+        };
+        let address = profile.to_address()?;
+        let intrinsic_address = self
+            .intrinsic
+            .as_ref()
+            .map(|profile| profile.to_address())
+            .transpose()?;
+
+        let pack = load_pack(
+            &self.path,
+            &self.solc_flags.config()?,
+            self.contract.as_deref(),
+        )?;
+        let (pack, _) = link_libraries(&pack, &library_assignments(&self.libraries)?, address)?;
+        let module_name = self
+            .move_module_name
+            .clone()
+            .unwrap_or_else(|| pack.name().to_string());
+        let target = translate_pack(
+            &pack,
+            module_name.clone(),
+            address,
+            &self.init_args,
+            self.convertion_flags,
+            intrinsic_address,
+        )?;
+
+        let rest_url = match (&self.url, &profile) {
+            (Some(url), _) => url.clone(),
+            (None, ProfileValue::Profile { name, .. }) => load_profile(name)?
+                .rest_url
+                .ok_or_else(|| anyhow!("The rest_url in the profile {name} is not specified"))?,
+            (None, ProfileValue::Address(_)) => {
+                bail!("Specify the node with `--url` or pass a profile name instead of the address")
+            }
+        };
+        let profile_config = ProfileConfig {
+            rest_url: Some(rest_url.trim_end_matches('/').to_string()),
+            ..ProfileConfig::vm(address)
+        };
+
+        let module_id = ModuleId::new(address, Identifier::new(module_name)?);
+        let deployed = load_module(&profile_config, &module_id, &Remote::from_env())?;
+
+        if deployed == target.bytecode {
+            return Ok(format!(
+                "Verified: {} matches the translation of {:?}",
+                module_id.short_str_lossless(),
+                self.path
+            ));
+        }
+
+        let offset = deployed
+            .iter()
+            .zip(&target.bytecode)
+            .position(|(deployed, translated)| deployed != translated)
+            .unwrap_or_else(|| deployed.len().min(target.bytecode.len()));
+        bail!(
+            "The deployed module {} does not match the translation of {:?}.\n\
+            Deployed: {} bytes, translated: {} bytes, first difference at offset {offset}",
+            module_id.short_str_lossless(),
+            self.path,
+            deployed.len(),
+            target.bytecode.len(),
+        )
+    }
+}
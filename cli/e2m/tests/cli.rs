@@ -175,6 +175,9 @@ fn checking_the_file_structure(project_dir: &Path, module_name: &str) {
     );
     assert!(project_dir.join(format!("{module_name}.abi")).exists());
     assert!(project_dir.join(format!("{module_name}.mv")).exists());
+    assert!(project_dir
+        .join(format!("source/sources/{module_name}.move"))
+        .exists());
 }
 
 trait StrLastLine {
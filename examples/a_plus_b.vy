@@ -0,0 +1,24 @@
+# @version ^0.3.7
+
+@external
+@pure
+def plus() -> uint256:
+    return self._plus(13, 14)
+
+
+@external
+@pure
+def plus_1(a: uint256, b: uint256) -> uint256:
+    return self._plus(a, b)
+
+
+@internal
+@pure
+def _plus(a: uint256, b: uint256) -> uint256:
+    return a + b
+
+
+@external
+@pure
+def minus() -> uint256:
+    return 14 - 13
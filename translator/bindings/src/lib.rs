@@ -0,0 +1,614 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use anyhow::{bail, Error};
+use ethabi::{Contract, Event, Function, Param, ParamType};
+
+use eth::abi::entry_name;
+use eth::abi::names::{event_name, param_names, snake_case, RUST_KEYWORDS};
+use eth::Flags;
+
+/// Methods of the generated client.
+const METHODS: [&str; 6] = [
+    "new",
+    "module_id",
+    "constructor",
+    "entry",
+    "function",
+    "event",
+];
+
+/// Prefixes of the methods generated for a function: the payload builder and the decoder.
+const FUNCTION_METHODS: [&str; 2] = ["", "decode_"];
+
+/// Prefixes of the methods generated for an event: the decoder.
+const EVENT_METHODS: [&str; 1] = ["decode_"];
+
+/// Generated bindings.
+pub struct RustBindings {
+    /// Source of the bindings module.
+    pub source: String,
+    /// Functions and events without bindings, with the reasons.
+    /// They are listed in the header of `source` as well.
+    pub unsupported: Vec<String>,
+}
+
+/// Rust bindings of the translated module.
+///
+/// The bindings contain a client with a method per Solidity function that builds the `EntryFunction`
+/// payload, a decoder of the values returned by each function and a struct with a decoder per event.
+/// The values are encoded with `eth::abi::codec`, the same codecs as used by the `MoveExecutor`.
+/// The overloaded items get the selector as the suffix, see [`entry_name`].
+pub fn rust_bindings(module_name: &str, abi: &str, flags: Flags) -> Result<RustBindings, Error> {
+    let contract: Contract = serde_json::from_str(abi)?;
+    let client = camel_case(module_name);
+
+    let mut items = String::new();
+    let mut unsupported = Vec::new();
+    let mut taken = METHODS.iter().map(|name| name.to_string()).collect();
+    for fun in contract.functions() {
+        let entry = entry_name(&contract, fun);
+        let name = item_name(snake_case(&entry), &FUNCTION_METHODS, &mut taken);
+        let mut item = String::new();
+        match write_function(&mut item, &name, &entry, fun, flags) {
+            Ok(()) => items.push_str(&item),
+            Err(err) => {
+                release(&name, &FUNCTION_METHODS, &mut taken);
+                unsupported.push(format!("function `{entry}`: {err}"));
+            }
+        }
+    }
+
+    let mut events = String::new();
+    for event in contract.events() {
+        let event_id = event_name(&contract, event);
+        let name = item_name(
+            format!("{}_event", snake_case(&event_id)),
+            &EVENT_METHODS,
+            &mut taken,
+        );
+        let overload = contract
+            .events_by_name(&event.name)?
+            .iter()
+            .position(|overload| overload.signature() == event.signature())
+            .unwrap_or_default();
+        let (mut item, mut structs) = (String::new(), String::new());
+        match write_event(&mut item, &mut structs, &name, &event_id, overload, event) {
+            Ok(()) => {
+                items.push_str(&item);
+                events.push_str(&structs);
+            }
+            Err(err) => {
+                release(&name, &EVENT_METHODS, &mut taken);
+                unsupported.push(format!("event `{event_id}`: {err}"));
+            }
+        }
+    }
+
+    let mut buff = String::new();
+    writeln!(buff, "// Rust bindings of the `{module_name}` Move module.")?;
+    writeln!(buff, "// Generated by e2m, do not edit.")?;
+    if !unsupported.is_empty() {
+        writeln!(buff, "//")?;
+        writeln!(buff, "// Not generated:")?;
+        for item in &unsupported {
+            writeln!(buff, "// - {item}")?;
+        }
+    }
+    writeln!(buff)?;
+    writeln!(buff, "use anyhow::{{anyhow, Result}};")?;
+    writeln!(buff, "use aptos_types::transaction::EntryFunction;")?;
+    writeln!(buff, "#[allow(unused_imports)]")?;
+    writeln!(buff, "use eth::abi::codec;")?;
+    writeln!(buff, "#[allow(unused_imports)]")?;
+    writeln!(buff, "use ethabi::ethereum_types::{{H160, U256}};")?;
+    writeln!(buff, "#[allow(unused_imports)]")?;
+    writeln!(buff, "use ethabi::{{Contract, Event, Function, Token}};")?;
+    writeln!(
+        buff,
+        "use move_core_types::account_address::AccountAddress;"
+    )?;
+    writeln!(buff, "use move_core_types::identifier::Identifier;")?;
+    writeln!(buff, "use move_core_types::language_storage::ModuleId;")?;
+    writeln!(buff, "#[allow(unused_imports)]")?;
+    writeln!(buff, "use move_core_types::value::MoveTypeLayout;")?;
+    writeln!(buff)?;
+    writeln!(buff, "const ABI: &str = {};", raw_string(abi))?;
+    writeln!(buff)?;
+
+    writeln!(buff, "/// Client of the `{module_name}` module.")?;
+    writeln!(buff, "pub struct {client} {{")?;
+    writeln!(buff, "{:width$}module: ModuleId,", "", width = 4)?;
+    writeln!(buff, "{:width$}abi: Contract,", "", width = 4)?;
+    writeln!(buff, "}}")?;
+    writeln!(buff)?;
+
+    writeln!(buff, "impl {client} {{")?;
+    write_common(&mut buff, module_name)?;
+    buff.push_str(&items);
+    write_helpers(&mut buff)?;
+    writeln!(buff, "}}")?;
+    buff.push_str(&events);
+    Ok(RustBindings {
+        source: buff,
+        unsupported,
+    })
+}
+
+/// Rust raw string literal with enough `#` to hold `val`.
+fn raw_string(val: &str) -> String {
+    let mut hashes = 0;
+    let mut run = None;
+    for ch in val.chars() {
+        run = match (ch, run) {
+            ('"', _) => Some(0),
+            ('#', Some(len)) => Some(len + 1),
+            _ => None,
+        };
+        hashes = hashes.max(run.unwrap_or_default());
+    }
+    let hashes = "#".repeat(hashes + 1);
+    format!("r{hashes}\"{val}\"{hashes}")
+}
+
+fn write_common(buff: &mut String, module_name: &str) -> Result<(), Error> {
+    writeln!(
+        buff,
+        "{:width$}pub fn new(address: AccountAddress) -> Result<Self> {{",
+        "",
+        width = 4
+    )?;
+    writeln!(buff, "{:width$}Ok(Self {{", "", width = 8)?;
+    writeln!(
+        buff,
+        "{:width$}module: ModuleId::new(address, Identifier::new(\"{module_name}\")?),",
+        "",
+        width = 12
+    )?;
+    writeln!(
+        buff,
+        "{:width$}abi: serde_json::from_str(ABI)?,",
+        "",
+        width = 12
+    )?;
+    writeln!(buff, "{:width$}}})", "", width = 8)?;
+    writeln!(buff, "{:width$}}}", "", width = 4)?;
+    writeln!(buff)?;
+
+    writeln!(
+        buff,
+        "{:width$}pub fn module_id(&self) -> &ModuleId {{",
+        "",
+        width = 4
+    )?;
+    writeln!(buff, "{:width$}&self.module", "", width = 8)?;
+    writeln!(buff, "{:width$}}}", "", width = 4)?;
+    writeln!(buff)?;
+
+    writeln!(
+        buff,
+        "{:width$}/// Payload of the module constructor.",
+        "",
+        width = 4
+    )?;
+    writeln!(
+        buff,
+        "{:width$}pub fn constructor(&self) -> Result<EntryFunction> {{",
+        "",
+        width = 4
+    )?;
+    writeln!(
+        buff,
+        "{:width$}self.entry(\"constructor\", vec![])",
+        "",
+        width = 8
+    )?;
+    writeln!(buff, "{:width$}}}", "", width = 4)?;
+    Ok(())
+}
+
+fn write_helpers(buff: &mut String) -> Result<(), Error> {
+    writeln!(buff)?;
+    writeln!(
+        buff,
+        "{:width$}fn entry(&self, name: &str, args: Vec<Vec<u8>>) -> Result<EntryFunction> {{",
+        "",
+        width = 4
+    )?;
+    writeln!(buff, "{:width$}Ok(EntryFunction::new(", "", width = 8)?;
+    writeln!(buff, "{:width$}self.module.clone(),", "", width = 12)?;
+    writeln!(buff, "{:width$}Identifier::new(name)?,", "", width = 12)?;
+    writeln!(buff, "{:width$}vec![],", "", width = 12)?;
+    writeln!(buff, "{:width$}args,", "", width = 12)?;
+    writeln!(buff, "{:width$}))", "", width = 8)?;
+    writeln!(buff, "{:width$}}}", "", width = 4)?;
+    writeln!(buff)?;
+
+    writeln!(buff, "{:width$}#[allow(dead_code)]", "", width = 4)?;
+    writeln!(
+        buff,
+        "{:width$}fn function(&self, selector: [u8; 4]) -> Result<&Function> {{",
+        "",
+        width = 4
+    )?;
+    writeln!(buff, "{:width$}self.abi", "", width = 8)?;
+    writeln!(buff, "{:width$}.functions()", "", width = 12)?;
+    writeln!(
+        buff,
+        "{:width$}.find(|fun| fun.short_signature() == selector)",
+        "",
+        width = 12
+    )?;
+    writeln!(
+        buff,
+        "{:width$}.ok_or_else(|| anyhow!(\"Function {{selector:?}} not found\"))",
+        "",
+        width = 12
+    )?;
+    writeln!(buff, "{:width$}}}", "", width = 4)?;
+    writeln!(buff)?;
+
+    writeln!(buff, "{:width$}#[allow(dead_code)]", "", width = 4)?;
+    writeln!(
+        buff,
+        "{:width$}fn event(&self, name: &str, overload: usize) -> Result<&Event> {{",
+        "",
+        width = 4
+    )?;
+    writeln!(buff, "{:width$}self.abi", "", width = 8)?;
+    writeln!(buff, "{:width$}.events_by_name(name)?", "", width = 12)?;
+    writeln!(buff, "{:width$}.get(overload)", "", width = 12)?;
+    writeln!(
+        buff,
+        "{:width$}.ok_or_else(|| anyhow!(\"Event {{name}} not found\"))",
+        "",
+        width = 12
+    )?;
+    writeln!(buff, "{:width$}}}", "", width = 4)?;
+    Ok(())
+}
+
+fn write_function(
+    buff: &mut String,
+    name: &str,
+    entry: &str,
+    fun: &Function,
+    flags: Flags,
+) -> Result<(), Error> {
+    let inputs = names(&fun.inputs);
+    let u128_input = flags.native_input && flags.u128_io;
+    let mut params = vec!["&self".to_string()];
+    let mut tokens = vec![];
+    for (param_name, param) in inputs.iter().zip(&fun.inputs) {
+        params.push(format!(
+            "{param_name}: {}",
+            map_type(&param.kind, u128_input)?
+        ));
+        tokens.push(to_token(&param.kind, param_name, u128_input)?);
+    }
+
+    let u128_output = flags.native_output && flags.u128_io;
+    let outputs = fun
+        .outputs
+        .iter()
+        .map(|param| map_type(&param.kind, u128_output))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let signature = signature(&fun.name, &fun.inputs);
+    let selector = fun
+        .short_signature()
+        .iter()
+        .map(|byte| format!("{byte:#04x}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    writeln!(buff)?;
+    writeln!(
+        buff,
+        "{:width$}/// Payload of `{signature}`.",
+        "",
+        width = 4
+    )?;
+    if has_int(&fun.inputs) {
+        writeln!(
+            buff,
+            "{:width$}/// The `int` arguments are the two's complement `U256`.",
+            "",
+            width = 4
+        )?;
+    }
+    writeln!(
+        buff,
+        "{:width$}pub fn {name}({}) -> Result<EntryFunction> {{",
+        "",
+        params.join(", "),
+        width = 4
+    )?;
+    writeln!(
+        buff,
+        "{:width$}let tokens: [Token; {}] = [{}];",
+        "",
+        tokens.len(),
+        tokens.join(", "),
+        width = 8
+    )?;
+    if flags.native_input {
+        writeln!(buff, "{:width$}let args = tokens", "", width = 8)?;
+        writeln!(buff, "{:width$}.iter()", "", width = 12)?;
+        writeln!(
+            buff,
+            "{:width$}.map(|token| codec::encode_move_arg(token, {}))",
+            "",
+            flags.u128_io,
+            width = 12
+        )?;
+        writeln!(buff, "{:width$}.collect::<Result<_>>()?;", "", width = 12)?;
+        writeln!(
+            buff,
+            "{:width$}self.entry(\"{entry}\", args)",
+            "",
+            width = 8
+        )?;
+    } else {
+        writeln!(
+            buff,
+            "{:width$}let args = codec::encode_eth_args(self.function([{selector}])?, &tokens)?;",
+            "",
+            width = 8
+        )?;
+        writeln!(
+            buff,
+            "{:width$}self.entry(\"{entry}\", vec![args])",
+            "",
+            width = 8
+        )?;
+    }
+    writeln!(buff, "{:width$}}}", "", width = 4)?;
+
+    if outputs.is_empty() || flags.hidden_output {
+        return Ok(());
+    }
+
+    let ret = if outputs.len() == 1 {
+        outputs[0].to_string()
+    } else {
+        format!("({})", outputs.join(", "))
+    };
+    writeln!(buff)?;
+    writeln!(
+        buff,
+        "{:width$}/// Decodes the values returned by `{signature}`.",
+        "",
+        width = 4
+    )?;
+    if has_int(&fun.outputs) {
+        writeln!(
+            buff,
+            "{:width$}/// The `int` values are the two's complement `U256`.",
+            "",
+            width = 4
+        )?;
+    }
+    writeln!(
+        buff,
+        "{:width$}pub fn decode_{name}(&self, returns: &[(Vec<u8>, MoveTypeLayout)]) -> Result<{ret}> {{",
+        "",
+        width = 4
+    )?;
+    if flags.native_output {
+        writeln!(
+            buff,
+            "{:width$}// The structs are recognized by the tags of the annotated layouts.",
+            "",
+            width = 8
+        )?;
+        writeln!(
+            buff,
+            "{:width$}let mut tokens = codec::decode_move_values(returns, &self.module)?.into_iter();",
+            "",
+            width = 8
+        )?;
+    } else {
+        writeln!(
+            buff,
+            "{:width$}let mut tokens = codec::decode_eth_output(self.function([{selector}])?, returns)?.into_iter();",
+            "",
+            width = 8
+        )?;
+    }
+    if outputs.len() == 1 {
+        writeln!(
+            buff,
+            "{:width$}codec::next_token(&mut tokens)",
+            "",
+            width = 8
+        )?;
+    } else {
+        writeln!(buff, "{:width$}Ok((", "", width = 8)?;
+        for _ in &outputs {
+            writeln!(
+                buff,
+                "{:width$}codec::next_token(&mut tokens)?,",
+                "",
+                width = 12
+            )?;
+        }
+        writeln!(buff, "{:width$}))", "", width = 8)?;
+    }
+    writeln!(buff, "{:width$}}}", "", width = 4)?;
+    Ok(())
+}
+
+fn write_event(
+    buff: &mut String,
+    structs: &mut String,
+    name: &str,
+    event_id: &str,
+    overload: usize,
+    event: &Event,
+) -> Result<(), Error> {
+    let params = event
+        .inputs
+        .iter()
+        .map(|param| Param {
+            name: param.name.clone(),
+            kind: param.kind.clone(),
+            internal_type: None,
+        })
+        .collect::<Vec<_>>();
+    let fields = names(&params);
+    // Indexed dynamic values are stored as hashes.
+    let types = event
+        .inputs
+        .iter()
+        .map(|param| match param.kind {
+            ParamType::String | ParamType::Bytes if param.indexed => Ok("Vec<u8>"),
+            _ => map_type(&param.kind, false),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let struct_name = format!("{}Event", camel_case(event_id));
+    let signature = signature(&event.name, &params);
+
+    writeln!(structs)?;
+    writeln!(structs, "/// `{signature}` event.")?;
+    writeln!(structs, "#[derive(Debug, Clone, PartialEq, Eq)]")?;
+    writeln!(structs, "pub struct {struct_name} {{")?;
+    for (field, tp) in fields.iter().zip(&types) {
+        writeln!(structs, "{:width$}pub {field}: {tp},", "", width = 4)?;
+    }
+    writeln!(structs, "}}")?;
+
+    writeln!(buff)?;
+    writeln!(
+        buff,
+        "{:width$}/// Decodes the BCS encoded `{signature}` event.",
+        "",
+        width = 4
+    )?;
+    writeln!(
+        buff,
+        "{:width$}pub fn decode_{name}(&self, event: &[u8]) -> Result<{struct_name}> {{",
+        "",
+        width = 4
+    )?;
+    writeln!(
+        buff,
+        "{:width$}let mut tokens = codec::decode_event(self.event(\"{}\", {overload})?, event)?.into_iter();",
+        "",
+        event.name,
+        width = 8
+    )?;
+    writeln!(buff, "{:width$}Ok({struct_name} {{", "", width = 8)?;
+    for field in &fields {
+        writeln!(
+            buff,
+            "{:width$}{field}: codec::next_token(&mut tokens)?,",
+            "",
+            width = 12
+        )?;
+    }
+    writeln!(buff, "{:width$}}})", "", width = 8)?;
+    writeln!(buff, "{:width$}}}", "", width = 4)?;
+    Ok(())
+}
+
+fn has_int(params: &[Param]) -> bool {
+    params
+        .iter()
+        .any(|param| matches!(param.kind, ParamType::Int(_)))
+}
+
+fn signature(name: &str, params: &[Param]) -> String {
+    let types = params
+        .iter()
+        .map(|param| param.kind.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("{name}({types})")
+}
+
+fn map_type(kind: &ParamType, u128_io: bool) -> Result<&'static str, Error> {
+    Ok(match kind {
+        ParamType::Uint(_) => {
+            if u128_io {
+                "u128"
+            } else {
+                "U256"
+            }
+        }
+        ParamType::Int(_) if u128_io => {
+            bail!("Signed integers have no u128 representation: {kind:?}")
+        }
+        // Two's complement, as `Token::Int`.
+        ParamType::Int(_) => "U256",
+        ParamType::Bool => "bool",
+        ParamType::Address => "H160",
+        ParamType::String => "String",
+        ParamType::FixedBytes(_) | ParamType::Bytes => "Vec<u8>",
+        _ => bail!("Unsupported type: {kind:?}"),
+    })
+}
+
+fn to_token(kind: &ParamType, name: &str, u128_io: bool) -> Result<String, Error> {
+    let value = if u128_io {
+        format!("U256::from({name})")
+    } else {
+        name.to_string()
+    };
+    Ok(match kind {
+        ParamType::Uint(_) => format!("Token::Uint({value})"),
+        ParamType::Int(_) => format!("Token::Int({name})"),
+        ParamType::Bool => format!("Token::Bool({name})"),
+        ParamType::Address => format!("Token::Address({name})"),
+        ParamType::String => format!("Token::String({name})"),
+        ParamType::FixedBytes(_) => format!("Token::FixedBytes({name})"),
+        ParamType::Bytes => format!("Token::Bytes({name})"),
+        _ => bail!("Unsupported type: {kind:?}"),
+    })
+}
+
+/// Rust names of the parameters, see [`param_names`].
+fn names(params: &[Param]) -> Vec<String> {
+    param_names(params, &RUST_KEYWORDS)
+}
+
+/// Name of the item such that all its methods, the `prefixes` followed by the name, are free.
+/// The keywords get the `_` suffix, the taken names get the `_1`, `_2`, ... suffix.
+fn item_name(mut name: String, prefixes: &[&str], taken: &mut HashSet<String>) -> String {
+    if RUST_KEYWORDS.contains(&name.as_str()) {
+        name.push('_');
+    }
+    let mut candidate = name.clone();
+    let mut idx = 1;
+    while prefixes
+        .iter()
+        .any(|prefix| taken.contains(&format!("{prefix}{candidate}")))
+    {
+        candidate = format!("{name}_{idx}");
+        idx += 1;
+    }
+    for prefix in prefixes {
+        taken.insert(format!("{prefix}{candidate}"));
+    }
+    candidate
+}
+
+/// Frees the methods of the item without bindings.
+fn release(name: &str, prefixes: &[&str], taken: &mut HashSet<String>) {
+    for prefix in prefixes {
+        taken.remove(&format!("{prefix}{name}"));
+    }
+}
+
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
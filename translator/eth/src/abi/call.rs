@@ -33,11 +33,11 @@ pub fn fn_params_str_split(params: &str) -> Result<Vec<&str>> {
                 None
             }
             _ if quote => None,
-            '[' => {
+            '[' | '(' => {
                 lf += 1;
                 None
             }
-            ']' => {
+            ']' | ')' => {
                 lf -= 1;
                 None
             }
@@ -78,6 +78,7 @@ impl EthEncodeConstructor for Constructor {
 
 pub trait EthEncodeByString {
     fn short_signature_in_hex(&self) -> String;
+    fn call_by_tokens(&self, tokens: &[Token]) -> Result<Bytes>;
     fn call_by_vec_str(&self, params: &[&str]) -> Result<Bytes>;
     fn call_by_str(&self, params: &str) -> Result<Bytes> {
         let params = fn_params_str_split(params)?;
@@ -94,7 +95,11 @@ impl EthEncodeByString for Function {
             .zip(params.iter().map(|v| v as &str))
             .collect::<Vec<_>>();
         let tokens: Vec<Token> = params.iter().map(to_token).collect::<Result<_, _>>()?;
-        let result = self.encode_input(&tokens)?;
+        self.call_by_tokens(&tokens)
+    }
+
+    fn call_by_tokens(&self, tokens: &[Token]) -> Result<Bytes> {
+        let result = self.encode_input(tokens)?;
         Ok(result)
     }
 
@@ -112,8 +117,11 @@ impl EthEncodeByString for Constructor {
             .zip(params.iter().map(|v| v as &str))
             .collect::<Vec<_>>();
         let tokens: Vec<Token> = params.iter().map(to_token).collect::<Result<_, _>>()?;
+        self.call_by_tokens(&tokens)
+    }
 
-        let result = self.encode_input(self.short_signature().into(), &tokens)?;
+    fn call_by_tokens(&self, tokens: &[Token]) -> Result<Bytes> {
+        let result = self.encode_input(self.short_signature().into(), tokens)?;
         Ok(result)
     }
 
@@ -186,7 +194,38 @@ impl Display for FunHash {
 mod test {
     use ethabi::Contract;
 
-    use crate::abi::call::EthEncodeByString;
+    use crate::abi::call::{fn_params_str_split, EthEncodeByString};
+
+    #[test]
+    fn test_params_split() {
+        // The flat parameters of the sol tests and the `e2m call` arguments.
+        assert_eq!(fn_params_str_split("").unwrap(), Vec::<&str>::new());
+        assert_eq!(fn_params_str_split(" 5 ").unwrap(), vec!["5"]);
+        assert_eq!(
+            fn_params_str_split("0x42, 100, true").unwrap(),
+            vec!["0x42", "100", "true"]
+        );
+        assert_eq!(
+            fn_params_str_split(r#""hello, world", 0x0102"#).unwrap(),
+            vec![r#""hello, world""#, "0x0102"]
+        );
+        assert_eq!(
+            fn_params_str_split(r#""say \"hi\", twice", 2"#).unwrap(),
+            vec![r#""say \"hi\", twice""#, "2"]
+        );
+        assert_eq!(
+            fn_params_str_split("[1, 2, 3], 4").unwrap(),
+            vec!["[1, 2, 3]", "4"]
+        );
+
+        // The native structs are nested into the vectors and the structs.
+        assert_eq!(
+            fn_params_str_split("0x42, (1, [2, 3]), [(4), (5)]").unwrap(),
+            vec!["0x42", "(1, [2, 3])", "[(4), (5)]"]
+        );
+        assert!(fn_params_str_split("(1, 2").is_err());
+        assert!(fn_params_str_split("[1, 2)), 3").is_err());
+    }
 
     /// Encoding and decoding input/output
     ///
@@ -0,0 +1,534 @@
+//! Codecs of the translated module calls.
+//! Shared by the `MoveExecutor` and the generated Rust bindings.
+
+use anyhow::{anyhow, bail, ensure, Result};
+use ethabi::ethereum_types::{H160, H256, U256};
+use ethabi::{Event, Function, RawLog, Token};
+use move_core_types::account_address::AccountAddress;
+use move_core_types::language_storage::{ModuleId, StructTag, CORE_CODE_ADDRESS};
+use move_core_types::value::{MoveStruct, MoveStructLayout, MoveTypeLayout, MoveValue};
+use serde::{Deserialize, Serialize};
+
+use crate::abi::call::{fn_params_str_split, EthEncodeByString, FUN_HASH_LEN};
+
+/// Name of the shared intrinsic library module, see `intrinsic::LIBRARY_NAME`.
+const INTRINSIC_LIBRARY: &str = "intrinsic";
+
+/// BCS representation of the intrinsic `U256`.
+#[derive(Serialize, Deserialize)]
+pub struct U256Wrapper(pub [u64; 4]);
+
+/// Event emitted by the translated module.
+#[derive(Deserialize)]
+struct MoveEvent {
+    data: Vec<u8>,
+    topics: Vec<U256Wrapper>,
+}
+
+/// Encodes the argument of a function with the eth-ABI input.
+/// The selector is dropped: each function of the translated module knows its own.
+pub fn encode_eth_args<F: EthEncodeByString>(fun: &F, tokens: &[Token]) -> Result<Vec<u8>> {
+    encode_eth_request(&fun.call_by_tokens(tokens)?)
+}
+
+/// Encodes the call data produced by `EthEncodeByString` as the argument of the translated function.
+pub fn encode_eth_request(request: &[u8]) -> Result<Vec<u8>> {
+    ensure!(request.len() >= FUN_HASH_LEN, "Request without a selector");
+    Ok(bcs::to_bytes(&request[FUN_HASH_LEN..])?)
+}
+
+/// Encodes the argument of a function with the native input.
+pub fn encode_move_arg(token: &Token, u128_io: bool) -> Result<Vec<u8>> {
+    Ok(match token {
+        Token::Bool(val) => bcs::to_bytes(val)?,
+        Token::Uint(val) | Token::Int(val) => {
+            if u128_io {
+                ensure!(
+                    *val <= U256::from(u128::MAX),
+                    "{val} does not fit into u128"
+                );
+                bcs::to_bytes(&val.as_u128())?
+            } else {
+                bcs::to_bytes(&val.0)?
+            }
+        }
+        Token::Address(val) => bcs::to_bytes(&to_move_address(val))?,
+        Token::Bytes(val) | Token::FixedBytes(val) => bcs::to_bytes(val)?,
+        Token::String(val) => bcs::to_bytes(val.as_bytes())?,
+        _ => bail!("Unsupported argument: {token:?}"),
+    })
+}
+
+/// Decodes the values returned by a function of the `module` with the native output.
+pub fn decode_move_values(
+    result: &[(Vec<u8>, MoveTypeLayout)],
+    module: &ModuleId,
+) -> Result<Vec<Token>> {
+    result
+        .iter()
+        .map(|(val, layout)| decode_move_value(val, layout, module))
+        .collect()
+}
+
+/// Decodes the native value by its layout.
+///
+/// The structs are recognized by the tags of the annotated layouts
+/// (`MoveStructLayout::WithTypes`): the intrinsic `U256` of the `module` is a number and
+/// `0x1::string::String` is a string. Any other struct, as well as a struct of a runtime layout,
+/// is a tuple of its fields.
+pub fn decode_move_value(val: &[u8], layout: &MoveTypeLayout, module: &ModuleId) -> Result<Token> {
+    let val = MoveValue::simple_deserialize(val, layout)?;
+    to_token(val, layout, module)
+}
+
+fn to_token(val: MoveValue, layout: &MoveTypeLayout, module: &ModuleId) -> Result<Token> {
+    Ok(match (val, layout) {
+        (MoveValue::Bool(val), _) => Token::Bool(val),
+        (MoveValue::U8(val), _) => Token::Uint(U256::from(val)),
+        (MoveValue::U64(val), _) => Token::Uint(U256::from(val)),
+        (MoveValue::U128(val), _) => Token::Uint(U256::from(val)),
+        (MoveValue::Address(val) | MoveValue::Signer(val), _) => Token::Address(H160::from_slice(
+            &val.into_bytes()[AccountAddress::LENGTH - 20..],
+        )),
+        (MoveValue::Vector(vals), MoveTypeLayout::Vector(inner)) => match inner.as_ref() {
+            MoveTypeLayout::U8 => Token::Bytes(to_bytes(vals)?),
+            inner => Token::Array(
+                vals.into_iter()
+                    .map(|val| to_token(val, inner, module))
+                    .collect::<Result<_>>()?,
+            ),
+        },
+        (MoveValue::Struct(val), MoveTypeLayout::Struct(layout)) => {
+            let mut fields = val.into_fields();
+            match struct_kind(layout, module) {
+                StructKind::U256 => {
+                    let mut words = [0; 4];
+                    for (word, field) in words.iter_mut().zip(fields) {
+                        match field {
+                            MoveValue::U64(val) => *word = val,
+                            _ => bail!("Invalid U256"),
+                        }
+                    }
+                    Token::Uint(U256(words))
+                }
+                StructKind::String => match fields.pop() {
+                    Some(MoveValue::Vector(bytes)) => {
+                        Token::String(String::from_utf8(to_bytes(bytes)?)?)
+                    }
+                    _ => bail!("Invalid String"),
+                },
+                StructKind::Tuple => Token::Tuple(
+                    fields
+                        .into_iter()
+                        .zip(field_layouts(layout))
+                        .map(|(val, layout)| to_token(val, layout, module))
+                        .collect::<Result<_>>()?,
+                ),
+            }
+        }
+        (val, layout) => bail!("Unsupported value {val:?} of {layout:?}"),
+    })
+}
+
+fn to_bytes(vals: Vec<MoveValue>) -> Result<Vec<u8>> {
+    vals.into_iter()
+        .map(|val| match val {
+            MoveValue::U8(val) => Ok(val),
+            _ => bail!("Expected u8, found {val:?}"),
+        })
+        .collect()
+}
+
+/// Parses the argument of a function with the native input by its annotated layout.
+///
+/// A vector is `[a, b]`, a `vector<u8>` is hex as well. A struct is `(a, b)`,
+/// the `U256` is a decimal number and the `String` is the text.
+pub fn parse_move_value(
+    val: &str,
+    layout: &MoveTypeLayout,
+    module: &ModuleId,
+) -> Result<MoveValue> {
+    let val = val.trim();
+    Ok(match layout {
+        MoveTypeLayout::Bool => MoveValue::Bool(val.parse()?),
+        MoveTypeLayout::U8 => MoveValue::U8(val.parse()?),
+        MoveTypeLayout::U64 => MoveValue::U64(val.parse()?),
+        MoveTypeLayout::U128 => MoveValue::U128(val.parse()?),
+        MoveTypeLayout::Address => MoveValue::Address(AccountAddress::from_hex_literal(val)?),
+        MoveTypeLayout::Signer => MoveValue::Signer(AccountAddress::from_hex_literal(val)?),
+        MoveTypeLayout::Vector(inner) => match val.strip_prefix('[') {
+            Some(list) => {
+                let list = list
+                    .strip_suffix(']')
+                    .ok_or_else(|| anyhow!("Expected `]` at the end of {val}"))?;
+                MoveValue::Vector(
+                    fn_params_str_split(list)?
+                        .into_iter()
+                        .map(|val| parse_move_value(val, inner, module))
+                        .collect::<Result<_>>()?,
+                )
+            }
+            None if matches!(inner.as_ref(), MoveTypeLayout::U8) => {
+                MoveValue::vector_u8(hex::decode(val.trim_start_matches("0x"))?)
+            }
+            None => bail!("Expected a vector `[..]`, found {val}"),
+        },
+        MoveTypeLayout::Struct(layout) => {
+            MoveValue::Struct(MoveStruct::Runtime(match struct_kind(layout, module) {
+                StructKind::U256 => U256::from_dec_str(val)?
+                    .0
+                    .into_iter()
+                    .map(MoveValue::U64)
+                    .collect(),
+                StructKind::String => {
+                    let text = val
+                        .strip_prefix('"')
+                        .and_then(|val| val.strip_suffix('"'))
+                        .unwrap_or(val);
+                    vec![MoveValue::vector_u8(text.as_bytes().to_vec())]
+                }
+                StructKind::Tuple => {
+                    let tuple = val
+                        .strip_prefix('(')
+                        .and_then(|val| val.strip_suffix(')'))
+                        .ok_or_else(|| anyhow!("Expected a struct `(..)`, found {val}"))?;
+                    let vals = fn_params_str_split(tuple)?;
+                    let fields = field_layouts(layout);
+                    ensure!(
+                        vals.len() == fields.len(),
+                        "Expected {} fields, found {val}",
+                        fields.len()
+                    );
+                    vals.into_iter()
+                        .zip(fields)
+                        .map(|(val, layout)| parse_move_value(val, layout, module))
+                        .collect::<Result<_>>()?
+                }
+            }))
+        }
+        _ => bail!("Unsupported argument type {layout:?}"),
+    })
+}
+
+enum StructKind {
+    U256,
+    String,
+    Tuple,
+}
+
+/// Kind of the struct by its tag. The intrinsic `U256` is declared either in the translated `module`
+/// or in the shared intrinsic library.
+fn struct_kind(layout: &MoveStructLayout, module: &ModuleId) -> StructKind {
+    let tag = match layout {
+        MoveStructLayout::WithTypes { type_, .. } => type_,
+        _ => return StructKind::Tuple,
+    };
+    if !tag.type_params.is_empty() {
+        return StructKind::Tuple;
+    }
+    match (tag.module.as_str(), tag.name.as_str()) {
+        (_, "U256") if is_intrinsic(tag, module) => StructKind::U256,
+        ("string", "String") if tag.address == CORE_CODE_ADDRESS => StructKind::String,
+        _ => StructKind::Tuple,
+    }
+}
+
+/// Whether the struct is declared by the copy of the intrinsics in the `module`
+/// or by the shared intrinsic library, which may be published at any address.
+fn is_intrinsic(tag: &StructTag, module: &ModuleId) -> bool {
+    (tag.address == *module.address() && tag.module.as_ident_str() == module.name())
+        || tag.module.as_str() == INTRINSIC_LIBRARY
+}
+
+/// Layouts of the struct fields in the declaration order.
+fn field_layouts(layout: &MoveStructLayout) -> Vec<&MoveTypeLayout> {
+    match layout {
+        MoveStructLayout::Runtime(fields) => fields.iter().collect(),
+        MoveStructLayout::WithFields(fields) | MoveStructLayout::WithTypes { fields, .. } => {
+            fields.iter().map(|field| &field.layout).collect()
+        }
+    }
+}
+
+/// Decodes the value returned by a function with the eth-ABI output.
+pub fn decode_eth_output(
+    fun: &Function,
+    result: &[(Vec<u8>, MoveTypeLayout)],
+) -> Result<Vec<Token>> {
+    if let Some((val, _)) = result.first() {
+        let result: Vec<u8> = bcs::from_bytes(val).map_err(|e| anyhow!(e))?;
+        Ok(fun.decode_output(&result)?)
+    } else {
+        Ok(Vec::new())
+    }
+}
+
+/// Decodes the BCS encoded event of the translated module.
+/// Returns the event parameters in the declaration order.
+pub fn decode_event(event: &Event, data: &[u8]) -> Result<Vec<Token>> {
+    let log = event.parse_log(decode_raw_log(data)?)?;
+    Ok(log.params.into_iter().map(|param| param.value).collect())
+}
+
+/// Decodes the event emitted by the translated module into the EVM log.
+pub fn decode_raw_log(data: &[u8]) -> Result<RawLog> {
+    let MoveEvent { data, topics } = bcs::from_bytes(data)?;
+    let topics = topics
+        .into_iter()
+        .map(|topic| {
+            let mut buf = [0u8; 32];
+            U256(topic.0).to_big_endian(&mut buf);
+            H256::from(buf)
+        })
+        .collect();
+    Ok(RawLog { topics, data })
+}
+
+/// Move address of the Ethereum address.
+pub fn to_move_address(address: &H160) -> AccountAddress {
+    let mut buf = [0u8; AccountAddress::LENGTH];
+    buf[AccountAddress::LENGTH - address.as_bytes().len()..].copy_from_slice(address.as_bytes());
+    AccountAddress::new(buf)
+}
+
+/// Takes the next token and converts it to `T`.
+pub fn next_token<T: FromToken>(tokens: &mut impl Iterator<Item = Token>) -> Result<T> {
+    let token = tokens.next().ok_or_else(|| anyhow!("Not enough values"))?;
+    T::from_token(token)
+}
+
+/// Conversion of a decoded token into a typed value.
+pub trait FromToken: Sized {
+    fn from_token(token: Token) -> Result<Self>;
+}
+
+impl FromToken for U256 {
+    fn from_token(token: Token) -> Result<Self> {
+        match token {
+            Token::Uint(val) | Token::Int(val) => Ok(val),
+            _ => bail!("Expected uint, found {token:?}"),
+        }
+    }
+}
+
+impl FromToken for u128 {
+    fn from_token(token: Token) -> Result<Self> {
+        let val = U256::from_token(token)?;
+        ensure!(val <= U256::from(u128::MAX), "{val} does not fit into u128");
+        Ok(val.as_u128())
+    }
+}
+
+impl FromToken for bool {
+    fn from_token(token: Token) -> Result<Self> {
+        match token {
+            Token::Bool(val) => Ok(val),
+            _ => bail!("Expected bool, found {token:?}"),
+        }
+    }
+}
+
+impl FromToken for H160 {
+    fn from_token(token: Token) -> Result<Self> {
+        match token {
+            Token::Address(val) => Ok(val),
+            _ => bail!("Expected address, found {token:?}"),
+        }
+    }
+}
+
+impl FromToken for Vec<u8> {
+    fn from_token(token: Token) -> Result<Self> {
+        match token {
+            Token::Bytes(val) | Token::FixedBytes(val) => Ok(val),
+            Token::String(val) => Ok(val.into_bytes()),
+            _ => bail!("Expected bytes, found {token:?}"),
+        }
+    }
+}
+
+impl FromToken for String {
+    fn from_token(token: Token) -> Result<Self> {
+        match token {
+            Token::String(val) => Ok(val),
+            Token::Bytes(val) => Ok(String::from_utf8(val)?),
+            _ => bail!("Expected string, found {token:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethabi::ethereum_types::{H160, U256};
+    use ethabi::Token;
+    use move_core_types::account_address::AccountAddress;
+    use move_core_types::identifier::Identifier;
+    use move_core_types::language_storage::{ModuleId, StructTag};
+    use move_core_types::value::{MoveFieldLayout, MoveStructLayout, MoveTypeLayout};
+
+    use crate::abi::codec::{
+        decode_move_value, encode_move_arg, next_token, parse_move_value, to_move_address,
+    };
+
+    #[test]
+    fn test_move_args() {
+        let val = U256::from(1000);
+        assert_eq!(
+            encode_move_arg(&Token::Uint(val), false).unwrap(),
+            bcs::to_bytes(&val.0).unwrap()
+        );
+        assert_eq!(
+            encode_move_arg(&Token::Uint(val), true).unwrap(),
+            bcs::to_bytes(&1000u128).unwrap()
+        );
+        assert!(encode_move_arg(&Token::Uint(U256::MAX), true).is_err());
+
+        let address = to_move_address(&H160::from_low_u64_be(0x42));
+        assert_eq!(address, AccountAddress::from_hex_literal("0x42").unwrap());
+    }
+
+    /// Layout of the struct with its tag, as the Move VM reports it for the parameters.
+    fn annotated(
+        address: &str,
+        module: &str,
+        name: &str,
+        fields: Vec<MoveTypeLayout>,
+    ) -> MoveTypeLayout {
+        let tag = StructTag {
+            address: AccountAddress::from_hex_literal(address).unwrap(),
+            module: Identifier::new(module).unwrap(),
+            name: Identifier::new(name).unwrap(),
+            type_params: vec![],
+        };
+        let fields = fields
+            .into_iter()
+            .enumerate()
+            .map(|(idx, layout)| {
+                MoveFieldLayout::new(Identifier::new(format!("v{idx}")).unwrap(), layout)
+            })
+            .collect();
+        MoveTypeLayout::Struct(MoveStructLayout::with_types(tag, fields))
+    }
+
+    #[test]
+    fn test_move_values() {
+        let module = ModuleId::new(
+            AccountAddress::from_hex_literal("0x42").unwrap(),
+            Identifier::new("APlusB").unwrap(),
+        );
+        let round_trip = |val: &str, layout: MoveTypeLayout| {
+            let bytes = parse_move_value(val, &layout, &module)
+                .unwrap()
+                .simple_serialize()
+                .unwrap();
+            decode_move_value(&bytes, &layout, &module).unwrap()
+        };
+        let vector = |layout| MoveTypeLayout::Vector(Box::new(layout));
+        let u256 = || annotated("0x42", "APlusB", "U256", vec![MoveTypeLayout::U64; 4]);
+        let string = annotated("0x1", "string", "String", vec![vector(MoveTypeLayout::U8)]);
+
+        assert_eq!(
+            round_trip("[1, 2]", vector(MoveTypeLayout::U64)),
+            Token::Array(vec![Token::Uint(U256::from(1)), Token::Uint(U256::from(2))])
+        );
+        assert_eq!(
+            round_trip("[[1], []]", vector(vector(MoveTypeLayout::U128))),
+            Token::Array(vec![
+                Token::Array(vec![Token::Uint(U256::from(1))]),
+                Token::Array(vec![]),
+            ])
+        );
+        assert_eq!(
+            round_trip("0x0102", vector(MoveTypeLayout::U8)),
+            Token::Bytes(vec![1, 2])
+        );
+        assert_eq!(
+            round_trip("[1, 2]", vector(MoveTypeLayout::U8)),
+            Token::Bytes(vec![1, 2])
+        );
+        assert_eq!(
+            round_trip("[100000000000000000000000]", vector(u256())),
+            Token::Array(vec![Token::Uint(
+                U256::from_dec_str("100000000000000000000000").unwrap()
+            )])
+        );
+        assert_eq!(
+            round_trip("\"hello, world\"", string),
+            Token::String("hello, world".to_string())
+        );
+        assert_eq!(
+            round_trip(
+                "(true, 0x42, [(1)])",
+                MoveTypeLayout::Struct(MoveStructLayout::new(vec![
+                    MoveTypeLayout::Bool,
+                    MoveTypeLayout::Address,
+                    vector(MoveTypeLayout::Struct(MoveStructLayout::new(vec![
+                        MoveTypeLayout::U8
+                    ]))),
+                ]))
+            ),
+            Token::Tuple(vec![
+                Token::Bool(true),
+                Token::Address(H160::from_low_u64_be(0x42)),
+                Token::Array(vec![Token::Tuple(vec![Token::Uint(U256::from(1))])]),
+            ])
+        );
+
+        // The intrinsic `U256` of the shared library.
+        assert_eq!(
+            round_trip(
+                "7",
+                annotated("0x1", "intrinsic", "U256", vec![MoveTypeLayout::U64; 4])
+            ),
+            Token::Uint(U256::from(7))
+        );
+        // A struct of the same shape is not a `U256` or a `String`.
+        assert_eq!(
+            round_trip(
+                "(1, 2, 3, 4)",
+                annotated("0x43", "APlusB", "U256", vec![MoveTypeLayout::U64; 4])
+            ),
+            Token::Tuple((1..=4).map(|val| Token::Uint(U256::from(val))).collect())
+        );
+        assert_eq!(
+            round_trip(
+                "(1, 2, 3, 4)",
+                annotated("0x42", "Demo", "Point", vec![MoveTypeLayout::U64; 4])
+            ),
+            Token::Tuple((1..=4).map(|val| Token::Uint(U256::from(val))).collect())
+        );
+        assert_eq!(
+            round_trip(
+                "(0x01)",
+                MoveTypeLayout::Struct(MoveStructLayout::new(vec![vector(MoveTypeLayout::U8)]))
+            ),
+            Token::Tuple(vec![Token::Bytes(vec![1])])
+        );
+        assert_eq!(
+            round_trip(
+                "(0x01)",
+                annotated("0x42", "string", "String", vec![vector(MoveTypeLayout::U8)])
+            ),
+            Token::Tuple(vec![Token::Bytes(vec![1])])
+        );
+
+        assert!(parse_move_value("1, 2", &vector(MoveTypeLayout::U64), &module).is_err());
+        assert!(parse_move_value(
+            "(1)",
+            &MoveTypeLayout::Struct(MoveStructLayout::new(vec![
+                MoveTypeLayout::U8,
+                MoveTypeLayout::U8
+            ])),
+            &module
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_next_token() {
+        let mut tokens = vec![Token::Uint(U256::from(1)), Token::Bool(true)].into_iter();
+        assert_eq!(next_token::<u128>(&mut tokens).unwrap(), 1);
+        assert!(next_token::<bool>(&mut tokens).unwrap());
+        assert!(next_token::<bool>(&mut tokens).is_err());
+    }
+}
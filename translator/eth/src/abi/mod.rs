@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashSet};
 
 use anyhow::{Context, Error};
 use ethabi::{Contract, Param};
@@ -8,11 +8,13 @@ use crate::bytecode::types::EthType;
 use crate::Function;
 
 pub mod call;
+pub mod codec;
+pub mod names;
 
 pub struct MoveAbi {
     name: String,
     identifiers: HashSet<String>,
-    functions: HashMap<FunHash, Function>,
+    functions: BTreeMap<FunHash, Function>,
 }
 
 impl MoveAbi {
@@ -21,9 +23,9 @@ impl MoveAbi {
             .functions()
             .map(|fun| {
                 let hash = FunHash::from(fun.short_signature());
-                (hash, map_function(hash, fun))
+                (hash, map_function(hash, entry_name(abi, fun), fun))
             })
-            .fold((HashMap::new(), HashSet::new()), |mut acc, (hash, fun)| {
+            .fold((BTreeMap::new(), HashSet::new()), |mut acc, (hash, fun)| {
                 acc.1.insert(fun.name.to_string());
                 acc.0.insert(hash, fun);
                 acc
@@ -36,7 +38,7 @@ impl MoveAbi {
         })
     }
 
-    pub fn functions(&self) -> &HashMap<FunHash, Function> {
+    pub fn functions(&self) -> &BTreeMap<FunHash, Function> {
         &self.functions
     }
 
@@ -49,6 +51,25 @@ impl MoveAbi {
     }
 }
 
+/// Name of the entry function of the translated module.
+/// The overloaded functions get the selector as the suffix, `transfer_a9059cbb`.
+pub fn entry_name(abi: &Contract, fun: &ethabi::Function) -> String {
+    let overloaded = abi
+        .functions_by_name(&fun.name)
+        .map(|functions| functions.len() > 1)
+        .unwrap_or_default();
+    if overloaded {
+        format!("{}_{}", fun.name, hex::encode(fun.short_signature()))
+    } else {
+        fun.name.clone()
+    }
+}
+
+/// Function of the ABI by the name of its entry function, see [`entry_name`].
+pub fn find_function<'a>(abi: &'a Contract, name: &str) -> Option<&'a ethabi::Function> {
+    abi.functions().find(|fun| entry_name(abi, fun) == name)
+}
+
 fn map_types(types: Vec<Param>) -> Result<Vec<EthType>, Error> {
     types
         .into_iter()
@@ -56,7 +77,7 @@ fn map_types(types: Vec<Param>) -> Result<Vec<EthType>, Error> {
         .collect()
 }
 
-fn map_function(hash: FunHash, fun: &ethabi::Function) -> Function {
+fn map_function(hash: FunHash, name: String, fun: &ethabi::Function) -> Function {
     let move_input = vec![EthType::Address, EthType::Bytes];
     let move_output = vec![EthType::Bytes];
 
@@ -69,7 +90,7 @@ fn map_function(hash: FunHash, fun: &ethabi::Function) -> Function {
         .unwrap();
 
     Function {
-        name: fun.name.clone(),
+        name,
         eth_input: move_input,
         hash,
         eth_output: move_output,
@@ -0,0 +1,131 @@
+//! Names of the items generated from the ABI.
+//! Shared by the Move client and the generated Rust bindings.
+
+use std::collections::HashSet;
+
+use ethabi::{Contract, Event, Param};
+
+pub const MOVE_KEYWORDS: [&str; 27] = [
+    "abort",
+    "acquires",
+    "as",
+    "break",
+    "const",
+    "continue",
+    "copy",
+    "else",
+    "false",
+    "friend",
+    "fun",
+    "if",
+    "invariant",
+    "let",
+    "loop",
+    "module",
+    "move",
+    "native",
+    "public",
+    "return",
+    "script",
+    "spec",
+    "struct",
+    "true",
+    "use",
+    "while",
+    "address",
+];
+
+pub const RUST_KEYWORDS: [&str; 51] = [
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield", "Self",
+];
+
+/// Name of the event. The overloaded events get the topic prefix as the suffix.
+pub fn event_name(abi: &Contract, event: &Event) -> String {
+    let overloaded = abi
+        .events_by_name(&event.name)
+        .map(|events| events.len() > 1)
+        .unwrap_or_default();
+    if overloaded {
+        format!("{}_{}", event.name, hex::encode(&event.signature()[..4]))
+    } else {
+        event.name.clone()
+    }
+}
+
+/// Names of the parameters as locals.
+/// Unnamed parameters and names that do not start with a lowercase letter get the `arg_<idx>` prefix,
+/// see [`unique`] for the `reserved` names and the duplicates.
+pub fn param_names(params: &[Param], reserved: &[&str]) -> Vec<String> {
+    let mut taken = HashSet::new();
+    params
+        .iter()
+        .enumerate()
+        .map(|(idx, param)| {
+            let mut name = snake_case(param.name.trim_start_matches('_'));
+            if !name.starts_with(|c: char| c.is_ascii_lowercase()) {
+                name = format!("arg_{idx}{name}");
+            }
+            unique(name, reserved, &mut taken)
+        })
+        .collect()
+}
+
+/// Takes the name: the `reserved` names get the `_` suffix,
+/// the names already `taken` get the `_1`, `_2`, ... suffix.
+pub fn unique(mut name: String, reserved: &[&str], taken: &mut HashSet<String>) -> String {
+    if reserved.contains(&name.as_str()) {
+        name.push('_');
+    }
+    let mut candidate = name.clone();
+    let mut idx = 1;
+    while !taken.insert(candidate.clone()) {
+        candidate = format!("{name}_{idx}");
+        idx += 1;
+    }
+    candidate
+}
+
+pub fn snake_case(name: &str) -> String {
+    let mut res = String::with_capacity(name.len());
+    let mut prev: Option<char> = None;
+    for ch in name.chars() {
+        if ch.is_ascii_uppercase() {
+            if prev.map_or(false, |p| p.is_ascii_lowercase() || p.is_ascii_digit()) {
+                res.push('_');
+            }
+            res.push(ch.to_ascii_lowercase());
+        } else {
+            res.push(ch);
+        }
+        prev = Some(ch);
+    }
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use ethabi::{Param, ParamType};
+
+    use crate::abi::names::{param_names, snake_case, MOVE_KEYWORDS};
+
+    #[test]
+    fn test_param_names() {
+        let params = ["_fromAddr", "", "move", "a", "a", "A"]
+            .into_iter()
+            .map(|name| Param {
+                name: name.to_string(),
+                kind: ParamType::Bool,
+                internal_type: None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            param_names(&params, &MOVE_KEYWORDS),
+            vec!["from_addr", "arg_1", "move_", "a", "a_1", "a_2"]
+        );
+        assert_eq!(snake_case("balanceOfHTTP2Owner"), "balance_of_http2_owner");
+    }
+}
@@ -0,0 +1,247 @@
+//! Hardhat and Foundry build artifacts.
+//!
+//! A Hardhat artifact keeps `bytecode` as a hex string next to `sourceName` and `linkReferences`.
+//! A Foundry artifact keeps `bytecode` as an object with `object`, `sourceMap` and
+//! `linkReferences`, and the compiled source in `metadata.settings.compilationTarget`.
+//! The creation bytecode is translated, `deployedBytecode` is not used.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, ensure, Result};
+use serde_json::Value;
+
+use crate::compile::{pack_units, read_ir, Evm, EvmPack};
+
+/// Directories of the artifacts which are not contracts.
+const SKIPPED_DIRS: [&str; 3] = ["build-info", "cache", "debug"];
+
+/// Contract of a build artifact.
+#[derive(Debug, Clone)]
+struct Artifact {
+    /// Source unit of the contract.
+    source: String,
+    evm: Evm,
+    /// Libraries referenced by the bytecode as `(<source unit>, <name>)`.
+    references: Vec<(String, String)>,
+}
+
+/// Loads the contracts of a Hardhat or Foundry artifact, or of a directory of artifacts.
+/// The libraries referenced by a single artifact are looked up in the same artifacts directory.
+pub fn load_artifacts<P: AsRef<Path>>(path: P) -> Result<EvmPack> {
+    let path = path.as_ref();
+    if path.is_dir() {
+        let mut files = Vec::new();
+        collect_artifacts(path, &mut files)?;
+        files.sort();
+        let artifacts = files
+            .iter()
+            .filter_map(|file| match Artifact::read(file) {
+                Ok(artifact) => artifact,
+                Err(err) => {
+                    log::debug!("Skipped {file:?}: {err}");
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        ensure!(
+            !artifacts.is_empty(),
+            "Couldn't find an artifact in {path:?}"
+        );
+        return pack_artifacts(artifacts, |_| true, "", path);
+    }
+
+    let artifact =
+        Artifact::read(path)?.ok_or_else(|| anyhow!("The artifact has no bytecode: {path:?}"))?;
+    let source = artifact.source.clone();
+    let name = artifact.evm.name().to_string();
+
+    // The libraries may reference other libraries.
+    let mut artifacts = vec![artifact];
+    let mut index = 0;
+    while index < artifacts.len() {
+        for (lib_source, lib_name) in artifacts[index].references.clone() {
+            let known = artifacts
+                .iter()
+                .any(|item| item.source == lib_source && item.evm.name() == lib_name);
+            if known {
+                continue;
+            }
+            match find_library(path, &source, &lib_source, &lib_name) {
+                Some(library) => artifacts.push(library),
+                None => {
+                    log::warn!("Couldn't find the artifact of the library {lib_source}:{lib_name}")
+                }
+            }
+        }
+        index += 1;
+    }
+    pack_artifacts(artifacts, |item| item == source, &name, path)
+}
+
+fn pack_artifacts(
+    artifacts: Vec<Artifact>,
+    is_target: impl Fn(&str) -> bool,
+    default: &str,
+    path: &Path,
+) -> Result<EvmPack> {
+    let units = artifacts
+        .into_iter()
+        .map(|artifact| (artifact.source, artifact.evm))
+        .collect();
+    pack_units(units, is_target, default).map_err(|err| anyhow!("{err} {path:?}"))
+}
+
+impl Artifact {
+    /// Reads the artifact. Returns `None` for the contracts without bytecode.
+    fn read(path: &Path) -> Result<Option<Artifact>> {
+        let json: Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let abi = json
+            .get("abi")
+            .and_then(|abi| abi.as_array())
+            .ok_or_else(|| anyhow!("Not an artifact: {path:?}"))?;
+        // Hardhat keeps the hex string, Foundry the solc `evm.bytecode` object.
+        let bytecode = json
+            .get("bytecode")
+            .ok_or_else(|| anyhow!("Not an artifact: {path:?}"))?;
+        let bin = match bytecode {
+            Value::String(bin) => bin.as_str(),
+            bytecode => bytecode
+                .get("object")
+                .and_then(|item| item.as_str())
+                .unwrap_or_default(),
+        };
+        let bin = bin.trim_start_matches("0x");
+        if bin.is_empty() {
+            return Ok(None);
+        }
+
+        let target = compilation_target(&json);
+        let name = json
+            .get("contractName")
+            .and_then(|item| item.as_str())
+            .map(ToString::to_string)
+            .or_else(|| target.as_ref().map(|(_, name)| name.clone()))
+            .or_else(|| {
+                // Foundry names the artifacts `<name>.json` or `<name>.<solc version>.json`.
+                let file_name = path.file_name()?.to_string_lossy();
+                Some(file_name.split('.').next()?.to_string())
+            })
+            .ok_or_else(|| anyhow!("Couldn't find the contract name: {path:?}"))?;
+        let source = json
+            .get("sourceName")
+            .and_then(|item| item.as_str())
+            .map(ToString::to_string)
+            .or_else(|| target.map(|(source, _)| source))
+            .or_else(|| {
+                let source = json.get("ast")?.get("absolutePath")?.as_str()?;
+                Some(source.to_string())
+            })
+            .unwrap_or_default();
+
+        let link_references = json
+            .get("linkReferences")
+            .or_else(|| bytecode.get("linkReferences"));
+        let references = link_references
+            .and_then(|item| item.as_object())
+            .map(|sources| {
+                sources
+                    .iter()
+                    .filter_map(|(source, libraries)| Some((source, libraries.as_object()?)))
+                    .flat_map(|(source, libraries)| {
+                        libraries
+                            .keys()
+                            .map(move |name| (source.clone(), name.clone()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let source_map = bytecode
+            .get("sourceMap")
+            .and_then(|item| item.as_str())
+            .unwrap_or_default();
+        let storage_layout = match json.get("storageLayout") {
+            Some(Value::String(layout)) => layout.clone(),
+            Some(Value::Null) | None => "".to_string(),
+            Some(layout) => layout.to_string(),
+        };
+        let abi = if abi.is_empty() {
+            "".to_string()
+        } else {
+            Value::Array(abi.clone()).to_string()
+        };
+
+        Ok(Some(Artifact {
+            source,
+            evm: Evm {
+                name: Arc::new(name),
+                bin: Arc::new(bin.to_string()),
+                abi: Arc::new(abi),
+                storage_layout: Arc::new(storage_layout),
+                source_map: Arc::new(source_map.to_string()),
+                ir: Arc::new(read_ir(&json)),
+            },
+            references,
+        }))
+    }
+}
+
+/// `(<source unit>, <name>)` of the contract in the Foundry metadata.
+fn compilation_target(json: &Value) -> Option<(String, String)> {
+    let metadata = match json.get("metadata")? {
+        Value::String(metadata) => serde_json::from_str(metadata).ok()?,
+        metadata => metadata.clone(),
+    };
+    let (source, name) = metadata
+        .get("settings")?
+        .get("compilationTarget")?
+        .as_object()?
+        .iter()
+        .next()?;
+    Some((source.clone(), name.as_str()?.to_string()))
+}
+
+/// Looks up the artifact of the library next to the artifact of the contract from `source`.
+/// Hardhat keeps the artifacts in `<root>/<source unit>/<name>.json`,
+/// Foundry in `<root>/<source file name>/<name>.json`.
+fn find_library(artifact: &Path, source: &str, lib_source: &str, name: &str) -> Option<Artifact> {
+    let dir = artifact.parent()?;
+    let file_name = format!("{name}.json");
+    let mut candidates = Vec::new();
+    if !source.is_empty() && dir.ends_with(source) {
+        let depth = Path::new(source).components().count();
+        if let Some(root) = dir.ancestors().nth(depth) {
+            candidates.push(root.join(lib_source).join(&file_name));
+        }
+    }
+    if let (Some(root), Some(lib_file)) = (dir.parent(), Path::new(lib_source).file_name()) {
+        candidates.push(root.join(lib_file).join(&file_name));
+    }
+
+    candidates
+        .into_iter()
+        .filter(|path| path.is_file())
+        .filter_map(|path| Artifact::read(&path).ok().flatten())
+        .find(|library| library.evm.name() == name)
+}
+
+/// Collects the json files of the artifacts directory.
+fn collect_artifacts(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if path.is_dir() {
+            if !SKIPPED_DIRS.contains(&name.as_str()) {
+                collect_artifacts(&path, files)?;
+            }
+        } else if name.ends_with(".json") && !name.ends_with(".dbg.json") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
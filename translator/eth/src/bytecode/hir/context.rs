@@ -1,9 +1,14 @@
+use crate::bytecode::hir::ir::{_Expr, Expr, VarId};
 use crate::bytecode::hir::stack::Stack;
 use crate::bytecode::hir::vars::Vars;
 use crate::bytecode::loc::Loc;
+use crate::layout::Layout;
 use crate::{Flags, Function, Offset};
 use primitive_types::U256;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Size of the memory area which solc uses to hash mapping keys.
+pub const SCRATCH_SIZE: u128 = 0x40;
 
 #[derive(Debug, Clone)]
 pub struct Context<'a, 'b> {
@@ -18,6 +23,43 @@ pub struct Context<'a, 'b> {
     pub stack: Stack,
     pub vars: Vars,
     jmp_id: usize,
+    layout: &'b Layout,
+    tracking: Tracking,
+}
+
+/// Mapping entries addressed by solc as `keccak256(key . slot)` of the scratch space and the
+/// memory copied from the code. The mapping entries are tracked only if the layout has mappings
+/// or the mappings are kept by their native keys.
+#[derive(Debug, Clone, Default)]
+pub struct Tracking {
+    /// Words written to the scratch space.
+    scratch: BTreeMap<U256, Expr>,
+    /// Scratch words which are not written to the memory yet.
+    pending: BTreeSet<U256>,
+    /// Hashes of the mapping entries as (key, mapping slot).
+    entries: BTreeMap<VarId, (Expr, U256)>,
+    /// Memory bytes copied from the code.
+    code: CodeMemory,
+    /// Last word with a function selector written to the memory: the head of the library call.
+    selector: Option<U256>,
+}
+
+/// Memory filled with `CODECOPY`. Vyper reads its jump tables from the code this way.
+/// The memory which is not written yet is zero.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct CodeMemory {
+    bytes: BTreeMap<u128, u8>,
+    /// Something but the code was written to the memory, the bytes out of `bytes` are unknown.
+    written: bool,
+}
+
+impl CodeMemory {
+    fn unknown() -> CodeMemory {
+        CodeMemory {
+            bytes: BTreeMap::new(),
+            written: true,
+        }
+    }
 }
 
 impl<'a, 'b> Context<'a, 'b> {
@@ -26,6 +68,7 @@ impl<'a, 'b> Context<'a, 'b> {
         contract_address: U256,
         flags: Flags,
         contract: &'b [u8],
+        layout: &'b Layout,
     ) -> Context<'a, 'b> {
         Context {
             address: contract_address,
@@ -39,6 +82,8 @@ impl<'a, 'b> Context<'a, 'b> {
             loc: Loc::new(0u128, 0u128, ()),
             jmp_id: 0,
             contract,
+            layout,
+            tracking: Default::default(),
         }
     }
 
@@ -107,4 +152,193 @@ impl<'a, 'b> Context<'a, 'b> {
             (id.0 + id.1, true)
         }
     }
+
+    pub fn layout(&self) -> &Layout {
+        self.layout
+    }
+
+    pub fn track_mappings(&self) -> bool {
+        self.layout.has_mappings() || self.flags.native_mappings
+    }
+
+    /// Mappings are addressed by their native keys. The scratch writes are deferred until
+    /// something reads them.
+    pub fn native_mappings(&self) -> bool {
+        self.flags.native_mappings
+    }
+
+    pub fn set_scratch(&mut self, offset: U256, val: Expr) {
+        self.tracking.scratch.insert(offset, val);
+    }
+
+    pub fn scratch(&self, offset: U256) -> Option<&Expr> {
+        self.tracking.scratch.get(&offset)
+    }
+
+    pub fn clear_scratch(&mut self) {
+        self.tracking.scratch.clear();
+        self.tracking.pending.clear();
+    }
+
+    pub fn defer_scratch(&mut self, offset: U256) {
+        self.tracking.pending.insert(offset);
+    }
+
+    pub fn has_pending_scratch(&self) -> bool {
+        !self.tracking.pending.is_empty()
+    }
+
+    /// Takes the deferred scratch words. They stay tracked.
+    pub fn take_pending_scratch(&mut self) -> Vec<(U256, Expr)> {
+        let pending = std::mem::take(&mut self.tracking.pending);
+        pending
+            .into_iter()
+            .filter_map(|offset| Some((offset, self.tracking.scratch.get(&offset)?.clone())))
+            .collect()
+    }
+
+    /// Remembers the code copied to the memory at `offset`.
+    pub fn copy_code(&mut self, offset: &Expr, code: &[u8]) {
+        match offset.resolve(self).filter(|offset| offset.bits() <= 64) {
+            Some(offset) => {
+                let offset = offset.as_u128();
+                self.tracking.code.bytes.extend(
+                    code.iter()
+                        .enumerate()
+                        .map(|(i, byte)| (offset + i as u128, *byte)),
+                );
+            }
+            None => self.forget_memory(),
+        }
+    }
+
+    /// Forgets the memory which is written by anything but the code copy.
+    /// The whole memory is forgotten if the written range is unknown.
+    pub fn write_memory(&mut self, offset: &Expr, len: Option<U256>) {
+        let offset = offset.resolve(self).filter(|offset| offset.bits() <= 64);
+        let len = len.filter(|len| len.bits() <= 64);
+        match (offset, len) {
+            (Some(offset), Some(len)) => {
+                let range = offset.as_u128()..offset.as_u128() + len.as_u128();
+                let code = &mut self.tracking.code;
+                code.bytes.retain(|offset, _| !range.contains(offset));
+                code.written = true;
+            }
+            _ => self.forget_memory(),
+        }
+    }
+
+    /// Forgets the whole memory.
+    pub fn forget_memory(&mut self) {
+        self.tracking.code = CodeMemory::unknown();
+    }
+
+    /// Memory word at `offset` which is built from the copied code and the zero memory.
+    /// Words without the copied bytes are not resolved.
+    pub fn code_word(&self, offset: U256) -> Option<U256> {
+        if offset.bits() > 64 {
+            return None;
+        }
+        let offset = offset.as_u128();
+        let code = &self.tracking.code;
+        let mut word = [0u8; 32];
+        let mut copied = false;
+        for (i, byte) in word.iter_mut().enumerate() {
+            match code.bytes.get(&(offset + i as u128)) {
+                Some(val) => {
+                    *byte = *val;
+                    copied = true;
+                }
+                None if !code.written => {}
+                None => return None,
+            }
+        }
+        copied.then(|| U256::from_big_endian(&word))
+    }
+
+    /// Remembers the word if it is a function selector followed by zeros.
+    pub fn track_selector(&mut self, word: U256) {
+        if !word.is_zero() && (word << 32).is_zero() {
+            self.tracking.selector = Some(word);
+        }
+    }
+
+    pub fn selector(&self) -> Option<U256> {
+        self.tracking.selector
+    }
+
+    pub fn set_entry(&mut self, hash: VarId, key: Expr, slot: U256) {
+        self.tracking.entries.insert(hash, (key, slot));
+    }
+
+    /// Mapping entry which is addressed by the expression.
+    pub fn entry(&self, expr: &Expr) -> Option<&(Expr, U256)> {
+        match expr.as_ref() {
+            _Expr::Var(var) => self.tracking.entries.get(var),
+            _Expr::Copy(expr) => self.entry(expr),
+            _ => None,
+        }
+    }
+
+    /// Forgets the entry which is addressed by the reassigned variable.
+    pub fn forget_entry(&mut self, var: VarId) {
+        self.tracking.entries.remove(&var);
+    }
+
+    /// Moves the known entries to the stack variables which replace them after the flush.
+    pub fn remap_entries(&mut self, stack_entries: Vec<(VarId, (Expr, U256))>) {
+        self.tracking.entries.retain(|var, _| var.is_tmp());
+        self.tracking.entries.extend(stack_entries);
+    }
+
+    /// Temporary variables which hold the tracked mapping keys.
+    pub fn tracked_vars(&self) -> Vec<VarId> {
+        self.tracking
+            .scratch
+            .values()
+            .chain(self.tracking.entries.values().map(|(key, _)| key))
+            .flat_map(|expr| expr.slot_vars())
+            .collect()
+    }
+
+    /// Forgets the tracked scratch, entries and memory.
+    pub fn reset_tracking(&mut self) {
+        self.tracking.scratch.clear();
+        self.tracking.pending.clear();
+        self.tracking.entries.clear();
+        self.tracking.selector = None;
+        self.forget_memory();
+    }
+
+    /// Keeps the scratch words, the entries and the memory bytes which are the same on both
+    /// joined paths.
+    pub fn merge_tracking(&mut self, other: &Tracking) {
+        self.tracking
+            .scratch
+            .retain(|offset, word| other.scratch.get(offset) == Some(word));
+        self.tracking
+            .pending
+            .retain(|offset| other.pending.contains(offset));
+        self.tracking
+            .entries
+            .retain(|var, entry| other.entries.get(var) == Some(entry));
+        if self.tracking.selector != other.selector {
+            self.tracking.selector = None;
+        }
+        if self.tracking.code != other.code {
+            self.tracking
+                .code
+                .bytes
+                .retain(|offset, byte| other.code.bytes.get(offset) == Some(byte));
+            self.tracking.code.written = true;
+        }
+    }
+
+    pub fn tracking(&self) -> Tracking {
+        self.tracking.clone()
+    }
+
+    pub fn restore_tracking(&mut self, tracking: Tracking) {
+        self.tracking = tracking;
+    }
 }
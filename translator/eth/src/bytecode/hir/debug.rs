@@ -1,10 +1,19 @@
 use crate::bytecode::hir::executor::math::{BinaryOp, TernaryOp, UnaryOp};
-use crate::bytecode::hir::ir::{Stmt, _Expr};
+use crate::bytecode::hir::ir::{_Expr, Stmt};
 use crate::bytecode::loc::Loc;
 
 use anyhow::Error;
 use std::fmt::{Display, Formatter, Write};
 
+fn print_keys<B: Write>(buf: &mut B, keys: &[Loc<_Expr>]) -> Result<(), Error> {
+    for key in keys {
+        write!(buf, "[")?;
+        print_expr(buf, key)?;
+        write!(buf, "]")?;
+    }
+    Ok(())
+}
+
 pub fn print_expr<B: Write>(buf: &mut B, expr: &_Expr) -> Result<(), Error> {
     match expr {
         _Expr::Val(val) => write!(buf, "{}", val)?,
@@ -19,6 +28,28 @@ pub fn print_expr<B: Write>(buf: &mut B, expr: &_Expr) -> Result<(), Error> {
             print_expr(buf, key)?;
             write!(buf, ")")?
         }
+        _Expr::HashedSLoad(key) => {
+            write!(buf, "sload_hashed(")?;
+            print_expr(buf, key)?;
+            write!(buf, ")")?
+        }
+        _Expr::FieldLoad(field) => write!(buf, "state.{field}")?,
+        _Expr::EntryLoad(field, key, slot) => {
+            write!(buf, "state.{field}[")?;
+            print_expr(buf, key)?;
+            write!(buf, "] or sload(")?;
+            print_expr(buf, slot)?;
+            write!(buf, ")")?
+        }
+        _Expr::MapSlot(slot, keys) => {
+            write!(buf, "slot(mapping({slot})")?;
+            print_keys(buf, keys)?;
+            write!(buf, ")")?
+        }
+        _Expr::MapLoad(slot, keys) => {
+            write!(buf, "mapping({slot})")?;
+            print_keys(buf, keys)?;
+        }
         _Expr::Signer => write!(buf, "signer")?,
         _Expr::MSize => write!(buf, "msize")?,
         _Expr::ArgsSize => write!(buf, "args_size")?,
@@ -103,6 +134,9 @@ pub fn print_expr<B: Write>(buf: &mut B, expr: &_Expr) -> Result<(), Error> {
         _Expr::BlockCoinbase => {
             write!(buf, "block_coinbase()")?;
         }
+        _Expr::ReturnDataSize => {
+            write!(buf, "return_data_size()")?;
+        }
     }
     Ok(())
 }
@@ -140,6 +174,32 @@ pub fn print_stmt<B: Write>(buf: &mut B, stmt: &Loc<Stmt>) -> Result<(), Error>
             print_expr(buf, var)?;
             writeln!(buf, ");")?;
         }
+        Stmt::HashedSStore { key, val } => {
+            write!(buf, "sstore_hashed(")?;
+            print_expr(buf, key)?;
+            write!(buf, ", ")?;
+            print_expr(buf, val)?;
+            writeln!(buf, ");")?;
+        }
+        Stmt::FieldStore { field, val } => {
+            write!(buf, "state.{field} = ")?;
+            print_expr(buf, val)?;
+            writeln!(buf, ";")?;
+        }
+        Stmt::EntryStore { field, key, val } => {
+            write!(buf, "state.{field}[")?;
+            print_expr(buf, key)?;
+            write!(buf, "] = ")?;
+            print_expr(buf, val)?;
+            writeln!(buf, ";")?;
+        }
+        Stmt::MapStore { slot, keys, val } => {
+            write!(buf, "mapping({slot})")?;
+            print_keys(buf, keys)?;
+            write!(buf, " = ")?;
+            print_expr(buf, val)?;
+            writeln!(buf, ";")?;
+        }
         Stmt::Log {
             offset,
             len,
@@ -190,6 +250,34 @@ pub fn print_stmt<B: Write>(buf: &mut B, stmt: &Loc<Stmt>) -> Result<(), Error>
             print_expr(buf, dest)?;
             writeln!(buf, ", {});", hex::encode(vec))?;
         }
+        Stmt::Call {
+            module,
+            function,
+            offset,
+            len,
+        } => {
+            write!(buf, "call {}::{}(", module, function)?;
+            print_expr(buf, offset)?;
+            write!(buf, ", ")?;
+            print_expr(buf, len)?;
+            writeln!(buf, ");")?;
+        }
+        Stmt::WriteResult { dest, len } => {
+            write!(buf, "write_result(")?;
+            print_expr(buf, dest)?;
+            write!(buf, ", ")?;
+            print_expr(buf, len)?;
+            writeln!(buf, ");")?;
+        }
+        Stmt::ReturnDataCopy { dest, offset, len } => {
+            write!(buf, "returndatacopy(")?;
+            print_expr(buf, dest)?;
+            write!(buf, ", ")?;
+            print_expr(buf, offset)?;
+            write!(buf, ", ")?;
+            print_expr(buf, len)?;
+            writeln!(buf, ");")?;
+        }
     }
     Ok(())
 }
@@ -1,7 +1,14 @@
+use anyhow::{anyhow, bail, ensure, Error};
+use primitive_types::U256;
+
+use crate::abi::call::FUN_HASH_LEN;
 use crate::bytecode::hir::context::Context;
+use crate::bytecode::hir::executor::math::BinaryOp;
+use crate::bytecode::hir::executor::memory::above_scratch;
 use crate::bytecode::hir::executor::{ExecutionResult, InstructionHandler};
-use crate::bytecode::hir::ir::Expr;
-use crate::Hir;
+use crate::bytecode::hir::ir::{Expr, _Expr};
+use crate::link::LibraryLink;
+use crate::{Hir, OpCode};
 
 pub enum CallOp {
     Call,
@@ -15,3 +22,65 @@ impl InstructionHandler for CallOp {
         todo!()
     }
 }
+
+/// Translates the instruction which calls or checks the linked library.
+/// The library function is called by the selector of the call data. The call either succeeds or
+/// aborts the transaction, so its status is always `1`.
+pub fn call_library(
+    library: &LibraryLink,
+    code: &OpCode,
+    args: Vec<Expr>,
+    ir: &mut Hir,
+    ctx: &mut Context,
+) -> Result<_Expr, Error> {
+    match code {
+        // The module of the library is published before the contract.
+        OpCode::ExtCodeSize => return Ok(_Expr::Val(U256::one())),
+        OpCode::DelegateCall | OpCode::StaticCall => {}
+        _ => bail!(
+            "The library {} is called with {:?}. Only DELEGATECALL and STATICCALL are supported",
+            library.path,
+            code
+        ),
+    }
+    let flags = ctx.flags();
+    ensure!(
+        !flags.native_input && !flags.native_output && !flags.hidden_output,
+        "The library {} can only be called with the Ethereum interface",
+        library.path
+    );
+    let function = ctx
+        .selector()
+        .and_then(|selector| library.function(selector))
+        .ok_or_else(|| {
+            anyhow!(
+                "The function of the library {} called at {} is unknown",
+                library.path,
+                ctx.loc.start
+            )
+        })?
+        .to_string();
+
+    let [_gas, _address, offset, len, dest, dest_len]: [Expr; 6] = args
+        .try_into()
+        .map_err(|_| anyhow!("Invalid arguments of the library call"))?;
+    let selector_len = ctx.loc.wrap(_Expr::Val(U256::from(FUN_HASH_LEN)));
+    let offset = ctx.loc.wrap(_Expr::BinaryOp(
+        BinaryOp::Add,
+        Box::new(offset),
+        Box::new(selector_len.clone()),
+    ));
+    let len = ctx.loc.wrap(_Expr::BinaryOp(
+        BinaryOp::Sub,
+        Box::new(len),
+        Box::new(selector_len),
+    ));
+    ir.call(&ctx.loc, library.module.clone(), function, offset, len);
+
+    ctx.write_memory(&dest, dest_len.resolve(ctx));
+    if ctx.track_mappings() && !above_scratch(&dest, ctx) {
+        ctx.clear_scratch();
+    }
+    ir.write_result(&ctx.loc, dest, dest_len);
+    Ok(_Expr::Val(U256::one()))
+}
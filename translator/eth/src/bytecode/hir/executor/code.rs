@@ -1,6 +1,7 @@
 use crate::bytecode::hir::context::Context;
+use crate::bytecode::hir::executor::memory::above_scratch;
 use crate::bytecode::hir::executor::{ExecutionResult, InstructionHandler};
-use crate::bytecode::hir::ir::{Expr, _Expr};
+use crate::bytecode::hir::ir::{_Expr, Expr};
 use crate::Hir;
 use primitive_types::U256;
 
@@ -22,7 +23,11 @@ impl InstructionHandler for CodeOp {
     fn handle(&self, mut params: Vec<Expr>, ir: &mut Hir, ctx: &mut Context) -> ExecutionResult {
         match self {
             CodeOp::CodeSize => ExecutionResult::Output(_Expr::Val(U256::from(ctx.code_size()))),
-            CodeOp::CallDataCopy => ExecutionResult::None,
+            CodeOp::CallDataCopy => {
+                let length = params[2].resolve(ctx);
+                ctx.write_memory(&params[0], length);
+                ExecutionResult::None
+            }
             CodeOp::CodeCopy => {
                 let length = params.remove(2);
                 let offset = params.remove(1);
@@ -32,6 +37,10 @@ impl InstructionHandler for CodeOp {
                     let offset = offset.as_u128();
                     let length = length.as_u128();
                     let code = ctx.contract_slice(offset, length).to_vec();
+                    if ctx.track_mappings() && !above_scratch(&dest_offset, ctx) {
+                        ctx.clear_scratch();
+                    }
+                    ctx.copy_code(&dest_offset, &code);
                     ir.code_copy(&ctx.loc, dest_offset, code);
                     ExecutionResult::None
                 } else {
@@ -44,11 +53,18 @@ impl InstructionHandler for CodeOp {
             CodeOp::ExtCodeCopy => {
                 todo!()
             }
-            CodeOp::ReturnDataSize => {
-                todo!()
-            }
+            CodeOp::ReturnDataSize => ExecutionResult::Output(_Expr::ReturnDataSize),
             CodeOp::ReturnDataCopy => {
-                todo!()
+                let length = params.remove(2);
+                let offset = params.remove(1);
+                let dest_offset = params.remove(0);
+
+                ctx.write_memory(&dest_offset, length.resolve(ctx));
+                if ctx.track_mappings() && !above_scratch(&dest_offset, ctx) {
+                    ctx.clear_scratch();
+                }
+                ir.return_data_copy(&ctx.loc, dest_offset, offset, length);
+                ExecutionResult::None
             }
             CodeOp::ExtCodeHash => {
                 todo!()
@@ -1,4 +1,5 @@
-use crate::bytecode::hir::context::Context;
+use crate::bytecode::hir::context::{Context, SCRATCH_SIZE};
+use crate::bytecode::hir::executor::memory::write_scratch;
 use crate::bytecode::hir::executor::{ExecutionResult, InstructionHandler};
 use crate::bytecode::hir::ir::{Expr, _Expr};
 use crate::Hir;
@@ -10,14 +11,66 @@ impl InstructionHandler for Sha3 {
     fn handle(&self, mut params: Vec<Expr>, ir: &mut Hir, ctx: &mut Context) -> ExecutionResult {
         let len = params.remove(1);
         let addr = params.remove(0);
+        if ctx.native_mappings() {
+            if let Some(slot) = map_slot(&addr, &len, ctx) {
+                return ExecutionResult::Output(slot);
+            }
+            write_scratch(ir, ctx);
+        }
+        let entry = if ctx.track_mappings() {
+            mapping_entry(&addr, &len, ctx)
+        } else {
+            None
+        };
         let id = ir.assign(
             ctx.loc.wrap(_Expr::Hash(Box::new(addr), Box::new(len))),
             &mut ctx.vars,
         );
+        if let Some((key, slot)) = entry {
+            ctx.set_entry(id, key, slot);
+        }
         ExecutionResult::Output(id.into())
     }
 }
 
+/// Key and slot of the mapping entry if the hashed memory is the scratch space filled by solc.
+fn mapping_entry(addr: &Expr, len: &Expr, ctx: &Context) -> Option<(Expr, U256)> {
+    if !addr.resolve(ctx)?.is_zero() || len.resolve(ctx)? != U256::from(SCRATCH_SIZE) {
+        return None;
+    }
+    let key = ctx.scratch(U256::zero())?.clone();
+    let slot = ctx.scratch(U256::from(32))?.resolve(ctx)?;
+    ctx.layout().mapping_field(slot)?;
+    Some((key, slot))
+}
+
+/// Slot of the mapping entry which is kept by its native keys.
+/// Nested mappings hash the key with the slot of the outer entry.
+fn map_slot(addr: &Expr, len: &Expr, ctx: &Context) -> Option<_Expr> {
+    if !addr.resolve(ctx)?.is_zero() || len.resolve(ctx)? != U256::from(SCRATCH_SIZE) {
+        return None;
+    }
+    let key = ctx.scratch(U256::zero())?;
+    let key = if key.is_var() {
+        key.wrap(_Expr::Copy(Box::new(key.clone())))
+    } else {
+        key.clone()
+    };
+
+    let slot = ctx.scratch(U256::from(32))?;
+    if let _Expr::MapSlot(slot, keys) = slot.as_ref() {
+        let mut keys = keys.clone();
+        keys.push(key);
+        return Some(_Expr::MapSlot(*slot, keys));
+    }
+    let slot = slot.resolve(ctx)?;
+    if ctx.layout().mapping_field(slot).is_some() {
+        // The typed state keeps the entries of this mapping.
+        return None;
+    }
+    Some(_Expr::MapSlot(slot, vec![key]))
+}
+
 pub struct Address;
 
 impl InstructionHandler for Address {
@@ -1,8 +1,10 @@
-use crate::bytecode::hir::context::Context;
+use primitive_types::U256;
+
+use crate::bytecode::hir::context::{Context, SCRATCH_SIZE};
 use crate::bytecode::hir::executor::{ExecutionResult, InstructionHandler};
-use crate::bytecode::hir::ir::{Expr, _Expr};
+use crate::bytecode::hir::ir::{_Expr, Expr};
 
-use crate::Hir;
+use crate::{Hir, OpCode};
 
 #[derive(Debug, Clone)]
 pub enum MemoryOp {
@@ -16,19 +18,38 @@ impl InstructionHandler for MemoryOp {
     fn handle(&self, mut params: Vec<Expr>, ir: &mut Hir, ctx: &mut Context) -> ExecutionResult {
         match self {
             MemoryOp::MLoad => {
-                let addr = Box::new(params.remove(0));
+                let addr = params.remove(0);
+                if let Some(word) = addr.resolve(ctx).and_then(|addr| ctx.code_word(addr)) {
+                    return ExecutionResult::Output(word.into());
+                }
+                let addr = Box::new(addr);
                 let id = ir.assign(ctx.loc.wrap(_Expr::MLoad(addr)), &mut ctx.vars);
                 ExecutionResult::Output(id.into())
             }
             MemoryOp::MStore => {
                 let val = params.remove(1);
                 let addr = params.remove(0);
-                ir.mstore(&ctx.loc, addr, val);
+                ctx.write_memory(&addr, Some(U256::from(32)));
+                if let Some(word) = val.resolve(ctx) {
+                    ctx.track_selector(word);
+                }
+                let val = if ctx.track_mappings() {
+                    track_scratch(&addr, val, ir, ctx)
+                } else {
+                    Some(val)
+                };
+                if let Some(val) = val {
+                    ir.mstore(&ctx.loc, addr, val);
+                }
                 ExecutionResult::None
             }
             MemoryOp::MStore8 => {
                 let val = params.remove(1);
                 let addr = params.remove(0);
+                ctx.write_memory(&addr, Some(U256::one()));
+                if ctx.track_mappings() && !above_scratch(&addr, ctx) {
+                    ctx.clear_scratch();
+                }
                 ir.mstore8(&ctx.loc, addr, val);
                 ExecutionResult::None
             }
@@ -36,3 +57,83 @@ impl InstructionHandler for MemoryOp {
         }
     }
 }
+
+/// Remembers the words which solc writes to the scratch space to hash a mapping key.
+/// Non-constant words are kept in temporary variables so that the key can be used after the hashing.
+/// Returns the value to write or `None` if the write is deferred.
+fn track_scratch(addr: &Expr, val: Expr, ir: &mut Hir, ctx: &mut Context) -> Option<Expr> {
+    let offset = match addr.resolve(ctx) {
+        Some(offset) if offset.is_zero() || offset == U256::from(32) => offset,
+        _ => {
+            if !above_scratch(addr, ctx) {
+                write_scratch(ir, ctx);
+                ctx.clear_scratch();
+            }
+            return Some(val);
+        }
+    };
+
+    let val = if let Some(word) = val.resolve(ctx) {
+        ctx.set_scratch(offset, ctx.loc.wrap(_Expr::Val(word)));
+        val
+    } else if val.is_map_slot() {
+        ctx.set_scratch(offset, val.clone());
+        val
+    } else {
+        let var = ir.assign(val, &mut ctx.vars);
+        ctx.set_scratch(offset, ctx.loc.wrap(_Expr::Var(var)));
+        ctx.loc
+            .wrap(_Expr::Copy(Box::new(ctx.loc.wrap(_Expr::Var(var)))))
+    };
+
+    if ctx.native_mappings() {
+        ctx.defer_scratch(offset);
+        None
+    } else {
+        Some(val)
+    }
+}
+
+/// Writes the deferred scratch words to the memory.
+pub fn write_scratch(ir: &mut Hir, ctx: &mut Context) {
+    for (offset, word) in ctx.take_pending_scratch() {
+        let val = if word.is_var() {
+            word.wrap(_Expr::Copy(Box::new(word.clone())))
+        } else {
+            word
+        };
+        ir.mstore(&ctx.loc, ctx.loc.wrap(_Expr::Val(offset)), val);
+    }
+}
+
+/// Checks whether the instruction may observe the deferred scratch words.
+/// `MStore` and `Sha3` write them out by themselves.
+pub fn reads_scratch(code: &OpCode, args: &[Expr], ctx: &Context) -> bool {
+    match code {
+        OpCode::MLoad | OpCode::MStore8 | OpCode::CodeCopy => !above_scratch(&args[0], ctx),
+        OpCode::Return | OpCode::Log(_) => {
+            let is_empty = args[1]
+                .resolve(ctx)
+                .map(|len| len.is_zero())
+                .unwrap_or(false);
+            !is_empty && !above_scratch(&args[0], ctx)
+        }
+        OpCode::MSize
+        | OpCode::ExtCodeCopy
+        | OpCode::ReturnDataCopy
+        | OpCode::Call
+        | OpCode::CallCode
+        | OpCode::DelegateCall
+        | OpCode::StaticCall
+        | OpCode::Create
+        | OpCode::Create2 => true,
+        _ => false,
+    }
+}
+
+/// Checks whether the memory write at `addr` can't touch the scratch space.
+pub fn above_scratch(addr: &Expr, ctx: &Context) -> bool {
+    addr.resolve(ctx)
+        .map(|addr| addr >= U256::from(SCRATCH_SIZE))
+        .unwrap_or(false)
+}
@@ -71,7 +71,7 @@ impl InstructionHandler for Instruction {
             OpCode::JumpDest => NoOp.handle(params, ir, context),
 
             OpCode::Balance => TxMeta::Balance.handle(params, ir, context),
-            OpCode::Origin => TxMeta::Balance.handle(params, ir, context),
+            OpCode::Origin => TxMeta::Origin.handle(params, ir, context),
             OpCode::Caller => TxMeta::Caller.handle(params, ir, context),
             OpCode::CallValue => TxMeta::CallValue.handle(params, ir, context),
             OpCode::CallDataLoad => TxMeta::CallDataLoad.handle(params, ir, context),
@@ -1,6 +1,6 @@
 use crate::bytecode::hir::context::Context;
 use crate::bytecode::hir::executor::{ExecutionResult, InstructionHandler};
-use crate::bytecode::hir::ir::{Expr, _Expr};
+use crate::bytecode::hir::ir::{_Expr, Expr};
 
 use crate::Hir;
 
@@ -13,16 +13,93 @@ impl InstructionHandler for StorageOp {
     fn handle(&self, mut params: Vec<Expr>, ir: &mut Hir, ctx: &mut Context) -> ExecutionResult {
         match self {
             StorageOp::SLoad => {
-                let addr = Box::new(params.remove(0));
-                let id = ir.assign(ctx.loc.wrap(_Expr::SLoad(addr)), &mut ctx.vars);
+                let addr = params.remove(0);
+                if let _Expr::MapSlot(slot, keys) = addr.as_ref() {
+                    let expr = ctx.loc.wrap(_Expr::MapLoad(*slot, keys.clone()));
+                    let id = ir.assign(expr, &mut ctx.vars);
+                    return ExecutionResult::Output(id.into());
+                }
+                let expr = match typed_slot(&addr, ctx) {
+                    Some(Slot::Field(field)) => _Expr::FieldLoad(field),
+                    Some(Slot::Entry(field, key)) => {
+                        _Expr::EntryLoad(field, Box::new(key), Box::new(addr))
+                    }
+                    None => {
+                        check_untyped(&addr, ir, ctx);
+                        if is_hashed(&addr, ctx) {
+                            _Expr::HashedSLoad(Box::new(addr))
+                        } else {
+                            _Expr::SLoad(Box::new(addr))
+                        }
+                    }
+                };
+                let id = ir.assign(ctx.loc.wrap(expr), &mut ctx.vars);
                 ExecutionResult::Output(id.into())
             }
             StorageOp::SStore => {
                 let val = params.remove(1);
                 let addr = params.remove(0);
-                ir.sstore(&ctx.loc, addr, val);
+                if let _Expr::MapSlot(slot, keys) = addr.as_ref() {
+                    ir.map_store(&ctx.loc, *slot, keys.clone(), val);
+                    return ExecutionResult::None;
+                }
+                match typed_slot(&addr, ctx) {
+                    Some(Slot::Field(field)) => ir.field_store(&ctx.loc, field, val),
+                    Some(Slot::Entry(field, key)) => ir.entry_store(&ctx.loc, field, key, val),
+                    None => {
+                        check_untyped(&addr, ir, ctx);
+                        if is_hashed(&addr, ctx) {
+                            ir.hashed_sstore(&ctx.loc, addr, val)
+                        } else {
+                            ir.sstore(&ctx.loc, addr, val)
+                        }
+                    }
+                }
                 ExecutionResult::None
             }
         }
     }
 }
+
+/// Slot of the typed state.
+enum Slot {
+    Field(usize),
+    /// Mapping field and the entry key.
+    Entry(usize, Expr),
+}
+
+fn typed_slot(addr: &Expr, ctx: &Context) -> Option<Slot> {
+    let layout = ctx.layout();
+    if layout.is_empty() {
+        return None;
+    }
+    if let Some(slot) = addr.resolve(ctx) {
+        return layout.value_field(slot).map(Slot::Field);
+    }
+
+    let (key, slot) = ctx.entry(addr)?;
+    let field = layout.mapping_field(*slot)?;
+    let key = if key.is_var() {
+        key.wrap(_Expr::Copy(Box::new(key.clone())))
+    } else {
+        key.clone()
+    };
+    Some(Slot::Entry(field, key))
+}
+
+/// Marks the raw access by a computed slot if the layout has typed mappings.
+/// Such a slot may be equal to the raw slot of a typed entry, which is kept only in its field.
+fn check_untyped(addr: &Expr, ir: &mut Hir, ctx: &Context) {
+    if ctx.layout().has_mappings() && addr.resolve(ctx).is_none() {
+        ir.untyped_slot();
+    }
+}
+
+/// Checks whether the slot may address a mapping entry kept by its native keys.
+///
+/// Only the constant slots are known to be raw. A slot computed at runtime (including a keccak256
+/// result passed from another block) may be equal to the raw slot of an entry, so it is looked up
+/// among the entries first.
+fn is_hashed(addr: &Expr, ctx: &Context) -> bool {
+    ctx.native_mappings() && addr.resolve(ctx).is_none()
+}
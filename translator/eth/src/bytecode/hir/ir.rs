@@ -5,6 +5,7 @@ use crate::bytecode::hir::vars::Vars;
 use crate::bytecode::loc::Loc;
 use crate::Offset;
 use anyhow::Error;
+use move_core_types::language_storage::ModuleId;
 use primitive_types::U256;
 use std::collections::BTreeMap;
 use std::fmt::{Debug, Display, Formatter, Write};
@@ -13,6 +14,8 @@ use std::fmt::{Debug, Display, Formatter, Write};
 pub struct Hir {
     statement: Vec<Loc<Stmt>>,
     labels: BTreeMap<Label, usize>,
+    /// The raw storage is accessed by a computed slot which may be an entry of a typed mapping.
+    untyped_slots: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -33,6 +36,26 @@ pub enum Stmt {
         key: Expr,
         val: Expr,
     },
+    /// Store to the keccak256 slot which may be the raw slot of a mapping entry kept by its native keys.
+    HashedSStore {
+        key: Expr,
+        val: Expr,
+    },
+    FieldStore {
+        field: usize,
+        val: Expr,
+    },
+    EntryStore {
+        field: usize,
+        key: Expr,
+        val: Expr,
+    },
+    /// Entry of the mapping at `slot` addressed by the keys of the nested mappings.
+    MapStore {
+        slot: U256,
+        keys: Vec<Expr>,
+        val: Expr,
+    },
     Log {
         offset: Expr,
         len: Expr,
@@ -46,6 +69,24 @@ pub enum Stmt {
     },
     BrunchTrue(Expr, Label),
     Brunch(Label),
+    /// Call of the function of the linked library with the arguments in the memory.
+    /// The arguments follow the selector of the function.
+    Call {
+        module: ModuleId,
+        function: String,
+        offset: Expr,
+        len: Expr,
+    },
+    /// Writes at most `len` bytes of the data returned by the last call to the memory.
+    WriteResult {
+        dest: Expr,
+        len: Expr,
+    },
+    ReturnDataCopy {
+        dest: Expr,
+        offset: Expr,
+        len: Expr,
+    },
 }
 
 pub type Expr = Loc<_Expr>;
@@ -56,6 +97,16 @@ pub enum _Expr {
     Var(VarId),
     MLoad(Box<Expr>),
     SLoad(Box<Expr>),
+    /// Load of the keccak256 slot, see [`Stmt::HashedSStore`].
+    HashedSLoad(Box<Expr>),
+    /// Value field of the typed state.
+    FieldLoad(usize),
+    /// Entry of the mapping field as (field, key, raw slot).
+    EntryLoad(usize, Box<Expr>, Box<Expr>),
+    /// Raw slot of the mapping entry as (mapping slot, keys of the nested mappings).
+    MapSlot(U256, Vec<Expr>),
+    /// Mapping entry addressed by its native keys as (mapping slot, keys of the nested mappings).
+    MapLoad(U256, Vec<Expr>),
     Signer,
     MSize,
     ArgsSize,
@@ -74,6 +125,7 @@ pub enum _Expr {
     BlockHash(Box<Expr>),
     BlockCoinbase,
     BlockDifficulty,
+    ReturnDataSize,
 }
 
 impl Expr {
@@ -84,6 +136,7 @@ impl Expr {
                 expr.unvar(ctx)
             }
             _Expr::Val(_)
+            | _Expr::FieldLoad(_)
             | _Expr::Signer
             | _Expr::MSize
             | _Expr::ArgsSize
@@ -93,7 +146,8 @@ impl Expr {
             | _Expr::BlockHeight
             | _Expr::BlockTimestamp
             | _Expr::BlockCoinbase
-            | _Expr::BlockDifficulty => self.clone(),
+            | _Expr::BlockDifficulty
+            | _Expr::ReturnDataSize => self.clone(),
             _Expr::MLoad(expr) => {
                 let expr = expr.unvar(ctx);
                 self.wrap(_Expr::MLoad(Box::new(expr)))
@@ -102,6 +156,23 @@ impl Expr {
                 let expr = expr.unvar(ctx);
                 self.wrap(_Expr::SLoad(Box::new(expr)))
             }
+            _Expr::HashedSLoad(expr) => {
+                let expr = expr.unvar(ctx);
+                self.wrap(_Expr::HashedSLoad(Box::new(expr)))
+            }
+            _Expr::EntryLoad(field, key, slot) => {
+                let key = key.unvar(ctx);
+                let slot = slot.unvar(ctx);
+                self.wrap(_Expr::EntryLoad(*field, Box::new(key), Box::new(slot)))
+            }
+            _Expr::MapSlot(slot, keys) => {
+                let keys = keys.iter().map(|key| key.unvar(ctx)).collect();
+                self.wrap(_Expr::MapSlot(*slot, keys))
+            }
+            _Expr::MapLoad(slot, keys) => {
+                let keys = keys.iter().map(|key| key.unvar(ctx)).collect();
+                self.wrap(_Expr::MapLoad(*slot, keys))
+            }
             _Expr::Args(expr) => {
                 let expr = expr.unvar(ctx);
                 self.wrap(_Expr::Args(Box::new(expr)))
@@ -157,6 +228,11 @@ impl _Expr {
             }
             _Expr::MLoad(_) => None,
             _Expr::SLoad(_) => None,
+            _Expr::HashedSLoad(_) => None,
+            _Expr::FieldLoad(_) => None,
+            _Expr::EntryLoad(_, _, _) => None,
+            _Expr::MapSlot(_, _) => None,
+            _Expr::MapLoad(_, _) => None,
             _Expr::Signer => None,
             _Expr::MSize => None,
             _Expr::ArgsSize => None,
@@ -188,6 +264,7 @@ impl _Expr {
             _Expr::BlockHash(_) => None,
             _Expr::BlockCoinbase => None,
             _Expr::BlockDifficulty => None,
+            _Expr::ReturnDataSize => None,
         }
     }
 
@@ -195,6 +272,20 @@ impl _Expr {
         matches!(self, _Expr::Var(_))
     }
 
+    pub fn is_map_slot(&self) -> bool {
+        matches!(self, _Expr::MapSlot(_, _))
+    }
+
+    /// Variables which hold the keys of the mapping slot.
+    pub fn slot_vars(&self) -> Vec<VarId> {
+        match self {
+            _Expr::Var(var) => vec![*var],
+            _Expr::Copy(expr) => expr.slot_vars(),
+            _Expr::MapSlot(_, keys) => keys.iter().flat_map(|key| key.slot_vars()).collect(),
+            _ => vec![],
+        }
+    }
+
     pub fn as_val(&self) -> Option<U256> {
         match self {
             _Expr::Val(val) => Some(*val),
@@ -241,6 +332,11 @@ impl Hir {
         var
     }
 
+    /// Assigns the expression to the existing variable.
+    pub fn set_var(&mut self, loc: &Loc<()>, var: VarId, expr: Expr) {
+        self.statement.push(loc.wrap(Stmt::Assign(var, expr)));
+    }
+
     pub fn abort(&mut self, loc: &Loc<()>, code: u8) {
         self.statement.push(loc.wrap(Stmt::Abort(code)));
     }
@@ -271,6 +367,32 @@ impl Hir {
         self.statement.push(loc.wrap(Stmt::CodeCopy(dest, code)));
     }
 
+    pub fn call(
+        &mut self,
+        loc: &Loc<()>,
+        module: ModuleId,
+        function: String,
+        offset: Expr,
+        len: Expr,
+    ) {
+        self.statement.push(loc.wrap(Stmt::Call {
+            module,
+            function,
+            offset,
+            len,
+        }));
+    }
+
+    pub fn write_result(&mut self, loc: &Loc<()>, dest: Expr, len: Expr) {
+        self.statement
+            .push(loc.wrap(Stmt::WriteResult { dest, len }));
+    }
+
+    pub fn return_data_copy(&mut self, loc: &Loc<()>, dest: Expr, offset: Expr, len: Expr) {
+        self.statement
+            .push(loc.wrap(Stmt::ReturnDataCopy { dest, offset, len }));
+    }
+
     pub fn save_stack(&mut self, loc: &Loc<()>, context: BTreeMap<VarId, Expr>) {
         self.statement.push(loc.wrap(Stmt::StoreStack(context)));
     }
@@ -282,6 +404,35 @@ impl Hir {
         }));
     }
 
+    pub fn hashed_sstore(&mut self, loc: &Loc<()>, key: Expr, val: Expr) {
+        self.statement
+            .push(loc.wrap(Stmt::HashedSStore { key, val }));
+    }
+
+    pub fn field_store(&mut self, loc: &Loc<()>, field: usize, val: Expr) {
+        self.statement
+            .push(loc.wrap(Stmt::FieldStore { field, val }));
+    }
+
+    /// Marks the raw access by a computed slot which isn't matched as an entry of a typed mapping.
+    pub fn untyped_slot(&mut self) {
+        self.untyped_slots = true;
+    }
+
+    pub fn has_untyped_slots(&self) -> bool {
+        self.untyped_slots
+    }
+
+    pub fn entry_store(&mut self, loc: &Loc<()>, field: usize, key: Expr, val: Expr) {
+        self.statement
+            .push(loc.wrap(Stmt::EntryStore { field, key, val }));
+    }
+
+    pub fn map_store(&mut self, loc: &Loc<()>, slot: U256, keys: Vec<Expr>, val: Expr) {
+        self.statement
+            .push(loc.wrap(Stmt::MapStore { slot, keys, val }));
+    }
+
     pub fn true_brunch(&mut self, loc: &Loc<()>, cnd: Expr, label: Label) {
         self.statement.push(loc.wrap(Stmt::BrunchTrue(cnd, label)));
     }
@@ -307,6 +458,19 @@ impl Hir {
         self.statement.push(loc.wrap(Stmt::Brunch(label)));
     }
 
+    /// Appends the statements of another HIR.
+    pub fn append(&mut self, other: Hir) {
+        let offset = self.statement.len();
+        self.labels.extend(
+            other
+                .labels
+                .into_iter()
+                .map(|(label, idx)| (label, idx + offset)),
+        );
+        self.statement.extend(other.statement);
+        self.untyped_slots |= other.untyped_slots;
+    }
+
     pub fn statements(&self) -> &[Loc<Stmt>] {
         &self.statement
     }
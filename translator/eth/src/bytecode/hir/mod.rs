@@ -1,10 +1,15 @@
 use crate::bytecode::block::InstructionBlock;
 use crate::bytecode::hir::context::Context;
+use crate::bytecode::hir::executor::call::call_library;
+use crate::bytecode::hir::executor::memory::{reads_scratch, write_scratch};
 use crate::bytecode::hir::executor::{ExecutionResult, InstructionHandler};
 use crate::bytecode::hir::ir::{Expr, Label, VarId, _Expr};
 use crate::bytecode::hir::vars::Vars;
 
+use crate::bytecode::pre_processing::jump_table::jump_table_targets;
 use crate::bytecode::tracing::tracer::{FlowTrace, Tracer};
+use crate::layout::Layout;
+use crate::link::LibraryLink;
 use crate::{Flags, Function, Hir, Offset, OpCode};
 use anyhow::{anyhow, ensure, Context as ErrorContext, Error};
 use primitive_types::U256;
@@ -17,11 +22,20 @@ pub mod ir;
 pub mod stack;
 pub mod vars;
 
+/// Builds the HIR of the contract functions.
+pub trait HirTranslator {
+    fn translate_fun(&self, fun: &Function, contract_address: U256) -> Result<Hir, Error>;
+
+    fn layout(&self) -> &Layout;
+}
+
 pub struct HirBuilder {
     contract: HashMap<Offset, InstructionBlock>,
     flags: Flags,
     flow: FlowTrace,
     contract_code: Vec<u8>,
+    layout: Layout,
+    libraries: Vec<LibraryLink>,
 }
 
 impl HirBuilder {
@@ -29,23 +43,22 @@ impl HirBuilder {
         contract: HashMap<Offset, InstructionBlock>,
         flags: Flags,
         contract_code: Vec<u8>,
+        layout: Layout,
+        libraries: Vec<LibraryLink>,
     ) -> Result<Self, Error> {
-        let flow = Tracer::new(&contract).trace()?;
+        let flow = Tracer::new(&contract)
+            .with_jump_targets(jump_table_targets(&contract, &contract_code))
+            .trace()?;
         Ok(Self {
             contract,
             flags,
             flow,
             contract_code,
+            layout,
+            libraries,
         })
     }
 
-    pub fn translate_fun(&self, fun: &Function, contract_address: U256) -> Result<Hir, Error> {
-        let mut ctx = Context::new(fun, contract_address, self.flags, &self.contract_code);
-        let mut ir = Hir::default();
-        self.translate_blocks(Offset::default(), &mut ir, &mut ctx)?;
-        Ok(ir)
-    }
-
     fn translate_blocks(
         &self,
         start: Offset,
@@ -58,7 +71,10 @@ impl HirBuilder {
             match self.translate_block(block, ir, ctx)? {
                 BlockResult::Jmp(block) => {
                     if self.flow.loops.contains_key(&block) {
-                        self.flush_context(ctx, ir);
+                        write_scratch(ir, ctx);
+                        self.flush_context(ctx, ir, false);
+                        // Memory and stack values are unknown at the loop head.
+                        ctx.reset_tracking();
                         let (from, new_loop) = ctx.create_loop(block, block_id);
                         let label = Label::new(block).from(from);
                         if new_loop {
@@ -79,7 +95,7 @@ impl HirBuilder {
                 } => {
                     let jmp_id = ctx.next_jmp_id();
                     let cnd = ir.assign(cnd, &mut ctx.vars);
-                    self.flush_context(ctx, ir);
+                    self.flush_context(ctx, ir, true);
                     ir.true_brunch(
                         &ctx.loc,
                         ctx.loc.wrap(_Expr::Var(cnd)),
@@ -87,10 +103,12 @@ impl HirBuilder {
                     );
                     let stack = ctx.stack.clone();
                     let vars = ctx.vars.clone();
+                    let tracking = ctx.tracking();
                     self.translate_blocks(false_br, ir, ctx)?;
                     ir.label(&ctx.loc, Label::new(true_br).from(jmp_id));
                     ctx.stack = stack;
                     ctx.vars = vars;
+                    ctx.restore_tracking(tracking);
                     self.translate_blocks(true_br, ir, ctx)?;
                     return Ok(());
                 }
@@ -101,12 +119,51 @@ impl HirBuilder {
         }
     }
 
-    fn flush_context(&self, ctx: &mut Context, ir: &mut Hir) {
+    /// Library which is called or checked by the instruction.
+    fn called_library(&self, op: &OpCode, args: &[Expr], ctx: &Context) -> Option<&LibraryLink> {
+        let address = match op {
+            OpCode::Call | OpCode::CallCode | OpCode::DelegateCall | OpCode::StaticCall => {
+                args.get(1)?
+            }
+            OpCode::ExtCodeSize => args.first()?,
+            _ => return None,
+        }
+        .resolve(ctx)?;
+        self.libraries
+            .iter()
+            .find(|library| library.encoded_address() == address)
+    }
+
+    /// Saves the stack to the variables.
+    /// Mapping slots are pure, so they may be kept on the stack unless the flush precedes a loop.
+    fn flush_context(&self, ctx: &mut Context, ir: &mut Hir, keep_slots: bool) {
         let stack = ctx.stack.take();
         let mut stack_dump = BTreeMap::new();
         let last_idx = stack.len() - 1;
         let mut vars = Vars::default();
+        // The tracked mapping keys outlive the flush.
+        let kept_vars = stack
+            .iter()
+            .filter(|var| keep_slots && var.is_map_slot())
+            .flat_map(|var| var.slot_vars());
+        for var in ctx.tracked_vars().into_iter().chain(kept_vars) {
+            if let Some(expr) = ctx.vars.get(&var) {
+                vars.set(var, expr.unvar(ctx));
+            }
+        }
+        let stack_entries = stack
+            .iter()
+            .enumerate()
+            .filter_map(|(i, var)| {
+                let entry = ctx.entry(var)?.clone();
+                Some((VarId::new_var((last_idx - i) as u32), entry))
+            })
+            .collect();
         for (i, var) in stack.into_iter().enumerate() {
+            if keep_slots && var.is_map_slot() {
+                ctx.stack.push(var);
+                continue;
+            }
             let var_id = VarId::new_var((last_idx - i) as u32);
 
             let unvaried = var.unvar(ctx);
@@ -123,6 +180,7 @@ impl HirBuilder {
             ctx.stack.push(ctx.loc.wrap(_Expr::Var(var_id)));
         }
         ctx.vars = vars;
+        ctx.remap_entries(stack_entries);
         ir.save_stack(&ctx.loc, stack_dump);
     }
 
@@ -146,6 +204,15 @@ impl HirBuilder {
 
             let args = ctx.stack.pop_vec(pops);
             ensure!(pops == args.len(), "Invalid stake state.");
+            if ctx.has_pending_scratch() && reads_scratch(&inst.1, &args, ctx) {
+                write_scratch(ir, ctx);
+            }
+            if let Some(library) = self.called_library(&inst.1, &args, ctx) {
+                let output = call_library(library, &inst.1, args, ir, ctx)
+                    .with_context(|| format!("call of the library {}", library.module))?;
+                ctx.stack.push(ctx.loc.wrap(output));
+                continue;
+            }
             let result = inst.handle(args, ir, ctx);
 
             match result {
@@ -188,6 +255,10 @@ impl HirBuilder {
             .stack
             .get_mut(pops)
             .ok_or_else(|| anyhow!("Invalid stack state. "))?;
+        if src.is_map_slot() {
+            ctx.stack.dup(pops);
+            return Ok(());
+        }
         if !src.is_var() {
             let var = ir.assign(src.clone(), &mut ctx.vars);
             *src = ctx.loc.wrap(_Expr::Var(var));
@@ -203,6 +274,25 @@ impl HirBuilder {
     }
 }
 
+impl HirTranslator for HirBuilder {
+    fn translate_fun(&self, fun: &Function, contract_address: U256) -> Result<Hir, Error> {
+        let mut ctx = Context::new(
+            fun,
+            contract_address,
+            self.flags,
+            &self.contract_code,
+            &self.layout,
+        );
+        let mut ir = Hir::default();
+        self.translate_blocks(Offset::default(), &mut ir, &mut ctx)?;
+        Ok(ir)
+    }
+
+    fn layout(&self) -> &Layout {
+        &self.layout
+    }
+}
+
 pub enum BlockResult {
     Jmp(Offset),
     CndJmp {
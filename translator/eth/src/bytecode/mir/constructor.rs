@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use crate::bytecode::loc::Loc;
 use primitive_types::U256;
@@ -7,9 +7,13 @@ use crate::bytecode::mir::ir::expression::Expression;
 use crate::bytecode::mir::ir::statement::Statement;
 use crate::bytecode::mir::ir::types::{SType, Value};
 use crate::bytecode::mir::translation::variables::Variables;
+use crate::layout::Layout;
 use crate::Mir;
 
-pub fn make_constructor(store: HashMap<U256, U256>) -> Mir {
+/// Constructor which puts the storage of the static initialization.
+/// Slots of the typed state variables are put into their fields, the other slots stay raw.
+/// The layout has no typed mappings if the static initialization writes their entries.
+pub fn make_constructor(store: BTreeMap<U256, U256>, layout: &Layout) -> Mir {
     let mut mir = Mir::default();
     let mut variables = Variables::new(vec![SType::Signer]);
     let loc: Loc<()> = Loc::default();
@@ -20,12 +24,45 @@ pub fn make_constructor(store: HashMap<U256, U256>) -> Mir {
         loc.wrap(Expression::GetStore.ty(SType::Storage)),
     )));
 
+    let state_var = if layout.is_empty() {
+        None
+    } else {
+        mir.push(loc.wrap(Statement::InitState(variables.borrow_param(0))));
+        let state_var = variables.borrow(SType::State);
+        mir.push(loc.wrap(Statement::Assign(
+            state_var,
+            loc.wrap(Expression::GetState.ty(SType::State)),
+        )));
+        Some(state_var)
+    };
+
+    let num = |val: U256| loc.wrap(Expression::Const(Value::from(val)).ty(SType::Num));
     for (key, value) in store {
-        mir.push(loc.wrap(Statement::SStore {
-            storage: store_var,
-            key: loc.wrap(Expression::Const(Value::from(key)).ty(SType::Num)),
-            val: loc.wrap(Expression::Const(Value::from(value)).ty(SType::Num)),
-        }));
+        let state = match state_var {
+            Some(state) => state,
+            None => {
+                mir.push(loc.wrap(Statement::SStore {
+                    storage: store_var,
+                    key: num(key),
+                    val: num(value),
+                }));
+                continue;
+            }
+        };
+
+        if let Some(field) = layout.value_field(key) {
+            mir.push(loc.wrap(Statement::SetField {
+                state,
+                field,
+                val: num(value),
+            }));
+        } else {
+            mir.push(loc.wrap(Statement::SStore {
+                storage: store_var,
+                key: num(key),
+                val: num(value),
+            }));
+        }
     }
     mir.push(loc.wrap(Statement::Result(vec![])));
     mir.set_locals(variables.locals());
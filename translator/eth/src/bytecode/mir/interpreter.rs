@@ -0,0 +1,802 @@
+//! Interpreter of the MIR.
+//!
+//! The intrinsics of the translated module are modeled on `U256` with the semantics of their Move
+//! implementation, so a function gives the same result here and in the Move VM unless the Move
+//! code generation is wrong. Mapping entries and the raw slots share the storage as in the EVM.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+
+use anyhow::{anyhow, bail, ensure, Error};
+use primitive_types::U256;
+use sha3::{Digest, Keccak256};
+
+use crate::bytecode::hir::executor::math::{BinaryOp, UnaryOp};
+use crate::bytecode::hir::ir::Label;
+use crate::bytecode::loc::Loc;
+use crate::bytecode::mir::ir::expression::{Cast, Expression, TypedExpr};
+use crate::bytecode::mir::ir::statement::Statement;
+use crate::bytecode::mir::ir::types::{SType, Value};
+use crate::bytecode::mir::translation::variables::Variable;
+use crate::layout::{entry_slot, Layout};
+use crate::Mir;
+
+/// Abort code of the memory access beyond the limit.
+pub const OUT_OF_MEMORY: u64 = 2;
+/// Abort code of the `U256` which doesn't fit into `u128`.
+pub const EWORDS_OVERFLOW: u64 = 1;
+/// Abort code of the copy out of the returned data.
+pub const INVALID_RANGE: u64 = 3;
+/// Number of the statements after which the execution is stopped.
+const MAX_STEPS: usize = 10_000_000;
+
+/// Runtime value of a variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Val {
+    Num(U256),
+    RawNum(u128),
+    Bool(bool),
+    /// 32 bytes of the account address.
+    Signer(U256),
+    Address(U256),
+    Bytes(Vec<u8>),
+    Storage,
+    State,
+    Memory,
+}
+
+impl Val {
+    fn num(&self) -> Result<U256, Error> {
+        match self {
+            Val::Num(val) => Ok(*val),
+            _ => Err(anyhow!("Expected a number, found {self:?}")),
+        }
+    }
+
+    fn bool(&self) -> Result<bool, Error> {
+        match self {
+            Val::Bool(val) => Ok(*val),
+            _ => Err(anyhow!("Expected a bool, found {self:?}")),
+        }
+    }
+
+    fn bytes(&self) -> Result<&[u8], Error> {
+        match self {
+            Val::Bytes(val) => Ok(val),
+            _ => Err(anyhow!("Expected bytes, found {self:?}")),
+        }
+    }
+
+    /// Value of the address-like types as the key of the typed state or of the mapping.
+    fn key(&self) -> Result<U256, Error> {
+        match self {
+            Val::Num(val) | Val::Address(val) => Ok(*val),
+            _ => Err(anyhow!("Expected a key, found {self:?}")),
+        }
+    }
+}
+
+/// Abort of the translated function with the code of the Move VM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Abort(pub u64);
+
+impl Display for Abort {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "aborted with {}", self.0)
+    }
+}
+
+impl std::error::Error for Abort {}
+
+/// Event emitted by the function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Log {
+    pub topics: Vec<U256>,
+    pub data: Vec<u8>,
+}
+
+/// Persistent state of the module.
+#[derive(Debug, Clone, Default)]
+pub struct State {
+    /// Raw slots and the mapping entries. The zero slots are omitted.
+    pub storage: BTreeMap<U256, U256>,
+    /// Value fields of the typed state.
+    pub fields: BTreeMap<usize, U256>,
+    /// Mapping fields of the typed state by the field and the key.
+    pub entries: BTreeMap<(usize, U256), U256>,
+}
+
+impl State {
+    /// The whole state as the EVM slots. The typed fields are placed at their slots of the
+    /// `layout` and override the raw slots. The zero slots are omitted.
+    pub fn slots(&self, layout: &Layout) -> BTreeMap<U256, U256> {
+        let mut slots = self.storage.clone();
+        let fields = self
+            .fields
+            .iter()
+            .map(|(field, val)| (layout.fields()[*field].slot, *val));
+        let entries = self
+            .entries
+            .iter()
+            .map(|((field, key), val)| (entry_slot(*key, layout.fields()[*field].slot), *val));
+        for (slot, val) in fields.chain(entries) {
+            if val.is_zero() {
+                slots.remove(&slot);
+            } else {
+                slots.insert(slot, val);
+            }
+        }
+        slots
+    }
+}
+
+/// Block and transaction values seen by the function.
+#[derive(Debug, Clone)]
+pub struct Env {
+    pub block_height: U256,
+    pub block_timestamp: U256,
+    pub gas_price: U256,
+    pub gas_limit: U256,
+    /// Balances of the accounts by their 32 bytes address.
+    pub balances: BTreeMap<U256, U256>,
+    /// Limit of the memory in bytes.
+    pub max_memory: u64,
+}
+
+impl Default for Env {
+    fn default() -> Self {
+        Env {
+            block_height: U256::zero(),
+            block_timestamp: U256::zero(),
+            gas_price: U256::from(100),
+            gas_limit: U256::from(10_000_000),
+            balances: BTreeMap::new(),
+            max_memory: 32 * 1024,
+        }
+    }
+}
+
+/// Result of a successful call.
+#[derive(Debug, Clone)]
+pub struct Output {
+    pub returns: Vec<Val>,
+    pub logs: Vec<Log>,
+}
+
+/// Runs the function. The `args` are the parameters of the function starting with the signer.
+/// The state is changed only if the function returns. An abort of the function is an error
+/// with the [`Abort`] cause.
+pub fn run(mir: &Mir, args: Vec<Val>, state: &mut State, env: &Env) -> Result<Output, Error> {
+    let mut interpreter = Interpreter::new(mir, args, state.clone(), env)?;
+    let returns = interpreter.run()?;
+    *state = interpreter.state;
+    Ok(Output {
+        returns,
+        logs: interpreter.logs,
+    })
+}
+
+struct Interpreter<'a> {
+    mir: &'a Mir,
+    env: &'a Env,
+    locals: Vec<Option<Val>>,
+    labels: HashMap<Label, usize>,
+    memory: Memory,
+    state: State,
+    logs: Vec<Log>,
+}
+
+impl<'a> Interpreter<'a> {
+    fn new(mir: &'a Mir, args: Vec<Val>, state: State, env: &'a Env) -> Result<Self, Error> {
+        let labels = mir
+            .statements()
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, st)| match st.as_ref() {
+                Statement::Label(label) => Some((*label, idx)),
+                _ => None,
+            })
+            .collect();
+        let mut locals = args.into_iter().map(Some).collect::<Vec<_>>();
+        locals.resize(locals.len() + mir.locals().len(), None);
+        Ok(Interpreter {
+            mir,
+            env,
+            locals,
+            labels,
+            memory: Memory::new(env.max_memory)?,
+            state,
+            logs: vec![],
+        })
+    }
+
+    fn run(&mut self) -> Result<Vec<Val>, Error> {
+        let mir = self.mir;
+        let statements = mir.statements();
+        let mut pc = 0;
+        for _ in 0..MAX_STEPS {
+            let st = statements
+                .get(pc)
+                .ok_or_else(|| anyhow!("The function has no result"))?;
+            pc += 1;
+            match st.as_ref() {
+                Statement::InitStorage(_) | Statement::InitState(_) => {}
+                Statement::StoreStack(ctx) => {
+                    let vals = ctx
+                        .iter()
+                        .map(|(var, expr)| Ok((*var, self.expr(expr)?)))
+                        .collect::<Result<Vec<_>, Error>>()?;
+                    for (var, val) in vals {
+                        self.set(var, val)?;
+                    }
+                }
+                Statement::Assign(var, expr) => {
+                    let val = self.expr(expr)?;
+                    self.set(*var, val)?;
+                }
+                Statement::MStore { offset, val, .. } => {
+                    let offset = self.num(offset)?;
+                    let val = self.num(val)?;
+                    self.memory.store(offset, val)?;
+                }
+                Statement::MStore8 { offset, val, .. } => {
+                    let offset = self.num(offset)?;
+                    let val = self.num(val)?;
+                    self.memory.store8(offset, val)?;
+                }
+                Statement::CodeCopy { dest, data, .. } => {
+                    let dest = self.num(dest)?;
+                    self.memory.code_copy(dest, data)?;
+                }
+                Statement::Call {
+                    module, function, ..
+                } => bail!("The call of {module}::{function} is not interpreted"),
+                Statement::WriteResult {
+                    dest, data, len, ..
+                } => {
+                    let dest = self.num(dest)?;
+                    let len = self.num(len)?.low_u64();
+                    let data = self.get(*data)?.bytes()?.to_vec();
+                    let len = data.len().min(len as usize);
+                    self.memory.copy_data(dest, &data[..len])?;
+                }
+                Statement::ReturnDataCopy {
+                    dest,
+                    data,
+                    offset,
+                    len,
+                    ..
+                } => {
+                    let dest = self.num(dest)?;
+                    let offset = self.num(offset)?.low_u64() as usize;
+                    let len = self.num(len)?.low_u64() as usize;
+                    if len != 0 {
+                        let data = self.get(*data)?;
+                        let data = data.bytes()?;
+                        let data = offset
+                            .checked_add(len)
+                            .and_then(|end| data.get(offset..end))
+                            .ok_or(Abort(INVALID_RANGE))?
+                            .to_vec();
+                        self.memory.copy_data(dest, &data)?;
+                    }
+                }
+                Statement::SStore { key, val, .. } | Statement::HashedSStore { key, val, .. } => {
+                    let key = self.num(key)?;
+                    let val = self.num(val)?;
+                    self.store(key, val);
+                }
+                Statement::SetField { field, val, .. } => {
+                    let val = self.num(val)?;
+                    self.state.fields.insert(*field, val);
+                }
+                Statement::SetEntry {
+                    field, key, val, ..
+                } => {
+                    let key = self.expr(key)?.key()?;
+                    let val = self.num(val)?;
+                    self.state.entries.insert((*field, key), val);
+                }
+                Statement::MapStore {
+                    slot, keys, val, ..
+                } => {
+                    let slot = self.map_slot(slot, keys)?;
+                    let val = self.num(val)?;
+                    self.store(slot, val);
+                }
+                Statement::Abort(code) => return Err(Abort(u64::from(*code)).into()),
+                Statement::Result(vars) => {
+                    return vars.iter().map(|var| self.get(*var)).collect();
+                }
+                Statement::Log {
+                    offset,
+                    len,
+                    topics,
+                    ..
+                } => {
+                    let offset = self.num(offset)?;
+                    let len = self.num(len)?;
+                    let topics = topics
+                        .iter()
+                        .map(|topic| self.num(topic))
+                        .collect::<Result<_, _>>()?;
+                    let data = self.memory.slice(offset, len)?;
+                    self.logs.push(Log { topics, data });
+                }
+                Statement::Label(_) => {}
+                Statement::BrTrue(cnd, label) => {
+                    if self.expr(cnd)?.bool()? {
+                        pc = self.jump(label)?;
+                    }
+                }
+                Statement::Br(label) => {
+                    pc = self.jump(label)?;
+                }
+            }
+        }
+        bail!("The function exceeded {MAX_STEPS} statements")
+    }
+
+    fn jump(&self, label: &Label) -> Result<usize, Error> {
+        self.labels
+            .get(label)
+            .copied()
+            .ok_or_else(|| anyhow!("Unknown label {label}"))
+    }
+
+    fn get(&self, var: Variable) -> Result<Val, Error> {
+        self.locals
+            .get(var.index() as usize)
+            .cloned()
+            .flatten()
+            .ok_or_else(|| anyhow!("The variable {} is not set", var.index()))
+    }
+
+    fn set(&mut self, var: Variable, val: Val) -> Result<(), Error> {
+        let local = self
+            .locals
+            .get_mut(var.index() as usize)
+            .ok_or_else(|| anyhow!("Unknown variable {}", var.index()))?;
+        *local = Some(val);
+        Ok(())
+    }
+
+    fn load(&self, key: U256) -> U256 {
+        self.state.storage.get(&key).copied().unwrap_or_default()
+    }
+
+    fn store(&mut self, key: U256, val: U256) {
+        if val.is_zero() {
+            self.state.storage.remove(&key);
+        } else {
+            self.state.storage.insert(key, val);
+        }
+    }
+
+    fn map_slot(&mut self, slot: &Loc<TypedExpr>, keys: &[Loc<TypedExpr>]) -> Result<U256, Error> {
+        let mut slot = self.num(slot)?;
+        for key in keys {
+            slot = entry_slot(self.expr(key)?.key()?, slot);
+        }
+        Ok(slot)
+    }
+
+    fn num(&mut self, expr: &Loc<TypedExpr>) -> Result<U256, Error> {
+        self.expr(expr)?.num()
+    }
+
+    fn expr(&mut self, expr: &Loc<TypedExpr>) -> Result<Val, Error> {
+        Ok(match expr.expr.as_ref() {
+            Expression::Const(Value::Number(val)) => Val::Num(*val),
+            Expression::Const(Value::Bool(val)) => Val::Bool(*val),
+            Expression::GetMem => Val::Memory,
+            Expression::GetStore => Val::Storage,
+            Expression::GetState => Val::State,
+            Expression::GetField { field, .. } => {
+                Val::Num(self.state.fields.get(field).copied().unwrap_or_default())
+            }
+            Expression::GetEntry {
+                field, key, slot, ..
+            } => {
+                let key = self.expr(key)?.key()?;
+                let slot = self.num(slot)?;
+                Val::Num(match self.state.entries.get(&(*field, key)) {
+                    Some(val) => *val,
+                    None => self.load(slot),
+                })
+            }
+            Expression::MapSlot { slot, keys } => Val::Num(self.map_slot(slot, keys)?),
+            Expression::MapLoad { slot, keys, .. } => {
+                let slot = self.map_slot(slot, keys)?;
+                Val::Num(self.load(slot))
+            }
+            Expression::MLoad { offset, .. } => {
+                let offset = self.num(offset)?;
+                Val::Num(self.memory.load(offset)?)
+            }
+            Expression::MSlice { offset, len, .. } => {
+                let offset = self.num(offset)?;
+                let len = self.num(len)?;
+                Val::Bytes(self.memory.slice(offset, len)?)
+            }
+            Expression::SLoad { key, .. } | Expression::HashedSLoad { key, .. } => {
+                let key = self.num(key)?;
+                Val::Num(self.load(key))
+            }
+            Expression::MSize { .. } => Val::Num(U256::from(self.memory.effective_len)),
+            Expression::MoveVar(var) | Expression::CopyVar(var) => self.get(*var)?,
+            Expression::Unary(op, arg) => {
+                let arg = self.expr(arg)?;
+                match (op, arg) {
+                    (UnaryOp::Not, Val::Bool(arg)) => Val::Bool(!arg),
+                    (UnaryOp::IsZero, Val::Num(arg)) => Val::Bool(arg.is_zero()),
+                    (op, Val::Num(arg)) => Val::Num(op.calc(arg)),
+                    (op, arg) => bail!("{op:?} of {arg:?}"),
+                }
+            }
+            Expression::Binary(op, arg, arg1) => {
+                let arg = self.expr(arg)?;
+                let arg1 = self.expr(arg1)?;
+                match (arg, arg1) {
+                    (Val::Bool(arg), Val::Bool(arg1)) if *op == BinaryOp::Eq => {
+                        Val::Bool(arg == arg1)
+                    }
+                    (Val::Num(arg), Val::Num(arg1)) => {
+                        let res = op.calc(arg, arg1);
+                        if expr.ty == SType::Bool {
+                            Val::Bool(!res.is_zero())
+                        } else {
+                            Val::Num(res)
+                        }
+                    }
+                    (arg, arg1) => bail!("{op:?} of {arg:?} and {arg1:?}"),
+                }
+            }
+            Expression::Ternary(op, arg, arg1, arg2) => {
+                let arg = self.num(arg)?;
+                let arg1 = self.num(arg1)?;
+                let arg2 = self.num(arg2)?;
+                Val::Num(op.calc(arg, arg1, arg2))
+            }
+            Expression::Cast(arg, cast) => {
+                let arg = self.expr(arg)?;
+                self.cast(arg, cast)?
+            }
+            Expression::BytesLen(data) => Val::Num(U256::from(self.get(*data)?.bytes()?.len() + 4)),
+            Expression::DataLen(data) => Val::Num(U256::from(self.get(*data)?.bytes()?.len())),
+            Expression::ReadNum { data, offset } => {
+                let offset = self.num(offset)?.low_u64();
+                let offset = offset
+                    .checked_sub(4)
+                    .ok_or_else(|| anyhow!("The offset {offset} is in the selector"))?;
+                Val::Num(from_bytes(self.get(*data)?.bytes()?, offset)?)
+            }
+            Expression::Hash { offset, len, .. } => {
+                let offset = self.num(offset)?;
+                let len = self.num(len)?;
+                let data = self.memory.slice(offset, len)?;
+                Val::Num(U256::from_big_endian(Keccak256::digest(data).as_slice()))
+            }
+            Expression::Balance { address } => {
+                let address = self.num(address)?;
+                Val::Num(self.env.balances.get(&address).copied().unwrap_or_default())
+            }
+            // The intrinsics abort: Aptos has no remaining gas, difficulty or miner to report.
+            Expression::Gas | Expression::BlockDifficulty | Expression::BlockCoinbase => {
+                return Err(Abort(0).into());
+            }
+            Expression::GasPrice => Val::Num(self.env.gas_price),
+            Expression::GasLimit => Val::Num(self.env.gas_limit),
+            Expression::BlockHeight => Val::Num(self.env.block_height),
+            Expression::BlockTimestamp => Val::Num(self.env.block_timestamp),
+            Expression::BlockHash { number } => {
+                // The address made of the BCS bytes of the number.
+                let number = self.num(number)?;
+                let mut buf = [0u8; 32];
+                for (word, chunk) in number.0.iter().zip(buf.chunks_mut(8)) {
+                    chunk.copy_from_slice(&word.to_le_bytes());
+                }
+                Val::Address(U256::from_big_endian(&buf))
+            }
+        })
+    }
+
+    fn cast(&self, arg: Val, cast: &Cast) -> Result<Val, Error> {
+        Ok(match (cast, arg) {
+            (Cast::BoolToNum, Val::Bool(val)) => Val::Num(U256::from(u8::from(val))),
+            (Cast::SignerToNum, Val::Signer(val)) | (Cast::AddressToNum, Val::Address(val)) => {
+                Val::Num(val & address_mask())
+            }
+            (Cast::BytesToNum, Val::Bytes(val)) => Val::Num(from_bytes(&val, 0)?),
+            (Cast::NumToBool, Val::Num(val)) => Val::Bool(!val.is_zero()),
+            (Cast::NumToAddress, Val::Num(val)) => Val::Address(val),
+            (Cast::RawNumToNum, Val::RawNum(val)) => Val::Num(U256::from(val)),
+            (Cast::NumToRawNum, Val::Num(val)) => {
+                if val > U256::from(u128::MAX) {
+                    return Err(Abort(EWORDS_OVERFLOW).into());
+                }
+                Val::RawNum(val.as_u128())
+            }
+            (cast, arg) => bail!("Can't cast {arg:?} with {cast:?}"),
+        })
+    }
+}
+
+/// Lower 20 bytes of the word.
+fn address_mask() -> U256 {
+    (U256::one() << 160) - 1
+}
+
+/// Big-endian word of the `data` at the `offset`.
+fn from_bytes(data: &[u8], offset: u64) -> Result<U256, Error> {
+    let word = usize::try_from(offset)
+        .ok()
+        .and_then(|offset| data.get(offset..offset.checked_add(32)?))
+        .ok_or_else(|| anyhow!("The word at {offset} is out of the {} bytes", data.len()))?;
+    Ok(U256::from_big_endian(word))
+}
+
+/// Memory of the function. The offsets are truncated to `u64` as in the Move memory.
+struct Memory {
+    data: Vec<u8>,
+    effective_len: u64,
+    limit: u64,
+}
+
+impl Memory {
+    fn new(limit: u64) -> Result<Memory, Error> {
+        ensure!(limit > 0, "Empty memory");
+        Ok(Memory {
+            data: vec![],
+            effective_len: 0,
+            limit,
+        })
+    }
+
+    fn load(&mut self, offset: U256) -> Result<U256, Error> {
+        let offset = offset.low_u64();
+        self.resize_offset(offset, 32)?;
+        Ok(U256::from_big_endian(&self.read(offset, 32)))
+    }
+
+    fn store(&mut self, offset: U256, val: U256) -> Result<(), Error> {
+        let offset = offset.low_u64();
+        if end(offset, 32)? >= self.limit {
+            return Err(Abort(OUT_OF_MEMORY).into());
+        }
+        self.resize_offset(offset, 32)?;
+        let mut buf = [0u8; 32];
+        val.to_big_endian(&mut buf);
+        self.write(offset, &buf)
+    }
+
+    fn store8(&mut self, offset: U256, val: U256) -> Result<(), Error> {
+        let offset = offset.low_u64();
+        self.resize_offset(offset, 1)?;
+        self.write(offset, &[val.low_u64() as u8])
+    }
+
+    fn code_copy(&mut self, offset: U256, code: &[u8]) -> Result<(), Error> {
+        let offset = offset.low_u64();
+        self.resize_offset(offset, 32)?;
+        if end(offset, code.len() as u64)? >= self.limit {
+            return Err(Abort(OUT_OF_MEMORY).into());
+        }
+        self.write(offset, code)
+    }
+
+    fn copy_data(&mut self, offset: U256, data: &[u8]) -> Result<(), Error> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let offset = offset.low_u64();
+        self.resize_offset(offset, 32)?;
+        self.resize_offset(offset, data.len() as u64)?;
+        if end(offset, data.len() as u64)? >= self.limit {
+            return Err(Abort(OUT_OF_MEMORY).into());
+        }
+        self.write(offset, data)
+    }
+
+    /// Bytes of the range. The bytes beyond the data are zero, the memory isn't expanded.
+    fn slice(&self, offset: U256, len: U256) -> Result<Vec<u8>, Error> {
+        let len = len.low_u64();
+        if len == 0 {
+            return Ok(vec![]);
+        }
+        let offset = offset.low_u64();
+        ensure!(
+            end(offset, len)? <= self.limit,
+            "The slice {offset}..+{len} exceeds the memory limit"
+        );
+        Ok(self.read(offset, len))
+    }
+
+    fn read(&self, offset: u64, len: u64) -> Vec<u8> {
+        (offset..offset + len)
+            .map(|idx| self.data.get(idx as usize).copied().unwrap_or_default())
+            .collect()
+    }
+
+    fn write(&mut self, offset: u64, bytes: &[u8]) -> Result<(), Error> {
+        let end = end(offset, bytes.len() as u64)?;
+        ensure!(
+            end <= self.limit,
+            "The write {offset}..{end} exceeds the memory limit"
+        );
+        let (offset, end) = (offset as usize, end as usize);
+        if self.data.len() < end {
+            self.data.resize(end, 0);
+        }
+        self.data[offset..end].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    fn resize_offset(&mut self, offset: u64, len: u64) -> Result<(), Error> {
+        let end = end(offset, len)?;
+        if end > self.effective_len {
+            self.effective_len = if end % 32 == 0 {
+                end
+            } else {
+                end + (32 - end % 32)
+            };
+        }
+        Ok(())
+    }
+}
+
+/// End of the range. The overflow is an arithmetic error of the Move VM.
+fn end(offset: u64, len: u64) -> Result<u64, Error> {
+    offset
+        .checked_add(len)
+        .ok_or_else(|| anyhow!("Arithmetic overflow of {offset} + {len}"))
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use anyhow::Error;
+    use primitive_types::U256;
+
+    use crate::bytecode::loc::Loc;
+    use crate::bytecode::mir::constructor::make_constructor;
+    use crate::bytecode::mir::interpreter::{run, Abort, Env, State, Val};
+    use crate::bytecode::mir::ir::expression::Expression;
+    use crate::bytecode::mir::ir::statement::Statement;
+    use crate::bytecode::mir::ir::types::{SType, Value};
+    use crate::bytecode::mir::translation::variables::Variables;
+    use crate::layout::Layout;
+    use crate::Mir;
+
+    const LAYOUT: &str = r#"{
+        "storage": [
+            {"astId": 3, "contract": "t.sol:T", "label": "total", "offset": 0, "slot": "0", "type": "t_uint256"},
+            {"astId": 7, "contract": "t.sol:T", "label": "balances", "offset": 0, "slot": "1", "type": "t_mapping(t_address,t_uint256)"}
+        ],
+        "types": {
+            "t_address": {"encoding": "inplace", "label": "address", "numberOfBytes": "20"},
+            "t_mapping(t_address,t_uint256)": {"encoding": "mapping", "key": "t_address", "label": "mapping(address => uint256)", "numberOfBytes": "32", "value": "t_uint256"},
+            "t_uint256": {"encoding": "inplace", "label": "uint256", "numberOfBytes": "32"}
+        }
+    }"#;
+
+    #[test]
+    fn test_constructor() {
+        let store = BTreeMap::from([(U256::zero(), U256::from(7)), (U256::one(), U256::zero())]);
+        let mir = make_constructor(store, &Layout::default());
+
+        let mut state = State::default();
+        let output = run(
+            &mir,
+            vec![Val::Signer(U256::from(0x42))],
+            &mut state,
+            &Env::default(),
+        )
+        .unwrap();
+        assert!(output.returns.is_empty());
+        assert!(output.logs.is_empty());
+        assert_eq!(
+            state.storage,
+            BTreeMap::from([(U256::zero(), U256::from(7))])
+        );
+    }
+
+    #[test]
+    fn test_typed_constructor() {
+        let layout = Layout::parse(LAYOUT).unwrap();
+        let store = BTreeMap::from([
+            (U256::zero(), U256::from(7)),
+            (U256::from(5), U256::from(11)),
+        ]);
+        let mir = make_constructor(store.clone(), &layout);
+
+        let mut state = State::default();
+        run(
+            &mir,
+            vec![Val::Signer(U256::from(0x42))],
+            &mut state,
+            &Env::default(),
+        )
+        .unwrap();
+        assert_eq!(state.fields, BTreeMap::from([(0, U256::from(7))]));
+        assert!(state.entries.is_empty());
+        assert_eq!(
+            state.storage,
+            BTreeMap::from([(U256::from(5), U256::from(11))])
+        );
+        assert_eq!(state.slots(&layout), store);
+    }
+
+    /// Runs the MIR which returns the value of `expr`.
+    fn run_expr(expr: Expression, env: &Env) -> Result<Vec<Val>, Error> {
+        let loc: Loc<()> = Loc::default();
+        let mut mir = Mir::default();
+        let mut variables = Variables::new(vec![SType::Signer]);
+        let var = variables.borrow(SType::Num);
+        mir.push(loc.wrap(Statement::Assign(var, loc.wrap(expr.ty(SType::Num)))));
+        mir.push(loc.wrap(Statement::Result(vec![var])));
+        mir.set_locals(variables.locals());
+        run(
+            &mir,
+            vec![Val::Signer(U256::one())],
+            &mut State::default(),
+            env,
+        )
+        .map(|output| output.returns)
+    }
+
+    #[test]
+    fn test_env() {
+        let env = Env {
+            gas_limit: U256::from(42),
+            ..Env::default()
+        };
+        assert_eq!(
+            run_expr(Expression::GasLimit, &env).unwrap(),
+            vec![Val::Num(U256::from(42))]
+        );
+        for expr in [
+            Expression::Gas,
+            Expression::BlockDifficulty,
+            Expression::BlockCoinbase,
+        ] {
+            let err = run_expr(expr, &env).unwrap_err();
+            assert_eq!(err.downcast_ref::<Abort>(), Some(&Abort(0)));
+        }
+    }
+
+    #[test]
+    fn test_abort() {
+        let loc: Loc<()> = Loc::default();
+        let mut mir = Mir::default();
+        let mut variables = Variables::new(vec![SType::Signer]);
+        mir.push(loc.wrap(Statement::InitStorage(variables.borrow_param(0))));
+        let storage = variables.borrow(SType::Storage);
+        mir.push(loc.wrap(Statement::Assign(
+            storage,
+            loc.wrap(Expression::GetStore.ty(SType::Storage)),
+        )));
+        let num =
+            |val: u64| loc.wrap(Expression::Const(Value::from(U256::from(val))).ty(SType::Num));
+        mir.push(loc.wrap(Statement::SStore {
+            storage,
+            key: num(1),
+            val: num(5),
+        }));
+        mir.push(loc.wrap(Statement::Abort(7)));
+        mir.set_locals(variables.locals());
+
+        // The state of the aborted call is dropped.
+        let mut state = State::default();
+        let err = run(
+            &mir,
+            vec![Val::Signer(U256::one())],
+            &mut state,
+            &Env::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err.downcast_ref::<Abort>(), Some(&Abort(7)));
+        assert!(state.storage.is_empty());
+    }
+}
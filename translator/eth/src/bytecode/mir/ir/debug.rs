@@ -52,6 +52,9 @@ impl Display for Statement {
             Statement::InitStorage(var) => {
                 write!(f, "InitStorage({})", var)
             }
+            Statement::InitState(var) => {
+                write!(f, "InitState({})", var)
+            }
             Statement::StoreStack(ctx) => {
                 writeln!(f, "[")?;
                 for (var, loc) in ctx {
@@ -91,6 +94,45 @@ impl Display for Statement {
             Statement::SStore { storage, key, val } => {
                 write!(f, "{}.SStore({}, {});", storage, key.as_ref(), val.as_ref())
             }
+            Statement::HashedSStore { storage, key, val } => {
+                write!(
+                    f,
+                    "{}.HashedSStore({}, {});",
+                    storage,
+                    key.as_ref(),
+                    val.as_ref()
+                )
+            }
+            Statement::SetField { state, field, val } => {
+                write!(f, "{}.SetField({}, {});", state, field, val.as_ref())
+            }
+            Statement::SetEntry {
+                state,
+                field,
+                key,
+                val,
+            } => {
+                write!(
+                    f,
+                    "{}.SetEntry({}, {}, {});",
+                    state,
+                    field,
+                    key.as_ref(),
+                    val.as_ref()
+                )
+            }
+            Statement::MapStore {
+                storage,
+                slot,
+                keys,
+                val,
+            } => {
+                write!(f, "{}.MapStore({}", storage, slot.as_ref())?;
+                for key in keys {
+                    write!(f, ", {}", key.as_ref())?;
+                }
+                write!(f, ", {});", val.as_ref())
+            }
             Statement::Abort(code) => {
                 write!(f, "Abort({});", code)
             }
@@ -145,6 +187,55 @@ impl Display for Statement {
                     hex::encode(data),
                 )
             }
+            Statement::Call {
+                result,
+                signer,
+                module,
+                function,
+                args,
+            } => {
+                write!(
+                    f,
+                    "{} = {}::{}({}, {});",
+                    result,
+                    module,
+                    function,
+                    signer,
+                    args.as_ref()
+                )
+            }
+            Statement::WriteResult {
+                memory,
+                dest,
+                data,
+                len,
+            } => {
+                write!(
+                    f,
+                    "{}.WriteResult({}, {}, {});",
+                    memory,
+                    dest.as_ref(),
+                    data,
+                    len.as_ref()
+                )
+            }
+            Statement::ReturnDataCopy {
+                memory,
+                dest,
+                data,
+                offset,
+                len,
+            } => {
+                write!(
+                    f,
+                    "{}.ReturnDataCopy({}, {}, {}, {});",
+                    memory,
+                    dest.as_ref(),
+                    data,
+                    offset.as_ref(),
+                    len.as_ref()
+                )
+            }
         }
     }
 }
@@ -161,6 +252,47 @@ impl Display for TypedExpr {
             Expression::GetStore => {
                 write!(f, "GetStore()")
             }
+            Expression::GetState => {
+                write!(f, "GetState()")
+            }
+            Expression::GetField { state, field } => {
+                write!(f, "{}.GetField({})", state, field)
+            }
+            Expression::GetEntry {
+                state,
+                storage,
+                field,
+                key,
+                slot,
+            } => {
+                write!(
+                    f,
+                    "{}.GetEntry({}, {}, {}.SLoad({}))",
+                    state,
+                    field,
+                    key.as_ref(),
+                    storage,
+                    slot.as_ref()
+                )
+            }
+            Expression::MapSlot { slot, keys } => {
+                write!(f, "MapSlot({}", slot.as_ref())?;
+                for key in keys {
+                    write!(f, ", {}", key.as_ref())?;
+                }
+                write!(f, ")")
+            }
+            Expression::MapLoad {
+                storage,
+                slot,
+                keys,
+            } => {
+                write!(f, "{}.MapLoad({}", storage, slot.as_ref())?;
+                for key in keys {
+                    write!(f, ", {}", key.as_ref())?;
+                }
+                write!(f, ")")
+            }
             Expression::MLoad { memory, offset } => {
                 write!(f, "{}.MLoad({})", memory, offset.as_ref())
             }
@@ -180,6 +312,9 @@ impl Display for TypedExpr {
             Expression::SLoad { storage, key } => {
                 write!(f, "{}.SLoad({})", storage, key.as_ref())
             }
+            Expression::HashedSLoad { storage, key } => {
+                write!(f, "{}.HashedSLoad({})", storage, key.as_ref())
+            }
             Expression::MSize { memory } => {
                 write!(f, "{}.MSize()", memory)
             }
@@ -221,6 +356,9 @@ impl Display for TypedExpr {
             Expression::BytesLen(var) => {
                 write!(f, "{}.BytesLen()", var)
             }
+            Expression::DataLen(var) => {
+                write!(f, "{}.DataLen()", var)
+            }
             Expression::ReadNum { data, offset } => {
                 write!(f, "{}.ReadNum({})", data, offset.as_ref())
             }
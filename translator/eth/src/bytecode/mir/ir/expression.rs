@@ -11,6 +11,30 @@ pub enum Expression {
     Const(Value),
     GetMem,
     GetStore,
+    GetState,
+    GetField {
+        state: Variable,
+        field: usize,
+    },
+    /// Entry of the mapping field. Falls back to the raw `slot` of the storage.
+    GetEntry {
+        state: Variable,
+        storage: Variable,
+        field: usize,
+        key: Loc<TypedExpr>,
+        slot: Loc<TypedExpr>,
+    },
+    /// Raw slot of the mapping entry.
+    MapSlot {
+        slot: Loc<TypedExpr>,
+        keys: Vec<Loc<TypedExpr>>,
+    },
+    /// Mapping entry addressed by the keys of the nested mappings.
+    MapLoad {
+        storage: Variable,
+        slot: Loc<TypedExpr>,
+        keys: Vec<Loc<TypedExpr>>,
+    },
     MLoad {
         memory: Variable,
         offset: Loc<TypedExpr>,
@@ -24,6 +48,11 @@ pub enum Expression {
         storage: Variable,
         key: Loc<TypedExpr>,
     },
+    /// Load of the keccak256 slot which may be the raw slot of a mapping entry.
+    HashedSLoad {
+        storage: Variable,
+        key: Loc<TypedExpr>,
+    },
     MSize {
         memory: Variable,
     },
@@ -34,6 +63,8 @@ pub enum Expression {
     Ternary(TernaryOp, Loc<TypedExpr>, Loc<TypedExpr>, Loc<TypedExpr>),
     Cast(Loc<TypedExpr>, Cast),
     BytesLen(Variable),
+    /// Length of the data returned by the library call.
+    DataLen(Variable),
     ReadNum {
         data: Variable,
         offset: Loc<TypedExpr>,
@@ -79,6 +110,14 @@ impl TypedExpr {
     }
 }
 
+/// Kinds of the mapping keys: the bit `i` is set if the key `i` is an address.
+pub fn key_kinds(keys: &[Loc<TypedExpr>]) -> u64 {
+    keys.iter()
+        .enumerate()
+        .filter(|(_, key)| key.ty == SType::Address)
+        .fold(0, |kinds, (i, _)| kinds | 1 << i)
+}
+
 impl From<U256> for TypedExpr {
     fn from(val: U256) -> Self {
         Expression::Const(Value::Number(val)).ty(SType::Num)
@@ -2,11 +2,13 @@ use crate::bytecode::hir::ir::Label;
 use crate::bytecode::loc::{Loc, Location};
 use crate::bytecode::mir::ir::expression::TypedExpr;
 use crate::bytecode::mir::translation::variables::Variable;
+use move_core_types::language_storage::ModuleId;
 use std::collections::BTreeMap;
 
 #[derive(Debug, Clone)]
 pub enum Statement {
     InitStorage(Variable),
+    InitState(Variable),
     StoreStack(BTreeMap<Variable, Loc<TypedExpr>>),
     Assign(Variable, Loc<TypedExpr>),
     MStore {
@@ -29,6 +31,29 @@ pub enum Statement {
         key: Loc<TypedExpr>,
         val: Loc<TypedExpr>,
     },
+    /// Store to the keccak256 slot which may be the raw slot of a mapping entry.
+    HashedSStore {
+        storage: Variable,
+        key: Loc<TypedExpr>,
+        val: Loc<TypedExpr>,
+    },
+    SetField {
+        state: Variable,
+        field: usize,
+        val: Loc<TypedExpr>,
+    },
+    SetEntry {
+        state: Variable,
+        field: usize,
+        key: Loc<TypedExpr>,
+        val: Loc<TypedExpr>,
+    },
+    MapStore {
+        storage: Variable,
+        slot: Loc<TypedExpr>,
+        keys: Vec<Loc<TypedExpr>>,
+        val: Loc<TypedExpr>,
+    },
     Abort(u8),
     Result(Vec<Variable>),
     Log {
@@ -41,6 +66,28 @@ pub enum Statement {
     Label(Label),
     BrTrue(Loc<TypedExpr>, Label),
     Br(Label),
+    /// Call of the function of the linked library. The returned data is kept in `result`.
+    Call {
+        result: Variable,
+        signer: Variable,
+        module: ModuleId,
+        function: String,
+        args: Loc<TypedExpr>,
+    },
+    /// Writes at most `len` bytes of the returned `data` to the memory.
+    WriteResult {
+        memory: Variable,
+        dest: Loc<TypedExpr>,
+        data: Variable,
+        len: Loc<TypedExpr>,
+    },
+    ReturnDataCopy {
+        memory: Variable,
+        dest: Loc<TypedExpr>,
+        data: Variable,
+        offset: Loc<TypedExpr>,
+        len: Loc<TypedExpr>,
+    },
 }
 
 impl Statement {
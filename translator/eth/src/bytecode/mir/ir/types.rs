@@ -6,6 +6,7 @@ use crate::U256;
 #[derive(Ord, PartialOrd, Eq, PartialEq, Debug, Clone, Hash, Copy)]
 pub enum SType {
     Storage,
+    State,
     Memory,
     Num,
     RawNum,
@@ -30,6 +31,7 @@ impl Display for SType {
                 SType::Num => "num",
                 SType::Bool => "bool",
                 SType::Storage => "Storage",
+                SType::State => "State",
                 SType::Memory => "Memory",
                 SType::Signer => "Signer",
                 SType::Bytes => "vector<u8>",
@@ -1,4 +1,5 @@
 pub mod constructor;
+pub mod interpreter;
 /// Mid-level Intermediate Representation
 pub mod ir;
 pub mod translation;
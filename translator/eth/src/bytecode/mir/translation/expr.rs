@@ -1,6 +1,7 @@
-use crate::bytecode::hir::ir::{Expr, VarId, _Expr};
+use crate::bytecode::hir::ir::{_Expr, Expr, VarId};
 use crate::bytecode::loc::Loc;
 use anyhow::{bail, ensure, Error};
+use primitive_types::U256;
 
 use crate::bytecode::mir::ir::expression::{Expression, TypedExpr};
 use crate::bytecode::mir::ir::types::{LocalIndex, SType};
@@ -37,6 +38,42 @@ impl<'a> MirTranslator<'a> {
                 }
                 .ty(SType::Num)
             }
+            _Expr::HashedSLoad(key) => {
+                let key = self.translate_expr(*key)?;
+                Expression::HashedSLoad {
+                    storage: self.store_var,
+                    key: self.cast_expr(key, SType::Num)?,
+                }
+                .ty(SType::Num)
+            }
+            _Expr::FieldLoad(field) => Expression::GetField {
+                state: self.state_var()?,
+                field,
+            }
+            .ty(SType::Num),
+            _Expr::EntryLoad(field, key, slot) => {
+                let key = self.translate_entry_key(field, *key)?;
+                let slot = self.translate_expr(*slot)?;
+                Expression::GetEntry {
+                    state: self.state_var()?,
+                    storage: self.store_var,
+                    field,
+                    key,
+                    slot: self.cast_expr(slot, SType::Num)?,
+                }
+                .ty(SType::Num)
+            }
+            _Expr::MapSlot(slot, keys) => Expression::MapSlot {
+                slot: TypedExpr::from(slot).loc(loc),
+                keys: self.translate_map_keys(slot, keys)?,
+            }
+            .ty(SType::Num),
+            _Expr::MapLoad(slot, keys) => Expression::MapLoad {
+                storage: self.store_var,
+                slot: TypedExpr::from(slot).loc(loc),
+                keys: self.translate_map_keys(slot, keys)?,
+            }
+            .ty(SType::Num),
             _Expr::Signer => {
                 let signer = self.vars.borrow_param(self.signer_index);
                 let signer = Expression::CopyVar(signer).ty(signer.ty()).loc(loc);
@@ -92,6 +129,10 @@ impl<'a> MirTranslator<'a> {
             }
             _Expr::BlockCoinbase => Expression::BlockCoinbase.ty(SType::Num),
             _Expr::BlockDifficulty => Expression::BlockDifficulty.ty(SType::Num),
+            _Expr::ReturnDataSize => match self.return_data {
+                Some(data) => Expression::DataLen(data).ty(SType::Num),
+                None => U256::zero().into(),
+            },
         };
         Ok(res.loc(loc))
     }
@@ -1,16 +1,18 @@
 use std::collections::{BTreeMap, HashMap};
 
 use anyhow::{anyhow, Error};
+use move_core_types::language_storage::ModuleId;
 use primitive_types::U256;
 
 use crate::bytecode::hir::executor::math::BinaryOp;
 use crate::bytecode::hir::ir::{Expr, Stmt, VarId};
 use crate::bytecode::loc::Loc;
-use crate::bytecode::mir::ir::expression::Expression;
+use crate::bytecode::mir::ir::expression::{Expression, TypedExpr};
 use crate::bytecode::mir::ir::statement::Statement;
 use crate::bytecode::mir::ir::types::{LocalIndex, SType, Value};
 use crate::bytecode::mir::ir::Mir;
 use crate::bytecode::mir::translation::variables::{Variable, Variables};
+use crate::layout::Layout;
 use crate::{Flags, Function, Hir};
 
 pub mod cast;
@@ -29,6 +31,10 @@ pub struct MirTranslator<'a> {
     pub(super) mir: Mir,
     pub(super) mem_var: Variable,
     pub(super) store_var: Variable,
+    pub(super) state_var: Option<Variable>,
+    /// Data returned by the last library call.
+    pub(super) return_data: Option<Variable>,
+    pub(super) layout: &'a Layout,
     pub(super) signer_index: LocalIndex,
     pub(super) args_index: LocalIndex,
     pub(super) flags: Flags,
@@ -36,7 +42,7 @@ pub struct MirTranslator<'a> {
 }
 
 impl<'a> MirTranslator<'a> {
-    pub fn new(fun: &'a Function, flags: Flags) -> MirTranslator<'a> {
+    pub fn new(fun: &'a Function, flags: Flags, layout: &'a Layout) -> MirTranslator<'a> {
         let loc = Loc::default();
         let signer = (0, SType::Signer);
         let args = (1, SType::Bytes);
@@ -62,6 +68,18 @@ impl<'a> MirTranslator<'a> {
                 .loc(loc),
         );
 
+        let state_var = if layout.is_empty() {
+            None
+        } else {
+            let state_var = variables.borrow(SType::State);
+            mir.push(
+                state_var
+                    .assign(Expression::GetState.ty(SType::State).loc(loc))
+                    .loc(loc),
+            );
+            Some(state_var)
+        };
+
         let mem_var = variables.borrow(SType::Memory);
         mir.push(
             mem_var
@@ -77,6 +95,9 @@ impl<'a> MirTranslator<'a> {
             mir,
             mem_var,
             store_var,
+            state_var,
+            return_data: None,
+            layout,
             signer_index: signer.0,
             args_index: args.0,
             flags,
@@ -104,8 +125,36 @@ impl<'a> MirTranslator<'a> {
         }
     }
 
+    /// The returned data is empty until the first library call.
+    fn prepare_return_data(&mut self, hir: &Hir) {
+        let calls = hir.statements().iter().any(|stmt| {
+            matches!(
+                stmt.as_ref(),
+                Stmt::Call { .. } | Stmt::ReturnDataCopy { .. }
+            )
+        });
+        if !calls {
+            return;
+        }
+
+        let data = self.vars.borrow(SType::Bytes);
+        let zero = self
+            .loc
+            .wrap(Expression::Const(Value::from(U256::zero())).ty(SType::Num));
+        let empty = Expression::MSlice {
+            memory: self.mem_var,
+            offset: zero.clone(),
+            len: zero,
+        }
+        .ty(SType::Bytes);
+        self.mir
+            .push(self.loc.wrap(data.assign(self.loc.wrap(empty))));
+        self.return_data = Some(data);
+    }
+
     pub fn translate(mut self, hir: Hir) -> Result<Mir, Error> {
         self.prepare_context_vars(&hir);
+        self.prepare_return_data(&hir);
         let instructions = hir.inner();
         self.translate_instructions(instructions)?;
         self.mir.set_locals(self.vars.locals());
@@ -133,7 +182,19 @@ impl<'a> MirTranslator<'a> {
                     self.translate_mem_store(addr, val)?;
                 }
                 Stmt::SStore { key, val } => {
-                    self.translate_s_store(key, val)?;
+                    self.translate_s_store(key, val, false)?;
+                }
+                Stmt::HashedSStore { key, val } => {
+                    self.translate_s_store(key, val, true)?;
+                }
+                Stmt::FieldStore { field, val } => {
+                    self.translate_field_store(field, val)?;
+                }
+                Stmt::EntryStore { field, key, val } => {
+                    self.translate_entry_store(field, key, val)?;
+                }
+                Stmt::MapStore { slot, keys, val } => {
+                    self.translate_map_store(slot, keys, val)?;
                 }
                 Stmt::Log {
                     offset,
@@ -171,6 +232,33 @@ impl<'a> MirTranslator<'a> {
                     };
                     self.mir.push(code_copy.loc(self.loc));
                 }
+                Stmt::Call {
+                    module,
+                    function,
+                    offset,
+                    len,
+                } => {
+                    self.translate_call(module, function, offset, len)?;
+                }
+                Stmt::WriteResult { dest, len } => {
+                    let write_result = Statement::WriteResult {
+                        memory: self.mem_var,
+                        dest: self.translate_num(dest)?,
+                        data: self.return_data()?,
+                        len: self.translate_num(len)?,
+                    };
+                    self.mir.push(write_result.loc(self.loc));
+                }
+                Stmt::ReturnDataCopy { dest, offset, len } => {
+                    let return_data_copy = Statement::ReturnDataCopy {
+                        memory: self.mem_var,
+                        dest: self.translate_num(dest)?,
+                        data: self.return_data()?,
+                        offset: self.translate_num(offset)?,
+                        len: self.translate_num(len)?,
+                    };
+                    self.mir.push(return_data_copy.loc(self.loc));
+                }
             }
         }
         Ok(())
@@ -209,6 +297,40 @@ impl<'a> MirTranslator<'a> {
         Ok(())
     }
 
+    fn translate_num(&mut self, expr: Expr) -> Result<Loc<TypedExpr>, Error> {
+        let expr = self.translate_expr(expr)?;
+        self.cast_expr(expr, SType::Num)
+    }
+
+    fn return_data(&self) -> Result<Variable, Error> {
+        self.return_data
+            .ok_or_else(|| anyhow!("The returned data is used without the library calls"))
+    }
+
+    fn translate_call(
+        &mut self,
+        module: ModuleId,
+        function: String,
+        offset: Expr,
+        len: Expr,
+    ) -> Result<(), Error> {
+        let args = Expression::MSlice {
+            memory: self.mem_var,
+            offset: self.translate_num(offset)?,
+            len: self.translate_num(len)?,
+        }
+        .ty(SType::Bytes);
+        let call = Statement::Call {
+            result: self.return_data()?,
+            signer: self.vars.borrow_param(self.signer_index),
+            module,
+            function,
+            args: args.loc(self.loc),
+        };
+        self.mir.push(call.loc(self.loc));
+        Ok(())
+    }
+
     fn translate_log(&mut self, offset: Expr, len: Expr, topics: Vec<Expr>) -> Result<(), Error> {
         let topics = topics
             .into_iter()
@@ -1,13 +1,25 @@
 use crate::bytecode::hir::ir::Expr;
 
-use anyhow::{ensure, Error};
+use anyhow::{anyhow, ensure, Error};
 
+use crate::bytecode::loc::Loc;
+use crate::bytecode::mir::ir::expression::TypedExpr;
 use crate::bytecode::mir::ir::statement::Statement;
 use crate::bytecode::mir::ir::types::SType;
+use crate::bytecode::mir::translation::variables::Variable;
+use crate::layout::{FieldKind, KeyType};
 use crate::MirTranslator;
+use primitive_types::U256;
 
 impl<'a> MirTranslator<'a> {
-    pub(super) fn translate_s_store(&mut self, key: Expr, val: Expr) -> Result<(), Error> {
+    /// Translates the store to the raw storage.
+    /// The `hashed` slots are checked for the mapping entries kept by their native keys.
+    pub(super) fn translate_s_store(
+        &mut self,
+        key: Expr,
+        val: Expr,
+        hashed: bool,
+    ) -> Result<(), Error> {
         let key = self.translate_expr(key)?;
         let val = self.translate_expr(val)?;
         let val = self.cast_expr(val, SType::Num)?;
@@ -17,11 +29,107 @@ impl<'a> MirTranslator<'a> {
             "Expected Number type for storage address"
         );
 
-        self.mir.push(self.loc.wrap(Statement::SStore {
-            storage: self.store_var,
+        let storage = self.store_var;
+        let stmt = if hashed {
+            Statement::HashedSStore { storage, key, val }
+        } else {
+            Statement::SStore { storage, key, val }
+        };
+        self.mir.push(self.loc.wrap(stmt));
+        Ok(())
+    }
+
+    pub(super) fn translate_field_store(&mut self, field: usize, val: Expr) -> Result<(), Error> {
+        let val = self.translate_expr(val)?;
+        let val = self.cast_expr(val, SType::Num)?;
+        self.mir.push(self.loc.wrap(Statement::SetField {
+            state: self.state_var()?,
+            field,
+            val,
+        }));
+        Ok(())
+    }
+
+    pub(super) fn translate_entry_store(
+        &mut self,
+        field: usize,
+        key: Expr,
+        val: Expr,
+    ) -> Result<(), Error> {
+        let key = self.translate_entry_key(field, key)?;
+        let val = self.translate_expr(val)?;
+        let val = self.cast_expr(val, SType::Num)?;
+        self.mir.push(self.loc.wrap(Statement::SetEntry {
+            state: self.state_var()?,
+            field,
             key,
             val,
         }));
         Ok(())
     }
+
+    pub(super) fn translate_map_store(
+        &mut self,
+        slot: U256,
+        keys: Vec<Expr>,
+        val: Expr,
+    ) -> Result<(), Error> {
+        let keys = self.translate_map_keys(slot, keys)?;
+        let val = self.translate_expr(val)?;
+        let val = self.cast_expr(val, SType::Num)?;
+        self.mir.push(self.loc.wrap(Statement::MapStore {
+            storage: self.store_var,
+            slot: self.loc.wrap(TypedExpr::from(slot)),
+            keys,
+            val,
+        }));
+        Ok(())
+    }
+
+    /// Translates the keys of the mapping at `slot` to their native types.
+    /// The keys which are not described by the layout are numbers.
+    pub(super) fn translate_map_keys(
+        &mut self,
+        slot: U256,
+        keys: Vec<Expr>,
+    ) -> Result<Vec<Loc<TypedExpr>>, Error> {
+        let types = self.layout.mapping_keys(slot);
+        keys.into_iter()
+            .enumerate()
+            .map(|(i, key)| {
+                let key = self.translate_expr(key)?;
+                let key = self.cast_expr(key, SType::Num)?;
+                match types.get(i) {
+                    Some(KeyType::Address) => self.cast_expr(key, SType::Address),
+                    Some(KeyType::Num) | None => Ok(key),
+                }
+            })
+            .collect()
+    }
+
+    /// Translates the key of the mapping field to its native type.
+    pub(super) fn translate_entry_key(
+        &mut self,
+        field: usize,
+        key: Expr,
+    ) -> Result<Loc<TypedExpr>, Error> {
+        let kind = self
+            .layout
+            .fields()
+            .get(field)
+            .map(|field| field.kind)
+            .ok_or_else(|| anyhow!("Unknown state field {field}"))?;
+        let key = self.translate_expr(key)?;
+        let key = self.cast_expr(key, SType::Num)?;
+        match kind {
+            FieldKind::Mapping(KeyType::Address) => self.cast_expr(key, SType::Address),
+            FieldKind::Mapping(KeyType::Num) => Ok(key),
+            FieldKind::Value => Err(anyhow!("State field {field} is not a mapping")),
+        }
+    }
+
+    pub(super) fn state_var(&self) -> Result<Variable, Error> {
+        self.state_var
+            .ok_or_else(|| anyhow!("The contract has no typed state"))
+    }
 }
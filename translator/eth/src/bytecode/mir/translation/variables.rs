@@ -115,6 +115,10 @@ impl Locals {
 pub struct Variable(LocalIndex, SType);
 
 impl Variable {
+    pub fn new(idx: LocalIndex, tp: SType) -> Variable {
+        Variable(idx, tp)
+    }
+
     pub fn none() -> Variable {
         Variable(0, SType::Signer)
     }
@@ -0,0 +1,71 @@
+use std::collections::{BTreeSet, HashMap};
+
+use primitive_types::U256;
+
+use crate::bytecode::block::{InstructionBlock, Offset};
+use crate::OpCode;
+
+type Blocks = HashMap<Offset, InstructionBlock>;
+
+/// Size of the code offsets in the jump tables.
+const ENTRY_SIZE: usize = 2;
+
+/// Jump destinations which are reachable through the jump tables only.
+/// Vyper dispatches the selectors by the tables of 2-byte code offsets, which are read with
+/// `CODECOPY` and `MLOAD`. Such destinations are never pushed or fallen through, but their
+/// offsets are stored in the code.
+pub fn jump_table_targets(blocks: &Blocks, code: &[u8]) -> BTreeSet<Offset> {
+    let mut referenced = BTreeSet::new();
+    for block in blocks.values() {
+        for inst in block.iter() {
+            if let Some(val) = inst.as_push() {
+                referenced.insert(U256::from(val));
+            }
+        }
+        if let Some(last) = block.last() {
+            if !last.halts_execution() && last.1 != OpCode::Jump {
+                referenced.insert(U256::from(last.next().0));
+            }
+        }
+    }
+
+    let stored = code
+        .windows(ENTRY_SIZE)
+        .map(|entry| u16::from_be_bytes([entry[0], entry[1]]) as u128)
+        .collect::<BTreeSet<_>>();
+
+    blocks
+        .values()
+        .filter(|block| block.first().map(|inst| inst.1 == OpCode::JumpDest) == Some(true))
+        .map(|block| block.start)
+        .filter(|start| !referenced.contains(&U256::from(start.0)))
+        .filter(|start| stored.contains(&start.0))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use crate::bytecode::block::{BlockIter, Offset};
+    use crate::bytecode::ops::InstructionIter;
+    use crate::bytecode::pre_processing::jump_table::jump_table_targets;
+
+    #[test]
+    fn test_jump_table_targets() {
+        // 00: PUSH1 04 JUMP STOP
+        // 04: JUMPDEST STOP - pushed
+        // 06: JUMPDEST STOP - stored in the table
+        // 08: JUMPDEST STOP - unreachable
+        // 0a: STOP, the table: 0006
+        let code = hex::decode("600456005b005b005b000006").unwrap();
+        let mut instructions = InstructionIter::new(code.clone());
+        let blocks = BlockIter::new(&mut instructions)
+            .map(|block| (block.start, block))
+            .collect();
+        assert_eq!(
+            jump_table_targets(&blocks, &code),
+            BTreeSet::from([Offset::from(6_u128)])
+        );
+    }
+}
@@ -1,2 +1,3 @@
 pub mod ctor;
+pub mod jump_table;
 pub mod swarm;
@@ -1,4 +1,4 @@
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use anyhow::{anyhow, Context as ErrContext, Error};
 
@@ -10,6 +10,8 @@ use crate::{Offset, OpCode, U256};
 pub struct Tracer<'a> {
     blocks: &'a HashMap<Offset, InstructionBlock>,
     executor: Executor,
+    /// Destinations of the jumps to the computed offsets.
+    jump_targets: BTreeSet<Offset>,
 }
 
 impl<'a> Tracer<'a> {
@@ -17,9 +19,16 @@ impl<'a> Tracer<'a> {
         Self {
             blocks,
             executor: Executor::default(),
+            jump_targets: BTreeSet::new(),
         }
     }
 
+    /// Sets the destinations of the jumps to the computed offsets, such as the jump tables.
+    pub fn with_jump_targets(mut self, jump_targets: BTreeSet<Offset>) -> Self {
+        self.jump_targets = jump_targets;
+        self
+    }
+
     pub fn trace(&mut self) -> Result<FlowTrace, Error> {
         let io = self.calculate_io()?;
         let loops = self.clone().find_loops()?;
@@ -31,10 +40,15 @@ impl<'a> Tracer<'a> {
         block.last().map(|lst| lst.offset() + lst.size()).unwrap()
     }
 
-    fn find_funcs(&mut self, loops: &HashMap<Offset, Loop>) -> HashMap<Offset, Func> {
-        let mut funcs: HashMap<Offset, Func> = HashMap::new();
+    /// Blocks in the order of their offsets, so that the trace doesn't depend on the hash order.
+    fn sorted_blocks(&self) -> BTreeMap<&Offset, &InstructionBlock> {
+        self.blocks.iter().collect()
+    }
 
-        for (id, block) in self.blocks {
+    fn find_funcs(&mut self, loops: &BTreeMap<Offset, Loop>) -> BTreeMap<Offset, Func> {
+        let mut funcs: BTreeMap<Offset, Func> = BTreeMap::new();
+
+        for (id, block) in self.sorted_blocks() {
             let last = if let Some(last) = block.last() {
                 last
             } else {
@@ -78,16 +92,16 @@ impl<'a> Tracer<'a> {
             .collect()
     }
 
-    fn check_func(&self, _id: &Offset, _fun: &Func, _loops: &HashMap<Offset, Loop>) -> bool {
+    fn check_func(&self, _id: &Offset, _fun: &Func, _loops: &BTreeMap<Offset, Loop>) -> bool {
         //todo filter out functions that are not really functions)
         true
     }
 
-    fn find_loops(&mut self) -> Result<HashMap<Offset, Loop>, Error> {
+    fn find_loops(&mut self) -> Result<BTreeMap<Offset, Loop>, Error> {
         let mut id = Offset::default();
         let mut stack: Vec<Fork> = vec![];
         let mut loop_candidates: HashMap<Offset, (Offset, Vec<Offset>)> = HashMap::new();
-        let mut loops: HashMap<Offset, Loop> = HashMap::new();
+        let mut loops: BTreeMap<Offset, Loop> = BTreeMap::new();
         let mut breaks: HashMap<Offset, Offset> = HashMap::new();
         loop {
             let block = self
@@ -98,6 +112,20 @@ impl<'a> Tracer<'a> {
 
             let res = self.executor.exec(block);
             match res {
+                Next::Jmp(StackItem::Calc(_)) if !self.jump_targets.is_empty() => {
+                    // The computed jump may lead to any of the jump targets.
+                    let mut targets = self.jump_targets.iter().copied();
+                    let first = targets.next().expect("the jump targets are not empty");
+                    for target in targets {
+                        stack.push(Fork {
+                            id,
+                            exec: self.executor.clone(),
+                            state: (target, target),
+                            next_br: Some(target),
+                        });
+                    }
+                    id = first;
+                }
                 Next::Jmp(jmp) => {
                     let jmp = jmp.as_positive()?;
                     if let Some(lp) = breaks.get(&jmp) {
@@ -135,7 +163,7 @@ impl<'a> Tracer<'a> {
                                             loop_exit,
                                             loop_br,
                                             continuous: *lp.last().unwrap(),
-                                            breaks: HashSet::new(),
+                                            breaks: BTreeSet::new(),
                                             fork: fork.clone(),
                                             loop_ctx: Default::default(),
                                         },
@@ -196,9 +224,9 @@ impl<'a> Tracer<'a> {
         }
     }
 
-    fn calculate_io(&self) -> Result<HashMap<Offset, BlockIO>, Error> {
-        let mut io: HashMap<Offset, BlockIO> = HashMap::new();
-        for (id, block) in self.blocks {
+    fn calculate_io(&self) -> Result<BTreeMap<Offset, BlockIO>, Error> {
+        let mut io: BTreeMap<Offset, BlockIO> = BTreeMap::new();
+        for (id, block) in self.sorted_blocks() {
             let mut exec = Executor::default();
             let res = exec.exec_one(block);
 
@@ -218,7 +246,7 @@ impl<'a> Tracer<'a> {
         Ok(io)
     }
 
-    pub fn fill_io(&self, lp: &mut Loop, loops: &HashMap<Offset, Loop>) -> Result<(), Error> {
+    pub fn fill_io(&self, lp: &mut Loop, loops: &BTreeMap<Offset, Loop>) -> Result<(), Error> {
         let mut exec = Executor::default();
         let mut block_id = lp.root;
         let exit = lp.loop_exit;
@@ -293,7 +321,7 @@ pub struct Context {
 #[derive(Debug)]
 pub struct Func {
     pub entry_point: Offset,
-    pub calls: HashMap<Offset, Call>,
+    pub calls: BTreeMap<Offset, Call>,
 }
 
 #[derive(Debug)]
@@ -316,16 +344,16 @@ pub struct Loop {
     pub loop_exit: Offset,
     pub loop_br: Offset,
     pub continuous: Offset,
-    pub breaks: HashSet<Offset>,
+    pub breaks: BTreeSet<Offset>,
     pub fork: Fork,
     pub loop_ctx: LoopCtx,
 }
 
 #[derive(Debug)]
 pub struct FlowTrace {
-    pub io: HashMap<Offset, BlockIO>,
-    pub funcs: HashMap<Offset, Func>,
-    pub loops: HashMap<Offset, Loop>,
+    pub io: BTreeMap<Offset, BlockIO>,
+    pub funcs: BTreeMap<Offset, Func>,
+    pub loops: BTreeMap<Offset, Loop>,
 }
 
 pub type ID = usize;
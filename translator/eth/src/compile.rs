@@ -1,20 +1,29 @@
+use std::collections::BTreeSet;
 use std::fs;
-use std::path::Path;
-use std::process::Command;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 use std::sync::Arc;
 
 use anyhow::{anyhow, ensure, Result};
 use ethabi::Contract;
-use serde_json::Value;
-use tempdir::TempDir;
+use serde::Deserialize;
+use serde_json::{json, Value};
 
 use crate::bytecode::pre_processing::swarm::remove_swarm_hash;
+use crate::link;
+use crate::link::LibraryLink;
 
 #[derive(Debug, Clone)]
 pub struct Evm {
     pub name: Arc<String>,
     pub bin: Arc<String>,
     pub abi: Arc<String>,
+    pub storage_layout: Arc<String>,
+    /// Source map of the creation bytecode, if the compiler reported it.
+    pub source_map: Arc<String>,
+    /// Yul IR of the contract, if the compiler reported it.
+    pub ir: Arc<String>,
 }
 
 impl Evm {
@@ -29,27 +38,159 @@ impl Evm {
     pub fn abi(&self) -> &str {
         &self.abi
     }
+
+    pub fn storage_layout(&self) -> &str {
+        &self.storage_layout
+    }
+
+    pub fn source_map(&self) -> &str {
+        &self.source_map
+    }
+
+    pub fn ir(&self) -> &str {
+        &self.ir
+    }
+}
+
+/// Default number of the optimizer runs.
+pub const DEFAULT_OPTIMIZER_RUNS: u32 = 200;
+
+/// Settings of the compilation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct SolcConfig {
+    /// The solc binary. If not specified, `solc` is taken from the PATH.
+    pub solc: Option<PathBuf>,
+    /// The vyper binary of the vy files. If not specified, `vyper` is taken from the PATH.
+    pub vyper: Option<PathBuf>,
+    /// Root of the source tree. If not specified, the directory of the compiled file.
+    pub base_path: Option<PathBuf>,
+    /// Additional directories to look for the imports in.
+    pub include_paths: Vec<PathBuf>,
+    /// Import remappings in the `[context:]prefix=target` form.
+    /// The remappings of `remappings.txt` in the base path are applied before them.
+    pub remappings: Vec<String>,
+    /// Enables the optimizer.
+    pub optimize: bool,
+    /// Number of the optimizer runs. Enables the optimizer.
+    pub optimizer_runs: Option<u32>,
+    /// Compiles through the Yul IR.
+    pub via_ir: bool,
+}
+
+impl SolcConfig {
+    pub fn solc(&self) -> &Path {
+        self.solc.as_deref().unwrap_or_else(|| Path::new("solc"))
+    }
+
+    pub fn vyper(&self) -> &Path {
+        self.vyper.as_deref().unwrap_or_else(|| Path::new("vyper"))
+    }
+
+    fn optimizer_enabled(&self) -> bool {
+        self.optimize || self.optimizer_runs.is_some()
+    }
+
+    fn optimizer(&self) -> Value {
+        json!({
+            "enabled": self.optimizer_enabled(),
+            "runs": self.optimizer_runs.unwrap_or(DEFAULT_OPTIMIZER_RUNS),
+        })
+    }
 }
 
 pub fn build_sol<P: AsRef<Path>>(path: P) -> Result<EvmPack> {
+    build_sol_with(path, &SolcConfig::default())
+}
+
+/// Compiles the sol file through the solc standard-JSON interface.
+/// The imports are resolved against the base path, the include paths and the remappings.
+/// The deployable contracts are taken from the passed file only,
+/// the libraries of the imported files are kept as modules.
+pub fn build_sol_with<P: AsRef<Path>>(path: P, config: &SolcConfig) -> Result<EvmPack> {
     let path = path.as_ref().canonicalize()?;
-    let name = path
-        .file_name()
-        .map(|name| name.to_string_lossy().to_string())
-        .ok_or_else(|| anyhow!("Invalid sol path:{:?}", path))?;
+    let base_path = match &config.base_path {
+        Some(base_path) => base_path.canonicalize()?,
+        None => path
+            .parent()
+            .ok_or_else(|| anyhow!("Invalid sol path:{:?}", path))?
+            .to_path_buf(),
+    };
+    let source_name = path
+        .strip_prefix(&base_path)
+        .map_err(|_| anyhow!("{path:?} is outside of the base path {base_path:?}"))?
+        .to_string_lossy()
+        .replace('\\', "/");
+
+    let mut remappings = read_remappings(&base_path)?;
+    remappings.extend(config.remappings.iter().cloned());
 
-    let dir = TempDir::new("sol")?;
-    if !dir.path().exists() {
-        fs::create_dir_all(dir.path())?;
+    // The Yul frontend translates the IR which the optimizer would produce.
+    let ir = if config.optimizer_enabled() {
+        "irOptimized"
+    } else {
+        "ir"
+    };
+    let mut settings = json!({
+        "remappings": remappings,
+        "optimizer": config.optimizer(),
+        "outputSelection": {
+            "*": {
+                "*": ["abi", "evm.bytecode.object", "evm.bytecode.sourceMap", "storageLayout", ir],
+            },
+        },
+    });
+    if config.via_ir {
+        settings["viaIR"] = Value::Bool(true);
     }
-    let contract = dir.path().join(name);
-    fs::write(contract, fs::read_to_string(&path)?)?;
+    let input = json!({
+        "language": "Solidity",
+        "sources": {
+            &source_name: {
+                "content": fs::read_to_string(&path)?,
+            },
+        },
+        "settings": settings,
+    });
 
-    let output = Command::new("solc")
-        .current_dir(&dir)
-        .args(["--combined-json", "abi,bin"])
-        .arg(&path)
-        .output()?;
+    let mut allowed_paths = vec![base_path.clone()];
+    allowed_paths.extend(config.include_paths.iter().cloned());
+    allowed_paths.extend(
+        remappings
+            .iter()
+            .filter_map(|remapping| remapping.split_once('='))
+            .map(|(_, target)| base_path.join(target))
+            .filter(|target| target.exists()),
+    );
+
+    let mut command = Command::new(config.solc());
+    command
+        .current_dir(&base_path)
+        .arg("--standard-json")
+        .arg("--base-path")
+        .arg(&base_path);
+    for include_path in &config.include_paths {
+        command.arg("--include-path").arg(include_path);
+    }
+    let mut solc = command
+        .arg("--allow-paths")
+        .arg(
+            allowed_paths
+                .iter()
+                .map(|path| path.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| anyhow!("Failed to run {:?}: {err}", config.solc()))?;
+    solc.stdin
+        .take()
+        .ok_or_else(|| anyhow!("Failed to open the solc stdin"))?
+        .write_all(input.to_string().as_bytes())?;
+    let output = solc.wait_with_output()?;
 
     ensure!(
         output.status.success(),
@@ -58,51 +199,131 @@ pub fn build_sol<P: AsRef<Path>>(path: P) -> Result<EvmPack> {
     );
 
     let json_output: Value = serde_json::from_str(&String::from_utf8(output.stdout)?)?;
-    let list_evm = json_output
-        .get("contracts")
-        .and_then(|item| item.as_object())
-        .map(|item| {
-            item.iter()
-                .filter_map(|(name, json)| {
-                    let (_, name) = name.rsplit_once(':')?;
-
-                    let abi = json.get("abi")?;
-                    let abi_string = if abi.as_array().map(|item| !item.is_empty()).unwrap_or(false)
-                    {
-                        abi.to_string()
-                    } else {
-                        "".to_string()
-                    };
-
-                    let bin = json.get("bin")?.as_str().unwrap_or_default().to_string();
-                    if bin.is_empty() {
-                        return None;
-                    }
-                    Some(Evm {
-                        name: Arc::new(name.to_string()),
-                        abi: Arc::new(abi_string),
-                        bin: Arc::new(bin),
-                    })
+    let errors = json_output
+        .get("errors")
+        .and_then(|errors| errors.as_array())
+        .map(|errors| {
+            errors
+                .iter()
+                .filter(|error| {
+                    error.get("severity").and_then(|item| item.as_str()) == Some("error")
                 })
-                .collect::<Vec<Evm>>()
+                .filter_map(|error| error.get("formattedMessage").and_then(|item| item.as_str()))
+                .collect::<Vec<_>>()
         })
+        .unwrap_or_default();
+    ensure!(
+        errors.is_empty(),
+        "Compilation error:\n{}",
+        errors.join("\n")
+    );
+
+    let sources = json_output
+        .get("contracts")
+        .and_then(|item| item.as_object())
         .ok_or_else(|| anyhow!("Couldn't find a contract. {path:?}"))?;
 
-    let (mut r_contracts, r_modules): (Vec<_>, Vec<_>) =
-        list_evm.into_iter().partition(|item| !item.abi.is_empty());
+    let units = sources
+        .iter()
+        .filter_map(|(source, contracts)| Some((source, contracts.as_object()?)))
+        .flat_map(|(source, contracts)| {
+            contracts
+                .iter()
+                .filter_map(move |(name, json)| Some((source.clone(), parse_evm(name, json)?)))
+        })
+        .collect::<Vec<_>>();
+
+    // The deployable contracts are taken from the compiled file only,
+    // the contract named after the file is selected by default.
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    pack_units(units, |source| source == source_name, &stem)
+        .map_err(|err| anyhow!("{err} {path:?}"))
+}
+
+/// Sorts the compiled units `(<source unit>, contract)` into the contracts, the modules and the
+/// libraries. The deployable contracts are taken from the sources accepted by `is_target` only.
+/// The contract named `default` is selected, else the first one.
+pub(crate) fn pack_units(
+    units: Vec<(String, Evm)>,
+    is_target: impl Fn(&str) -> bool,
+    default: &str,
+) -> Result<EvmPack> {
+    // The libraries with the external functions are referenced by the placeholders.
+    let placeholders = units
+        .iter()
+        .flat_map(|(_, evm)| link::placeholders(evm.bin()))
+        .collect::<BTreeSet<_>>();
+
+    let mut r_contracts = Vec::new();
+    let mut r_modules = Vec::new();
+    let mut r_libraries = Vec::new();
+    for (source, evm) in units {
+        let library_path = format!("{source}:{}", evm.name());
+        if placeholders.contains(&link::placeholder(&library_path)) {
+            r_libraries.push(Library {
+                path: library_path,
+                evm,
+            });
+        } else if evm.abi.is_empty() {
+            r_modules.push(evm);
+        } else if is_target(&source) {
+            r_contracts.push(evm);
+        }
+    }
 
     ensure!(
-        r_contracts.len() == 1,
-        "It was expected that there would be one contract in the file. {path:?}"
+        !r_contracts.is_empty(),
+        "It was expected that there would be at least one contract in the file."
     );
 
-    Ok(EvmPack::from((r_contracts.remove(0), r_modules)))
+    let contract = r_contracts
+        .iter()
+        .find(|item| item.name() == default)
+        .unwrap_or(&r_contracts[0])
+        .clone();
+
+    Ok(EvmPack {
+        contract,
+        contracts: r_contracts,
+        modules: r_modules,
+        libraries: r_libraries,
+        links: Vec::new(),
+    })
+}
+
+/// External library compiled with the contracts.
+#[derive(Debug, Clone)]
+pub struct Library {
+    /// Fully qualified name of the library as `<source unit>:<name>`.
+    pub path: String,
+    pub evm: Evm,
+}
+
+impl Library {
+    pub fn name(&self) -> &str {
+        self.evm.name()
+    }
+
+    /// The library as a pack to be translated into its own module.
+    pub fn pack(&self, links: &[LibraryLink]) -> Result<EvmPack> {
+        EvmPack::from((self.evm.clone(), Vec::new())).link(links)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct EvmPack {
+    /// The selected contract.
     contract: Evm,
+    /// All deployable contracts of the source file, including the selected one.
+    contracts: Vec<Evm>,
     modules: Vec<Evm>,
+    /// External libraries used by the contracts.
+    libraries: Vec<Library>,
+    /// Libraries linked into the bytecode.
+    links: Vec<LibraryLink>,
 }
 
 impl EvmPack {
@@ -110,10 +331,140 @@ impl EvmPack {
         &self.contract
     }
 
+    pub fn contracts(&self) -> &Vec<Evm> {
+        &self.contracts
+    }
+
+    /// Selects the contract with the given name.
+    pub fn select(&self, name: &str) -> Result<EvmPack> {
+        let contract = self
+            .contracts
+            .iter()
+            .find(|item| item.name() == name)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Contract {name} was not found. Available contracts: {}",
+                    self.contract_names().join(", ")
+                )
+            })?;
+        Ok(EvmPack {
+            contract: contract.clone(),
+            ..self.clone()
+        })
+    }
+
+    /// Splits the pack into packs with each of the contracts selected.
+    pub fn split(&self) -> Vec<EvmPack> {
+        self.contracts
+            .iter()
+            .map(|contract| EvmPack {
+                contract: contract.clone(),
+                ..self.clone()
+            })
+            .collect()
+    }
+
+    pub fn contract_names(&self) -> Vec<&str> {
+        self.contracts.iter().map(|item| item.name()).collect()
+    }
+
     pub fn modules(&self) -> &Vec<Evm> {
         &self.modules
     }
 
+    pub fn libraries(&self) -> &Vec<Library> {
+        &self.libraries
+    }
+
+    /// Libraries which are called by the selected contract, directly or through other libraries.
+    pub fn used_libraries(&self) -> Vec<&Library> {
+        let mut used: Vec<&Library> = Vec::new();
+        let mut placeholders = Vec::from_iter(link::placeholders(self.bin_contract()));
+        while let Some(placeholder) = placeholders.pop() {
+            let library = self
+                .libraries
+                .iter()
+                .find(|library| link::placeholder(&library.path) == placeholder);
+            if let Some(library) = library {
+                if used.iter().all(|item| item.path != library.path) {
+                    placeholders.extend(link::placeholders(library.evm.bin()));
+                    used.push(library);
+                }
+            }
+        }
+        used
+    }
+
+    pub fn links(&self) -> &[LibraryLink] {
+        &self.links
+    }
+
+    /// Replaces the library placeholders in the bytecode with the addresses of the linked modules.
+    /// Fails if the selected contract still has unlinked libraries.
+    pub fn link(&self, links: &[LibraryLink]) -> Result<EvmPack> {
+        let links = links
+            .iter()
+            .map(|link| self.with_functions(link))
+            .collect::<Result<Vec<_>>>()?;
+        let links = links.as_slice();
+        let link_evm = |evm: &Evm| Evm {
+            bin: Arc::new(link::link(evm.bin(), links)),
+            ..evm.clone()
+        };
+        let pack = EvmPack {
+            contract: link_evm(&self.contract),
+            contracts: self.contracts.iter().map(link_evm).collect(),
+            modules: self.modules.iter().map(link_evm).collect(),
+            libraries: self
+                .libraries
+                .iter()
+                .map(|library| Library {
+                    path: library.path.clone(),
+                    evm: link_evm(&library.evm),
+                })
+                .collect(),
+            links: links.to_vec(),
+        };
+
+        let unlinked = link::placeholders(pack.bin_contract());
+        ensure!(
+            unlinked.is_empty(),
+            "The contract {} has unlinked libraries: {}",
+            pack.name(),
+            self.library_names(&unlinked).join(", ")
+        );
+        Ok(pack)
+    }
+
+    /// Adds the functions of the library ABI to the link if the library is compiled with the pack.
+    fn with_functions(&self, link: &LibraryLink) -> Result<LibraryLink> {
+        let library = self
+            .libraries
+            .iter()
+            .find(|library| library.path == link.path);
+        match library {
+            Some(library) if !library.evm.abi().is_empty() && link.functions.is_empty() => {
+                let abi = serde_json::from_str(library.evm.abi())?;
+                Ok(link.clone().with_abi(&abi))
+            }
+            _ => Ok(link.clone()),
+        }
+    }
+
+    /// Names of the libraries with the passed placeholders.
+    fn library_names(&self, placeholders: &BTreeSet<String>) -> Vec<String> {
+        placeholders
+            .iter()
+            .map(|placeholder| {
+                self.libraries
+                    .iter()
+                    .find(|library| link::placeholder(&library.path) == *placeholder)
+                    .map(|library| library.path.clone())
+                    .unwrap_or_else(|| placeholder.clone())
+            })
+            .collect()
+    }
+
     pub fn abi(&self) -> Result<Contract> {
         let abi = serde_json::from_str(self.contract.abi.as_str())?;
         Ok(abi)
@@ -127,6 +478,16 @@ impl EvmPack {
         self.contract.abi.as_str()
     }
 
+    /// solc `storageLayout` of the contract, if the compiler reported it.
+    pub fn storage_layout(&self) -> Option<&str> {
+        Some(self.contract.storage_layout()).filter(|layout| !layout.is_empty())
+    }
+
+    /// Yul IR of the selected contract.
+    pub fn ir(&self) -> Option<&str> {
+        Some(self.contract.ir()).filter(|ir| !ir.is_empty())
+    }
+
     pub fn code(&self) -> Result<Vec<u8>> {
         let mut result: Vec<u8> = self
             .modules
@@ -168,12 +529,81 @@ impl EvmPack {
 impl From<(Evm, Vec<Evm>)> for EvmPack {
     fn from(data: (Evm, Vec<Evm>)) -> Self {
         EvmPack {
+            contracts: vec![data.0.clone()],
             contract: data.0,
             modules: data.1,
+            libraries: Vec::new(),
+            links: Vec::new(),
         }
     }
 }
 
+/// Parses the standard-JSON output of the contract. Returns `None` for the contracts without bytecode.
+fn parse_evm(name: &str, json: &Value) -> Option<Evm> {
+    let abi = json.get("abi")?;
+    let abi_string = if abi.as_array().map(|item| !item.is_empty()).unwrap_or(false) {
+        abi.to_string()
+    } else {
+        "".to_string()
+    };
+
+    let storage_layout = match json.get("storageLayout") {
+        Some(Value::String(layout)) => layout.clone(),
+        Some(Value::Null) | None => "".to_string(),
+        Some(layout) => layout.to_string(),
+    };
+
+    let bin = json
+        .get("evm")?
+        .get("bytecode")?
+        .get("object")?
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    if bin.is_empty() {
+        return None;
+    }
+    let source_map = json
+        .get("evm")?
+        .get("bytecode")?
+        .get("sourceMap")
+        .and_then(|item| item.as_str())
+        .unwrap_or_default()
+        .to_string();
+    Some(Evm {
+        name: Arc::new(name.to_string()),
+        abi: Arc::new(abi_string),
+        bin: Arc::new(bin),
+        storage_layout: Arc::new(storage_layout),
+        source_map: Arc::new(source_map),
+        ir: Arc::new(read_ir(json)),
+    })
+}
+
+/// Yul IR of the standard-JSON output or of the artifact. The optimized IR is preferred.
+pub(crate) fn read_ir(json: &Value) -> String {
+    ["irOptimized", "ir"]
+        .iter()
+        .filter_map(|key| json.get(key)?.as_str())
+        .find(|ir| !ir.is_empty())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Reads the remappings of `remappings.txt` in the base path, one per line.
+fn read_remappings(base_path: &Path) -> Result<Vec<String>> {
+    let path = base_path.join("remappings.txt");
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(ToString::to_string)
+        .collect())
+}
+
 fn evm_bytecode(mut bytecode: Vec<u8>) -> Vec<u8> {
     remove_swarm_hash(&mut bytecode);
     bytecode
@@ -0,0 +1,314 @@
+//! Typed storage layout of the contract.
+//! Built from the solc `storageLayout` output.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use anyhow::{anyhow, Error};
+use primitive_types::U256;
+use serde::Deserialize;
+use sha3::{Digest, Keccak256};
+
+/// State variable which is kept in a field of the typed state resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub slot: U256,
+    pub kind: FieldKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    /// Value which occupies the whole slot.
+    Value,
+    /// Mapping of single-slot values.
+    Mapping(KeyType),
+}
+
+/// Native key type of a mapping field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyType {
+    Num,
+    Address,
+}
+
+/// State variables of the contract which can be kept in typed fields.
+///
+/// Packed slots, strings, bytes, arrays, structs and mappings with such values are not described
+/// here: they stay in the raw storage table.
+#[derive(Debug, Clone, Default)]
+pub struct Layout {
+    fields: Vec<Field>,
+    /// Key types of the mappings by their slots, the outer key first.
+    /// The keys of any value type are described, including the mappings which stay raw.
+    mapping_keys: BTreeMap<U256, Vec<KeyType>>,
+    /// Slots of all the state variables. The other slots are computed: the mapping entries, the
+    /// array elements and the long strings.
+    declared: BTreeSet<U256>,
+}
+
+impl Layout {
+    /// Parses the solc `storageLayout` JSON.
+    pub fn parse(json: &str) -> Result<Layout, Error> {
+        let layout: SolcLayout = serde_json::from_str(json)?;
+        let types = layout.types.unwrap_or_default();
+
+        let mut vars_per_slot: HashMap<&str, usize> = HashMap::new();
+        for var in &layout.storage {
+            *vars_per_slot.entry(var.slot.as_str()).or_default() += 1;
+        }
+
+        let mut fields = Vec::new();
+        let mut mapping_keys = BTreeMap::new();
+        let mut declared = BTreeSet::new();
+        for var in &layout.storage {
+            declared.insert(parse_slot(var)?);
+            let tp = types
+                .get(&var.tp)
+                .ok_or_else(|| anyhow!("Unknown type {} of {}", var.tp, var.label))?;
+            if tp.encoding == "mapping" {
+                mapping_keys.insert(parse_slot(var)?, nested_keys(tp, &types));
+            }
+            if var.offset != 0 || vars_per_slot[var.slot.as_str()] != 1 {
+                log::debug!("State variable {} is packed. Keeping it raw.", var.label);
+                continue;
+            }
+            let kind = match tp.encoding.as_str() {
+                "inplace" if tp.is_word() => FieldKind::Value,
+                "mapping" => match mapping_key(tp, &types) {
+                    Some(key) => FieldKind::Mapping(key),
+                    None => {
+                        log::debug!("Mapping {} is not supported. Keeping it raw.", var.label);
+                        continue;
+                    }
+                },
+                _ => {
+                    log::debug!(
+                        "State variable {} is not a word. Keeping it raw.",
+                        var.label
+                    );
+                    continue;
+                }
+            };
+            fields.push(Field {
+                name: var.label.clone(),
+                slot: parse_slot(var)?,
+                kind,
+            });
+        }
+        Ok(Layout {
+            fields,
+            mapping_keys,
+            declared,
+        })
+    }
+
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn has_mappings(&self) -> bool {
+        self.fields
+            .iter()
+            .any(|field| matches!(field.kind, FieldKind::Mapping(_)))
+    }
+
+    /// Index of the value field stored at `slot`.
+    pub fn value_field(&self, slot: U256) -> Option<usize> {
+        self.fields
+            .iter()
+            .position(|field| field.slot == slot && field.kind == FieldKind::Value)
+    }
+
+    /// Index of the mapping field declared at `slot`.
+    pub fn mapping_field(&self, slot: U256) -> Option<usize> {
+        self.fields
+            .iter()
+            .position(|field| field.slot == slot && matches!(field.kind, FieldKind::Mapping(_)))
+    }
+
+    /// Key types of the (nested) mapping declared at `slot`, the outer key first.
+    /// Empty if the slot is unknown.
+    pub fn mapping_keys(&self, slot: U256) -> &[KeyType] {
+        self.mapping_keys
+            .get(&slot)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Checks whether a state variable starts at `slot`.
+    pub fn is_declared(&self, slot: U256) -> bool {
+        self.declared.contains(&slot)
+    }
+
+    /// The same layout with the mappings kept raw.
+    pub fn without_mappings(&self) -> Layout {
+        Layout {
+            fields: self
+                .fields
+                .iter()
+                .filter(|field| field.kind == FieldKind::Value)
+                .cloned()
+                .collect(),
+            mapping_keys: self.mapping_keys.clone(),
+            declared: self.declared.clone(),
+        }
+    }
+}
+
+/// Slot of the mapping entry: `keccak256(key . slot)`.
+pub fn entry_slot(key: U256, slot: U256) -> U256 {
+    let mut buf = [0u8; 64];
+    key.to_big_endian(&mut buf[..32]);
+    slot.to_big_endian(&mut buf[32..]);
+    U256::from_big_endian(Keccak256::digest(buf).as_slice())
+}
+
+fn parse_slot(var: &SolcVar) -> Result<U256, Error> {
+    U256::from_dec_str(&var.slot)
+        .map_err(|err| anyhow!("Invalid slot {} of {}: {err:?}", var.slot, var.label))
+}
+
+fn mapping_key(tp: &SolcType, types: &HashMap<String, SolcType>) -> Option<KeyType> {
+    let key = types.get(tp.key.as_ref()?)?;
+    let value = types.get(tp.value.as_ref()?)?;
+    if value.encoding != "inplace" || !value.is_word() {
+        return None;
+    }
+    key_type(key)
+}
+
+/// Key types of the mapping and of the mappings nested into its values.
+/// Stops at the first key which is not a value type.
+fn nested_keys(tp: &SolcType, types: &HashMap<String, SolcType>) -> Vec<KeyType> {
+    let mut keys = Vec::new();
+    let mut tp = Some(tp);
+    while let Some(mapping) = tp.filter(|tp| tp.encoding == "mapping") {
+        match mapping
+            .key
+            .as_ref()
+            .and_then(|key| types.get(key))
+            .and_then(key_type)
+        {
+            Some(key) => keys.push(key),
+            None => break,
+        }
+        tp = mapping.value.as_ref().and_then(|value| types.get(value));
+    }
+    keys
+}
+
+fn key_type(key: &SolcType) -> Option<KeyType> {
+    if key.encoding != "inplace" {
+        return None;
+    }
+    if key.label.starts_with("address") || key.label.starts_with("contract ") {
+        Some(KeyType::Address)
+    } else {
+        Some(KeyType::Num)
+    }
+}
+
+#[derive(Deserialize)]
+struct SolcLayout {
+    storage: Vec<SolcVar>,
+    #[serde(default)]
+    types: Option<HashMap<String, SolcType>>,
+}
+
+#[derive(Deserialize)]
+struct SolcVar {
+    label: String,
+    offset: u64,
+    slot: String,
+    #[serde(rename = "type")]
+    tp: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SolcType {
+    encoding: String,
+    label: String,
+    number_of_bytes: String,
+    key: Option<String>,
+    value: Option<String>,
+}
+
+impl SolcType {
+    fn is_word(&self) -> bool {
+        self.number_of_bytes
+            .parse::<u64>()
+            .map(|size| size <= 32)
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use primitive_types::U256;
+
+    use crate::layout::{entry_slot, FieldKind, KeyType, Layout};
+
+    const LAYOUT: &str = r#"{
+        "storage": [
+            {"astId": 3, "contract": "t.sol:T", "label": "total", "offset": 0, "slot": "0", "type": "t_uint256"},
+            {"astId": 7, "contract": "t.sol:T", "label": "balances", "offset": 0, "slot": "1", "type": "t_mapping(t_address,t_uint256)"},
+            {"astId": 9, "contract": "t.sol:T", "label": "flag", "offset": 0, "slot": "2", "type": "t_bool"},
+            {"astId": 11, "contract": "t.sol:T", "label": "owner", "offset": 1, "slot": "2", "type": "t_address"},
+            {"astId": 13, "contract": "t.sol:T", "label": "name", "offset": 0, "slot": "3", "type": "t_string_storage"},
+            {"astId": 19, "contract": "t.sol:T", "label": "allowances", "offset": 0, "slot": "4", "type": "t_mapping(t_address,t_mapping(t_uint256,t_uint256))"}
+        ],
+        "types": {
+            "t_address": {"encoding": "inplace", "label": "address", "numberOfBytes": "20"},
+            "t_bool": {"encoding": "inplace", "label": "bool", "numberOfBytes": "1"},
+            "t_mapping(t_address,t_mapping(t_uint256,t_uint256))": {"encoding": "mapping", "key": "t_address", "label": "mapping(address => mapping(uint256 => uint256))", "numberOfBytes": "32", "value": "t_mapping(t_uint256,t_uint256)"},
+            "t_mapping(t_uint256,t_uint256)": {"encoding": "mapping", "key": "t_uint256", "label": "mapping(uint256 => uint256)", "numberOfBytes": "32", "value": "t_uint256"},
+            "t_mapping(t_address,t_uint256)": {"encoding": "mapping", "key": "t_address", "label": "mapping(address => uint256)", "numberOfBytes": "32", "value": "t_uint256"},
+            "t_string_storage": {"encoding": "bytes", "label": "string", "numberOfBytes": "32"},
+            "t_uint256": {"encoding": "inplace", "label": "uint256", "numberOfBytes": "32"}
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_layout() {
+        let layout = Layout::parse(LAYOUT).unwrap();
+        let fields = layout.fields();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].name, "total");
+        assert_eq!(fields[0].kind, FieldKind::Value);
+        assert_eq!(fields[1].name, "balances");
+        assert_eq!(fields[1].kind, FieldKind::Mapping(KeyType::Address));
+        assert_eq!(layout.value_field(U256::zero()), Some(0));
+        assert_eq!(layout.mapping_field(U256::one()), Some(1));
+        assert_eq!(layout.value_field(U256::from(2)), None);
+
+        assert!(layout.is_declared(U256::from(2)));
+        assert!(!layout.is_declared(entry_slot(U256::from(0x42), U256::one())));
+
+        assert_eq!(layout.mapping_keys(U256::one()), &[KeyType::Address]);
+        assert_eq!(
+            layout.mapping_keys(U256::from(4)),
+            &[KeyType::Address, KeyType::Num]
+        );
+        assert!(layout.mapping_keys(U256::zero()).is_empty());
+    }
+
+    #[test]
+    fn test_without_mappings() {
+        let layout = Layout::parse(LAYOUT).unwrap().without_mappings();
+        assert_eq!(layout.fields().len(), 1);
+        assert_eq!(layout.value_field(U256::zero()), Some(0));
+        assert!(!layout.has_mappings());
+        assert_eq!(layout.mapping_keys(U256::one()), &[KeyType::Address]);
+    }
+
+    #[test]
+    fn test_empty_layout() {
+        let layout = Layout::parse(r#"{"storage": [], "types": null}"#).unwrap();
+        assert!(layout.is_empty());
+    }
+}
@@ -1,10 +1,10 @@
 //! Simple EVM-bytecode disassembler.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use anyhow::Error;
+use anyhow::{anyhow, Error};
 use ethabi::Contract;
-use log::{log_enabled, trace};
+use log::{debug, log_enabled, trace};
 use primitive_types::U256;
 
 use bytecode::block::BlockIter;
@@ -17,17 +17,48 @@ use crate::abi::call::FunHash;
 use crate::abi::MoveAbi;
 use crate::bytecode::block::Offset;
 use crate::bytecode::hir::ir::Hir;
-use crate::bytecode::hir::HirBuilder;
+use crate::bytecode::hir::{HirBuilder, HirTranslator};
+use crate::bytecode::mir::constructor::make_constructor;
 use crate::bytecode::mir::ir::Mir;
 use crate::bytecode::mir::translation::MirTranslator;
 use crate::bytecode::types::Function;
+use crate::layout::Layout;
+use crate::link::LibraryLink;
 use crate::vm::static_initialization;
+use crate::yul::YulHirBuilder;
 
 pub mod abi;
+pub mod artifact;
 pub mod bytecode;
 pub mod compile;
+pub mod layout;
+pub mod link;
 pub mod program;
 pub mod vm;
+pub mod vyper;
+pub mod yul;
+
+/// Source of the function bodies.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Frontend<'a> {
+    /// The functions are traced through the EVM bytecode.
+    #[default]
+    Bytecode,
+    /// The functions are built from the Yul IR of the contract (solc `--ir` or `--ir-optimized`).
+    Yul(&'a str),
+}
+
+/// Settings of the program translation.
+#[derive(Debug, Clone, Default)]
+pub struct TranspileOptions<'a> {
+    pub flags: Flags,
+    pub layout: Layout,
+    /// External libraries linked into the bytecode.
+    pub libraries: &'a [LibraryLink],
+    pub frontend: Frontend<'a>,
+    /// Keep the printed HIR of the functions in the program. Only the snapshots need it.
+    pub print_hir: bool,
+}
 
 pub fn transpile_program(
     name: &str,
@@ -35,9 +66,16 @@ pub fn transpile_program(
     init_args: &str,
     abi_entries: &Contract,
     contract_addr: U256,
-    flags: Flags,
+    options: TranspileOptions,
 ) -> Result<Program, Error> {
-    let (contract_code, constructor) =
+    let TranspileOptions {
+        flags,
+        layout,
+        libraries,
+        frontend,
+        print_hir,
+    } = options;
+    let (contract_code, store) =
         static_initialization(bytecode_str, abi_entries, init_args, contract_addr)?;
     if log_enabled!(log::Level::Trace) {
         trace!("Bytecode: {}", &hex::encode(&contract_code));
@@ -45,37 +83,127 @@ pub fn transpile_program(
 
     let abi = MoveAbi::new(name, abi_entries)?;
 
-    let mut instructions = InstructionIter::new(contract_code);
-    let contract = BlockIter::new(&mut instructions)
-        .map(|block| (block.start, block))
-        .collect::<HashMap<_, _>>();
-
-    let contract_code = instructions.into_inner();
-    let hir = HirBuilder::new(contract, flags, contract_code)?;
-    let functions = abi
-        .functions()
-        .iter()
-        .map(|(hash, fun)| {
-            translate_function(&hir, fun, contract_addr, flags).map(|mir| (*hash, mir))
-        })
-        .collect::<Result<HashMap<FunHash, Mir>, _>>()?;
-    Program::new(constructor, functions, abi)
+    // The entries of the typed mappings are kept only in their fields. If the constructor writes
+    // an entry, its key is unknown, so the mappings stay raw.
+    let layout = match store.keys().find(|slot| !layout.is_declared(**slot)) {
+        Some(slot) if layout.has_mappings() => {
+            debug!("The constructor writes the computed slot {slot}. Keeping the mappings raw.");
+            layout.without_mappings()
+        }
+        _ => layout,
+    };
+
+    let (layout, (hir, functions)) = match frontend {
+        Frontend::Bytecode => {
+            let mut instructions = InstructionIter::new(contract_code);
+            let contract = BlockIter::new(&mut instructions)
+                .map(|block| (block.start, block))
+                .collect::<HashMap<_, _>>();
+
+            let contract_code = instructions.into_inner();
+            let build = |layout| {
+                HirBuilder::new(
+                    contract.clone(),
+                    flags,
+                    contract_code.clone(),
+                    layout,
+                    libraries.to_vec(),
+                )
+            };
+            translate_typed(build, layout, &abi, contract_addr, flags, print_hir)?
+        }
+        Frontend::Yul(ir) => {
+            let build = |layout| YulHirBuilder::new(ir, flags, contract_code.clone(), layout);
+            translate_typed(build, layout, &abi, contract_addr, flags, print_hir)?
+        }
+    };
+    let constructor = make_constructor(store, &layout);
+    Program::new(constructor, functions, hir, abi, layout)
 }
 
+/// Translates the functions with the typed mappings of the layout if every access to the storage
+/// by a computed slot is matched as their entry. Otherwise the functions are translated again with
+/// the mappings kept raw: an unmatched slot may be equal to the raw slot of a typed entry.
+/// Returns the layout of the translated functions.
+fn translate_typed<H: HirTranslator>(
+    build: impl Fn(Layout) -> Result<H, Error>,
+    layout: Layout,
+    abi: &MoveAbi,
+    contract_addr: U256,
+    flags: Flags,
+    print_hir: bool,
+) -> Result<(Layout, Functions), Error> {
+    if layout.has_mappings() {
+        let hir = build(layout.clone())?;
+        if let Some(functions) = translate_functions(&hir, abi, contract_addr, flags, print_hir)? {
+            return Ok((layout, functions));
+        }
+    }
+    let layout = layout.without_mappings();
+    let hir = build(layout.clone())?;
+    let functions = translate_functions(&hir, abi, contract_addr, flags, print_hir)?
+        .ok_or_else(|| anyhow!("Untyped slots without the typed mappings"))?;
+    Ok((layout, functions))
+}
+
+/// Printed HIR and MIR of the functions.
+type Functions = (BTreeMap<FunHash, String>, BTreeMap<FunHash, Mir>);
+
+/// Translates the functions. Returns `None` if a function accesses the raw storage by a computed
+/// slot while the layout has typed mappings.
+fn translate_functions(
+    hir: &impl HirTranslator,
+    abi: &MoveAbi,
+    contract_addr: U256,
+    flags: Flags,
+    print_hir: bool,
+) -> Result<Option<Functions>, Error> {
+    let mut hir_functions = BTreeMap::new();
+    let mut mir_functions = BTreeMap::new();
+    for (hash, fun) in abi.functions() {
+        let (hir, mir) = match translate_function(hir, fun, contract_addr, flags, print_hir)? {
+            Some(translated) => translated,
+            None => {
+                debug!(
+                    "{} accesses the storage by a computed slot. Keeping the mappings raw.",
+                    fun.name
+                );
+                return Ok(None);
+            }
+        };
+        if let Some(hir) = hir {
+            hir_functions.insert(*hash, hir);
+        }
+        mir_functions.insert(*hash, mir);
+    }
+    Ok(Some((hir_functions, mir_functions)))
+}
+
+/// Returns the MIR of the function and, if `print_hir` is set, its printed HIR.
+/// Returns `None` if the function accesses the raw storage by a computed slot while the layout has
+/// typed mappings.
 pub fn translate_function(
-    hir: &HirBuilder,
+    hir_translator: &impl HirTranslator,
     fun: &Function,
     contract_addr: U256,
     flags: Flags,
-) -> Result<Mir, Error> {
-    let hir = hir.translate_fun(fun, contract_addr)?;
-    let mut buff = String::new();
-    hir.print(&mut buff)?;
-    trace!("{}", buff);
-    let mir_translator = MirTranslator::new(fun, flags);
+    print_hir: bool,
+) -> Result<Option<(Option<String>, Mir)>, Error> {
+    let hir = hir_translator.translate_fun(fun, contract_addr)?;
+    if hir.has_untyped_slots() {
+        return Ok(None);
+    }
+    let mut buff = None;
+    if print_hir || log_enabled!(log::Level::Trace) {
+        let mut printed = String::new();
+        hir.print(&mut printed)?;
+        trace!("{}", printed);
+        buff = Some(printed).filter(|_| print_hir);
+    }
+    let mir_translator = MirTranslator::new(fun, flags, hir_translator.layout());
     let mir = mir_translator.translate(hir)?;
     mir.print(&fun.name);
-    Ok(mir)
+    Ok(Some((buff, mir)))
 }
 
 pub fn parse_bytecode(input: &str) -> Result<Vec<u8>, Error> {
@@ -96,6 +224,8 @@ pub struct Flags {
     pub native_output: bool,
     pub hidden_output: bool,
     pub u128_io: bool,
+    /// Keep the mapping entries by their native keys instead of the hashed slots.
+    pub native_mappings: bool,
 }
 
 #[allow(clippy::derivable_impls)]
@@ -106,6 +236,7 @@ impl Default for Flags {
             native_output: false,
             hidden_output: false,
             u128_io: false,
+            native_mappings: false,
         }
     }
 }
@@ -117,6 +248,7 @@ impl Flags {
             native_output: true,
             hidden_output: false,
             u128_io: false,
+            native_mappings: false,
         }
     }
 }
@@ -0,0 +1,164 @@
+//! Linking of the external Solidity libraries.
+//!
+//! solc leaves a `__$<hash>$__` placeholder in the bytecode for every address of a library with
+//! external functions. The library is linked to a Move module, and the placeholders are replaced
+//! with an EVM address derived from the module id. The calls of the library are translated into
+//! the calls of the module functions with the same names.
+
+use std::collections::BTreeSet;
+
+use ethabi::Contract;
+use move_core_types::language_storage::ModuleId;
+use primitive_types::{H160, U256};
+use sha3::{Digest, Keccak256};
+
+use crate::abi::call::FunHash;
+
+/// Length of the library placeholder in the hex bytecode.
+const PLACEHOLDER_LEN: usize = 40;
+
+/// Library linked to a Move module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryLink {
+    /// Fully qualified name of the library as `<source unit>:<name>`.
+    pub path: String,
+    /// Move module which implements the library.
+    pub module: ModuleId,
+    /// External functions of the library as (selector, name). They are known from the library ABI.
+    pub functions: Vec<(FunHash, String)>,
+}
+
+impl LibraryLink {
+    pub fn new<S: Into<String>>(path: S, module: ModuleId) -> LibraryLink {
+        LibraryLink {
+            path: path.into(),
+            module,
+            functions: vec![],
+        }
+    }
+
+    /// Adds the external functions of the library ABI.
+    pub fn with_abi(self, abi: &Contract) -> LibraryLink {
+        LibraryLink {
+            functions: abi
+                .functions()
+                .map(|fun| (FunHash::from(fun.short_signature()), fun.name.clone()))
+                .collect(),
+            ..self
+        }
+    }
+
+    /// Name of the function which is called with the selector at the head of the memory word.
+    pub fn function(&self, word: U256) -> Option<&str> {
+        self.functions
+            .iter()
+            .find(|(hash, _)| hash.as_frame() == word)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Short name of the library.
+    pub fn name(&self) -> &str {
+        library_name(&self.path)
+    }
+
+    /// EVM address of the library in the linked bytecode.
+    pub fn address(&self) -> H160 {
+        let hash = Keccak256::digest(self.module.short_str_lossless().as_bytes());
+        H160::from_slice(&hash[12..])
+    }
+
+    pub fn encoded_address(&self) -> U256 {
+        U256::from_big_endian(self.address().as_bytes())
+    }
+
+    pub fn placeholder(&self) -> String {
+        placeholder(&self.path)
+    }
+}
+
+/// Placeholder of the library with the fully qualified name `path` in the hex bytecode.
+pub fn placeholder(path: &str) -> String {
+    let hash = hex::encode(Keccak256::digest(path.as_bytes()));
+    format!("__${}$__", &hash[..PLACEHOLDER_LEN - 6])
+}
+
+/// Short name of the library with the fully qualified name `path`.
+pub fn library_name(path: &str) -> &str {
+    path.rsplit_once(':').map(|(_, name)| name).unwrap_or(path)
+}
+
+/// Placeholders of the unlinked libraries in the hex bytecode.
+pub fn placeholders(bin: &str) -> BTreeSet<String> {
+    let mut placeholders = BTreeSet::new();
+    let mut rest = bin;
+    while let Some(start) = rest.find("__$") {
+        let end = (start + PLACEHOLDER_LEN).min(rest.len());
+        placeholders.insert(rest[start..end].to_string());
+        rest = &rest[end..];
+    }
+    placeholders
+}
+
+/// Replaces the placeholders of the linked libraries with their addresses.
+pub fn link(bin: &str, links: &[LibraryLink]) -> String {
+    links.iter().fold(bin.to_string(), |bin, link| {
+        bin.replace(&link.placeholder(), &hex::encode(link.address().as_bytes()))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use ethabi::Contract;
+    use move_core_types::account_address::AccountAddress;
+    use move_core_types::identifier::Identifier;
+    use move_core_types::language_storage::ModuleId;
+
+    use crate::abi::call::FunHash;
+    use crate::link::{link, placeholder, placeholders, LibraryLink};
+
+    #[test]
+    fn test_link() {
+        let path = "contracts/math.sol:Math";
+        // solc: `__$` + the first 17 bytes of keccak256(path) + `$__`.
+        let placeholder = placeholder(path);
+        assert_eq!(placeholder.len(), 40);
+        assert!(placeholder.starts_with("__$") && placeholder.ends_with("$__"));
+
+        let bin = format!("6080{placeholder}01{placeholder}00");
+        assert_eq!(
+            placeholders(&bin).into_iter().collect::<Vec<_>>(),
+            vec![placeholder.clone()]
+        );
+
+        let module = ModuleId::new(
+            AccountAddress::from_hex_literal("0x42").unwrap(),
+            Identifier::new("Math").unwrap(),
+        );
+        let library = LibraryLink::new(path, module);
+        assert_eq!(library.name(), "Math");
+        let address = hex::encode(library.address().as_bytes());
+        let linked = link(&bin, &[library]);
+        assert!(placeholders(&linked).is_empty());
+        assert_eq!(linked, format!("6080{address}01{address}00"));
+    }
+
+    #[test]
+    fn test_library_functions() {
+        let abi: Contract = serde_json::from_str(
+            r#"[{"type":"function","name":"double","stateMutability":"pure",
+                "inputs":[{"name":"a","type":"uint256"}],"outputs":[{"name":"","type":"uint256"}]}]"#,
+        )
+        .unwrap();
+        let module = ModuleId::new(
+            AccountAddress::from_hex_literal("0x42").unwrap(),
+            Identifier::new("Calc").unwrap(),
+        );
+        let library = LibraryLink::new("calc.sol:Calc", module).with_abi(&abi);
+
+        let selector = FunHash::from(abi.function("double").unwrap().short_signature()).as_frame();
+        assert_eq!(library.function(selector), Some("double"));
+        // Only the selector is set in the memory word of the call data head.
+        assert_eq!(library.function(selector + 1), None);
+        assert_eq!(library.function(selector >> 224), None);
+    }
+}
@@ -1,27 +1,35 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::{Debug, Formatter};
 
 use anyhow::Error;
 
 use crate::abi::call::FunHash;
+use crate::layout::Layout;
 use crate::{Function, Mir, MoveAbi};
 
 pub struct Program {
     constructor: Mir,
-    functions_mir: HashMap<FunHash, Mir>,
+    functions_mir: BTreeMap<FunHash, Mir>,
+    /// Printed HIR of the functions.
+    functions_hir: BTreeMap<FunHash, String>,
     abi: MoveAbi,
+    layout: Layout,
 }
 
 impl Program {
     pub fn new(
         constructor: Mir,
-        functions_mir: HashMap<FunHash, Mir>,
+        functions_mir: BTreeMap<FunHash, Mir>,
+        functions_hir: BTreeMap<FunHash, String>,
         abi: MoveAbi,
+        layout: Layout,
     ) -> Result<Program, Error> {
         Ok(Program {
             constructor,
             functions_mir,
+            functions_hir,
             abi,
+            layout,
         })
     }
 
@@ -48,6 +56,11 @@ impl Program {
     pub fn constructor_mir(&self) -> &Mir {
         &self.constructor
     }
+
+    /// Typed state variables of the contract.
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
 }
 
 impl Debug for Program {
@@ -93,4 +106,20 @@ impl Program {
 
         output
     }
+
+    /// Printed HIR of the functions in the order of the ABI.
+    pub fn debug_hir(&self) -> String {
+        let mut output = String::new();
+        for fun in self.abi.functions().values() {
+            output += format!("public fun {}", fun).as_str();
+            output += " {\n";
+            if let Some(hir) = self.functions_hir.get(&fun.hash) {
+                output += hir;
+            } else {
+                output += "undefined\n";
+            }
+            output += "}\n";
+        }
+        output
+    }
 }
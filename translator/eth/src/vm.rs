@@ -1,7 +1,7 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::BTreeMap;
 use std::rc::Rc;
 
-use anyhow::{anyhow, bail, Error};
+use anyhow::{anyhow, bail, ensure, Error};
 use ethabi::Contract;
 use evm::backend::{MemoryBackend, MemoryVicinity};
 use evm::executor::stack::{MemoryStackState, StackExecutor, StackSubstateMetadata};
@@ -9,29 +9,33 @@ use evm::{Config, Context, ExitReason, Runtime};
 use primitive_types::{H160, H256, U256};
 
 use crate::abi::call::EthEncodeByString;
-use crate::bytecode::mir::constructor::make_constructor;
-use crate::Mir;
+use crate::link::placeholders;
 
+/// Runs the constructor. Returns the runtime code and the storage written by the constructor.
 pub fn static_initialization(
     bytecode: &str,
     abi: &Contract,
     args_str: &str,
     contract_addr: U256,
-) -> Result<(Vec<u8>, Mir), Error> {
+) -> Result<(Vec<u8>, BTreeMap<U256, U256>), Error> {
+    let unlinked = placeholders(bytecode);
+    ensure!(
+        unlinked.is_empty(),
+        "The bytecode has unlinked libraries: {}",
+        unlinked.into_iter().collect::<Vec<_>>().join(", ")
+    );
     let mut code = hex::decode(bytecode)?;
     let params = constructor_encode_params(abi, args_str)?;
     if !params.is_empty() {
         code.extend(&params[4..]);
     }
-    let (contract_code, store) = run_initialization(code, contract_addr)?;
-    let mir = make_constructor(store);
-    Ok((contract_code, mir))
+    run_initialization(code, contract_addr)
 }
 
 fn run_initialization(
     code: Vec<u8>,
     contract_addr: U256,
-) -> Result<(Vec<u8>, HashMap<U256, U256>), Error> {
+) -> Result<(Vec<u8>, BTreeMap<U256, U256>), Error> {
     let contract_addr = map_addr(contract_addr);
     let ctx = context(contract_addr);
     let cfg = Config::london();
@@ -49,7 +53,7 @@ fn run_initialization(
     let mut rt = Runtime::new(Rc::new(code), Rc::new(vec![]), ctx, &cfg);
     let exit_reason = executor.execute(&mut rt);
     let store = &executor.state().substate.storages;
-    let mut state = HashMap::new();
+    let mut state = BTreeMap::new();
     for ((addr, key), storage) in store {
         if *addr == contract_addr {
             state.insert(map_num(*key), map_num(*storage));
@@ -74,7 +78,7 @@ fn vicinity() -> MemoryVicinity {
     MemoryVicinity {
         block_base_fee_per_gas: U256::max_value(),
         gas_price: U256::from(1),
-        origin: H160::random(),
+        origin: H160::zero(),
         chain_id: U256::from(1u8),
         block_hashes: vec![
             "00000000000000001ebf88508a03865c71d452e25f4d51194196a1d22b6653dc"
@@ -0,0 +1,73 @@
+//! Vyper contracts.
+//!
+//! The contract is compiled by the local `vyper` binary into the abi and the creation bytecode
+//! and is named after the file. Vyper reports neither the storage layout in the solc format nor
+//! the Yul IR, so the state is kept in the raw slots and the functions are traced through the
+//! bytecode.
+
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+
+use anyhow::{anyhow, ensure, Result};
+use serde_json::Value;
+
+use crate::compile::{Evm, EvmPack};
+
+/// Target EVM version. The last version without `PUSH0`, which the translator doesn't support.
+const EVM_VERSION: &str = "paris";
+
+pub fn build_vy<P: AsRef<Path>>(path: P) -> Result<EvmPack> {
+    build_vy_with(path, Path::new("vyper"))
+}
+
+/// Compiles the vy file with the passed vyper binary.
+pub fn build_vy_with<P: AsRef<Path>>(path: P, vyper: &Path) -> Result<EvmPack> {
+    let path = path.as_ref().canonicalize()?;
+    let output = Command::new(vyper)
+        .arg("-f")
+        .arg("abi,bytecode")
+        .arg("--evm-version")
+        .arg(EVM_VERSION)
+        .arg(&path)
+        .output()
+        .map_err(|err| anyhow!("Failed to run {vyper:?}: {err}"))?;
+    ensure!(
+        output.status.success(),
+        "Compilation error:\n{}",
+        String::from_utf8(output.stderr).unwrap_or_default()
+    );
+
+    // The formats are printed line by line in the requested order.
+    let stdout = String::from_utf8(output.stdout)?;
+    let mut lines = stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+    let abi = lines
+        .next()
+        .ok_or_else(|| anyhow!("vyper didn't report the abi of {path:?}"))?;
+    let abi = serde_json::from_str::<Value>(abi)
+        .map_err(|err| anyhow!("Invalid abi of {path:?}: {err}"))?;
+    let bin = lines
+        .next()
+        .ok_or_else(|| anyhow!("vyper didn't report the bytecode of {path:?}"))?;
+    let bin = bin.trim_start_matches("0x");
+    ensure!(!bin.is_empty(), "The contract has no bytecode: {path:?}");
+
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .ok_or_else(|| anyhow!("Invalid vy path:{path:?}"))?;
+    Ok(EvmPack::from((
+        Evm {
+            name: Arc::new(name),
+            bin: Arc::new(bin.to_string()),
+            abi: Arc::new(abi.to_string()),
+            storage_layout: Arc::new("".to_string()),
+            source_map: Arc::new("".to_string()),
+            ir: Arc::new("".to_string()),
+        },
+        Vec::new(),
+    )))
+}
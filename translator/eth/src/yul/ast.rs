@@ -0,0 +1,103 @@
+use primitive_types::U256;
+
+use crate::bytecode::loc::Loc;
+
+/// Yul object with the code and the nested objects.
+#[derive(Debug, Clone)]
+pub struct Object {
+    pub name: String,
+    pub code: Block,
+    pub objects: Vec<Object>,
+}
+
+impl Object {
+    /// Object which holds the runtime code of the contract.
+    /// solc names it `<contract>_deployed`, the creation code has no other nested objects.
+    pub fn deployed(&self) -> &Object {
+        self.objects
+            .iter()
+            .find(|object| object.name.ends_with("_deployed"))
+            .or_else(|| self.objects.first())
+            .unwrap_or(self)
+    }
+}
+
+pub type Block = Vec<Statement>;
+
+pub type Statement = Loc<_Statement>;
+
+#[derive(Debug, Clone)]
+pub enum _Statement {
+    Block(Block),
+    FunctionDefinition(FunctionDefinition),
+    /// `let a, b := value`. The variables are zero without a value.
+    Let(Vec<String>, Option<Expression>),
+    Assign(Vec<String>, Expression),
+    Expression(Expression),
+    If(Expression, Block),
+    Switch {
+        value: Expression,
+        cases: Vec<(U256, Block)>,
+        default: Option<Block>,
+    },
+    For {
+        init: Block,
+        cnd: Expression,
+        post: Block,
+        body: Block,
+    },
+    Break,
+    Continue,
+    Leave,
+}
+
+#[derive(Debug, Clone)]
+pub struct FunctionDefinition {
+    pub name: String,
+    pub params: Vec<String>,
+    pub returns: Vec<String>,
+    pub body: Block,
+}
+
+pub type Expression = Loc<_Expression>;
+
+#[derive(Debug, Clone)]
+pub enum _Expression {
+    Literal(U256),
+    Identifier(String),
+    /// Builtin or user-defined function call.
+    Call(String, Vec<Expression>),
+}
+
+/// Names of the variables which are assigned in the block, not counting the nested functions.
+pub fn assigned_vars(block: &Block, vars: &mut Vec<String>) {
+    for stmt in block {
+        match stmt.as_ref() {
+            _Statement::Block(block) => assigned_vars(block, vars),
+            _Statement::Let(names, _) | _Statement::Assign(names, _) => {
+                vars.extend(names.iter().cloned())
+            }
+            _Statement::If(_, block) => assigned_vars(block, vars),
+            _Statement::Switch { cases, default, .. } => {
+                for (_, block) in cases {
+                    assigned_vars(block, vars);
+                }
+                if let Some(block) = default {
+                    assigned_vars(block, vars);
+                }
+            }
+            _Statement::For {
+                init, post, body, ..
+            } => {
+                assigned_vars(init, vars);
+                assigned_vars(post, vars);
+                assigned_vars(body, vars);
+            }
+            _Statement::FunctionDefinition(_)
+            | _Statement::Expression(_)
+            | _Statement::Break
+            | _Statement::Continue
+            | _Statement::Leave => {}
+        }
+    }
+}
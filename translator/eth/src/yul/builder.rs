@@ -0,0 +1,823 @@
+use std::collections::{HashMap, HashSet};
+use std::iter;
+
+use anyhow::{anyhow, bail, ensure, Error};
+use primitive_types::U256;
+
+use crate::bytecode::hir::context::{Context, Tracking};
+use crate::bytecode::hir::executor::math::{BinaryOp, UnaryOp};
+use crate::bytecode::hir::executor::memory::{reads_scratch, write_scratch};
+use crate::bytecode::hir::executor::{ExecutionResult, InstructionHandler};
+use crate::bytecode::hir::ir::{_Expr, Expr, Label, VarId};
+use crate::bytecode::hir::HirTranslator;
+use crate::bytecode::instruction::Instruction;
+use crate::bytecode::loc::Loc;
+use crate::layout::Layout;
+use crate::yul::ast::{
+    _Expression, _Statement, assigned_vars, Block, Expression, FunctionDefinition, Object,
+    Statement,
+};
+use crate::yul::parser::parse;
+use crate::{Flags, Function, Hir, OpCode};
+
+/// Builds the HIR of the contract functions from the runtime object of the Yul IR.
+pub struct YulHirBuilder {
+    object: Object,
+    flags: Flags,
+    contract_code: Vec<u8>,
+    layout: Layout,
+}
+
+impl YulHirBuilder {
+    pub fn new(
+        ir: &str,
+        flags: Flags,
+        contract_code: Vec<u8>,
+        layout: Layout,
+    ) -> Result<Self, Error> {
+        let object = parse(ir)?.deployed().clone();
+        Ok(Self {
+            object,
+            flags,
+            contract_code,
+            layout,
+        })
+    }
+}
+
+impl HirTranslator for YulHirBuilder {
+    fn translate_fun(&self, fun: &Function, contract_address: U256) -> Result<Hir, Error> {
+        let ctx = Context::new(
+            fun,
+            contract_address,
+            self.flags,
+            &self.contract_code,
+            &self.layout,
+        );
+        let mut translator = FunTranslator::new(ctx);
+        if translator.block(&self.object.code)? {
+            // The code which runs to its end stops.
+            translator.ir.stop(&translator.ctx.loc);
+        }
+
+        let mut ir = Hir::default();
+        if translator.var_seq != 0 {
+            let loc = Loc::default();
+            let vars = (0..translator.var_seq)
+                .map(|idx| (VarId::new_var(idx), loc.wrap(_Expr::Val(U256::zero()))))
+                .collect();
+            ir.save_stack(&loc, vars);
+        }
+        ir.append(translator.ir);
+        Ok(ir)
+    }
+
+    fn layout(&self) -> &Layout {
+        &self.layout
+    }
+}
+
+/// Values which are known on the path.
+#[derive(Clone)]
+struct State {
+    /// Constants and mapping slots of the variables.
+    known: HashMap<VarId, Expr>,
+    /// Variables which hold a mapping slot on some of the joined paths only.
+    unresolved_slots: HashSet<VarId>,
+    tracking: Tracking,
+}
+
+/// Targets of `break` and `continue` with the states which reach them.
+struct Loop {
+    brk: Label,
+    cont: Label,
+    brk_states: Vec<State>,
+    cont_states: Vec<State>,
+}
+
+/// Target of `leave` in the inlined function.
+struct Leave {
+    label: Label,
+    states: Vec<State>,
+}
+
+/// Translates the Yul code for the selected function.
+/// The user-defined functions are inlined, the Yul variables become the HIR variables.
+/// The constants are propagated, so the dispatcher is resolved statically.
+struct FunTranslator<'a, 'b, 'c> {
+    ctx: Context<'b, 'c>,
+    ir: Hir,
+    vars: Vec<HashMap<&'a str, VarId>>,
+    functions: Vec<HashMap<&'a str, &'a FunctionDefinition>>,
+    known: HashMap<VarId, Expr>,
+    unresolved_slots: HashSet<VarId>,
+    var_seq: u32,
+    loops: Vec<Loop>,
+    leave: Vec<Leave>,
+    calls: Vec<&'a str>,
+}
+
+impl<'a, 'b, 'c> FunTranslator<'a, 'b, 'c> {
+    fn new(ctx: Context<'b, 'c>) -> Self {
+        Self {
+            ctx,
+            ir: Hir::default(),
+            vars: vec![],
+            functions: vec![],
+            known: HashMap::new(),
+            unresolved_slots: HashSet::new(),
+            var_seq: 0,
+            loops: vec![],
+            leave: vec![],
+            calls: vec![],
+        }
+    }
+
+    /// Translates the block. Returns `false` if the block never runs to its end.
+    fn block(&mut self, block: &'a Block) -> Result<bool, Error> {
+        self.enter_scope(block);
+        let result = self.statements(block);
+        self.exit_scope();
+        result
+    }
+
+    fn enter_scope(&mut self, block: &'a Block) {
+        let functions = block
+            .iter()
+            .filter_map(|stmt| match stmt.as_ref() {
+                _Statement::FunctionDefinition(fun) => Some((fun.name.as_str(), fun)),
+                _ => None,
+            })
+            .collect();
+        self.functions.push(functions);
+        self.vars.push(HashMap::new());
+    }
+
+    fn exit_scope(&mut self) {
+        self.functions.pop();
+        if let Some(vars) = self.vars.pop() {
+            for var in vars.values() {
+                self.known.remove(var);
+                self.unresolved_slots.remove(var);
+            }
+        }
+    }
+
+    fn statements(&mut self, block: &'a Block) -> Result<bool, Error> {
+        for stmt in block {
+            if !self.statement(stmt)? {
+                // The rest of the block is unreachable.
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn statement(&mut self, stmt: &'a Statement) -> Result<bool, Error> {
+        self.ctx.loc = stmt.wrap(());
+        let loc = self.ctx.loc;
+        match stmt.as_ref() {
+            _Statement::Block(block) => self.block(block),
+            _Statement::FunctionDefinition(_) => Ok(true),
+            _Statement::Let(names, value) => {
+                let values = match value {
+                    Some(value) => match self.values(value)? {
+                        Some(values) => values,
+                        None => return Ok(false),
+                    },
+                    None => names.iter().map(|_| zero(&loc)).collect(),
+                };
+                ensure!(
+                    values.len() == names.len(),
+                    "Expected {} values for {}, found {}",
+                    names.len(),
+                    names.join(", "),
+                    values.len()
+                );
+                for (name, value) in names.iter().zip(values) {
+                    let var = self.declare(name);
+                    self.assign(&loc, var, value);
+                }
+                Ok(true)
+            }
+            _Statement::Assign(names, value) => {
+                let values = match self.values(value)? {
+                    Some(values) => values,
+                    None => return Ok(false),
+                };
+                ensure!(
+                    values.len() == names.len(),
+                    "Expected {} values for {}, found {}",
+                    names.len(),
+                    names.join(", "),
+                    values.len()
+                );
+                for (name, value) in names.iter().zip(values) {
+                    let var = self.lookup(name)?;
+                    self.assign(&loc, var, value);
+                }
+                Ok(true)
+            }
+            _Statement::Expression(expr) => match self.values(expr)? {
+                Some(values) => {
+                    ensure!(
+                        values.is_empty(),
+                        "The values of the expression statement are not used"
+                    );
+                    Ok(true)
+                }
+                None => Ok(false),
+            },
+            _Statement::If(cnd, body) => self.if_(&loc, cnd, body),
+            _Statement::Switch {
+                value,
+                cases,
+                default,
+            } => self.switch(&loc, value, cases, default.as_ref()),
+            _Statement::For {
+                init,
+                cnd,
+                post,
+                body,
+            } => {
+                self.enter_scope(init);
+                let result = self.for_(&loc, init, cnd, post, body);
+                self.exit_scope();
+                result
+            }
+            _Statement::Break => {
+                let state = self.jump_state();
+                let lp = self
+                    .loops
+                    .last_mut()
+                    .ok_or_else(|| anyhow!("`break` outside of a loop"))?;
+                lp.brk_states.push(state);
+                self.ir.goto(&loc, lp.brk);
+                Ok(false)
+            }
+            _Statement::Continue => {
+                let state = self.jump_state();
+                let lp = self
+                    .loops
+                    .last_mut()
+                    .ok_or_else(|| anyhow!("`continue` outside of a loop"))?;
+                lp.cont_states.push(state);
+                self.ir.goto(&loc, lp.cont);
+                Ok(false)
+            }
+            _Statement::Leave => {
+                let state = self.jump_state();
+                let leave = self
+                    .leave
+                    .last_mut()
+                    .ok_or_else(|| anyhow!("`leave` outside of a function"))?;
+                leave.states.push(state);
+                self.ir.goto(&loc, leave.label);
+                Ok(false)
+            }
+        }
+    }
+
+    fn if_(&mut self, loc: &Loc<()>, cnd: &'a Expression, body: &'a Block) -> Result<bool, Error> {
+        let cnd = match self.value(cnd)? {
+            Some(cnd) => cnd,
+            None => return Ok(false),
+        };
+        if let Some(cnd) = cnd.resolve(&self.ctx) {
+            return if cnd.is_zero() {
+                Ok(true)
+            } else {
+                self.block(body)
+            };
+        }
+
+        write_scratch(&mut self.ir, &mut self.ctx);
+        let end = self.next_label();
+        self.ir.true_brunch(loc, is_zero(loc, cnd), end);
+        let mut states = vec![self.state()];
+        if self.block(body)? {
+            states.push(self.jump_state());
+        }
+        self.ir.label(loc, end);
+        self.merge(states);
+        Ok(true)
+    }
+
+    fn switch(
+        &mut self,
+        loc: &Loc<()>,
+        value: &'a Expression,
+        cases: &'a [(U256, Block)],
+        default: Option<&'a Block>,
+    ) -> Result<bool, Error> {
+        let value = match self.value(value)? {
+            Some(value) => value,
+            None => return Ok(false),
+        };
+        if let Some(value) = value.resolve(&self.ctx) {
+            let block = cases
+                .iter()
+                .find(|(case, _)| *case == value)
+                .map(|(_, block)| block)
+                .or(default);
+            return match block {
+                Some(block) => self.block(block),
+                None => Ok(true),
+            };
+        }
+
+        // The value is compared with each case.
+        let var = self.new_var();
+        self.ir.set_var(loc, var, value);
+        write_scratch(&mut self.ir, &mut self.ctx);
+        let labels = cases.iter().map(|_| self.next_label()).collect::<Vec<_>>();
+        for ((case, _), label) in cases.iter().zip(&labels) {
+            let cnd = _Expr::BinaryOp(
+                BinaryOp::Eq,
+                Box::new(copy(loc, var)),
+                Box::new(loc.wrap(_Expr::Val(*case))),
+            );
+            self.ir.true_brunch(loc, loc.wrap(cnd), *label);
+        }
+
+        let end = self.next_label();
+        let before = self.state();
+        let mut states = vec![];
+        let branches = iter::once((None, default)).chain(
+            labels
+                .into_iter()
+                .zip(cases)
+                .map(|(label, (_, block))| (Some(label), Some(block))),
+        );
+        for (label, block) in branches {
+            if let Some(label) = label {
+                self.ir.label(loc, label);
+            }
+            self.restore(before.clone());
+            let runs_through = match block {
+                Some(block) => self.block(block)?,
+                None => true,
+            };
+            if runs_through {
+                states.push(self.jump_state());
+                self.ir.goto(loc, end);
+            }
+        }
+        if states.is_empty() {
+            return Ok(false);
+        }
+        self.ir.label(loc, end);
+        self.merge(states);
+        Ok(true)
+    }
+
+    fn for_(
+        &mut self,
+        loc: &Loc<()>,
+        init: &'a Block,
+        cnd: &'a Expression,
+        post: &'a Block,
+        body: &'a Block,
+    ) -> Result<bool, Error> {
+        if !self.statements(init)? {
+            return Ok(false);
+        }
+        write_scratch(&mut self.ir, &mut self.ctx);
+        // The variables which the iterations assign and the memory are unknown at the loop head.
+        let mut assigned = vec![];
+        assigned_vars(body, &mut assigned);
+        assigned_vars(post, &mut assigned);
+        for name in &assigned {
+            if let Ok(var) = self.lookup(name) {
+                self.forget(var);
+            }
+        }
+        self.ctx.clear_scratch();
+        self.ctx.forget_memory();
+
+        let head = self.next_label();
+        let brk = self.next_label();
+        let cont = self.next_label();
+        self.ir.label(loc, head);
+        let cnd = match self.value(cnd)? {
+            Some(cnd) => cnd,
+            None => return Ok(false),
+        };
+        let mut brk_states = vec![];
+        match cnd.resolve(&self.ctx) {
+            Some(cnd) if cnd.is_zero() => return Ok(true),
+            Some(_) => {}
+            None => {
+                write_scratch(&mut self.ir, &mut self.ctx);
+                self.ir.true_brunch(loc, is_zero(loc, cnd), brk);
+                brk_states.push(self.state());
+            }
+        }
+
+        self.loops.push(Loop {
+            brk,
+            cont,
+            brk_states,
+            cont_states: vec![],
+        });
+        let runs_through = self.block(body);
+        let mut lp = self.loops.pop().expect("the loop was pushed");
+        if runs_through? {
+            lp.cont_states.push(self.jump_state());
+        }
+        if !lp.cont_states.is_empty() {
+            self.ir.label(loc, cont);
+            self.merge(lp.cont_states);
+            if self.block(post)? {
+                write_scratch(&mut self.ir, &mut self.ctx);
+                self.ir.goto(loc, head);
+            }
+        }
+
+        if lp.brk_states.is_empty() {
+            return Ok(false);
+        }
+        self.ir.label(loc, brk);
+        self.merge(lp.brk_states);
+        Ok(true)
+    }
+
+    /// Evaluates the expression with a single value.
+    /// Returns `None` if the evaluation never completes.
+    fn value(&mut self, expr: &'a Expression) -> Result<Option<Expr>, Error> {
+        match expr.as_ref() {
+            _Expression::Literal(val) => Ok(Some(expr.wrap(_Expr::Val(*val)))),
+            _Expression::Identifier(name) => {
+                let var = self.lookup(name)?;
+                self.read(&expr.wrap(()), var, name).map(Some)
+            }
+            _Expression::Call(name, _) => match self.values(expr)? {
+                Some(values) => {
+                    ensure!(
+                        values.len() == 1,
+                        "`{name}` returns {} values where one is expected",
+                        values.len()
+                    );
+                    Ok(values.into_iter().next())
+                }
+                None => Ok(None),
+            },
+        }
+    }
+
+    /// Evaluates the expression with any number of values.
+    fn values(&mut self, expr: &'a Expression) -> Result<Option<Vec<Expr>>, Error> {
+        let (name, args) = match expr.as_ref() {
+            _Expression::Call(name, args) => (name, args),
+            _ => return Ok(self.value(expr)?.map(|value| vec![value])),
+        };
+        // The arguments are evaluated from right to left.
+        let mut values = Vec::with_capacity(args.len());
+        for arg in args.iter().rev() {
+            match self.value(arg)? {
+                Some(value) => values.push(value),
+                None => return Ok(None),
+            }
+        }
+        values.reverse();
+
+        self.ctx.loc = expr.wrap(());
+        match self.function(name) {
+            Some(fun) => self.call(fun, values),
+            None => self.builtin(name, values),
+        }
+    }
+
+    /// Inlines the user-defined function.
+    fn call(
+        &mut self,
+        fun: &'a FunctionDefinition,
+        args: Vec<Expr>,
+    ) -> Result<Option<Vec<Expr>>, Error> {
+        ensure!(
+            args.len() == fun.params.len(),
+            "`{}` expects {} arguments, found {}",
+            fun.name,
+            fun.params.len(),
+            args.len()
+        );
+        ensure!(
+            !self.calls.contains(&fun.name.as_str()),
+            "Recursive Yul functions are not supported: {}",
+            fun.name
+        );
+
+        // The function sees its parameters and return variables only.
+        let loc = self.ctx.loc;
+        let scopes = std::mem::take(&mut self.vars);
+        let loops = std::mem::take(&mut self.loops);
+        self.calls.push(&fun.name);
+        self.vars.push(HashMap::new());
+        for (param, arg) in fun.params.iter().zip(args) {
+            let var = self.declare(param);
+            self.assign(&loc, var, arg);
+        }
+        let returns = fun
+            .returns
+            .iter()
+            .map(|name| {
+                let var = self.declare(name);
+                self.assign(&loc, var, zero(&loc));
+                (var, name)
+            })
+            .collect::<Vec<_>>();
+
+        let label = self.next_label();
+        self.leave.push(Leave {
+            label,
+            states: vec![],
+        });
+        let runs_through = self.block(&fun.body);
+        let leave = self.leave.pop().expect("the function was entered");
+        let result = runs_through.and_then(|runs_through| {
+            let has_leave = !leave.states.is_empty();
+            let mut states = leave.states;
+            if runs_through {
+                states.push(self.jump_state());
+            }
+            if states.is_empty() {
+                return Ok(None);
+            }
+            if has_leave {
+                self.ir.label(&loc, label);
+            }
+            self.merge(states);
+            returns
+                .iter()
+                .map(|(var, name)| self.read(&loc, *var, name))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Some)
+        });
+
+        self.exit_scope_vars();
+        self.vars = scopes;
+        self.loops = loops;
+        self.calls.pop();
+        result
+    }
+
+    fn builtin(&mut self, name: &str, args: Vec<Expr>) -> Result<Option<Vec<Expr>>, Error> {
+        match name {
+            "memoryguard" => {
+                ensure!(args.len() == 1, "`memoryguard` expects one argument");
+                return Ok(Some(args));
+            }
+            "pop" => return Ok(Some(vec![])),
+            "linkersymbol" => {
+                bail!("Calls of the external libraries are not supported by the Yul frontend yet")
+            }
+            "datasize" | "dataoffset" | "datacopy" | "setimmutable" | "loadimmutable" => {
+                bail!("`{name}` is not supported by the Yul frontend")
+            }
+            _ => {}
+        }
+        let code = opcode(name).ok_or_else(|| anyhow!("Unsupported Yul builtin: {name}"))?;
+        let inst = Instruction::new(self.ctx.loc.start, code);
+        ensure!(
+            args.len() == inst.pops(),
+            "`{name}` expects {} arguments, found {}",
+            inst.pops(),
+            args.len()
+        );
+        match &inst.1 {
+            OpCode::CodeCopy => ensure!(
+                args[1..].iter().all(|arg| arg.resolve(&self.ctx).is_some()),
+                "Only constant code can be copied"
+            ),
+            OpCode::CallDataLoad if self.ctx.flags().native_input => ensure!(
+                args[0].resolve(&self.ctx).is_some(),
+                "Dynamic types are not supported with the native input"
+            ),
+            _ => {}
+        }
+        if self.ctx.has_pending_scratch() && reads_scratch(&inst.1, &args, &self.ctx) {
+            write_scratch(&mut self.ir, &mut self.ctx);
+        }
+        match inst.handle(args, &mut self.ir, &mut self.ctx) {
+            ExecutionResult::Output(output) => Ok(Some(vec![self.ctx.loc.wrap(output)])),
+            ExecutionResult::None => Ok(Some(vec![])),
+            ExecutionResult::End => Ok(None),
+            ExecutionResult::Jmp(_) | ExecutionResult::CndJmp { .. } => {
+                bail!("Unexpected jump of `{name}`")
+            }
+        }
+    }
+
+    fn function(&self, name: &str) -> Option<&'a FunctionDefinition> {
+        self.functions
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .copied()
+    }
+
+    fn lookup(&self, name: &str) -> Result<VarId, Error> {
+        self.vars
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .copied()
+            .ok_or_else(|| anyhow!("Unknown Yul variable: {name}"))
+    }
+
+    fn declare(&mut self, name: &'a str) -> VarId {
+        let var = self.new_var();
+        if let Some(scope) = self.vars.last_mut() {
+            scope.insert(name, var);
+        }
+        var
+    }
+
+    fn new_var(&mut self) -> VarId {
+        let var = VarId::new_var(self.var_seq);
+        self.var_seq += 1;
+        var
+    }
+
+    fn next_label(&mut self) -> Label {
+        Label::new(self.ctx.next_jmp_id())
+    }
+
+    /// Forgets the knowledge of the function variables when the inlined function returns.
+    fn exit_scope_vars(&mut self) {
+        while let Some(vars) = self.vars.pop() {
+            for var in vars.values() {
+                self.known.remove(var);
+                self.unresolved_slots.remove(var);
+            }
+        }
+    }
+
+    fn read(&self, loc: &Loc<()>, var: VarId, name: &str) -> Result<Expr, Error> {
+        ensure!(
+            !self.unresolved_slots.contains(&var),
+            "The mapping slot in `{name}` depends on the control flow, which is not supported yet"
+        );
+        Ok(match self.known.get(&var) {
+            Some(val) => loc.wrap(val.as_ref().clone()),
+            None => copy(loc, var),
+        })
+    }
+
+    fn assign(&mut self, loc: &Loc<()>, var: VarId, value: Expr) {
+        let entry = self.ctx.entry(&value).cloned();
+        let known = if let Some(val) = value.resolve(&self.ctx) {
+            Some(loc.wrap(_Expr::Val(val)))
+        } else if value.is_map_slot() {
+            Some(value.clone())
+        } else {
+            None
+        };
+        self.ir.set_var(loc, var, value);
+        self.forget(var);
+        if let Some(known) = known {
+            self.known.insert(var, known);
+        }
+        if let Some((key, slot)) = entry {
+            self.ctx.set_entry(var, key, slot);
+        }
+    }
+
+    fn forget(&mut self, var: VarId) {
+        self.known.remove(&var);
+        self.unresolved_slots.remove(&var);
+        self.ctx.forget_entry(var);
+    }
+
+    fn state(&self) -> State {
+        State {
+            known: self.known.clone(),
+            unresolved_slots: self.unresolved_slots.clone(),
+            tracking: self.ctx.tracking(),
+        }
+    }
+
+    /// State of the path which jumps to a label. The deferred scratch words are written first.
+    fn jump_state(&mut self) -> State {
+        write_scratch(&mut self.ir, &mut self.ctx);
+        self.state()
+    }
+
+    fn restore(&mut self, state: State) {
+        self.known = state.known;
+        self.unresolved_slots = state.unresolved_slots;
+        self.ctx.restore_tracking(state.tracking);
+    }
+
+    /// Joins the paths. Only the values which are the same on all paths stay known.
+    fn merge(&mut self, states: Vec<State>) {
+        let mut states = states.into_iter();
+        if let Some(state) = states.next() {
+            self.restore(state);
+        }
+        for state in states {
+            let slots = self
+                .known
+                .iter()
+                .chain(state.known.iter())
+                .filter(|(var, val)| {
+                    val.is_map_slot() && self.known.get(*var) != state.known.get(*var)
+                })
+                .map(|(var, _)| *var)
+                .collect::<Vec<_>>();
+            self.known
+                .retain(|var, val| state.known.get(var) == Some(val));
+            self.unresolved_slots.extend(slots);
+            self.unresolved_slots.extend(state.unresolved_slots);
+            self.ctx.merge_tracking(&state.tracking);
+        }
+
+        let live = self
+            .vars
+            .iter()
+            .flat_map(|scope| scope.values())
+            .collect::<HashSet<_>>();
+        self.known.retain(|var, _| live.contains(var));
+        self.unresolved_slots.retain(|var| live.contains(var));
+    }
+}
+
+fn zero(loc: &Loc<()>) -> Expr {
+    loc.wrap(_Expr::Val(U256::zero()))
+}
+
+fn copy(loc: &Loc<()>, var: VarId) -> Expr {
+    loc.wrap(_Expr::Copy(Box::new(loc.wrap(_Expr::Var(var)))))
+}
+
+fn is_zero(loc: &Loc<()>, expr: Expr) -> Expr {
+    loc.wrap(_Expr::UnaryOp(UnaryOp::IsZero, Box::new(expr)))
+}
+
+/// Instruction of the EVM dialect builtin.
+fn opcode(name: &str) -> Option<OpCode> {
+    let code = match name {
+        "stop" => OpCode::Stop,
+        "add" => OpCode::Add,
+        "sub" => OpCode::Sub,
+        "mul" => OpCode::Mul,
+        "div" => OpCode::Div,
+        "sdiv" => OpCode::SDiv,
+        "mod" => OpCode::Mod,
+        "smod" => OpCode::SMod,
+        "exp" => OpCode::Exp,
+        "not" => OpCode::Not,
+        "lt" => OpCode::Lt,
+        "gt" => OpCode::Gt,
+        "slt" => OpCode::SLt,
+        "sgt" => OpCode::SGt,
+        "eq" => OpCode::EQ,
+        "iszero" => OpCode::IsZero,
+        "and" => OpCode::And,
+        "or" => OpCode::Or,
+        "xor" => OpCode::Xor,
+        "byte" => OpCode::Byte,
+        "shl" => OpCode::Shl,
+        "shr" => OpCode::Shr,
+        "sar" => OpCode::Sar,
+        "addmod" => OpCode::AddMod,
+        "mulmod" => OpCode::MulMod,
+        "signextend" => OpCode::SignExtend,
+        "keccak256" => OpCode::Sha3,
+        "mload" => OpCode::MLoad,
+        "mstore" => OpCode::MStore,
+        "mstore8" => OpCode::MStore8,
+        "sload" => OpCode::SLoad,
+        "sstore" => OpCode::SStore,
+        "msize" => OpCode::MSize,
+        "gas" => OpCode::Gas,
+        "address" => OpCode::Addr,
+        "balance" => OpCode::Balance,
+        "caller" => OpCode::Caller,
+        "origin" => OpCode::Origin,
+        "callvalue" => OpCode::CallValue,
+        "calldataload" => OpCode::CallDataLoad,
+        "calldatasize" => OpCode::CallDataSize,
+        "calldatacopy" => OpCode::CallDataCopy,
+        "codesize" => OpCode::CodeSize,
+        "codecopy" => OpCode::CodeCopy,
+        "return" => OpCode::Return,
+        "revert" => OpCode::Revert,
+        "invalid" => OpCode::Invalid(0xfe),
+        "log0" => OpCode::Log(0),
+        "log1" => OpCode::Log(1),
+        "log2" => OpCode::Log(2),
+        "log3" => OpCode::Log(3),
+        "log4" => OpCode::Log(4),
+        "gasprice" => OpCode::GasPrice,
+        "blockhash" => OpCode::Blockhash,
+        "coinbase" => OpCode::Coinbase,
+        "timestamp" => OpCode::Timestamp,
+        "number" => OpCode::Number,
+        "difficulty" | "prevrandao" => OpCode::Difficulty,
+        "gaslimit" => OpCode::GasLimit,
+        // The external calls, the contract creation and the other builtins are not supported yet.
+        _ => return None,
+    };
+    Some(code)
+}
@@ -0,0 +1,12 @@
+//! Yul frontend.
+//!
+//! Builds the HIR from the Yul IR which solc emits with `--ir` or `--ir-optimized` instead of
+//! tracing the EVM bytecode. The runtime object is translated for each ABI function: the
+//! user-defined functions are inlined and the dispatcher is resolved by the constant propagation.
+//! The constructor still runs on the EVM bytecode.
+
+pub use builder::YulHirBuilder;
+
+pub mod ast;
+pub mod builder;
+pub mod parser;
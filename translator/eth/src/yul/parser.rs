@@ -0,0 +1,588 @@
+use anyhow::{anyhow, bail, ensure, Error};
+use primitive_types::U256;
+
+use crate::bytecode::loc::Loc;
+use crate::yul::ast::{Block, Expression, FunctionDefinition, Object, _Expression, _Statement};
+
+/// Parses the Yul object which solc prints with `--ir` or `--ir-optimized`.
+/// A plain code block is accepted as an object without a name.
+pub fn parse(source: &str) -> Result<Object, Error> {
+    let tokens = Lexer::new(source).tokenize()?;
+    let mut parser = Parser {
+        source,
+        tokens,
+        pos: 0,
+    };
+    let object = if parser.peek() == &Token::Punct("{") {
+        Object {
+            name: "".to_string(),
+            code: parser.block()?,
+            objects: vec![],
+        }
+    } else {
+        parser.object()?
+    };
+    ensure!(
+        parser.peek() == &Token::Eof,
+        parser.error("the end of the source")
+    );
+    Ok(object)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Number(U256),
+    Str(Vec<u8>),
+    HexStr(Vec<u8>),
+    Punct(&'static str),
+    Eof,
+}
+
+struct Lexer<'a> {
+    source: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(source: &'a str) -> Lexer<'a> {
+        Lexer {
+            source,
+            bytes: source.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn tokenize(mut self) -> Result<Vec<Loc<Token>>, Error> {
+        let mut tokens = vec![];
+        loop {
+            self.skip_trivia()?;
+            let start = self.pos;
+            let token = self.token()?;
+            let is_eof = token == Token::Eof;
+            tokens.push(Loc::new(start, self.pos, token));
+            if is_eof {
+                return Ok(tokens);
+            }
+        }
+    }
+
+    fn skip_trivia(&mut self) -> Result<(), Error> {
+        loop {
+            let rest = &self.bytes[self.pos..];
+            if rest.first().map(u8::is_ascii_whitespace).unwrap_or(false) {
+                self.pos += 1;
+            } else if rest.starts_with(b"//") {
+                while self.pos < self.bytes.len() && self.bytes[self.pos] != b'\n' {
+                    self.pos += 1;
+                }
+            } else if rest.starts_with(b"/*") {
+                let end = self.source[self.pos + 2..]
+                    .find("*/")
+                    .ok_or_else(|| anyhow!("Unterminated comment at {}", self.pos))?;
+                self.pos += end + 4;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn token(&mut self) -> Result<Token, Error> {
+        let rest = &self.bytes[self.pos..];
+        let first = match rest.first() {
+            Some(first) => *first,
+            None => return Ok(Token::Eof),
+        };
+        for punct in [":=", "->", "{", "}", "(", ")", ",", ":"] {
+            if rest.starts_with(punct.as_bytes()) {
+                self.pos += punct.len();
+                return Ok(Token::Punct(punct));
+            }
+        }
+        if rest.starts_with(b"hex\"") || rest.starts_with(b"hex'") {
+            self.pos += 3;
+            let str = self.string()?;
+            let str = String::from_utf8(str)?;
+            let hex = str.replace('_', "");
+            return Ok(Token::HexStr(hex::decode(hex)?));
+        }
+        if first == b'"' || first == b'\'' {
+            return Ok(Token::Str(self.string()?));
+        }
+        if first.is_ascii_digit() {
+            let len = rest
+                .iter()
+                .take_while(|ch| ch.is_ascii_alphanumeric())
+                .count();
+            let number = &self.source[self.pos..self.pos + len];
+            self.pos += len;
+            let val = if let Some(hex) = number.strip_prefix("0x") {
+                U256::from_str_radix(hex, 16).ok()
+            } else {
+                U256::from_dec_str(number).ok()
+            }
+            .ok_or_else(|| anyhow!("Invalid number {number} at {}", self.pos))?;
+            return Ok(Token::Number(val));
+        }
+        if is_ident_start(first) {
+            let len = rest.iter().take_while(|ch| is_ident_part(**ch)).count();
+            let ident = &self.source[self.pos..self.pos + len];
+            self.pos += len;
+            return Ok(Token::Ident(ident.to_string()));
+        }
+        bail!(
+            "Unexpected character {:?} at {}",
+            self.source[self.pos..].chars().next().unwrap_or_default(),
+            self.pos
+        )
+    }
+
+    /// Reads the quoted string with the escape sequences.
+    fn string(&mut self) -> Result<Vec<u8>, Error> {
+        let quote = self.bytes[self.pos];
+        let start = self.pos;
+        self.pos += 1;
+        let mut buf = vec![];
+        loop {
+            let ch = *self
+                .bytes
+                .get(self.pos)
+                .ok_or_else(|| anyhow!("Unterminated string at {start}"))?;
+            self.pos += 1;
+            if ch == quote {
+                return Ok(buf);
+            }
+            if ch != b'\\' {
+                buf.push(ch);
+                continue;
+            }
+            let escaped = *self
+                .bytes
+                .get(self.pos)
+                .ok_or_else(|| anyhow!("Unterminated string at {start}"))?;
+            self.pos += 1;
+            match escaped {
+                b'n' => buf.push(b'\n'),
+                b'r' => buf.push(b'\r'),
+                b't' => buf.push(b'\t'),
+                b'x' => {
+                    let hex = self.source.get(self.pos..self.pos + 2).unwrap_or_default();
+                    buf.push(u8::from_str_radix(hex, 16)?);
+                    self.pos += 2;
+                }
+                b'u' => {
+                    let hex = self.source.get(self.pos..self.pos + 4).unwrap_or_default();
+                    let ch = char::from_u32(u32::from_str_radix(hex, 16)?)
+                        .ok_or_else(|| anyhow!("Invalid unicode escape at {}", self.pos))?;
+                    buf.extend(ch.to_string().as_bytes());
+                    self.pos += 4;
+                }
+                b'\n' => {}
+                ch => buf.push(ch),
+            }
+        }
+    }
+}
+
+fn is_ident_start(ch: u8) -> bool {
+    ch.is_ascii_alphabetic() || ch == b'_' || ch == b'$'
+}
+
+fn is_ident_part(ch: u8) -> bool {
+    is_ident_start(ch) || ch.is_ascii_digit() || ch == b'.'
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Loc<Token>>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &Token {
+        self.tokens[self.pos].as_ref()
+    }
+
+    fn peek_at(&self, offset: usize) -> &Token {
+        let idx = (self.pos + offset).min(self.tokens.len() - 1);
+        self.tokens[idx].as_ref()
+    }
+
+    fn next(&mut self) -> Loc<Token> {
+        let token = self.tokens[self.pos].clone();
+        if self.pos < self.tokens.len() - 1 {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn start(&self) -> usize {
+        self.tokens[self.pos].start.0 as usize
+    }
+
+    fn end(&self) -> usize {
+        self.tokens[self.pos.saturating_sub(1)].end.0 as usize
+    }
+
+    fn error(&self, expected: &str) -> String {
+        let line = self.source[..self.start()].matches('\n').count() + 1;
+        format!(
+            "Yul parse error at line {line}: {expected} was expected, found {:?}",
+            self.peek()
+        )
+    }
+
+    fn expect(&mut self, punct: &'static str) -> Result<(), Error> {
+        if self.peek() == &Token::Punct(punct) {
+            self.next();
+            Ok(())
+        } else {
+            bail!(self.error(&format!("`{punct}`")))
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Token::Ident(ident) if ident == keyword)
+    }
+
+    fn keyword(&mut self, keyword: &str) -> Result<(), Error> {
+        if self.is_keyword(keyword) {
+            self.next();
+            Ok(())
+        } else {
+            bail!(self.error(&format!("`{keyword}`")))
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, Error> {
+        match self.peek().clone() {
+            Token::Ident(ident) => {
+                self.next();
+                Ok(ident)
+            }
+            _ => bail!(self.error("an identifier")),
+        }
+    }
+
+    /// Identifier with an optional type annotation.
+    fn typed_ident(&mut self) -> Result<String, Error> {
+        let ident = self.ident()?;
+        if self.peek() == &Token::Punct(":") {
+            self.next();
+            self.ident()?;
+        }
+        Ok(ident)
+    }
+
+    fn typed_idents(&mut self) -> Result<Vec<String>, Error> {
+        let mut idents = vec![self.typed_ident()?];
+        while self.peek() == &Token::Punct(",") {
+            self.next();
+            idents.push(self.typed_ident()?);
+        }
+        Ok(idents)
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        match self.peek().clone() {
+            Token::Str(str) => {
+                self.next();
+                Ok(String::from_utf8(str)?)
+            }
+            _ => bail!(self.error("a string")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Object, Error> {
+        self.keyword("object")?;
+        let name = self.string()?;
+        self.expect("{")?;
+        self.keyword("code")?;
+        let code = self.block()?;
+        let mut objects = vec![];
+        loop {
+            if self.is_keyword("object") {
+                objects.push(self.object()?);
+            } else if self.is_keyword("data") {
+                self.next();
+                self.string()?;
+                match self.next().inner() {
+                    Token::Str(_) | Token::HexStr(_) => {}
+                    _ => bail!(self.error("the data")),
+                }
+            } else {
+                break;
+            }
+        }
+        self.expect("}")?;
+        Ok(Object {
+            name,
+            code,
+            objects,
+        })
+    }
+
+    fn block(&mut self) -> Result<Block, Error> {
+        self.expect("{")?;
+        let mut block = vec![];
+        while self.peek() != &Token::Punct("}") {
+            ensure!(self.peek() != &Token::Eof, self.error("`}`"));
+            let start = self.start();
+            let stmt = self.statement()?;
+            block.push(Loc::new(start, self.end(), stmt));
+        }
+        self.next();
+        Ok(block)
+    }
+
+    fn statement(&mut self) -> Result<_Statement, Error> {
+        if self.peek() == &Token::Punct("{") {
+            return Ok(_Statement::Block(self.block()?));
+        }
+        let keyword = match self.peek() {
+            Token::Ident(ident) => ident.clone(),
+            _ => bail!(self.error("a statement")),
+        };
+        let is_call = self.peek_at(1) == &Token::Punct("(");
+        let stmt = match keyword.as_str() {
+            "function" if !is_call => {
+                self.next();
+                let name = self.ident()?;
+                self.expect("(")?;
+                let params = if self.peek() == &Token::Punct(")") {
+                    vec![]
+                } else {
+                    self.typed_idents()?
+                };
+                self.expect(")")?;
+                let returns = if self.peek() == &Token::Punct("->") {
+                    self.next();
+                    self.typed_idents()?
+                } else {
+                    vec![]
+                };
+                let body = self.block()?;
+                _Statement::FunctionDefinition(FunctionDefinition {
+                    name,
+                    params,
+                    returns,
+                    body,
+                })
+            }
+            "let" if !is_call => {
+                self.next();
+                let names = self.typed_idents()?;
+                let value = if self.peek() == &Token::Punct(":=") {
+                    self.next();
+                    Some(self.expression()?)
+                } else {
+                    None
+                };
+                _Statement::Let(names, value)
+            }
+            "if" if !is_call => {
+                self.next();
+                let cnd = self.expression()?;
+                _Statement::If(cnd, self.block()?)
+            }
+            "switch" if !is_call => {
+                self.next();
+                let value = self.expression()?;
+                let mut cases = vec![];
+                while self.is_keyword("case") {
+                    self.next();
+                    let literal = self.literal()?;
+                    cases.push((literal, self.block()?));
+                }
+                let default = if self.is_keyword("default") {
+                    self.next();
+                    Some(self.block()?)
+                } else {
+                    None
+                };
+                ensure!(
+                    !cases.is_empty() || default.is_some(),
+                    self.error("`case` or `default`")
+                );
+                _Statement::Switch {
+                    value,
+                    cases,
+                    default,
+                }
+            }
+            "for" if !is_call => {
+                self.next();
+                let init = self.block()?;
+                let cnd = self.expression()?;
+                let post = self.block()?;
+                let body = self.block()?;
+                _Statement::For {
+                    init,
+                    cnd,
+                    post,
+                    body,
+                }
+            }
+            "break" => {
+                self.next();
+                _Statement::Break
+            }
+            "continue" => {
+                self.next();
+                _Statement::Continue
+            }
+            "leave" => {
+                self.next();
+                _Statement::Leave
+            }
+            _ if is_call => _Statement::Expression(self.expression()?),
+            _ => {
+                let names = self.typed_idents()?;
+                self.expect(":=")?;
+                _Statement::Assign(names, self.expression()?)
+            }
+        };
+        Ok(stmt)
+    }
+
+    fn literal(&mut self) -> Result<U256, Error> {
+        let literal = match self.peek().clone() {
+            Token::Number(val) => val,
+            Token::Ident(ident) if ident == "true" => U256::one(),
+            Token::Ident(ident) if ident == "false" => U256::zero(),
+            Token::Str(bytes) | Token::HexStr(bytes) => {
+                // The string literals are left-aligned words.
+                ensure!(
+                    bytes.len() <= 32,
+                    self.error("a string literal of at most 32 bytes")
+                );
+                let mut word = [0u8; 32];
+                word[..bytes.len()].copy_from_slice(&bytes);
+                U256::from_big_endian(&word)
+            }
+            _ => bail!(self.error("a literal")),
+        };
+        self.next();
+        if self.peek() == &Token::Punct(":") {
+            self.next();
+            self.ident()?;
+        }
+        Ok(literal)
+    }
+
+    fn expression(&mut self) -> Result<Expression, Error> {
+        let start = self.start();
+        let expr = match self.peek().clone() {
+            Token::Ident(ident) if ident != "true" && ident != "false" => {
+                self.next();
+                if self.peek() == &Token::Punct("(") {
+                    self.next();
+                    let mut args = vec![];
+                    while self.peek() != &Token::Punct(")") {
+                        if !args.is_empty() {
+                            self.expect(",")?;
+                        }
+                        args.push(self.expression()?);
+                    }
+                    self.next();
+                    _Expression::Call(ident, args)
+                } else {
+                    _Expression::Identifier(ident)
+                }
+            }
+            _ => _Expression::Literal(self.literal()?),
+        };
+        Ok(Loc::new(start, self.end(), expr))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use primitive_types::U256;
+
+    use crate::yul::ast::{_Expression, _Statement};
+    use crate::yul::parser::parse;
+
+    #[test]
+    fn test_parse_object() {
+        let object = parse(
+            r#"
+            /// @use-src 0:"a.sol"
+            object "A_12" {
+                code {
+                    /// @src 0:25:80  "contract A {..."
+                    mstore(64, memoryguard(128))
+                    return(0, datasize("A_12_deployed"))
+                }
+                /** @src 0:25:80 */
+                object "A_12_deployed" {
+                    code {
+                        let x, y := f(0x2a, "ab")
+                        for { let i := 0 } lt(i, x) { i := add(i, 1) } {
+                            if eq(i, 3) { break }
+                        }
+                        switch y
+                        case 0 { leave }
+                        case 'a' {}
+                        default { revert(0, 0) }
+                        function f(a, b) -> c, d {
+                            c := a d := b
+                        }
+                    }
+                    data ".metadata" hex"a264_6970"
+                }
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(object.name, "A_12");
+        assert_eq!(object.code.len(), 2);
+        let deployed = object.deployed();
+        assert_eq!(deployed.name, "A_12_deployed");
+        assert_eq!(deployed.code.len(), 4);
+
+        match deployed.code[0].as_ref() {
+            _Statement::Let(names, Some(value)) => {
+                assert_eq!(names, &vec!["x".to_string(), "y".to_string()]);
+                match value.as_ref() {
+                    _Expression::Call(name, args) => {
+                        assert_eq!(name, "f");
+                        assert!(
+                            matches!(args[0].as_ref(), _Expression::Literal(val) if *val == U256::from(42))
+                        );
+                        let mut word = [0u8; 32];
+                        word[..2].copy_from_slice(b"ab");
+                        assert!(
+                            matches!(args[1].as_ref(), _Expression::Literal(val) if *val == U256::from_big_endian(&word))
+                        );
+                    }
+                    expr => panic!("unexpected expression {expr:?}"),
+                }
+            }
+            stmt => panic!("unexpected statement {stmt:?}"),
+        }
+        assert!(matches!(deployed.code[1].as_ref(), _Statement::For { .. }));
+        match deployed.code[2].as_ref() {
+            _Statement::Switch { cases, default, .. } => {
+                assert_eq!(cases.len(), 2);
+                assert!(default.is_some());
+            }
+            stmt => panic!("unexpected statement {stmt:?}"),
+        }
+        match deployed.code[3].as_ref() {
+            _Statement::FunctionDefinition(fun) => {
+                assert_eq!(fun.params, vec!["a".to_string(), "b".to_string()]);
+                assert_eq!(fun.returns, vec!["c".to_string(), "d".to_string()]);
+                assert_eq!(fun.body.len(), 2);
+            }
+            stmt => panic!("unexpected statement {stmt:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse("{ let x := }").unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+    }
+}
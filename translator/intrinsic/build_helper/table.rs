@@ -66,7 +66,7 @@ impl ###ENUM_NAME### {
 }
 ";
 
-const MEMORY_TABLE: [(&str, &str); 10] = [
+const MEMORY_TABLE: [(&str, &str); 13] = [
     ("New", "new_mem"),
     ("Size", "effective_len"),
     ("Load", "mload"),
@@ -77,17 +77,32 @@ const MEMORY_TABLE: [(&str, &str); 10] = [
     ("RequestBufferLen", "request_buffer_len"),
     ("ReadRequestBuffer", "read_request_buffer"),
     ("CodeCopy", "code_copy"),
+    ("DataLen", "data_len"),
+    ("ReturnDataCopy", "return_data_copy"),
+    ("WriteResult", "write_result"),
 ];
 
-const PERSIST_TABLE: [(&str, &str); 8] = [
+const PERSIST_TABLE: [(&str, &str); 20] = [
     ("InitContract", "init_contract"),
+    ("InitMappings", "init_mappings"),
     ("Store", "sstore"),
     ("Load", "sload"),
+    ("StoreHashed", "sstore_hashed"),
+    ("LoadHashed", "sload_hashed"),
     ("Log0", "log0"),
     ("Log1", "log1"),
     ("Log2", "log2"),
     ("Log3", "log3"),
     ("Log4", "log4"),
+    ("NewTable", "new_table"),
+    ("NewAddressTable", "new_address_table"),
+    ("LoadEntry", "load_entry"),
+    ("LoadAddressEntry", "load_address_entry"),
+    ("StoreEntry", "store_entry"),
+    ("StoreAddressEntry", "store_address_entry"),
+    ("MappingSlot", "mapping_slot"),
+    ("MappingLoad", "mapping_load"),
+    ("MappingStore", "mapping_store"),
 ];
 
 const U256_TABLE: [(&str, &str); 37] = [
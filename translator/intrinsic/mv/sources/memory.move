@@ -192,6 +192,58 @@ module self::memory {
         };
     }
 
+    // API
+    /// Returns len of the returned data in bytes.
+    public fun data_len(data: &vector<u8>): U256 {
+        new_u256(std::vector::length(data), 0, 0, 0)
+    }
+
+    // API
+    /// Copies `length` bytes of the returned data from `offset` to the memory. Fails out of the data.
+    public fun return_data_copy(mem: &mut Memory, position: U256, data: &vector<u8>, offset: U256, length: U256) {
+        let length = as_u64(length);
+        if (length == 0) {
+            return
+        };
+
+        let offset = as_u64(offset);
+        assert!(offset + length <= std::vector::length(data), INVALID_RANGE);
+        copy_data(mem, as_u64(position), data, offset, length);
+    }
+
+    // API
+    /// Writes the returned data to the memory. At most `length` bytes are written.
+    public fun write_result(mem: &mut Memory, position: U256, data: &vector<u8>, length: U256) {
+        let length = as_u64(length);
+        let data_len = std::vector::length(data);
+        if (length > data_len) {
+            length = data_len;
+        };
+        if (length == 0) {
+            return
+        };
+
+        copy_data(mem, as_u64(position), data, 0, length);
+    }
+
+    fun copy_data(mem: &mut Memory, position: u64, data: &vector<u8>, offset: u64, length: u64) {
+        let mem_size = resize(mem, position);
+        resize_offset(mem, position, length);
+        assert!(position + length < mem.limit, OUT_OF_MEMORY);
+
+        let idx = 0u64;
+        while (idx < length) {
+            let global_offset = position + idx;
+            let byte = *std::vector::borrow(data, offset + idx);
+            if (global_offset >= mem_size) {
+                std::vector::push_back(&mut mem.data, byte);
+            } else {
+                *std::vector::borrow_mut(&mut mem.data, global_offset) = byte;
+            };
+            idx = idx + 1;
+        };
+    }
+
     fun resize(mem: &mut Memory, len: u64): u64 {
         resize_offset(mem, len, WORD_SIZE);
 
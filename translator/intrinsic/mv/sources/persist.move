@@ -2,8 +2,9 @@ module self::persist {
     // Storage.
     //=================================================================================================================
 
-    use self::u256::{U256, zero};
+    use self::u256::{U256, zero, to_bytes, from_bytes};
     use self::memory::{Memory, mslice};
+    use aptos_std::aptos_hash;
 
     #[test_only]
     use self::u256::{from_u128, as_u128};
@@ -48,6 +49,218 @@ module self::persist {
         }
     }
 
+    // API
+    /// Stores the value at the hashed slot. The slot may be the raw slot of a mapping entry kept by its native keys.
+    public fun sstore_hashed(store: &mut Persist, key: U256, val: U256) acquires Mappings {
+        let mappings = borrow_global_mut<Mappings>(@self);
+        if (aptos_std::table::contains(&mappings.entries, key)) {
+            let entry = *aptos_std::table::borrow(&mappings.entries, key);
+            let mapping = aptos_std::table::borrow_mut(&mut mappings.mappings, entry.slot);
+            *aptos_std::table::borrow_mut(&mut mapping.values, entry.key) = val;
+            return
+        };
+
+        sstore(store, key, val)
+    }
+
+    // API
+    /// Loads the value of the hashed slot. The slot may be the raw slot of a mapping entry kept by its native keys.
+    public fun sload_hashed(store: &mut Persist, key: U256): U256 acquires Mappings {
+        let mappings = borrow_global<Mappings>(@self);
+        if (aptos_std::table::contains(&mappings.entries, key)) {
+            let entry = aptos_std::table::borrow(&mappings.entries, key);
+            let mapping = aptos_std::table::borrow(&mappings.mappings, entry.slot);
+            return *aptos_std::table::borrow(&mapping.values, entry.key)
+        };
+
+        sload(store, key)
+    }
+
+    // Typed state.
+    //=================================================================================================================
+
+    // API
+    /// Creates an empty mapping field of the typed state.
+    public fun new_table(): aptos_std::table::Table<U256, U256> {
+        aptos_std::table::new()
+    }
+
+    // API
+    public fun new_address_table(): aptos_std::table::Table<address, U256> {
+        aptos_std::table::new()
+    }
+
+    // API
+    /// Loads the mapping entry. Entries which were never written through the typed field are
+    /// read from the raw storage `slot`.
+    public fun load_entry(tbl: &aptos_std::table::Table<U256, U256>, key: U256, store: &mut Persist, slot: U256): U256 {
+        if (aptos_std::table::contains(tbl, key)) {
+            *aptos_std::table::borrow(tbl, key)
+        } else {
+            sload(store, slot)
+        }
+    }
+
+    // API
+    public fun load_address_entry(tbl: &aptos_std::table::Table<address, U256>, key: address, store: &mut Persist, slot: U256): U256 {
+        if (aptos_std::table::contains(tbl, key)) {
+            *aptos_std::table::borrow(tbl, key)
+        } else {
+            sload(store, slot)
+        }
+    }
+
+    // API
+    public fun store_entry(tbl: &mut aptos_std::table::Table<U256, U256>, key: U256, val: U256) {
+        if (aptos_std::table::contains(tbl, key)) {
+            aptos_std::table::remove(tbl, key);
+        };
+
+        aptos_std::table::add(tbl, key, val);
+    }
+
+    // API
+    public fun store_address_entry(tbl: &mut aptos_std::table::Table<address, U256>, key: address, val: U256) {
+        if (aptos_std::table::contains(tbl, key)) {
+            aptos_std::table::remove(tbl, key);
+        };
+
+        aptos_std::table::add(tbl, key, val);
+    }
+
+    // Mappings.
+    //=================================================================================================================
+
+    /// Mappings addressed by their native keys.
+    /// Only the modules translated with the native mappings keep it.
+    struct Mappings has key {
+        /// Mappings by their slots.
+        mappings: aptos_std::table::Table<U256, Mapping>,
+        /// Raw slots of the mapping entries. Only the raw accesses of the hashed slots look them up.
+        entries: aptos_std::table::Table<U256, EntryRef>,
+    }
+
+    // API
+    public fun init_mappings(self: &signer) {
+        move_to(self, Mappings {
+            mappings: aptos_std::table::new(),
+            entries: aptos_std::table::new(),
+        });
+    }
+
+    /// Entries of the mapping. The keys are indexed in the order of their first write so that they can be enumerated.
+    struct Mapping has store {
+        values: aptos_std::table::Table<MapKey, U256>,
+        keys: aptos_std::table::Table<u64, MapKey>,
+        len: u64,
+    }
+
+    /// Native keys of the nested mappings which address the entry.
+    /// The address keys and the number keys keep their order, `kinds` of the accessors tells which is which.
+    struct MapKey has store, copy, drop {
+        addrs: vector<address>,
+        nums: vector<U256>,
+    }
+
+    /// Mapping slot and the keys of the entry.
+    struct EntryRef has store, copy, drop {
+        slot: U256,
+        key: MapKey,
+    }
+
+    // API
+    /// Raw slot of the mapping entry: `keccak256(key . slot)` applied for each key of the nested mappings.
+    /// The bit `i` of `kinds` is set if the key `i` is an address.
+    public fun mapping_slot(slot: U256, addrs: vector<address>, nums: vector<U256>, kinds: u64): U256 {
+        let (a, n) = (0, 0);
+        let len = std::vector::length(&addrs) + std::vector::length(&nums);
+        while (a + n < len) {
+            let data = if ((kinds >> ((a + n) as u8)) & 1 == 1) {
+                a = a + 1;
+                address_word(*std::vector::borrow(&addrs, a - 1))
+            } else {
+                n = n + 1;
+                to_bytes(std::vector::borrow(&nums, n - 1))
+            };
+            std::vector::append(&mut data, to_bytes(&slot));
+            slot = from_bytes(&aptos_hash::keccak256(data), zero());
+        };
+        slot
+    }
+
+    /// Address as the 32 bytes of the EVM word.
+    fun address_word(addr: address): vector<u8> {
+        let encoded = std::bcs::to_bytes(&addr);
+        let i = 0u64;
+        while (i < 12) {
+            *std::vector::borrow_mut(&mut encoded, i) = 0;
+            i = i + 1;
+        };
+        encoded
+    }
+
+    // API
+    /// Loads the mapping entry. Entries which were never written by their keys are read from the raw storage.
+    public fun mapping_load(store: &mut Persist, slot: U256, addrs: vector<address>, nums: vector<U256>, kinds: u64): U256 acquires Mappings {
+        let key = MapKey { addrs, nums };
+        let mappings = &borrow_global<Mappings>(@self).mappings;
+        if (aptos_std::table::contains(mappings, slot)) {
+            let mapping = aptos_std::table::borrow(mappings, slot);
+            if (aptos_std::table::contains(&mapping.values, key)) {
+                return *aptos_std::table::borrow(&mapping.values, key)
+            };
+        };
+
+        sload(store, mapping_slot(slot, addrs, nums, kinds))
+    }
+
+    // API
+    public fun mapping_store(store: &mut Persist, slot: U256, addrs: vector<address>, nums: vector<U256>, kinds: u64, val: U256) acquires Mappings {
+        let mappings = borrow_global_mut<Mappings>(@self);
+        if (!aptos_std::table::contains(&mappings.mappings, slot)) {
+            aptos_std::table::add(&mut mappings.mappings, slot, Mapping {
+                values: aptos_std::table::new(),
+                keys: aptos_std::table::new(),
+                len: 0,
+            });
+        };
+
+        let key = MapKey { addrs, nums };
+        let mapping = aptos_std::table::borrow_mut(&mut mappings.mappings, slot);
+        if (aptos_std::table::contains(&mapping.values, key)) {
+            *aptos_std::table::borrow_mut(&mut mapping.values, key) = val;
+            return
+        };
+
+        // The first write of the entry: the raw slot now refers to it.
+        aptos_std::table::add(&mut mapping.values, key, val);
+        let index = mapping.len;
+        aptos_std::table::add(&mut mapping.keys, index, key);
+        mapping.len = index + 1;
+        let raw = mapping_slot(slot, addrs, nums, kinds);
+        if (aptos_std::table::contains(&store.tbl, raw)) {
+            aptos_std::table::remove(&mut store.tbl, raw);
+        };
+        aptos_std::table::add(&mut mappings.entries, raw, EntryRef { slot, key });
+    }
+
+    /// Number of the mapping entries which were written by their keys.
+    public fun mapping_len(slot: U256): u64 acquires Mappings {
+        let mappings = &borrow_global<Mappings>(@self).mappings;
+        if (aptos_std::table::contains(mappings, slot)) {
+            aptos_std::table::borrow(mappings, slot).len
+        } else {
+            0
+        }
+    }
+
+    /// Keys of the `index`th written entry of the mapping as the address keys and the number keys.
+    public fun mapping_key(slot: U256, index: u64): (vector<address>, vector<U256>) acquires Mappings {
+        let mapping = aptos_std::table::borrow(&borrow_global<Mappings>(@self).mappings, slot);
+        let key = aptos_std::table::borrow(&mapping.keys, index);
+        (key.addrs, key.nums)
+    }
+
     // Tests
     // problem with global borrowing
 
@@ -67,6 +280,57 @@ module self::persist {
         assert!(as_u128(sload(persist, from_u128(1))) == 1, 0);
     }
 
+    #[test_only]
+    struct TestState has key {
+        tbl: aptos_std::table::Table<address, U256>,
+    }
+
+    #[test(owner = @0x42)]
+    fun typed_entry_test(owner: &signer) acquires Persist {
+        init_contract(owner);
+        let persist = borrow_global_mut<Persist>(@self);
+        sstore(persist, from_u128(7), from_u128(3));
+
+        let tbl = new_address_table();
+        assert!(as_u128(load_address_entry(&tbl, @0x1, persist, from_u128(7))) == 3, 0);
+        store_address_entry(&mut tbl, @0x1, from_u128(5));
+        store_address_entry(&mut tbl, @0x1, from_u128(6));
+        assert!(as_u128(load_address_entry(&tbl, @0x1, persist, from_u128(7))) == 6, 1);
+        assert!(as_u128(load_address_entry(&tbl, @0x2, persist, from_u128(8))) == 0, 2);
+        move_to(owner, TestState { tbl });
+    }
+
+    #[test(owner = @0x42)]
+    fun mapping_test(owner: &signer) acquires Persist, Mappings {
+        init_contract(owner);
+        init_mappings(owner);
+        let persist = borrow_global_mut<Persist>(@self);
+        let slot = from_u128(1);
+        let addrs = std::vector::singleton(@0x42);
+        let nums = std::vector::empty();
+        let raw = mapping_slot(slot, addrs, nums, 1);
+        assert!(raw == mapping_slot(slot, std::vector::empty(), std::vector::singleton(from_u128(0x42)), 0), 0);
+
+        sstore(persist, raw, from_u128(3));
+        assert!(as_u128(mapping_load(persist, slot, addrs, nums, 1)) == 3, 1);
+
+        mapping_store(persist, slot, addrs, nums, 1, from_u128(5));
+        assert!(as_u128(mapping_load(persist, slot, addrs, nums, 1)) == 5, 2);
+        assert!(as_u128(sload_hashed(persist, raw)) == 5, 3);
+
+        sstore_hashed(persist, raw, from_u128(6));
+        assert!(as_u128(mapping_load(persist, slot, addrs, nums, 1)) == 6, 4);
+
+        let nested = std::vector::singleton(from_u128(7));
+        assert!(mapping_slot(raw, std::vector::empty(), nested, 0) == mapping_slot(slot, addrs, nested, 1), 5);
+        mapping_store(persist, slot, addrs, nested, 1, from_u128(8));
+        assert!(as_u128(mapping_load(persist, slot, addrs, nested, 1)) == 8, 6);
+        assert!(mapping_len(slot) == 2, 7);
+        assert!(mapping_len(from_u128(2)) == 0, 8);
+        let (key_addrs, key_nums) = mapping_key(slot, 1);
+        assert!(key_addrs == addrs && key_nums == nested, 9);
+    }
+
     // Events
     //==========================================================================
     struct Event has store, drop {
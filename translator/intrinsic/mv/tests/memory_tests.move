@@ -3,7 +3,7 @@ module self::memory_tests {
     use self::u256::{from_u128, as_u128, U256, from_bytes, zero, eq};
 
     #[test_only]
-    use self::memory::{request_buffer_len, code_copy, get_data};
+    use self::memory::{request_buffer_len, code_copy, get_data, data_len, return_data_copy, write_result};
 
     #[test]
     fun test_buff_len() {
@@ -196,4 +196,27 @@ module self::memory_tests {
         let mem = get_data(&memory);
         assert!(mem == &b"This is the large string that we are testing. Never ask strings for their size....\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0ps", 3);
     }
+
+    #[test]
+    fun test_return_data() {
+        let data = b"returned data";
+        assert!(as_u128(data_len(&data)) == 13, 1);
+
+        let memory = new_mem(1024);
+        write_result(&mut memory, from_u128(2), &data, from_u128(8));
+        assert!(get_data(&memory) == &b"\0\0returned", 2);
+
+        write_result(&mut memory, from_u128(0), &data, from_u128(100));
+        assert!(get_data(&memory) == &b"returned data", 3);
+
+        return_data_copy(&mut memory, from_u128(9), &data, from_u128(0), from_u128(8));
+        assert!(get_data(&memory) == &b"returned returned", 4);
+    }
+
+    #[test]
+    #[expected_failure]
+    fun test_return_data_out_of_range() {
+        let memory = new_mem(1024);
+        return_data_copy(&mut memory, from_u128(0), &b"data", from_u128(2), from_u128(3));
+    }
 }
@@ -1,14 +1,20 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use enum_iterator::all;
 use std::collections::HashSet;
 
 use crate::table::self_address_index;
+use move_binary_format::access::ModuleAccess;
 use move_binary_format::file_format::{
-    Constant, ConstantPoolIndex, FunctionHandleIndex, SignatureToken,
+    Ability, AbilitySet, AddressIdentifierIndex, Bytecode, Constant, ConstantPoolIndex,
+    FieldDefinition, FieldHandle, FieldHandleIndex, FunctionHandle, FunctionHandleIndex,
+    IdentifierIndex, ModuleHandle, ModuleHandleIndex, SignatureIndex, SignatureToken,
+    StructDefinition, StructDefinitionIndex, StructFieldInformation, StructHandle,
+    StructHandleIndex, TypeSignature,
 };
 use move_binary_format::{file_format::Visibility, CompiledModule};
 use move_core_types::account_address::AccountAddress;
 use move_core_types::identifier::Identifier;
-use move_core_types::language_storage::CORE_CODE_ADDRESS;
+use move_core_types::language_storage::{ModuleId, CORE_CODE_ADDRESS};
 
 pub mod table;
 
@@ -16,8 +22,25 @@ pub const TEMPLATE_MODULE: &[u8] = include_bytes!("../mv/template.mv");
 
 pub const TOML_TEMPLATE: &str = include_str!("../mv/Move.toml");
 
+/// Sources of the intrinsic modules as (module name, source) pairs.
+pub const SOURCES: [(&str, &str); 6] = [
+    ("info", include_str!("../mv/sources/info.move")),
+    ("memory", include_str!("../mv/sources/memory.move")),
+    ("persist", include_str!("../mv/sources/persist.move")),
+    ("u256", include_str!("../mv/sources/u256.move")),
+    ("u512", include_str!("../mv/sources/u512.move")),
+    ("utiles", include_str!("../mv/sources/u64.move")),
+];
+
 pub const SELF_ADDRESS_INDEX: ConstantPoolIndex = ConstantPoolIndex(4);
 
+/// Name of the shared intrinsic library module.
+pub const LIBRARY_NAME: &str = "intrinsic";
+
+/// Intrinsic modules without the contract storage. The shared library exports their functions
+/// and structs, the other intrinsics are copied into each translated module.
+pub const LIBRARY_SOURCES: [&str; 4] = ["memory", "u256", "u512", "utiles"];
+
 pub fn toml_template(name: &str, address: AccountAddress) -> String {
     TOML_TEMPLATE
         .replace("intrinsic", name)
@@ -29,23 +52,7 @@ pub fn template(
     name: &str,
     reserved_identifiers: &HashSet<String>,
 ) -> Result<CompiledModule> {
-    let mut module = CompiledModule::deserialize(TEMPLATE_MODULE)?;
-    module.address_identifiers[0] = address;
-    module.identifiers[0] = Identifier::new(name)?;
-    let const_address_index = self_address_index().0;
-    module.constant_pool[const_address_index as usize] = Constant {
-        type_: SignatureToken::Address,
-        data: address.to_vec(),
-    };
-
-    if address == CORE_CODE_ADDRESS {
-        module.address_identifiers.remove(1);
-        for handle in &mut module.module_handles {
-            if handle.address.0 == 1 {
-                handle.address.0 = 0;
-            }
-        }
-    }
+    let mut module = load_template(address, name)?;
 
     let public_functions = vec![
         table::U256::FromU128.handler(),
@@ -60,15 +67,418 @@ pub fn template(
         fun.visibility = Visibility::Private;
     }
 
+    // Stable renaming keeps the translation reproducible.
+    let mut taken = module
+        .identifiers
+        .iter()
+        .map(|ident| ident.to_string())
+        .chain(reserved_identifiers.iter().cloned())
+        .collect::<HashSet<_>>();
     for ident in &mut module.identifiers {
         if reserved_identifiers.contains(ident.as_str()) {
-            *ident = Identifier::new(format!("{}{}", ident, rand::random::<u16>()))?;
+            let name = (1..)
+                .map(|idx| format!("{ident}_{idx}"))
+                .find(|name| !taken.contains(name))
+                .expect("The range of suffixes is unbounded");
+            taken.insert(name.clone());
+            *ident = Identifier::new(name)?;
+        }
+    }
+
+    Ok(module)
+}
+
+/// Standalone intrinsic library which is published once and shared by translated modules.
+///
+/// Only the `U256` and `Memory` helpers called by the translated modules are public, see
+/// [`library_functions`]. The storage and the block info are kept by each translated module.
+pub fn library(address: AccountAddress) -> Result<CompiledModule> {
+    let mut module = load_template(address, LIBRARY_NAME)?;
+    let public_functions = library_functions(&module);
+    for fun in &mut module.function_defs {
+        fun.visibility = if public_functions.contains(&fun.function) {
+            Visibility::Public
+        } else {
+            Visibility::Private
+        };
+    }
+    Ok(module)
+}
+
+/// Functions of the library which are called by the translated modules: the public functions of
+/// the library sources and the functions of the `Memory` and `U256` enums.
+pub fn library_functions(module: &CompiledModule) -> HashSet<FunctionHandleIndex> {
+    let public = library_items("fun", true);
+    module
+        .function_handles
+        .iter()
+        .enumerate()
+        .filter(|(_, handle)| {
+            handle.module == module.self_handle_idx()
+                && public.contains(module.identifier_at(handle.name).as_str())
+        })
+        .map(|(idx, _)| FunctionHandleIndex(idx as u16))
+        .chain(all::<table::Memory>().map(|fun| fun.handler()))
+        .chain(all::<table::U256>().map(|fun| fun.handler()))
+        .collect()
+}
+
+/// Template of a module that imports the `U256` and `Memory` helpers from the library published
+/// at `library`.
+///
+/// The handles keep the indexes of `template`, so the `table` enums can be used as is. `Persist`
+/// and the block info stay in the module, but the definitions of the library structs are removed,
+/// so `Persist` is looked up by [`persist_def`]. The imported functions which the module does not
+/// call are cropped along with the other unused handles.
+pub fn shared_template(
+    address: AccountAddress,
+    name: &str,
+    library: AccountAddress,
+    reserved_identifiers: &HashSet<String>,
+) -> Result<CompiledModule> {
+    let mut module = load_template(address, name)?;
+    let self_handle = module.self_handle_idx();
+    let library_handle = module_handle(&mut module, library, LIBRARY_NAME)?;
+
+    let functions = library_items("fun", false);
+    for handle in &mut module.function_handles {
+        if handle.module == self_handle
+            && functions.contains(module.identifiers[handle.name.0 as usize].as_str())
+        {
+            handle.module = library_handle;
+        }
+    }
+    let structs = library_items("struct", false);
+    for handle in &mut module.struct_handles {
+        if handle.module == self_handle
+            && structs.contains(module.identifiers[handle.name.0 as usize].as_str())
+        {
+            handle.module = library_handle;
         }
     }
 
+    module
+        .function_defs
+        .retain(|def| module.function_handles[def.function.0 as usize].module == self_handle);
+    for fun in &mut module.function_defs {
+        fun.visibility = Visibility::Private;
+    }
+    remove_library_structs(&mut module)?;
+
+    // Only the local functions may clash with the functions of the contract.
+    let mut taken = module
+        .identifiers
+        .iter()
+        .map(|ident| ident.to_string())
+        .chain(reserved_identifiers.iter().cloned())
+        .collect::<HashSet<_>>();
+    for idx in 0..module.function_handles.len() {
+        let handle = &module.function_handles[idx];
+        let ident = module.identifier_at(handle.name).as_str();
+        if handle.module != self_handle || !reserved_identifiers.contains(ident) {
+            continue;
+        }
+        let name = (1..)
+            .map(|suffix| format!("{ident}_{suffix}"))
+            .find(|name| !taken.contains(name))
+            .expect("The range of suffixes is unbounded");
+        taken.insert(name.clone());
+        module.function_handles[idx].name = identifier(&mut module, &name)?;
+    }
+
     Ok(module)
 }
 
+/// Definition of `Persist` in a module built by `template` or `shared_template`.
+pub fn persist_def(module: &CompiledModule) -> Result<StructDefinitionIndex> {
+    let handle = match table::Persist::token() {
+        SignatureToken::MutableReference(token) => *token,
+        token => token,
+    };
+    module
+        .struct_defs
+        .iter()
+        .position(|def| SignatureToken::Struct(def.struct_handle) == handle)
+        .map(|idx| StructDefinitionIndex(idx as u16))
+        .ok_or_else(|| anyhow!("Persist definition not found in the template"))
+}
+
+/// Definition of `Mappings`, the native mappings of the contract, in a module built by `template`
+/// or `shared_template`.
+pub fn mappings_def(module: &CompiledModule) -> Result<StructDefinitionIndex> {
+    module
+        .struct_defs
+        .iter()
+        .position(|def| {
+            let handle = module.struct_handle_at(def.struct_handle);
+            handle.module == module.self_handle_idx()
+                && module.identifier_at(handle.name).as_str() == "Mappings"
+        })
+        .map(|idx| StructDefinitionIndex(idx as u16))
+        .ok_or_else(|| anyhow!("Mappings definition not found in the template"))
+}
+
+/// Removes the definitions of the structs which are imported from the library and
+/// renumbers the definitions and the field handles of the local structs.
+fn remove_library_structs(module: &mut CompiledModule) -> Result<()> {
+    let self_handle = module.self_handle_idx();
+    let mut kept = 0;
+    let defs = module
+        .struct_defs
+        .iter()
+        .map(|def| {
+            (module.struct_handles[def.struct_handle.0 as usize].module == self_handle).then(|| {
+                kept += 1;
+                StructDefinitionIndex(kept - 1)
+            })
+        })
+        .collect::<Vec<_>>();
+    module
+        .struct_defs
+        .retain(|def| module.struct_handles[def.struct_handle.0 as usize].module == self_handle);
+
+    let mut field_handles = Vec::new();
+    let fields = module
+        .field_handles
+        .iter()
+        .map(|handle| {
+            defs[handle.owner.0 as usize].map(|owner| {
+                field_handles.push(FieldHandle {
+                    owner,
+                    field: handle.field,
+                });
+                FieldHandleIndex(field_handles.len() as u16 - 1)
+            })
+        })
+        .collect::<Vec<_>>();
+    module.field_handles = field_handles;
+
+    let def = |idx: StructDefinitionIndex| {
+        defs[idx.0 as usize]
+            .ok_or_else(|| anyhow!("The library struct definition {} is used locally", idx.0))
+    };
+    let field = |idx: FieldHandleIndex| {
+        fields[idx.0 as usize]
+            .ok_or_else(|| anyhow!("The library field handle {} is used locally", idx.0))
+    };
+    for inst in &mut module.struct_def_instantiations {
+        inst.def = def(inst.def)?;
+    }
+    for inst in &mut module.field_instantiations {
+        inst.handle = field(inst.handle)?;
+    }
+    for fun in &mut module.function_defs {
+        for resource in &mut fun.acquires_global_resources {
+            *resource = def(*resource)?;
+        }
+        let code = match &mut fun.code {
+            Some(code) => &mut code.code,
+            None => continue,
+        };
+        for op in code {
+            match op {
+                Bytecode::Pack(idx)
+                | Bytecode::Unpack(idx)
+                | Bytecode::MutBorrowGlobal(idx)
+                | Bytecode::ImmBorrowGlobal(idx)
+                | Bytecode::Exists(idx)
+                | Bytecode::MoveFrom(idx)
+                | Bytecode::MoveTo(idx) => *idx = def(*idx)?,
+                Bytecode::MutBorrowField(idx) | Bytecode::ImmBorrowField(idx) => {
+                    *idx = field(*idx)?
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Names of the items of the `keyword` kind, e.g. `fun` or `struct`, declared by the library
+/// sources. If `public` is set, only the public items.
+fn library_items(keyword: &str, public: bool) -> HashSet<&'static str> {
+    SOURCES
+        .iter()
+        .filter(|(module, _)| LIBRARY_SOURCES.contains(module))
+        .flat_map(|(_, source)| source.lines())
+        .filter_map(|line| {
+            let line = line.trim_start();
+            let line = match line.strip_prefix("public ") {
+                Some(line) => line,
+                None if public => return None,
+                None => line,
+            };
+            line.strip_prefix(keyword)?
+                .strip_prefix(' ')?
+                .split(|ch: char| !ch.is_ascii_alphanumeric() && ch != '_')
+                .next()
+        })
+        .collect()
+}
+
+/// Typed state resource added to a module by `add_state`.
+#[derive(Debug, Clone)]
+pub struct StateDef {
+    pub def: StructDefinitionIndex,
+    handle: StructHandleIndex,
+    /// Field handles in the order of the declared fields.
+    pub fields: Vec<FieldHandleIndex>,
+}
+
+impl StateDef {
+    pub fn token(&self) -> SignatureToken {
+        SignatureToken::MutableReference(Box::new(SignatureToken::Struct(self.handle)))
+    }
+}
+
+/// Adds the `State` resource with the typed state variables of the contract.
+///
+/// Each field is a `U256` value or, if the key type is given, a `Table<key, U256>` mapping.
+pub fn add_state(
+    module: &mut CompiledModule,
+    fields: &[(&str, Option<SignatureToken>)],
+) -> Result<StateDef> {
+    let table = module
+        .struct_handles
+        .iter()
+        .position(|handle| module.identifier_at(handle.name).as_str() == "Table")
+        .map(|idx| StructHandleIndex(idx as u16))
+        .ok_or_else(|| anyhow!("Table handle not found in the template"))?;
+
+    let state_name = identifier(module, "State")?;
+    let handle = StructHandleIndex(module.struct_handles.len() as u16);
+    module.struct_handles.push(StructHandle {
+        module: module.self_handle_idx(),
+        name: state_name,
+        abilities: AbilitySet::singleton(Ability::Key),
+        type_parameters: vec![],
+    });
+
+    let def = StructDefinitionIndex(module.struct_defs.len() as u16);
+    let mut field_defs = Vec::with_capacity(fields.len());
+    let mut field_handles = Vec::with_capacity(fields.len());
+    for (idx, (name, key)) in fields.iter().enumerate() {
+        let signature = match key {
+            Some(key) => {
+                SignatureToken::StructInstantiation(table, vec![key.clone(), table::U256::token()])
+            }
+            None => table::U256::token(),
+        };
+        field_defs.push(FieldDefinition {
+            name: identifier(module, name)?,
+            signature: TypeSignature(signature),
+        });
+        field_handles.push(FieldHandleIndex(module.field_handles.len() as u16));
+        module.field_handles.push(FieldHandle {
+            owner: def,
+            field: idx as u16,
+        });
+    }
+    module.struct_defs.push(StructDefinition {
+        struct_handle: handle,
+        field_information: StructFieldInformation::Declared(field_defs),
+    });
+
+    Ok(StateDef {
+        def,
+        handle,
+        fields: field_handles,
+    })
+}
+
+/// Handle of the function `name` of the external module `id`. The handle is added if the
+/// module doesn't import the function yet.
+pub fn import_function(
+    module: &mut CompiledModule,
+    id: &ModuleId,
+    name: &str,
+    parameters: SignatureIndex,
+    return_: SignatureIndex,
+) -> Result<FunctionHandleIndex> {
+    let module_handle = module_handle(module, *id.address(), id.name().as_str())?;
+    let name = identifier(module, name)?;
+    if let Some(idx) = module
+        .function_handles
+        .iter()
+        .position(|handle| handle.module == module_handle && handle.name == name)
+    {
+        return Ok(FunctionHandleIndex(idx as u16));
+    }
+    module.function_handles.push(FunctionHandle {
+        module: module_handle,
+        name,
+        parameters,
+        return_,
+        type_parameters: vec![],
+    });
+    Ok(FunctionHandleIndex(
+        (module.function_handles.len() - 1) as u16,
+    ))
+}
+
+/// Handle of the module `name` at `address`. The handle is added if the module has none.
+fn module_handle(
+    module: &mut CompiledModule,
+    address: AccountAddress,
+    name: &str,
+) -> Result<ModuleHandleIndex> {
+    let address = match module
+        .address_identifiers
+        .iter()
+        .position(|addr| *addr == address)
+    {
+        Some(idx) => AddressIdentifierIndex(idx as u16),
+        None => {
+            module.address_identifiers.push(address);
+            AddressIdentifierIndex((module.address_identifiers.len() - 1) as u16)
+        }
+    };
+    let name = identifier(module, name)?;
+    if let Some(idx) = module
+        .module_handles
+        .iter()
+        .position(|handle| handle.address == address && handle.name == name)
+    {
+        return Ok(ModuleHandleIndex(idx as u16));
+    }
+    module.module_handles.push(ModuleHandle { address, name });
+    Ok(ModuleHandleIndex((module.module_handles.len() - 1) as u16))
+}
+
+fn load_template(address: AccountAddress, name: &str) -> Result<CompiledModule> {
+    let mut module = CompiledModule::deserialize(TEMPLATE_MODULE)?;
+    module.address_identifiers[0] = address;
+    module.identifiers[0] = Identifier::new(name)?;
+    let const_address_index = self_address_index().0;
+    module.constant_pool[const_address_index as usize] = Constant {
+        type_: SignatureToken::Address,
+        data: address.to_vec(),
+    };
+
+    if address == CORE_CODE_ADDRESS {
+        module.address_identifiers.remove(1);
+        for handle in &mut module.module_handles {
+            if handle.address.0 == 1 {
+                handle.address.0 = 0;
+            }
+        }
+    }
+
+    Ok(module)
+}
+
+fn identifier(module: &mut CompiledModule, name: &str) -> Result<IdentifierIndex> {
+    if let Some(idx) = module
+        .identifiers
+        .iter()
+        .position(|ident| ident.as_str() == name)
+    {
+        return Ok(IdentifierIndex(idx as u16));
+    }
+    module.identifiers.push(Identifier::new(name)?);
+    Ok(IdentifierIndex((module.identifiers.len() - 1) as u16))
+}
+
 pub trait Function {
     fn name(&self) -> &'static str;
     fn handler(&self) -> FunctionHandleIndex;
@@ -10,11 +10,13 @@ use move_binary_format::file_format::{
 use move_binary_format::CompiledModule;
 use move_bytecode_verifier::{CodeUnitVerifier, VerifierConfig};
 use move_core_types::account_address::AccountAddress;
-use move_core_types::identifier::Identifier;
+use move_core_types::identifier::{IdentStr, Identifier};
 use move_core_types::language_storage::{ModuleId, CORE_CODE_ADDRESS};
 
 use intrinsic::table::{self_address_index, Memory as Mem, Persist, U256 as Num};
-use intrinsic::{template, Function};
+use intrinsic::{
+    library, library_functions, persist_def, shared_template, template, Function, LIBRARY_NAME,
+};
 
 #[test]
 pub fn test_template_verification() {
@@ -44,6 +46,115 @@ pub fn test_template_verification_core() {
     CodeUnitVerifier::verify_module(&VerifierConfig::default(), &template).unwrap();
 }
 
+#[test]
+pub fn test_template_stable_renaming() {
+    let address = AccountAddress::random();
+    let reserved = ["div".to_string(), "mod".to_string()]
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+    let first = template(address, "template_module", &reserved).unwrap();
+    let second = template(address, "template_module", &reserved).unwrap();
+    assert_eq!(first, second);
+
+    let names = first
+        .identifiers
+        .iter()
+        .map(|ident| ident.as_str())
+        .collect::<Vec<_>>();
+    assert!(names.contains(&"div_1"));
+    assert!(names.contains(&"mod_1"));
+    assert!(!names.contains(&"div"));
+    assert!(!names.contains(&"mod"));
+}
+
+#[test]
+pub fn test_library_verification() {
+    let address = AccountAddress::random();
+    let library = library(address).unwrap();
+    assert_eq!(
+        library.self_id(),
+        ModuleId::new(address, Identifier::new(LIBRARY_NAME).unwrap())
+    );
+    CodeUnitVerifier::verify_module(&VerifierConfig::default(), &library).unwrap();
+}
+
+#[test]
+pub fn test_library_visibility() {
+    let library = library(AccountAddress::random()).unwrap();
+    let public_functions = library_functions(&library);
+    for fun in &library.function_defs {
+        if public_functions.contains(&fun.function) {
+            assert_eq!(fun.visibility, Visibility::Public);
+        } else {
+            assert_eq!(fun.visibility, Visibility::Private);
+        }
+    }
+    assert!(public_functions.contains(&Num::FromU128.handler()));
+    assert!(public_functions.contains(&Num::Lt.handler()));
+    assert!(public_functions.contains(&Mem::Store.handler()));
+    assert!(!public_functions.contains(&Persist::Store.handler()));
+
+    let private = library
+        .function_defs
+        .iter()
+        .filter(|def| def.visibility == Visibility::Private)
+        .map(|def| {
+            let handle = library.function_handle_at(def.function);
+            library.identifier_at(handle.name).as_str()
+        })
+        .collect::<HashSet<_>>();
+    assert!(private.contains("address_word"));
+    assert!(private.contains("resize"));
+    assert!(private.contains("sstore"));
+    assert!(private.contains("balance"));
+}
+
+#[test]
+pub fn test_shared_template() {
+    let library = AccountAddress::random();
+    let reserved = ["sstore".to_string(), "div".to_string()]
+        .into_iter()
+        .collect::<HashSet<_>>();
+    let module = shared_template(
+        AccountAddress::random(),
+        "template_module",
+        library,
+        &reserved,
+    )
+    .unwrap();
+    CodeUnitVerifier::verify_module(&VerifierConfig::default(), &module).unwrap();
+
+    let owner = |fun: &dyn Function| {
+        let handle = module.function_handle_at(fun.handler());
+        module.module_id_for_handle(module.module_handle_at(handle.module))
+    };
+    let name = |fun: &dyn Function| {
+        let handle = module.function_handle_at(fun.handler());
+        module.identifier_at(handle.name).as_str()
+    };
+    let library_id = ModuleId::new(library, Identifier::new(LIBRARY_NAME).unwrap());
+    assert_eq!(owner(&Num::Div), library_id);
+    assert_eq!(owner(&Mem::Store), library_id);
+    assert_eq!(owner(&Persist::Store), module.self_id());
+    // The local functions are renamed apart from the contract functions, the imported keep their names.
+    assert_eq!(name(&Num::Div), "div");
+    assert_eq!(name(&Persist::Store), "sstore_1");
+
+    assert!(module
+        .find_struct_def_by_name(IdentStr::new("U256").unwrap())
+        .is_none());
+    assert_eq!(
+        module
+            .struct_def_at(persist_def(&module).unwrap())
+            .struct_handle,
+        find_struct_by_name(&module, "Persist")
+    );
+    for def in &module.function_defs {
+        assert_eq!(def.visibility, Visibility::Private);
+    }
+}
+
 #[test]
 pub fn test_template() {
     let module = CompiledModule::deserialize(intrinsic::TEMPLATE_MODULE).unwrap();
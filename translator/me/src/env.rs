@@ -0,0 +1,210 @@
+//! Block, time, chain id and coin balances of the local runs.
+//!
+//! The values are written to the resources of the Aptos framework, so the intrinsics read them
+//! as on chain. A missing resource is created in the layout of the framework.
+
+use anyhow::{anyhow, Result};
+use aptos_types::access_path::AccessPath;
+use aptos_types::state_store::state_key::StateKey;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::{ResourceKey, StructTag, TypeTag, CORE_CODE_ADDRESS};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::resolver::Resolver;
+
+/// Epoch interval of a created `BlockResource` in microseconds, two hours.
+const EPOCH_INTERVAL: u64 = 7_200_000_000;
+const MICROS_PER_SECOND: u64 = 1_000_000;
+
+/// `0x1::event::EventHandle`.
+#[derive(Serialize, Deserialize)]
+struct EventHandle {
+    counter: u64,
+    /// `guid.id.creation_num`.
+    creation_num: u64,
+    /// `guid.id.addr`.
+    addr: AccountAddress,
+}
+
+impl EventHandle {
+    fn new(addr: AccountAddress, creation_num: u64) -> EventHandle {
+        EventHandle {
+            counter: 0,
+            creation_num,
+            addr,
+        }
+    }
+}
+
+/// `0x1::block::BlockResource`.
+#[derive(Serialize, Deserialize)]
+struct BlockResource {
+    height: u64,
+    epoch_interval: u64,
+    new_block_events: EventHandle,
+    update_epoch_interval_events: EventHandle,
+}
+
+/// `0x1::timestamp::CurrentTimeMicroseconds`.
+#[derive(Serialize, Deserialize)]
+struct CurrentTimeMicroseconds {
+    microseconds: u64,
+}
+
+/// `0x1::chain_id::ChainId`.
+#[derive(Serialize, Deserialize)]
+struct ChainId {
+    id: u8,
+}
+
+/// `0x1::account::Account`.
+#[derive(Serialize, Deserialize)]
+struct Account {
+    authentication_key: Vec<u8>,
+    sequence_number: u64,
+    guid_creation_num: u64,
+    coin_register_events: EventHandle,
+    key_rotation_events: EventHandle,
+    /// `Option<address>` of Move is a vector of at most one element.
+    rotation_capability_offer: Vec<AccountAddress>,
+    signer_capability_offer: Vec<AccountAddress>,
+}
+
+/// `0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>`.
+#[derive(Serialize, Deserialize)]
+struct CoinStore {
+    /// `coin.value`.
+    value: u64,
+    frozen: bool,
+    deposit_events: EventHandle,
+    withdraw_events: EventHandle,
+}
+
+/// Sets the height of the current block.
+pub fn set_block_height(resolver: &mut Resolver, height: u64) -> Result<()> {
+    let key = resource_key(CORE_CODE_ADDRESS, "block", "BlockResource", vec![])?;
+    let block = match read::<BlockResource>(resolver, &key)? {
+        Some(block) => BlockResource { height, ..block },
+        None => {
+            let guid = next_guids(resolver, CORE_CODE_ADDRESS, 2)?;
+            BlockResource {
+                height,
+                epoch_interval: EPOCH_INTERVAL,
+                new_block_events: EventHandle::new(CORE_CODE_ADDRESS, guid),
+                update_epoch_interval_events: EventHandle::new(CORE_CODE_ADDRESS, guid + 1),
+            }
+        }
+    };
+    write(resolver, key, &block)
+}
+
+/// Sets the time of the current block in seconds since the unix epoch.
+pub fn set_timestamp(resolver: &mut Resolver, seconds: u64) -> Result<()> {
+    let key = resource_key(
+        CORE_CODE_ADDRESS,
+        "timestamp",
+        "CurrentTimeMicroseconds",
+        vec![],
+    )?;
+    let microseconds = seconds
+        .checked_mul(MICROS_PER_SECOND)
+        .ok_or_else(|| anyhow!("The timestamp {seconds} is out of range"))?;
+    write(resolver, key, &CurrentTimeMicroseconds { microseconds })
+}
+
+/// Sets the id of the chain returned by `0x1::chain_id::get`.
+pub fn set_chain_id(resolver: &mut Resolver, id: u8) -> Result<()> {
+    let key = resource_key(CORE_CODE_ADDRESS, "chain_id", "ChainId", vec![])?;
+    write(resolver, key, &ChainId { id })
+}
+
+/// Sets the `AptosCoin` balance of the account. The account and its coin store are created if
+/// needed. The supply of the coin is not changed.
+pub fn set_balance(resolver: &mut Resolver, addr: AccountAddress, value: u64) -> Result<()> {
+    let coin = TypeTag::Struct(Box::new(StructTag {
+        address: CORE_CODE_ADDRESS,
+        module: Identifier::new("aptos_coin")?,
+        name: Identifier::new("AptosCoin")?,
+        type_params: vec![],
+    }));
+    let key = resource_key(addr, "coin", "CoinStore", vec![coin])?;
+    let store = match read::<CoinStore>(resolver, &key)? {
+        Some(store) => CoinStore { value, ..store },
+        None => {
+            create_account(resolver, addr)?;
+            let guid = next_guids(resolver, addr, 2)?;
+            CoinStore {
+                value,
+                frozen: false,
+                deposit_events: EventHandle::new(addr, guid),
+                withdraw_events: EventHandle::new(addr, guid + 1),
+            }
+        }
+    };
+    write(resolver, key, &store)
+}
+
+/// Creates the account as `0x1::account::create_account` does, unless it exists.
+fn create_account(resolver: &mut Resolver, addr: AccountAddress) -> Result<()> {
+    let key = resource_key(addr, "account", "Account", vec![])?;
+    if resolver.state_data.contains_key(&key) {
+        return Ok(());
+    }
+    let account = Account {
+        authentication_key: addr.to_vec(),
+        sequence_number: 0,
+        guid_creation_num: 2,
+        coin_register_events: EventHandle::new(addr, 0),
+        key_rotation_events: EventHandle::new(addr, 1),
+        rotation_capability_offer: vec![],
+        signer_capability_offer: vec![],
+    };
+    write(resolver, key, &account)
+}
+
+/// Reserves `count` creation numbers of the GUIDs of the account and returns the first one.
+/// An address without an account starts from zero.
+fn next_guids(resolver: &mut Resolver, addr: AccountAddress, count: u64) -> Result<u64> {
+    let key = resource_key(addr, "account", "Account", vec![])?;
+    match read::<Account>(resolver, &key)? {
+        Some(mut account) => {
+            let first = account.guid_creation_num;
+            account.guid_creation_num += count;
+            write(resolver, key, &account)?;
+            Ok(first)
+        }
+        None => Ok(0),
+    }
+}
+
+fn resource_key(
+    addr: AccountAddress,
+    module: &str,
+    name: &str,
+    type_params: Vec<TypeTag>,
+) -> Result<StateKey> {
+    let tag = StructTag {
+        address: CORE_CODE_ADDRESS,
+        module: Identifier::new(module)?,
+        name: Identifier::new(name)?,
+        type_params,
+    };
+    Ok(StateKey::AccessPath(AccessPath::resource_access_path(
+        ResourceKey::new(addr, tag),
+    )))
+}
+
+fn read<T: DeserializeOwned>(resolver: &Resolver, key: &StateKey) -> Result<Option<T>> {
+    resolver
+        .state_data
+        .get(key)
+        .map(|data| Ok(bcs::from_bytes(data)?))
+        .transpose()
+}
+
+fn write<T: Serialize>(resolver: &mut Resolver, key: StateKey, resource: &T) -> Result<()> {
+    resolver.set(key, bcs::to_bytes(resource)?);
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use std::fmt::{Display, Formatter};
+
+use anyhow::{anyhow, Result};
+use aptos_gas::{
+    AbstractValueSizeGasParameters, AptosGasMeter, AptosGasParameters, InitialGasSchedule,
+    NativeGasParameters, StorageGasParameters, LATEST_GAS_FEATURE_VERSION,
+};
+
+/// Default limit of the metered runs in gas units.
+pub const DEFAULT_MAX_GAS: u64 = 2_000_000;
+
+/// Gas schedule of the executor runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasSchedule {
+    /// Nothing is charged.
+    Unmetered,
+    /// Only the instructions are charged. The native functions and the storage are free.
+    Instructions,
+    /// The standard Aptos gas schedule: the instructions, the native functions, the storage and
+    /// the intrinsic gas of the transaction.
+    Standard,
+}
+
+impl Default for GasSchedule {
+    fn default() -> Self {
+        GasSchedule::Unmetered
+    }
+}
+
+impl GasSchedule {
+    pub(crate) fn native_params(&self) -> (NativeGasParameters, AbstractValueSizeGasParameters) {
+        match self {
+            GasSchedule::Standard => (
+                NativeGasParameters::initial(),
+                AbstractValueSizeGasParameters::initial(),
+            ),
+            GasSchedule::Unmetered | GasSchedule::Instructions => (
+                NativeGasParameters::zeros(),
+                AbstractValueSizeGasParameters::zeros(),
+            ),
+        }
+    }
+
+    /// Gas meter of a single transaction limited by `max_gas` gas units.
+    pub(crate) fn meter(&self, max_gas: u64) -> Result<Option<AptosGasMeter>> {
+        let params = AptosGasParameters::initial();
+        let storage = match self {
+            GasSchedule::Unmetered => return Ok(None),
+            GasSchedule::Instructions => StorageGasParameters::free_and_unlimited(),
+            GasSchedule::Standard => {
+                StorageGasParameters::new(LATEST_GAS_FEATURE_VERSION, Some(&params), None)
+                    .ok_or_else(|| anyhow!("Storage gas parameters are not available"))?
+            }
+        };
+        Ok(Some(AptosGasMeter::new(
+            LATEST_GAS_FEATURE_VERSION,
+            params,
+            storage,
+            max_gas,
+        )))
+    }
+
+    pub(crate) fn charges_storage(&self) -> bool {
+        *self == GasSchedule::Standard
+    }
+}
+
+/// Gas charged for a transaction in internal gas units.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GasUsed {
+    /// Instructions, native functions and resource reads.
+    pub instructions: u64,
+    /// Writes of the changed resources and modules.
+    pub storage: u64,
+    /// Intrinsic gas of the transaction.
+    pub intrinsic: u64,
+}
+
+impl GasUsed {
+    pub fn total(&self) -> u64 {
+        self.instructions + self.storage + self.intrinsic
+    }
+}
+
+impl Display for GasUsed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "instructions: {}, storage: {}, intrinsic: {}, total: {}",
+            self.instructions,
+            self.storage,
+            self.intrinsic,
+            self.total()
+        )
+    }
+}
+
+/// Runs `charge` and returns the internal gas units it charged.
+pub(crate) fn charged<T, E>(
+    meter: &mut AptosGasMeter,
+    charge: impl FnOnce(&mut AptosGasMeter) -> Result<T, E>,
+) -> Result<(T, u64), E> {
+    let balance = meter.balance_internal();
+    let res = charge(meter)?;
+    Ok((res, u64::from(balance - meter.balance_internal())))
+}
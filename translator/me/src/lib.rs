@@ -1,12 +1,15 @@
+use std::collections::{BTreeMap, HashMap};
+use std::iter;
+use std::path::Path;
 use std::str::FromStr;
 
 use anyhow::{anyhow, Result};
 use itertools::Itertools;
 use once_cell::sync::OnceCell;
-use serde::Deserialize;
 
 use aptos_crypto::HashValue;
-use aptos_gas::{AbstractValueSizeGasParameters, ChangeSetConfigs, NativeGasParameters};
+use aptos_gas::ChangeSetConfigs;
+use aptos_types::transaction::EntryFunction;
 use aptos_vm::{
     data_cache::StorageAdapter,
     move_vm_ext::{MoveVmExt, SessionId},
@@ -14,36 +17,39 @@ use aptos_vm::{
 };
 
 // evm
-use ethabi::{
-    ethereum_types::{H160, U256},
-    Contract, Token,
-};
+use ethabi::{ethereum_types::U256, Contract, RawLog, Token};
 
 use move_core_types::{
     account_address::AccountAddress,
     effects::Event,
+    gas_algebra::NumBytes,
     identifier::Identifier,
     language_storage::{ModuleId, CORE_CODE_ADDRESS},
+    resolver::MoveResolver,
     value::MoveTypeLayout,
 };
-use move_vm_runtime::session::LoadedFunctionInstantiation;
+use move_vm_runtime::session::{LoadedFunctionInstantiation, Session};
 use move_vm_types::{gas::UnmeteredGasMeter, loaded_data::runtime_types::Type};
 
 // local
-use eth::{
-    abi::call::{to_eth_address, EthEncodeByString},
-    Flags,
-};
+use eth::layout::Layout;
+use eth::{abi, abi::call::fn_params_str_split, abi::call::EthEncodeByString, abi::codec, Flags};
 
+pub mod env;
+pub mod gas;
 pub mod load;
 pub mod profile;
 pub mod resolver;
 pub mod solidity;
+pub mod state;
 mod stdlib;
 
+use crate::gas::{charged, GasSchedule, GasUsed, DEFAULT_MAX_GAS};
+use crate::load::fixture::Remote;
 use crate::resolver::Resolver;
 
-static INSTANCE: OnceCell<Resolver> = OnceCell::new();
+/// Genesis of the Aptos instance. It is published once and copied into each executor.
+static APTOS_GENESIS: OnceCell<Resolver> = OnceCell::new();
 
 pub struct MoveExecutor {
     pub resolver: Resolver,
@@ -51,52 +57,162 @@ pub struct MoveExecutor {
     seq: u64,
     entries: Contract,
     flags: Flags,
+    gas_schedule: GasSchedule,
+    max_gas: u64,
+    /// Storage layout the modules were translated with, to decode their typed state.
+    layout: Layout,
+    /// Named copies of the state and the sequence number to revert to.
+    snapshots: HashMap<String, (Resolver, u64)>,
 }
 
 impl MoveExecutor {
+    /// Executor which reads the remote state as set by `E2M_RECORD` and `E2M_REPLAY`.
     pub fn new(entries: Contract, flags: Flags, ins: MoveExecutorInstance) -> MoveExecutor {
-        let resolver = INSTANCE
-            .get_or_init(|| {
-                let mut resolver = Resolver::default();
-                match ins {
-                    MoveExecutorInstance::None => {}
-                    MoveExecutorInstance::Aptos => {
-                        let vm = Self::create_vm();
-                        let id = SessionId::Txn {
-                            sender: CORE_CODE_ADDRESS,
-                            sequence_number: 0,
-                            script_hash: vec![0; 32],
-                        };
-                        let adapter = StorageAdapter::new(&resolver);
-                        let mut session = vm.new_session(&adapter, id);
-
-                        stdlib::publish_std(&mut session);
-
-                        let output = session
-                            .finish()
-                            .unwrap()
-                            .into_change_set(
-                                &mut (),
-                                &ChangeSetConfigs::unlimited_at_gas_feature_version(3),
-                            )
-                            .unwrap();
-                        resolver.apply(output);
-                    }
-                }
-
-                resolver
-            })
-            .clone();
+        Self::with_remote(entries, flags, ins, Remote::from_env())
+    }
 
+    /// Executor which reads the remote state from the `remote` source.
+    pub fn with_remote(
+        entries: Contract,
+        flags: Flags,
+        ins: MoveExecutorInstance,
+        remote: Remote,
+    ) -> MoveExecutor {
+        let mut resolver = Self::genesis(ins);
+        resolver.remote = remote;
         MoveExecutor {
             resolver,
-            vm: Self::create_vm(),
+            vm: Self::create_vm(GasSchedule::Unmetered),
             seq: 1,
             entries,
             flags,
+            gas_schedule: GasSchedule::Unmetered,
+            max_gas: DEFAULT_MAX_GAS,
+            layout: Layout::default(),
+            snapshots: HashMap::new(),
+        }
+    }
+
+    fn genesis(ins: MoveExecutorInstance) -> Resolver {
+        match ins {
+            MoveExecutorInstance::None => Resolver::default(),
+            MoveExecutorInstance::Aptos => APTOS_GENESIS
+                .get_or_init(|| {
+                    let mut resolver = Resolver::default();
+                    let vm = Self::create_vm(GasSchedule::Unmetered);
+                    let id = SessionId::Txn {
+                        sender: CORE_CODE_ADDRESS,
+                        sequence_number: 0,
+                        script_hash: vec![0; 32],
+                    };
+                    let adapter = StorageAdapter::new(&resolver);
+                    let mut session = vm.new_session(&adapter, id);
+
+                    stdlib::publish_std(&mut session);
+
+                    let output = session
+                        .finish()
+                        .unwrap()
+                        .into_change_set(
+                            &mut (),
+                            &ChangeSetConfigs::unlimited_at_gas_feature_version(3),
+                        )
+                        .unwrap();
+                    resolver.apply(output);
+                    resolver
+                })
+                .clone(),
+        }
+    }
+
+    /// Independent executor with a copy of the state and the snapshots.
+    pub fn fork(&self) -> MoveExecutor {
+        MoveExecutor {
+            resolver: self.resolver.clone(),
+            vm: Self::create_vm(self.gas_schedule),
+            seq: self.seq,
+            entries: self.entries.clone(),
+            flags: self.flags,
+            gas_schedule: self.gas_schedule,
+            max_gas: self.max_gas,
+            layout: self.layout.clone(),
+            snapshots: self.snapshots.clone(),
         }
     }
 
+    /// Keeps a copy of the state as the snapshot `name`. A snapshot with the same name is replaced.
+    pub fn snapshot(&mut self, name: &str) {
+        self.snapshots
+            .insert(name.to_string(), (self.resolver.clone(), self.seq));
+    }
+
+    /// Reverts the state to the snapshot `name`. The snapshot is kept, so it can be reverted to again.
+    pub fn revert(&mut self, name: &str) -> Result<()> {
+        (self.resolver, self.seq) = self
+            .snapshots
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Snapshot {name:?} not found"))?;
+        Ok(())
+    }
+
+    /// Saves the state and the sequence number to the file in BCS.
+    /// The table handles are derived from the sequence number, so it is kept with the tables.
+    pub fn save_state<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.resolver.save(path, self.seq)
+    }
+
+    /// Replaces the state with the one saved by [`MoveExecutor::save_state`].
+    pub fn load_state<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let (resolver, seq) = Resolver::load(path)?;
+        self.resolver.state_data = resolver.state_data;
+        self.seq = seq;
+        Ok(())
+    }
+
+    /// Sets the height of the current block, `block.number` of the following calls.
+    pub fn set_block_height(&mut self, height: u64) -> Result<()> {
+        env::set_block_height(&mut self.resolver, height)
+    }
+
+    /// Sets the time of the current block in seconds, `block.timestamp` of the following calls.
+    pub fn set_timestamp(&mut self, seconds: u64) -> Result<()> {
+        env::set_timestamp(&mut self.resolver, seconds)
+    }
+
+    pub fn set_chain_id(&mut self, id: u8) -> Result<()> {
+        env::set_chain_id(&mut self.resolver, id)
+    }
+
+    /// Sets the `AptosCoin` balance of the account, `address.balance` of the following calls.
+    pub fn set_balance(&mut self, addr: &str, value: u64) -> Result<()> {
+        env::set_balance(
+            &mut self.resolver,
+            AccountAddress::from_hex_literal(addr)?,
+            value,
+        )
+    }
+
+    /// Charges the following deployments and runs by the `schedule`.
+    /// A transaction fails if it needs more than `max_gas` gas units.
+    pub fn set_gas_schedule(&mut self, schedule: GasSchedule, max_gas: u64) -> &mut Self {
+        self.vm = Self::create_vm(schedule);
+        self.gas_schedule = schedule;
+        self.max_gas = max_gas;
+        self
+    }
+
+    /// Decodes the typed state of the modules by the `layout` they were translated with.
+    pub fn set_layout(&mut self, layout: Layout) -> &mut Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Slots of the `module` in terms of the EVM. The zero slots are omitted.
+    pub fn storage(&self, module: &ModuleId) -> Result<BTreeMap<U256, U256>> {
+        state::storage(&self.resolver, module, &self.layout)
+    }
+
     pub fn run(
         &mut self,
         ident: &str,
@@ -115,7 +231,7 @@ impl MoveExecutor {
         self._run(ident, signer, params, Flags::native_interface())
     }
 
-    pub fn deploy(&mut self, addr: &str, module: Vec<u8>) -> Result<()> {
+    pub fn deploy(&mut self, addr: &str, module: Vec<u8>) -> Result<GasUsed> {
         let addr = AccountAddress::from_hex_literal(addr)?;
         let id = SessionId::Txn {
             sender: addr,
@@ -124,23 +240,48 @@ impl MoveExecutor {
         };
         self.seq += 1;
 
+        let txn_size = module.len() as u64;
+        let mut meter = self.gas_schedule.meter(self.max_gas)?;
+        let mut gas = GasUsed::default();
+
         let adapter = StorageAdapter::new(&self.resolver);
         let mut session = self.vm.new_session(&adapter, id);
-        session.publish_module(module, addr, &mut UnmeteredGasMeter)?;
+        match &mut meter {
+            Some(meter) => {
+                if self.gas_schedule.charges_storage() {
+                    gas.intrinsic = charged(meter, |meter| {
+                        meter.charge_intrinsic_gas_for_transaction(NumBytes::new(txn_size))
+                    })?
+                    .1;
+                }
+                gas.instructions =
+                    charged(meter, |meter| session.publish_module(module, addr, meter))?.1;
+            }
+            None => session.publish_module(module, addr, &mut UnmeteredGasMeter)?,
+        }
         let output = session.finish()?.into_change_set(
             &mut (),
             &ChangeSetConfigs::unlimited_at_gas_feature_version(3),
         )?;
+        if let Some(meter) = &mut meter {
+            if self.gas_schedule.charges_storage() {
+                gas.storage = charged(meter, |meter| {
+                    meter.charge_write_set_gas(output.write_set().iter())
+                })?
+                .1;
+            }
+        }
 
         self.resolver.apply(output);
-        Ok(())
+        Ok(gas)
     }
 
-    fn create_vm() -> MoveVmExt {
+    fn create_vm(schedule: GasSchedule) -> MoveVmExt {
         configure_for_unit_test();
+        let (native_params, abs_val_params) = schedule.native_params();
         MoveVmExt::new(
-            NativeGasParameters::zeros(),
-            AbstractValueSizeGasParameters::zeros(),
+            native_params,
+            abs_val_params,
             aptos_gas::LATEST_GAS_FEATURE_VERSION,
             aptos_types::on_chain_config::Features::default()
                 .is_enabled(aptos_types::on_chain_config::FeatureFlag::TREAT_FRIEND_AS_PRIVATE),
@@ -150,6 +291,15 @@ impl MoveExecutor {
         .unwrap()
     }
 
+    /// Executes the entry function payload built by the generated Rust bindings.
+    /// The signer is prepended to the payload arguments.
+    pub fn run_entry(&mut self, signer: &str, entry: EntryFunction) -> Result<ExecutionResult> {
+        let (module_id, ident, _, args) = entry.into_inner();
+        let signer = bcs::to_bytes(&AccountAddress::from_hex_literal(signer)?)?;
+        let args = iter::once(signer).chain(args).collect();
+        self.execute(module_id, ident, CallArgs::Encoded(args), self.flags)
+    }
+
     fn _run(
         &mut self,
         ident: &str,
@@ -158,11 +308,26 @@ impl MoveExecutor {
         flag: Flags,
     ) -> Result<ExecutionResult> {
         let (module_id, ident) = Self::prepare_ident(ident);
+        self.execute(module_id, ident, CallArgs::Str { signer, params }, flag)
+    }
 
+    fn execute(
+        &mut self,
+        module_id: ModuleId,
+        ident: Identifier,
+        args: CallArgs,
+        flag: Flags,
+    ) -> Result<ExecutionResult> {
+        let script_hash = match &args {
+            CallArgs::Str { params, .. } => {
+                HashValue::sha3_256_of(params.unwrap_or_default().as_bytes())
+            }
+            CallArgs::Encoded(args) => HashValue::sha3_256_of(&args.concat()),
+        };
         let id = SessionId::Txn {
             sender: *module_id.address(),
             sequence_number: self.seq,
-            script_hash: HashValue::sha3_256_of(params.unwrap_or_default().as_bytes()).to_vec(),
+            script_hash: script_hash.to_vec(),
         };
         self.seq += 1;
 
@@ -170,16 +335,57 @@ impl MoveExecutor {
         let mut session = self.vm.new_session(&adapter, id);
         let fn_name = ident.as_str();
 
-        let args = if flag.native_input {
-            let fun = session.load_function(&module_id, &ident, &[]);
-            self.prepare_move_args(signer, params, &fun.unwrap())?
+        // The returned structs are decoded by their tags, so the layouts are annotated.
+        let return_layouts = if flag.native_output && !flag.hidden_output {
+            let fun = session.load_function(&module_id, &ident, &[])?;
+            fun.return_
+                .iter()
+                .map(|tp| annotated_layout(&session, tp))
+                .collect::<Result<Vec<_>>>()?
         } else {
-            self.prepare_eth_args(signer, params, fn_name)?
+            vec![]
         };
 
-        let returns = session
-            .execute_entry_function(&module_id, &ident, vec![], args, &mut UnmeteredGasMeter)?
-            .return_values;
+        let args = match args {
+            CallArgs::Str { signer, params } => {
+                if flag.native_input {
+                    let fun = session.load_function(&module_id, &ident, &[]);
+                    self.prepare_move_args(&session, &module_id, signer, params, &fun.unwrap())?
+                } else {
+                    self.prepare_eth_args(signer, params, fn_name)?
+                }
+            }
+            CallArgs::Encoded(args) => args,
+        };
+
+        let txn_size = (args.iter().map(|arg| arg.len()).sum::<usize>()
+            + module_id.name().len()
+            + ident.len()) as u64;
+        let mut meter = self.gas_schedule.meter(self.max_gas)?;
+        let mut gas = GasUsed::default();
+        let returns = match &mut meter {
+            Some(meter) => {
+                if self.gas_schedule.charges_storage() {
+                    gas.intrinsic = charged(meter, |meter| {
+                        meter.charge_intrinsic_gas_for_transaction(NumBytes::new(txn_size))
+                    })?
+                    .1;
+                }
+                let (returns, used) = charged(meter, |meter| {
+                    session.execute_entry_function(&module_id, &ident, vec![], args, meter)
+                })?;
+                gas.instructions = used;
+                returns
+            }
+            None => session.execute_entry_function(
+                &module_id,
+                &ident,
+                vec![],
+                args,
+                &mut UnmeteredGasMeter,
+            )?,
+        }
+        .return_values;
 
         let result = session.finish().unwrap();
         let events = result.events.clone();
@@ -189,66 +395,60 @@ impl MoveExecutor {
                 &ChangeSetConfigs::unlimited_at_gas_feature_version(3),
             )
             .unwrap();
+        if let Some(meter) = &mut meter {
+            if self.gas_schedule.charges_storage() {
+                gas.storage = charged(meter, |meter| {
+                    meter.charge_write_set_gas(output.write_set().iter())
+                })?
+                .1;
+            }
+        }
 
+        let raw_returns = if flag.native_output {
+            returns
+                .into_iter()
+                .zip(return_layouts)
+                .map(|((val, _), layout)| (val, layout))
+                .collect::<Vec<_>>()
+        } else {
+            returns
+        };
         let returns = if flag.hidden_output {
             vec![]
         } else if flag.native_output {
-            self.decode_result_move(returns)?
+            codec::decode_move_values(&raw_returns, &module_id)?
         } else {
-            self.decode_result_eth(returns, fn_name)?
+            self.decode_result_eth(&raw_returns, fn_name)?
         };
 
+        let write_set = output.write_set().clone();
         self.resolver.apply(output);
 
-        Ok(ExecutionResult { returns, events })
-    }
-
-    fn decode_result_move(&self, result: Vec<(Vec<u8>, MoveTypeLayout)>) -> Result<Vec<Token>> {
-        result
-            .iter()
-            .map(|(val, tp)| match tp {
-                MoveTypeLayout::Bool => bcs::from_bytes::<bool>(val).map(Token::Bool),
-                MoveTypeLayout::U8 => {
-                    bcs::from_bytes::<u8>(val).map(|val| Token::Uint(U256::from(val)))
-                }
-                MoveTypeLayout::U64 => {
-                    bcs::from_bytes::<u64>(val).map(|val| Token::Uint(U256::from(val)))
-                }
-                MoveTypeLayout::U128 => {
-                    bcs::from_bytes::<u128>(val).map(|val| Token::Uint(U256::from(val)))
-                }
-                MoveTypeLayout::Address => bcs::from_bytes::<AccountAddress>(val)
-                    .map(|val| Token::Address(H160::from(to_eth_address(val.as_ref())))),
-                MoveTypeLayout::Vector(_) => {
-                    todo!()
-                }
-                MoveTypeLayout::Struct(_) => {
-                    bcs::from_bytes::<U256Wrapper>(val).map(|val| Token::Uint(U256(val.0)))
-                }
-                _ => unreachable!(),
-            })
-            .collect::<Result<Vec<Token>, _>>()
-            .map_err(|e| anyhow!(e))
+        // The constructor creates `Persist`, so the tables are looked up after the changes.
+        let storage = state::storage_writes(&self.resolver, &write_set, &module_id, &self.layout)?;
+        let logs = state::logs(&events, &module_id)?;
+
+        Ok(ExecutionResult {
+            returns,
+            raw_returns,
+            events,
+            storage,
+            logs,
+            gas,
+        })
     }
 
     fn decode_result_eth(
         &self,
-        result: Vec<(Vec<u8>, MoveTypeLayout)>,
+        result: &[(Vec<u8>, MoveTypeLayout)],
         fn_name: &str,
     ) -> Result<Vec<Token>> {
         if fn_name == "constructor" {
             Ok(Vec::new())
-        } else if !result.is_empty() {
-            let result: Vec<u8> = bcs::from_bytes(&result[0].0).map_err(|e| anyhow!(e))?;
-            let result = self
-                .entries
-                .functions_by_name(fn_name)?
-                .first()
-                .ok_or_else(|| anyhow!("Fn {fn_name:?} not found "))?
-                .decode_output(&result)?;
-            Ok(result)
         } else {
-            Ok(Vec::new())
+            let fun = abi::find_function(&self.entries, fn_name)
+                .ok_or_else(|| anyhow!("Fn {fn_name:?} not found "))?;
+            codec::decode_eth_output(fun, result)
         }
     }
 
@@ -262,52 +462,28 @@ impl MoveExecutor {
         )
     }
 
-    fn prepare_move_args(
+    /// Parses the arguments by the layouts of the parameters, see [`codec::parse_move_value`].
+    fn prepare_move_args<S: MoveResolver>(
         &self,
+        session: &Session<'_, '_, S>,
+        module: &ModuleId,
         signer: &str,
         args: Option<&str>,
         fun: &LoadedFunctionInstantiation,
     ) -> Result<Vec<Vec<u8>>> {
-        if let Some(args) = args {
-            let res = format!("{},{}", signer, args)
-                .split(',')
-                .zip(&fun.parameters)
-                .map(|(val, tp)| {
-                    let val = val.trim_matches(char::is_whitespace);
-                    match tp {
-                        Type::Bool => bcs::to_bytes(&val.parse::<bool>().unwrap()).unwrap(),
-                        Type::U8 => bcs::to_bytes(&val.parse::<u8>().unwrap()).unwrap(),
-                        Type::U64 => bcs::to_bytes(&val.parse::<u64>().unwrap()).unwrap(),
-                        Type::U128 => bcs::to_bytes(&val.parse::<u128>().unwrap()).unwrap(),
-                        Type::Address => {
-                            bcs::to_bytes(&AccountAddress::from_hex_literal(val).unwrap()).unwrap()
-                        }
-                        Type::Signer => {
-                            bcs::to_bytes(&AccountAddress::from_hex_literal(val).unwrap()).unwrap()
-                        }
-                        Type::Vector(tp) => match tp.as_ref() {
-                            Type::U8 => bcs::to_bytes(&hex::decode(val).unwrap()).unwrap(),
-                            _ => unreachable!(),
-                        },
-                        Type::Reference(tp) => match tp.as_ref() {
-                            Type::Signer => {
-                                bcs::to_bytes(&AccountAddress::from_hex_literal(val).unwrap())
-                                    .unwrap()
-                            }
-                            _ => unreachable!(),
-                        },
-                        Type::Struct(_) => U256::from_dec_str(val)
-                            .map(|v| bcs::to_bytes(&v.0).unwrap())
-                            .unwrap(),
-                        _ => unreachable!(),
-                    }
-                })
-                .collect::<Vec<Vec<u8>>>();
-            Ok(res)
-        } else {
-            let signer = bcs::to_bytes(&AccountAddress::from_hex_literal(signer).unwrap())?;
-            Ok(vec![signer])
-        }
+        let args = match args {
+            Some(args) => format!("{signer},{args}"),
+            None => signer.to_string(),
+        };
+        fn_params_str_split(&args)?
+            .into_iter()
+            .zip(&fun.parameters)
+            .map(|(val, tp)| {
+                codec::parse_move_value(val, &annotated_layout(session, tp)?, module)?
+                    .simple_serialize()
+                    .ok_or_else(|| anyhow!("Failed to serialize {val:?}"))
+            })
+            .collect()
     }
 
     fn prepare_eth_args(
@@ -322,14 +498,12 @@ impl MoveExecutor {
             let request = if fn_name == "constructor" {
                 self.entries.constructor().map(|fun| fun.call_by_str(args))
             } else {
-                self.entries
-                    .functions_by_name(fn_name)?
-                    .first()
-                    .map(|fun| fun.call_by_str(args))
+                let fun = abi::find_function(&self.entries, fn_name)
+                    .ok_or_else(|| anyhow!("Fn {fn_name:?} not found "))?;
+                Some(fun.call_by_str(args))
             };
             if let Some(req) = request {
-                let request = req?;
-                Ok(vec![signer, bcs::to_bytes(&request[4..])?])
+                Ok(vec![signer, codec::encode_eth_request(&req?)?])
             } else {
                 Ok(vec![signer])
             }
@@ -339,6 +513,29 @@ impl MoveExecutor {
     }
 }
 
+/// Layout of the value type with the struct tags, see [`codec::decode_move_value`].
+fn annotated_layout<S: MoveResolver>(
+    session: &Session<'_, '_, S>,
+    tp: &Type,
+) -> Result<MoveTypeLayout> {
+    let tp = match tp {
+        Type::Reference(tp) | Type::MutableReference(tp) => tp.as_ref(),
+        tp => tp,
+    };
+    Ok(session.get_fully_annotated_type_layout(&session.get_type_tag(tp)?)?)
+}
+
+/// Arguments of the call.
+enum CallArgs<'a> {
+    /// Arguments as a string, encoded according to the flags.
+    Str {
+        signer: &'a str,
+        params: Option<&'a str>,
+    },
+    /// BCS encoded arguments including the signer.
+    Encoded(Vec<Vec<u8>>),
+}
+
 #[derive(Copy, Clone)]
 pub enum MoveExecutorInstance {
     None,
@@ -354,7 +551,15 @@ impl Default for MoveExecutorInstance {
 #[derive(Debug)]
 pub struct ExecutionResult {
     pub returns: Vec<Token>,
+    /// Returned values as the BCS bytes and the layouts, as in the output of a view function.
+    pub raw_returns: Vec<(Vec<u8>, MoveTypeLayout)>,
     pub events: Vec<Event>,
+    /// Slots of the module written by the call.
+    pub storage: BTreeMap<U256, U256>,
+    /// Events of the module decoded into the EVM logs.
+    pub logs: Vec<RawLog>,
+    /// Gas charged by the gas schedule of the executor.
+    pub gas: GasUsed,
 }
 
 impl ExecutionResult {
@@ -362,6 +567,3 @@ impl ExecutionResult {
         self.returns.iter().map(|val| format!("{val:?}")).join(", ")
     }
 }
-
-#[derive(Deserialize)]
-pub struct U256Wrapper([u64; 4]);
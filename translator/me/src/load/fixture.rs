@@ -0,0 +1,146 @@
+//! Record and replay of the requests to the node.
+//!
+//! In the record mode the responses of the node are written to the fixture directory.
+//! In the replay mode the requests are served only from the fixtures, the node is not called.
+//! The mode is a field of the [`crate::resolver::Resolver`] set by the
+//! [`crate::MoveExecutor::with_remote`], [`Remote::from_env`] reads `E2M_RECORD=<DIR>` or
+//! `E2M_REPLAY=<DIR>`.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use aptos_crypto::HashValue;
+use itertools::Itertools;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Source of the responses.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Remote {
+    /// The requests are sent to the node.
+    #[default]
+    Live,
+    /// The requests are sent to the node and the responses are written to the directory.
+    Record(PathBuf),
+    /// The responses are read from the directory.
+    Replay(PathBuf),
+}
+
+impl Remote {
+    /// The mode set by `E2M_REPLAY=<DIR>` or `E2M_RECORD=<DIR>`, the node otherwise.
+    pub fn from_env() -> Remote {
+        if let Some(dir) = std::env::var_os("E2M_REPLAY") {
+            Remote::Replay(dir.into())
+        } else if let Some(dir) = std::env::var_os("E2M_RECORD") {
+            Remote::Record(dir.into())
+        } else {
+            Remote::Live
+        }
+    }
+}
+
+/// Request to the node. The fixture is found by the method, the path of the url, the accepted
+/// format and the body, so the responses recorded from one node are replayed for any other.
+pub(crate) struct Request<'a> {
+    pub remote: &'a Remote,
+    pub method: &'static str,
+    pub url: &'a str,
+    pub accept: &'static str,
+    pub body: Option<&'a str>,
+}
+
+/// Recorded response.
+#[derive(Serialize, Deserialize)]
+struct Fixture {
+    method: String,
+    path: String,
+    accept: String,
+    body: Option<String>,
+    /// Hex of the response bytes.
+    response: String,
+}
+
+impl Request<'_> {
+    /// Response to the request. `send` calls the node.
+    pub fn fetch(&self, send: impl FnOnce() -> Result<Vec<u8>>) -> Result<Vec<u8>> {
+        match self.remote {
+            Remote::Live => send(),
+            Remote::Record(dir) => {
+                let response = send()?;
+                let fixture = Fixture {
+                    method: self.method.to_string(),
+                    path: self.path()?,
+                    accept: self.accept.to_string(),
+                    body: self.canonical_body(),
+                    response: hex::encode(&response),
+                };
+                fs::create_dir_all(&dir)?;
+                fs::write(
+                    dir.join(self.file_name()?),
+                    serde_json::to_string_pretty(&fixture)?,
+                )?;
+                Ok(response)
+            }
+            Remote::Replay(dir) => {
+                let path = dir.join(self.file_name()?);
+                let fixture: Fixture =
+                    serde_json::from_str(&fs::read_to_string(&path).map_err(|err| {
+                        anyhow!(
+                            "The fixture of {} {} was not found in {dir:?}. {err}",
+                            self.method,
+                            self.path().unwrap_or_default()
+                        )
+                    })?)?;
+                Ok(hex::decode(fixture.response)?)
+            }
+        }
+    }
+
+    fn path(&self) -> Result<String> {
+        let url = Url::parse(self.url)?;
+        Ok(match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_string(),
+        })
+    }
+
+    /// The JSON body with the sorted keys, so the fixture doesn't depend on the order of the
+    /// keys in the request.
+    fn canonical_body(&self) -> Option<String> {
+        let body = self.body?;
+        Some(match serde_json::from_str::<Value>(body) {
+            Ok(json) => canonical_json(&json),
+            Err(_) => body.to_string(),
+        })
+    }
+
+    fn file_name(&self) -> Result<String> {
+        let key = format!(
+            "{} {}\n{}\n{}",
+            self.method,
+            self.path()?,
+            self.accept,
+            self.canonical_body().unwrap_or_default()
+        );
+        Ok(format!(
+            "{}.json",
+            HashValue::sha3_256_of(key.as_bytes()).to_hex()
+        ))
+    }
+}
+
+fn canonical_json(json: &Value) -> String {
+    match json {
+        Value::Object(map) => format!(
+            "{{{}}}",
+            map.iter()
+                .sorted_by_key(|(key, _)| *key)
+                .map(|(key, val)| format!("{}:{}", Value::from(key.as_str()), canonical_json(val)))
+                .join(",")
+        ),
+        Value::Array(items) => format!("[{}]", items.iter().map(canonical_json).join(",")),
+        json => json.to_string(),
+    }
+}
@@ -12,9 +12,11 @@ use move_core_types::{
     language_storage::{ModuleId, ResourceKey, StructTag},
 };
 
+pub mod fixture;
 mod response;
 
 use crate::{
+    load::fixture::{Remote, Request},
     load::response::{MoveModuleId, MoveResource},
     profile::ProfileConfig,
     resolver::{print_access_path::AccessPathToString, HandleRequest},
@@ -34,7 +36,7 @@ pub trait LoadRemoteData {
 
 impl LoadRemoteData for MoveExecutor {
     fn load_modules(&mut self, profile: &ProfileConfig, address: &AccountAddress) -> Result<()> {
-        for (name, bytecode) in load_modules(profile, address)? {
+        for (name, bytecode) in load_modules(profile, address, &self.resolver.remote)? {
             log::info!("loaded module: {}", name.to_string());
             self.resolver.state_data.insert(name, bytecode);
         }
@@ -42,9 +44,12 @@ impl LoadRemoteData for MoveExecutor {
     }
 
     fn load_resources(&mut self, profile: &ProfileConfig, address: &AccountAddress) -> Result<()> {
-        self.resolver.handler_data = load_handel_link(profile, address)?;
+        let remote = &self.resolver.remote;
+        let handler_data = load_handel_link(profile, address, remote)?;
+        let resources = load_resource(profile, address, remote)?;
 
-        for (state_key, bytecode) in load_resource(profile, address)? {
+        self.resolver.handler_data = handler_data;
+        for (state_key, bytecode) in resources {
             log::info!("loaded resource: {}", state_key.to_string());
             self.resolver.state_data.insert(state_key, bytecode);
         }
@@ -54,7 +59,11 @@ impl LoadRemoteData for MoveExecutor {
 }
 
 /// https://fullnode.devnet.aptoslabs.com/v1/tables/{table_handle}/item
-pub fn load_table_handle_u256(data: &HandleRequest, key: &Vec<u8>) -> Result<Option<Vec<u8>>> {
+pub fn load_table_handle_u256(
+    data: &HandleRequest,
+    key: &Vec<u8>,
+    remote: &Remote,
+) -> Result<Option<Vec<u8>>> {
     log::info!("load_table_handle_u256 {data:?} {key:?}");
 
     let mut headers = header::HeaderMap::new();
@@ -82,46 +91,47 @@ pub fn load_table_handle_u256(data: &HandleRequest, key: &Vec<u8>) -> Result<Opt
         log::trace!("{b}");
     }
 
-    let response = reqwest::blocking::Client::builder()
-        .default_headers(headers)
-        .build()
-        .map_err(|err| {
-            log::error!("request build: {err:?}");
-            anyhow!("The resource was not found. Check that the address is correct")
-        })?
-        .post(data.url.as_ref())
-        .body(body.to_string())
-        .send()
-        .map_err(|err| {
-            log::error!("send: {err:?}");
-            anyhow!("The resource was not found. Check that the address is correct")
-        })?;
-
-    log::trace!("status {}", response.status());
-
-    let result = match response.bytes() {
-        Ok(bytes) => {
-            let result = bytes.to_vec();
-            log::trace!("bytes: {:?}", &result);
-
-            match String::from_utf8(result.clone()).ok().and_then(|msg| {
-                log::trace!("load_table_handle_u256 response: {msg}");
-                serde_json::from_str::<serde_json::Value>(&msg).ok()
-            }) {
-                Some(err) => {
-                    log::error!(
-                        "load_table_handle_u256 json error: {}",
-                        serde_json::to_string_pretty(&err).unwrap()
-                    );
-                    None
-                }
-                None => Some(result),
-            }
-        }
-        Err(error) => {
-            log::error!("load_table_handle_u256: {error:?}");
+    let body = body.to_string();
+    let request = Request {
+        remote,
+        method: "POST",
+        url: data.url.as_ref(),
+        accept: "application/x-bcs",
+        body: Some(&body),
+    };
+    let result = request.fetch(|| {
+        let response = reqwest::blocking::Client::builder()
+            .default_headers(headers)
+            .build()
+            .map_err(|err| {
+                log::error!("request build: {err:?}");
+                anyhow!("The resource was not found. Check that the address is correct")
+            })?
+            .post(data.url.as_ref())
+            .body(body.clone())
+            .send()
+            .map_err(|err| {
+                log::error!("send: {err:?}");
+                anyhow!("The resource was not found. Check that the address is correct")
+            })?;
+
+        log::trace!("status {}", response.status());
+        Ok(response.bytes()?.to_vec())
+    })?;
+    log::trace!("bytes: {:?}", &result);
+
+    let result = match String::from_utf8(result.clone()).ok().and_then(|msg| {
+        log::trace!("load_table_handle_u256 response: {msg}");
+        serde_json::from_str::<serde_json::Value>(&msg).ok()
+    }) {
+        Some(err) => {
+            log::error!(
+                "load_table_handle_u256 json error: {}",
+                serde_json::to_string_pretty(&err).unwrap()
+            );
             None
         }
+        None => Some(result),
     };
     log::trace!("{result:?}");
 
@@ -133,6 +143,7 @@ pub fn load_table_handle_u256(data: &HandleRequest, key: &Vec<u8>) -> Result<Opt
 fn load_handel_link(
     profile: &ProfileConfig,
     address: &AccountAddress,
+    remote: &Remote,
 ) -> Result<HashMap<AccountAddress, HandleRequest>> {
     let address_hex = address.to_hex_literal();
 
@@ -142,7 +153,7 @@ fn load_handel_link(
         .ok_or_else(|| anyhow!("The rest_url in the profile is not specified"))?;
     let url = format!("{rest_url}/v1/accounts/{address_hex}/resources");
 
-    let result = request_json_by_url::<Vec<MoveResource>>(&url)?
+    let result = request_json_by_url::<Vec<MoveResource>>(&url, remote)?
         .into_iter()
         .filter_map(|resource| {
             let index = Identifier::from_str("events").ok()?;
@@ -177,11 +188,25 @@ fn load_handel_link(
     Ok(result)
 }
 
+/// Returns the bytecode of a published module
+/// URL: https://fullnode.devnet.aptoslabs.com/v1/accounts/{address}/modules
+pub fn load_module(
+    profile: &ProfileConfig,
+    module_id: &ModuleId,
+    remote: &Remote,
+) -> Result<Vec<u8>> {
+    let key = StateKey::AccessPath(AccessPath::code_access_path(module_id.clone()));
+    load_modules(profile, module_id.address(), remote)?
+        .remove(&key)
+        .ok_or_else(|| anyhow!("Module {module_id} was not found"))
+}
+
 /// Returns a list of modules with a bytecode
 /// URL: https://fullnode.devnet.aptoslabs.com/v1/accounts/{address}/modules
 fn load_modules(
     profile: &ProfileConfig,
     address: &AccountAddress,
+    remote: &Remote,
 ) -> Result<BTreeMap<StateKey, Vec<u8>>> {
     let address_hex = address.to_hex_literal();
 
@@ -191,7 +216,7 @@ fn load_modules(
         .ok_or_else(|| anyhow!("The rest_url in the profile is not specified"))?;
     let url = format!("{rest_url}/v1/accounts/{address_hex}/modules");
 
-    Ok(request_bcs_by_url::<MoveModuleId>(&url)?
+    Ok(request_bcs_by_url::<MoveModuleId>(&url, remote)?
         .into_iter()
         .map(|(module_id, bytecode)| {
             let acc = StateKey::AccessPath(AccessPath::code_access_path(ModuleId::new(
@@ -208,6 +233,7 @@ fn load_modules(
 fn load_resource(
     profile: &ProfileConfig,
     address: &AccountAddress,
+    remote: &Remote,
 ) -> Result<BTreeMap<StateKey, Vec<u8>>> {
     let address_hex = address.to_hex_literal();
 
@@ -217,7 +243,7 @@ fn load_resource(
         .ok_or_else(|| anyhow!("The rest_url in the profile is not specified"))?;
     let url = format!("{rest_url}/v1/accounts/{address_hex}/resources");
 
-    let list: BTreeMap<StateKey, Vec<u8>> = request_bcs_by_url::<StructTag>(&url)?
+    let list: BTreeMap<StateKey, Vec<u8>> = request_bcs_by_url::<StructTag>(&url, remote)?
         .into_iter()
         .map(|(st, value)| {
             let rs = ResourceKey::new(*address, st);
@@ -229,7 +255,7 @@ fn load_resource(
     Ok(list)
 }
 
-fn request_json_by_url<T>(url: &str) -> Result<T>
+fn request_json_by_url<T>(url: &str, remote: &Remote) -> Result<T>
 where
     T: serde::de::DeserializeOwned,
 {
@@ -239,26 +265,35 @@ where
         header::HeaderValue::from_static("application/json"),
     );
 
-    let response = reqwest::blocking::Client::builder()
-        .default_headers(headers)
-        .build()?
-        .get(url)
-        .send()
-        .map_err(|err| {
-            log::error!("send: {err:?}",);
-
-            anyhow!("The resource was not found. Check that the address is correct")
-        })?
-        .json()
-        .map_err(|err| {
-            log::error!("json error: {err:?}");
-
-            anyhow!("The resource was not found. Check that the address is correct")
-        })?;
-    Ok(response)
+    let request = Request {
+        remote,
+        method: "GET",
+        url,
+        accept: "application/json",
+        body: None,
+    };
+    let response = request.fetch(|| {
+        let response = reqwest::blocking::Client::builder()
+            .default_headers(headers)
+            .build()?
+            .get(url)
+            .send()
+            .map_err(|err| {
+                log::error!("send: {err:?}",);
+
+                anyhow!("The resource was not found. Check that the address is correct")
+            })?
+            .bytes()?;
+        Ok(response.to_vec())
+    })?;
+    serde_json::from_slice(&response).map_err(|err| {
+        log::error!("json error: {err:?}");
+
+        anyhow!("The resource was not found. Check that the address is correct")
+    })
 }
 
-fn request_bcs_by_url<T>(url: &str) -> Result<BTreeMap<T, Vec<u8>>>
+fn request_bcs_by_url<T>(url: &str, remote: &Remote) -> Result<BTreeMap<T, Vec<u8>>>
 where
     T: serde::de::DeserializeOwned + std::cmp::Ord,
 {
@@ -268,12 +303,22 @@ where
         header::HeaderValue::from_static("application/x-bcs"),
     );
 
-    let response = reqwest::blocking::Client::builder()
-        .default_headers(headers)
-        .build()?
-        .get(url)
-        .send()?
-        .bytes()?;
+    let request = Request {
+        remote,
+        method: "GET",
+        url,
+        accept: "application/x-bcs",
+        body: None,
+    };
+    let response = request.fetch(|| {
+        let response = reqwest::blocking::Client::builder()
+            .default_headers(headers)
+            .build()?
+            .get(url)
+            .send()?
+            .bytes()?;
+        Ok(response.to_vec())
+    })?;
     bcs::from_bytes(response.as_ref()).map_err(|err| {
         log::error!(
             "{err:?} {}",
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use reqwest::Url;
 
 use aptos_aggregator::transaction::ChangeSetExt;
@@ -8,6 +10,7 @@ use aptos_state_view::StateView;
 use aptos_types::state_store::{state_key::StateKey, state_storage_usage::StateStorageUsage};
 use aptos_types::write_set::WriteOp;
 
+use crate::load::fixture::Remote;
 use crate::load::load_table_handle_u256;
 use crate::resolver::print_access_path::AccessPathToString;
 use move_core_types::account_address::AccountAddress;
@@ -18,6 +21,8 @@ pub mod print_access_path;
 pub struct Resolver {
     pub state_data: HashMap<StateKey, Vec<u8>>,
     pub handler_data: HashMap<AccountAddress, HandleRequest>,
+    /// Source of the table items of the remote tables.
+    pub remote: Remote,
 }
 
 impl StateView for Resolver {
@@ -43,7 +48,7 @@ impl StateView for Resolver {
             StateKey::TableItem { handle, key } => {
                 log::trace!("handle {} {}", handle.0, hex::encode(key));
                 if let Some(data) = self.handler_data.get(&handle.0) {
-                    return load_table_handle_u256(data, key);
+                    return load_table_handle_u256(data, key, &self.remote);
                 }
             }
             StateKey::Raw(raw) => {
@@ -88,6 +93,29 @@ impl Resolver {
     pub fn remove(&mut self, state_key: &StateKey) -> Option<Vec<u8>> {
         self.state_data.remove(state_key)
     }
+
+    /// Saves the state and the sequence number of the next transaction to the `path` in BCS.
+    /// The handles of the remote tables are not saved.
+    pub fn save<P: AsRef<Path>>(&self, path: P, seq: u64) -> Result<()> {
+        let mut state = self.state_data.iter().collect::<Vec<_>>();
+        // The same state is always saved into the same bytes.
+        state.sort_by_cached_key(|(key, _)| bcs::to_bytes(key).unwrap_or_default());
+        fs::write(path, bcs::to_bytes(&(seq, state))?)?;
+        Ok(())
+    }
+
+    /// Loads the state and the sequence number saved by [`Resolver::save`].
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<(Resolver, u64)> {
+        let path = path.as_ref();
+        let (seq, state): (u64, Vec<(StateKey, Vec<u8>)>) = bcs::from_bytes(&fs::read(path)?)
+            .map_err(|err| anyhow!("Invalid state file {path:?}. {err}"))?;
+        let resolver = Resolver {
+            state_data: state.into_iter().collect(),
+            handler_data: HashMap::new(),
+            remote: Remote::default(),
+        };
+        Ok((resolver, seq))
+    }
 }
 
 #[derive(Debug, Clone)]
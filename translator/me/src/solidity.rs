@@ -24,14 +24,14 @@ impl FromSolidity for MoveExecutor {
     ) -> Result<MoveExecutor> {
         let pack = build_sol(path)?;
         let cfg = translator::Config {
-            contract_addr: signer,
-            name: pack.name(),
             initialization_args,
             flags,
+            ..translator::Config::for_pack(signer, &pack)
         };
         let mv = translator::translate(pack.bin_contract(), pack.abi_str(), cfg)
             .map_err(|err| anyhow!("translator: {err:?}"))?;
         let mut vm = MoveExecutor::new(pack.abi()?, flags, MoveExecutorInstance::Aptos);
+        vm.set_layout(mv.layout);
         vm.deploy(&signer.to_hex_literal(), mv.bytecode)?;
 
         Ok(vm)
@@ -0,0 +1,298 @@
+//! Storage and events of the translated modules in terms of the EVM.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{anyhow, ensure, Result};
+use aptos_types::access_path::AccessPath;
+use aptos_types::event::EventHandle;
+use aptos_types::state_store::state_key::StateKey;
+use aptos_types::state_store::table::TableHandle;
+use aptos_types::write_set::{WriteOp, WriteSet};
+use ethabi::ethereum_types::U256;
+use ethabi::RawLog;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::effects::Event;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::{ModuleId, ResourceKey, StructTag, TypeTag};
+
+use eth::abi::codec::{decode_raw_log, U256Wrapper};
+use eth::layout::{entry_slot, Field, FieldKind, KeyType, Layout};
+use serde::Deserialize;
+
+use crate::resolver::Resolver;
+
+/// Size of a `U256` and of a table handle in BCS.
+const WORD: usize = 32;
+
+/// Tables and resources which keep the storage of a translated module.
+///
+/// The raw slots are kept in the table of the `Persist` resource of the module. The typed state and
+/// the native mappings of the `Mappings` resource keep the other slots, which are mapped back to
+/// their raw slots.
+struct ModuleStorage<'a> {
+    /// Table of the raw slots.
+    slots: TableHandle,
+    /// BCS of the slot of the native mapping by the table of its values.
+    mappings: HashMap<TableHandle, Vec<u8>>,
+    /// Raw slots of the native mapping entries by the BCS of their `EntryRef`.
+    entries: HashMap<Vec<u8>, U256>,
+    /// Typed state resource.
+    state: StateKey,
+    layout: &'a Layout,
+    /// Mapping fields of the typed state by their tables.
+    state_tables: HashMap<TableHandle, &'a Field>,
+    /// The module keeps a typed state, but the layout to decode it is not set.
+    untyped_state: bool,
+}
+
+impl<'a> ModuleStorage<'a> {
+    /// Storage of the module. `None` if the module keeps no storage, e.g. before the constructor.
+    /// The typed state is decoded by the `layout` the module was translated with.
+    fn resolve(
+        resolver: &Resolver,
+        module: &ModuleId,
+        layout: &'a Layout,
+    ) -> Result<Option<ModuleStorage<'a>>> {
+        let persist = resolver.state_data.get(&resource_key(module, "Persist")?);
+        let persist: PersistData = match persist {
+            Some(data) => bcs::from_bytes(data)
+                .map_err(|err| anyhow!("Invalid Persist of {module}: {err}"))?,
+            None => return Ok(None),
+        };
+        let items = |table: AccountAddress| {
+            resolver
+                .state_data
+                .iter()
+                .filter_map(move |(key, val)| match key {
+                    StateKey::TableItem { handle, key } if handle.0 == table => Some((key, val)),
+                    _ => None,
+                })
+        };
+
+        let mut mappings = HashMap::new();
+        let mut entries = HashMap::new();
+        // Only the modules translated with the native mappings keep them.
+        if let Some(data) = resolver.state_data.get(&resource_key(module, "Mappings")?) {
+            let data: MappingsData = bcs::from_bytes(data)
+                .map_err(|err| anyhow!("Invalid Mappings of {module}: {err}"))?;
+            for (slot, mapping) in items(data.mappings) {
+                let mapping: MappingData = bcs::from_bytes(mapping)
+                    .map_err(|err| anyhow!("Invalid mapping of {module}: {err}"))?;
+                mappings.insert(TableHandle(mapping.values), slot.clone());
+            }
+            for (raw, entry) in items(data.entries) {
+                entries.insert(entry.clone(), decode_u256(raw)?);
+            }
+        }
+
+        let state = resource_key(module, "State")?;
+        let mut state_tables = HashMap::new();
+        let data = resolver.state_data.get(&state);
+        let untyped_state = data.is_some() && layout.is_empty();
+        if let Some(data) = data.filter(|_| !untyped_state) {
+            for (field, word) in state_words(module, data, layout)? {
+                if matches!(field.kind, FieldKind::Mapping(_)) {
+                    state_tables.insert(TableHandle(AccountAddress::from_bytes(word)?), field);
+                }
+            }
+        }
+
+        Ok(Some(ModuleStorage {
+            slots: TableHandle(persist.tbl),
+            mappings,
+            entries,
+            state,
+            layout,
+            state_tables,
+            untyped_state,
+        }))
+    }
+
+    /// Raw slot and value of the table item. `None` if the table keeps no slots.
+    /// A deleted item is zero.
+    fn item(
+        &self,
+        handle: &TableHandle,
+        key: &[u8],
+        val: Option<&[u8]>,
+    ) -> Result<Option<(U256, U256)>> {
+        let slot = if *handle == self.slots {
+            decode_u256(key)?
+        } else if let Some(slot) = self.mappings.get(handle) {
+            // `EntryRef` is the slot of the mapping followed by the keys of the entry.
+            *self
+                .entries
+                .get(&[slot.as_slice(), key].concat())
+                .ok_or_else(|| {
+                    anyhow!("The entry of the native mapping has no raw slot: {key:?}")
+                })?
+        } else if let Some(field) = self.state_tables.get(handle) {
+            let key = match field.kind {
+                FieldKind::Mapping(KeyType::Address) => U256::from_big_endian(key),
+                _ => decode_u256(key)?,
+            };
+            U256(entry_slot(primitive_types::U256(key.0), field.slot).0)
+        } else {
+            return Ok(None);
+        };
+        let val = val.map(decode_u256).transpose()?.unwrap_or_default();
+        Ok(Some((slot, val)))
+    }
+
+    /// Slots of the value fields of the typed state resource.
+    fn state_values(&self, module: &ModuleId, data: &[u8]) -> Result<Vec<(U256, U256)>> {
+        state_words(module, data, self.layout)?
+            .into_iter()
+            .filter(|(field, _)| field.kind == FieldKind::Value)
+            .map(|(field, word)| Ok((U256(field.slot.0), decode_u256(word)?)))
+            .collect()
+    }
+}
+
+/// Slots of the module. The zero slots are omitted.
+pub fn storage(
+    resolver: &Resolver,
+    module: &ModuleId,
+    layout: &Layout,
+) -> Result<BTreeMap<U256, U256>> {
+    let store = ModuleStorage::resolve(resolver, module, layout)?
+        .ok_or_else(|| anyhow!("The storage of {module} was not found"))?;
+    ensure!(
+        !store.untyped_state,
+        "The typed state of {module} can't be decoded without the storage layout"
+    );
+
+    let mut storage = BTreeMap::new();
+    // The typed entries fall back to the raw slots, so they take precedence.
+    let mut typed = Vec::new();
+    for (key, val) in &resolver.state_data {
+        match key {
+            StateKey::TableItem { handle, key } => {
+                if let Some((slot, val)) = store.item(handle, key, Some(val))? {
+                    if *handle == store.slots {
+                        storage.insert(slot, val);
+                    } else {
+                        typed.push((slot, val));
+                    }
+                }
+            }
+            key if *key == store.state => typed.extend(store.state_values(module, val)?),
+            _ => {}
+        }
+    }
+    storage.extend(typed);
+    storage.retain(|_, val| !val.is_zero());
+    Ok(storage)
+}
+
+/// Slots written by the `write_set`. A deleted slot is zero.
+pub fn storage_writes(
+    resolver: &Resolver,
+    write_set: &WriteSet,
+    module: &ModuleId,
+    layout: &Layout,
+) -> Result<BTreeMap<U256, U256>> {
+    let store = match ModuleStorage::resolve(resolver, module, layout)? {
+        Some(store) => store,
+        None => return Ok(BTreeMap::new()),
+    };
+    if store.untyped_state {
+        log::warn!("The storage layout of {module} is not set, its typed state is not reported");
+    }
+
+    let mut writes = BTreeMap::new();
+    // The first write of a native mapping entry deletes its raw slot, so the typed writes go last.
+    let mut typed = Vec::new();
+    for (key, op) in write_set {
+        let val = match op {
+            WriteOp::Creation(val) | WriteOp::Modification(val) => Some(val.as_slice()),
+            WriteOp::Deletion => None,
+        };
+        match key {
+            StateKey::TableItem { handle, key } => {
+                if let Some((slot, val)) = store.item(handle, key, val)? {
+                    if *handle == store.slots {
+                        writes.insert(slot, val);
+                    } else {
+                        typed.push((slot, val));
+                    }
+                }
+            }
+            key if *key == store.state && !store.untyped_state => {
+                if let Some(val) = val {
+                    typed.extend(store.state_values(module, val)?);
+                }
+            }
+            _ => {}
+        }
+    }
+    writes.extend(typed);
+    Ok(writes)
+}
+
+fn resource_key(module: &ModuleId, name: &str) -> Result<StateKey> {
+    let tag = StructTag {
+        address: *module.address(),
+        module: module.name().to_owned(),
+        name: Identifier::new(name)?,
+        type_params: vec![],
+    };
+    Ok(StateKey::AccessPath(AccessPath::resource_access_path(
+        ResourceKey::new(*module.address(), tag),
+    )))
+}
+
+/// Fields of the typed state with their words: each field is a `U256` or a table handle.
+fn state_words<'a, 'b>(
+    module: &ModuleId,
+    data: &'b [u8],
+    layout: &'a Layout,
+) -> Result<Vec<(&'a Field, &'b [u8])>> {
+    ensure!(
+        data.len() == layout.fields().len() * WORD,
+        "The typed state of {module} doesn't match the storage layout"
+    );
+    Ok(layout.fields().iter().zip(data.chunks(WORD)).collect())
+}
+
+/// BCS of the `Persist` resource.
+#[derive(Deserialize)]
+struct PersistData {
+    tbl: AccountAddress,
+    _events: EventHandle,
+}
+
+/// BCS of the `Mappings` resource.
+#[derive(Deserialize)]
+struct MappingsData {
+    mappings: AccountAddress,
+    entries: AccountAddress,
+}
+
+/// BCS of the native mapping.
+#[derive(Deserialize)]
+struct MappingData {
+    values: AccountAddress,
+    _keys: AccountAddress,
+    _len: u64,
+}
+
+/// Logs emitted by the module.
+pub fn logs(events: &[Event], module: &ModuleId) -> Result<Vec<RawLog>> {
+    events
+        .iter()
+        .filter(|(_, _, tp, _)| match tp {
+            TypeTag::Struct(tag) => {
+                tag.address == *module.address()
+                    && tag.module.as_ident_str() == module.name()
+                    && tag.name.as_str() == "Event"
+            }
+            _ => false,
+        })
+        .map(|(.., data)| decode_raw_log(data))
+        .collect()
+}
+
+fn decode_u256(data: &[u8]) -> Result<U256> {
+    Ok(U256(bcs::from_bytes::<U256Wrapper>(data)?.0))
+}
@@ -0,0 +1,581 @@
+//! Static gas estimation of the translated module.
+//!
+//! The estimate walks the control flow graph of every entry function and of the intrinsics it
+//! calls and prices the instructions with the Aptos instruction gas schedule.
+//! The costs are reported in internal gas units. Native functions and the functions of the
+//! modules which are not passed to the estimator are priced as a bare call.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+use move_binary_format::access::ModuleAccess;
+use move_binary_format::file_format::{
+    Bytecode, CodeOffset, FunctionDefinition, FunctionHandleIndex, SignatureToken,
+    StructFieldInformation,
+};
+use move_binary_format::CompiledModule;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::ModuleId;
+
+/// Assumed number of iterations of the loops inside the called functions.
+/// The intrinsic loops mostly walk over the bytes or the limbs of a word.
+pub const CALLEE_LOOP_ITERATIONS: u64 = 8;
+
+/// Gas estimation of the module entry functions.
+#[derive(Debug, Clone, Default)]
+pub struct GasReport {
+    pub functions: Vec<FunctionGas>,
+}
+
+impl GasReport {
+    pub fn function(&self, name: &str) -> Option<&FunctionGas> {
+        self.functions.iter().find(|fun| fun.name == name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FunctionGas {
+    pub name: String,
+    /// Cost of the most expensive path with a single pass through every loop of the function.
+    pub worst: u64,
+    /// Cost of the path when every branch is taken with equal probability and the aborting
+    /// paths are skipped.
+    pub typical: u64,
+    /// Loops of the function itself.
+    pub loops: Vec<LoopGas>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoopGas {
+    /// Code offset of the loop head.
+    pub offset: CodeOffset,
+    /// Worst-case cost of one more iteration of the loop.
+    pub per_iteration: u64,
+}
+
+impl Display for GasReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for fun in &self.functions {
+            writeln!(
+                f,
+                "{}: worst {}, typical {}",
+                fun.name, fun.worst, fun.typical
+            )?;
+            for lp in &fun.loops {
+                writeln!(
+                    f,
+                    "    loop at {}: {} per iteration",
+                    lp.offset, lp.per_iteration
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Estimates the gas of the entry functions of `module`.
+/// `deps` are the modules whose functions are analyzed when they are called,
+/// e.g. the shared intrinsic library.
+pub fn estimate(module: &CompiledModule, deps: &[&CompiledModule]) -> GasReport {
+    let mut estimator = Estimator::new(module, deps);
+    let functions = module
+        .function_defs()
+        .iter()
+        .filter(|def| def.is_entry)
+        .map(|def| {
+            let name = module
+                .identifier_at(module.function_handle_at(def.function).name)
+                .to_string();
+            let (cost, loops) = estimator.code_cost(module, def, false);
+            let call = call_cost(module, def.function);
+            FunctionGas {
+                name,
+                worst: call + cost.worst,
+                typical: call + cost.typical,
+                loops,
+            }
+        })
+        .collect();
+    GasReport { functions }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Cost {
+    worst: u64,
+    typical: u64,
+}
+
+impl Cost {
+    fn flat(cost: u64) -> Cost {
+        Cost {
+            worst: cost,
+            typical: cost,
+        }
+    }
+
+    fn add(&mut self, other: Cost) {
+        self.worst += other.worst;
+        self.typical += other.typical;
+    }
+}
+
+struct Estimator<'a> {
+    modules: HashMap<ModuleId, &'a CompiledModule>,
+    functions: HashMap<(ModuleId, Identifier), Cost>,
+    visiting: HashSet<(ModuleId, Identifier)>,
+}
+
+impl<'a> Estimator<'a> {
+    fn new(module: &'a CompiledModule, deps: &[&'a CompiledModule]) -> Estimator<'a> {
+        let modules = Some(module)
+            .into_iter()
+            .chain(deps.iter().copied())
+            .map(|module| (module.self_id(), module))
+            .collect();
+        Estimator {
+            modules,
+            functions: HashMap::new(),
+            visiting: HashSet::new(),
+        }
+    }
+
+    /// Cost of the called function including the call itself.
+    fn call(&mut self, module: &CompiledModule, handle: FunctionHandleIndex) -> Cost {
+        let fun = module.function_handle_at(handle);
+        let id = (
+            module.module_id_for_handle(module.module_handle_at(fun.module)),
+            module.identifier_at(fun.name).to_owned(),
+        );
+        let mut cost = Cost::flat(call_cost(module, handle));
+        cost.add(self.function(id));
+        cost
+    }
+
+    fn function(&mut self, id: (ModuleId, Identifier)) -> Cost {
+        if let Some(cost) = self.functions.get(&id) {
+            return *cost;
+        }
+        // Recursive calls are priced as a bare call.
+        if !self.visiting.insert(id.clone()) {
+            return Cost::default();
+        }
+        let module: Option<&'a CompiledModule> = self.modules.get(&id.0).copied();
+        let def = module.and_then(|module| {
+            module
+                .function_defs()
+                .iter()
+                .find(|def| {
+                    module.identifier_at(module.function_handle_at(def.function).name)
+                        == id.1.as_ident_str()
+                })
+                .map(|def| (module, def))
+        });
+        let cost = match def {
+            Some((module, def)) => self.code_cost(module, def, true).0,
+            None => Cost::default(),
+        };
+        self.visiting.remove(&id);
+        self.functions.insert(id, cost);
+        cost
+    }
+
+    /// Cost of the code unit and the per-iteration costs of its loops.
+    /// The loops of the called functions are assumed to run `CALLEE_LOOP_ITERATIONS` times.
+    /// Native functions cost nothing besides the call.
+    fn code_cost(
+        &mut self,
+        module: &CompiledModule,
+        def: &FunctionDefinition,
+        callee: bool,
+    ) -> (Cost, Vec<LoopGas>) {
+        let code = match &def.code {
+            Some(code) => code,
+            None => return (Cost::default(), vec![]),
+        };
+        let locals = module
+            .signature_at(module.function_handle_at(def.function).parameters)
+            .0
+            .iter()
+            .chain(&module.signature_at(code.locals).0)
+            .map(|tp| value_size(module, tp))
+            .collect::<Vec<_>>();
+        let cfg = Cfg::new(&code.code);
+        let mut costs = cfg
+            .blocks
+            .iter()
+            .map(|block| {
+                let mut cost = Cost::default();
+                for instr in &code.code[block.start as usize..=block.end as usize] {
+                    match instr {
+                        Bytecode::Call(handle) => cost.add(self.call(module, *handle)),
+                        Bytecode::CallGeneric(idx) => {
+                            let handle = module.function_instantiation_at(*idx).handle;
+                            cost.add(self.call(module, handle));
+                        }
+                        _ => cost.add(Cost::flat(instr_cost(module, &locals, instr))),
+                    }
+                }
+                cost
+            })
+            .collect::<Vec<_>>();
+
+        // Inner loops go first, so the outer loop bodies include their iterations.
+        let mut loop_gas = Vec::new();
+        for (head, tails) in cfg.loops() {
+            let per_iteration = cfg.longest_path(&costs, head, &tails);
+            if callee {
+                costs[head].worst += per_iteration.worst * (CALLEE_LOOP_ITERATIONS - 1);
+                costs[head].typical += per_iteration.typical * (CALLEE_LOOP_ITERATIONS - 1);
+            } else {
+                loop_gas.push(LoopGas {
+                    offset: cfg.blocks[head].start,
+                    per_iteration: per_iteration.worst,
+                });
+            }
+        }
+        loop_gas.sort_by_key(|lp| lp.offset);
+
+        (cfg.cost(&costs), loop_gas)
+    }
+}
+
+/// Basic block of the code: instructions from `start` to `end` inclusive.
+struct Block {
+    start: CodeOffset,
+    end: CodeOffset,
+    successors: Vec<usize>,
+}
+
+/// Control flow graph with the back edges split out.
+struct Cfg {
+    blocks: Vec<Block>,
+    /// Blocks in the reverse topological order of the forward edges.
+    post_order: Vec<usize>,
+    /// Back edges as (from, to) pairs.
+    back_edges: Vec<(usize, usize)>,
+    /// Whether the block reaches the return without aborting.
+    returns: Vec<bool>,
+}
+
+impl Cfg {
+    fn new(code: &[Bytecode]) -> Cfg {
+        let mut starts = vec![0];
+        for (offset, instr) in code.iter().enumerate() {
+            match instr {
+                Bytecode::Branch(target) | Bytecode::BrTrue(target) | Bytecode::BrFalse(target) => {
+                    starts.push(*target);
+                    starts.push(offset as CodeOffset + 1);
+                }
+                Bytecode::Ret | Bytecode::Abort => starts.push(offset as CodeOffset + 1),
+                _ => {}
+            }
+        }
+        starts.retain(|start| (*start as usize) < code.len());
+        starts.sort_unstable();
+        starts.dedup();
+
+        let index = starts
+            .iter()
+            .enumerate()
+            .map(|(idx, start)| (*start, idx))
+            .collect::<BTreeMap<_, _>>();
+        let blocks = starts
+            .iter()
+            .enumerate()
+            .map(|(idx, start)| {
+                let end = starts
+                    .get(idx + 1)
+                    .map(|next| next - 1)
+                    .unwrap_or(code.len() as CodeOffset - 1);
+                let successors = match &code[end as usize] {
+                    Bytecode::Branch(target) => vec![index[target]],
+                    Bytecode::BrTrue(target) | Bytecode::BrFalse(target) => {
+                        let mut successors = vec![index[target]];
+                        if idx + 1 < starts.len() {
+                            successors.push(idx + 1);
+                        }
+                        successors
+                    }
+                    Bytecode::Ret | Bytecode::Abort => vec![],
+                    _ if idx + 1 < starts.len() => vec![idx + 1],
+                    _ => vec![],
+                };
+                Block {
+                    start: *start,
+                    end,
+                    successors,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let (post_order, back_edges) = Self::dfs(&blocks);
+        let mut cfg = Cfg {
+            blocks,
+            post_order,
+            back_edges,
+            returns: vec![],
+        };
+        let mut returns = vec![false; cfg.blocks.len()];
+        for &block in &cfg.post_order {
+            returns[block] = matches!(code[cfg.blocks[block].end as usize], Bytecode::Ret)
+                || cfg.forward(block).any(|succ| returns[succ]);
+        }
+        cfg.returns = returns;
+        cfg
+    }
+
+    fn dfs(blocks: &[Block]) -> (Vec<usize>, Vec<(usize, usize)>) {
+        let mut post_order = Vec::with_capacity(blocks.len());
+        let mut back_edges = Vec::new();
+        let mut on_stack = vec![false; blocks.len()];
+        let mut visited = vec![false; blocks.len()];
+        let mut stack = vec![(0, 0)];
+        visited[0] = true;
+        on_stack[0] = true;
+        while let Some((block, next)) = stack.last_mut() {
+            let block = *block;
+            if let Some(&succ) = blocks[block].successors.get(*next) {
+                *next += 1;
+                if on_stack[succ] {
+                    back_edges.push((block, succ));
+                } else if !visited[succ] {
+                    visited[succ] = true;
+                    on_stack[succ] = true;
+                    stack.push((succ, 0));
+                }
+            } else {
+                on_stack[block] = false;
+                post_order.push(block);
+                stack.pop();
+            }
+        }
+        (post_order, back_edges)
+    }
+
+    /// Successors of the block along the forward edges.
+    fn forward(&self, block: usize) -> impl Iterator<Item = usize> + '_ {
+        self.blocks[block]
+            .successors
+            .iter()
+            .copied()
+            .filter(move |succ| !self.back_edges.contains(&(block, *succ)))
+    }
+
+    /// Loops as their head and the blocks that jump back to the head.
+    /// The inner loops finish earlier, so they come before the outer ones.
+    fn loops(&self) -> Vec<(usize, Vec<usize>)> {
+        let mut loops = BTreeMap::<usize, Vec<usize>>::new();
+        for (from, to) in &self.back_edges {
+            loops.entry(*to).or_default().push(*from);
+        }
+        let mut loops = loops.into_iter().collect::<Vec<_>>();
+        loops.sort_by_key(|(head, _)| self.post_order.iter().position(|block| block == head));
+        loops
+    }
+
+    /// Cost of the most expensive path from the loop head to one of the back edges.
+    /// The cost of the typical path is averaged over the branches inside the loop.
+    fn longest_path(&self, costs: &[Cost], head: usize, tails: &[usize]) -> Cost {
+        let mut paths: Vec<Option<Cost>> = vec![None; self.blocks.len()];
+        for &block in &self.post_order {
+            let succ = self
+                .forward(block)
+                .filter_map(|succ| paths[succ])
+                .collect::<Vec<_>>();
+            let tail = tails.contains(&block);
+            if !tail && succ.is_empty() {
+                continue;
+            }
+            let mut cost = costs[block];
+            if !tail {
+                cost.worst += succ.iter().map(|cost| cost.worst).max().unwrap_or_default();
+                cost.typical +=
+                    succ.iter().map(|cost| cost.typical).sum::<u64>() / succ.len() as u64;
+            }
+            paths[block] = Some(cost);
+            if block == head {
+                break;
+            }
+        }
+        paths[head].unwrap_or_default()
+    }
+
+    /// Cost of the code with a single pass through every loop.
+    fn cost(&self, costs: &[Cost]) -> Cost {
+        let mut paths = vec![Cost::default(); self.blocks.len()];
+        for &block in &self.post_order {
+            let succ = self.forward(block).collect::<Vec<_>>();
+            let returning = succ
+                .iter()
+                .copied()
+                .filter(|succ| self.returns[*succ])
+                .collect::<Vec<_>>();
+            let mut cost = costs[block];
+            cost.worst += succ
+                .iter()
+                .map(|succ| paths[*succ].worst)
+                .max()
+                .unwrap_or_default();
+            if !returning.is_empty() {
+                cost.typical += returning
+                    .iter()
+                    .map(|succ| paths[*succ].typical)
+                    .sum::<u64>()
+                    / returning.len() as u64;
+            }
+            paths[block] = cost;
+        }
+        paths.first().copied().unwrap_or_default()
+    }
+}
+
+// Instruction costs of the Aptos gas schedule in internal gas units.
+const NOP: u64 = 36;
+const RET: u64 = 220;
+const ABORT: u64 = 220;
+const BR: u64 = 441;
+const BRANCH: u64 = 294;
+const POP: u64 = 147;
+const LD: u64 = 220;
+const LD_U128: u64 = 294;
+const LD_CONST_BASE: u64 = 2389;
+const LD_CONST_PER_BYTE: u64 = 128;
+const BORROW_LOC: u64 = 220;
+const BORROW_FIELD: u64 = 735;
+const COPY_LOC_BASE: u64 = 294;
+const MOVE_LOC_BASE: u64 = 441;
+const ST_LOC_BASE: u64 = 441;
+const PER_ABS_VAL_UNIT: u64 = 14;
+const CALL_BASE: u64 = 3676;
+const CALL_PER_ARG: u64 = 367;
+const PACK_BASE: u64 = 808;
+const PACK_PER_FIELD: u64 = 147;
+const READ_REF_BASE: u64 = 735;
+const WRITE_REF_BASE: u64 = 735;
+const FREEZE_REF: u64 = 36;
+const CAST: u64 = 441;
+const ARITHMETIC: u64 = 588;
+const EQ_BASE: u64 = 367;
+const BORROW_GLOBAL: u64 = 1838;
+const EXISTS: u64 = 919;
+const MOVE_FROM: u64 = 1286;
+const MOVE_TO: u64 = 1838;
+const VEC_LEN: u64 = 808;
+const VEC_BORROW: u64 = 1213;
+const VEC_PUSH_BACK: u64 = 1396;
+const VEC_POP_BACK: u64 = 955;
+const VEC_SWAP: u64 = 1102;
+const VEC_PACK_BASE: u64 = 2205;
+const VEC_UNPACK_BASE: u64 = 1838;
+const VEC_PER_ELEM: u64 = 147;
+
+/// Abstract size of a value whose type is unknown or which is not a struct.
+const VALUE_SIZE: u64 = 40;
+
+fn call_cost(module: &CompiledModule, handle: FunctionHandleIndex) -> u64 {
+    let args = module
+        .signature_at(module.function_handle_at(handle).parameters)
+        .len() as u64;
+    CALL_BASE + CALL_PER_ARG * args
+}
+
+/// Cost of the instruction. `locals` are the abstract sizes of the parameters and the locals.
+fn instr_cost(module: &CompiledModule, locals: &[u64], instr: &Bytecode) -> u64 {
+    match instr {
+        Bytecode::Nop => NOP,
+        Bytecode::Ret => RET,
+        Bytecode::Abort => ABORT,
+        Bytecode::BrTrue(_) | Bytecode::BrFalse(_) => BR,
+        Bytecode::Branch(_) => BRANCH,
+        Bytecode::Pop => POP,
+        Bytecode::LdU8(_) | Bytecode::LdU64(_) | Bytecode::LdTrue | Bytecode::LdFalse => LD,
+        Bytecode::LdU128(_) => LD_U128,
+        Bytecode::LdConst(idx) => {
+            LD_CONST_BASE + LD_CONST_PER_BYTE * module.constant_at(*idx).data.len() as u64
+        }
+        Bytecode::ImmBorrowLoc(_) | Bytecode::MutBorrowLoc(_) => BORROW_LOC,
+        Bytecode::ImmBorrowField(_)
+        | Bytecode::MutBorrowField(_)
+        | Bytecode::ImmBorrowFieldGeneric(_)
+        | Bytecode::MutBorrowFieldGeneric(_) => BORROW_FIELD,
+        Bytecode::CopyLoc(idx) => {
+            COPY_LOC_BASE + PER_ABS_VAL_UNIT * locals.get(*idx as usize).unwrap_or(&VALUE_SIZE)
+        }
+        Bytecode::MoveLoc(_) => MOVE_LOC_BASE,
+        Bytecode::StLoc(_) => ST_LOC_BASE,
+        Bytecode::Pack(idx) | Bytecode::Unpack(idx) => {
+            PACK_BASE + PACK_PER_FIELD * field_count(&module.struct_def_at(*idx).field_information)
+        }
+        Bytecode::PackGeneric(idx) | Bytecode::UnpackGeneric(idx) => {
+            let def = module.struct_def_at(module.struct_instantiation_at(*idx).def);
+            PACK_BASE + PACK_PER_FIELD * field_count(&def.field_information)
+        }
+        Bytecode::ReadRef => READ_REF_BASE + PER_ABS_VAL_UNIT * VALUE_SIZE,
+        Bytecode::WriteRef => WRITE_REF_BASE,
+        Bytecode::FreezeRef => FREEZE_REF,
+        Bytecode::CastU8 | Bytecode::CastU64 | Bytecode::CastU128 => CAST,
+        Bytecode::Add
+        | Bytecode::Sub
+        | Bytecode::Mul
+        | Bytecode::Mod
+        | Bytecode::Div
+        | Bytecode::BitOr
+        | Bytecode::BitAnd
+        | Bytecode::Xor
+        | Bytecode::Shl
+        | Bytecode::Shr
+        | Bytecode::Or
+        | Bytecode::And
+        | Bytecode::Not
+        | Bytecode::Lt
+        | Bytecode::Gt
+        | Bytecode::Le
+        | Bytecode::Ge => ARITHMETIC,
+        Bytecode::Eq | Bytecode::Neq => EQ_BASE + PER_ABS_VAL_UNIT * VALUE_SIZE,
+        Bytecode::ImmBorrowGlobal(_)
+        | Bytecode::MutBorrowGlobal(_)
+        | Bytecode::ImmBorrowGlobalGeneric(_)
+        | Bytecode::MutBorrowGlobalGeneric(_) => BORROW_GLOBAL,
+        Bytecode::Exists(_) | Bytecode::ExistsGeneric(_) => EXISTS,
+        Bytecode::MoveFrom(_) | Bytecode::MoveFromGeneric(_) => MOVE_FROM,
+        Bytecode::MoveTo(_) | Bytecode::MoveToGeneric(_) => MOVE_TO,
+        Bytecode::VecLen(_) => VEC_LEN,
+        Bytecode::VecImmBorrow(_) | Bytecode::VecMutBorrow(_) => VEC_BORROW,
+        Bytecode::VecPushBack(_) => VEC_PUSH_BACK,
+        Bytecode::VecPopBack(_) => VEC_POP_BACK,
+        Bytecode::VecSwap(_) => VEC_SWAP,
+        Bytecode::VecPack(_, len) => VEC_PACK_BASE + VEC_PER_ELEM * len,
+        Bytecode::VecUnpack(_, len) => VEC_UNPACK_BASE + VEC_PER_ELEM * len,
+        Bytecode::Call(_) | Bytecode::CallGeneric(_) => CALL_BASE,
+    }
+}
+
+fn field_count(fields: &StructFieldInformation) -> u64 {
+    match fields {
+        StructFieldInformation::Native => 0,
+        StructFieldInformation::Declared(fields) => fields.len() as u64,
+    }
+}
+
+/// Abstract size of the value of the type.
+/// The struct sizes are only known for the structs declared in the module.
+fn value_size(module: &CompiledModule, tp: &SignatureToken) -> u64 {
+    match tp {
+        SignatureToken::Struct(handle) => module
+            .struct_defs()
+            .iter()
+            .find(|def| def.struct_handle == *handle)
+            .and_then(|def| match &def.field_information {
+                StructFieldInformation::Native => None,
+                StructFieldInformation::Declared(fields) => Some(
+                    VALUE_SIZE
+                        + fields
+                            .iter()
+                            .map(|field| value_size(module, &field.signature.0))
+                            .sum::<u64>(),
+                ),
+            })
+            .unwrap_or(VALUE_SIZE),
+        _ => VALUE_SIZE,
+    }
+}
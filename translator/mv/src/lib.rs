@@ -1,2 +1,3 @@
+pub mod gas;
 pub mod mv_ir;
 pub mod translator;
@@ -0,0 +1,190 @@
+/// Ethereum ABI encoding and decoding helpers used by the generated client modules.
+///
+/// Integers are represented as `u128`. Decoding a word that does not fit into `u128` aborts with `EOVERFLOW`.
+/// Wider integers are passed as words, the client converts them to the `U256` of the intrinsic library.
+module self::eth_abi {
+    use std::vector;
+
+    const EOVERFLOW: u64 = 1;
+    const EOUT_OF_RANGE: u64 = 2;
+
+    const WORD: u64 = 32;
+
+    /// Call data builder.
+    /// The head holds static values and offsets of dynamic values, the tail holds dynamic values.
+    /// The function selector is not encoded: each function of the translated module knows its own.
+    struct Encoder has drop {
+        head: vector<u8>,
+        tail: vector<u8>,
+        head_size: u64,
+    }
+
+    /// Creates an encoder for a call with `params` parameters.
+    public fun new_encoder(params: u64): Encoder {
+        Encoder {
+            head: vector::empty(),
+            tail: vector::empty(),
+            head_size: params * WORD,
+        }
+    }
+
+    public fun encode_u128(encoder: &mut Encoder, val: u128) {
+        vector::append(&mut encoder.head, u128_word(val));
+    }
+
+    public fun encode_bool(encoder: &mut Encoder, val: bool) {
+        encode_u128(encoder, if (val) 1 else 0);
+    }
+
+    public fun encode_address(encoder: &mut Encoder, val: address) {
+        let word = std::bcs::to_bytes(&val);
+        let i = 0;
+        while (i < 12) {
+            *vector::borrow_mut(&mut word, i) = 0;
+            i = i + 1;
+        };
+        vector::append(&mut encoder.head, word);
+    }
+
+    /// Encodes a 32-byte big-endian word, such as the bytes of a `U256`.
+    public fun encode_word(encoder: &mut Encoder, word: vector<u8>) {
+        assert!(vector::length(&word) == WORD, EOUT_OF_RANGE);
+        vector::append(&mut encoder.head, word);
+    }
+
+    /// Encodes a static `bytesN` value.
+    public fun encode_fixed_bytes(encoder: &mut Encoder, val: vector<u8>) {
+        assert!(vector::length(&val) <= WORD, EOUT_OF_RANGE);
+        while (vector::length(&val) < WORD) {
+            vector::push_back(&mut val, 0);
+        };
+        vector::append(&mut encoder.head, val);
+    }
+
+    /// Encodes a dynamic `bytes` or `string` value.
+    public fun encode_bytes(encoder: &mut Encoder, val: vector<u8>) {
+        let offset = encoder.head_size + vector::length(&encoder.tail);
+        vector::append(&mut encoder.head, u128_word((offset as u128)));
+        vector::append(&mut encoder.tail, u128_word((vector::length(&val) as u128)));
+        while (vector::length(&val) % WORD != 0) {
+            vector::push_back(&mut val, 0);
+        };
+        vector::append(&mut encoder.tail, val);
+    }
+
+    /// Returns the encoded call data.
+    public fun finish(encoder: Encoder): vector<u8> {
+        let Encoder { head, tail, head_size: _ } = encoder;
+        vector::append(&mut head, tail);
+        head
+    }
+
+    public fun decode_u128(data: &vector<u8>, index: u64): u128 {
+        read_u128(data, index * WORD)
+    }
+
+    public fun decode_bool(data: &vector<u8>, index: u64): bool {
+        decode_u128(data, index) != 0
+    }
+
+    public fun decode_address(data: &vector<u8>, index: u64): address {
+        aptos_framework::util::address_from_bytes(slice(data, index * WORD, WORD))
+    }
+
+    /// Decodes a 32-byte big-endian word.
+    public fun decode_word(data: &vector<u8>, index: u64): vector<u8> {
+        slice(data, index * WORD, WORD)
+    }
+
+    /// Decodes a static `bytesN` value.
+    public fun decode_fixed_bytes(data: &vector<u8>, index: u64, size: u64): vector<u8> {
+        slice(data, index * WORD, size)
+    }
+
+    /// Decodes a dynamic `bytes` or `string` value.
+    public fun decode_bytes(data: &vector<u8>, index: u64): vector<u8> {
+        let offset = (decode_u128(data, index) as u64);
+        let len = (read_u128(data, offset) as u64);
+        slice(data, offset + WORD, len)
+    }
+
+    fun u128_word(val: u128): vector<u8> {
+        let word = vector::empty();
+        let i = 0;
+        while (i < WORD) {
+            vector::push_back(&mut word, 0);
+            i = i + 1;
+        };
+        while (val > 0) {
+            i = i - 1;
+            *vector::borrow_mut(&mut word, i) = ((val & 0xff) as u8);
+            val = val >> 8;
+        };
+        word
+    }
+
+    fun read_u128(data: &vector<u8>, offset: u64): u128 {
+        assert!(vector::length(data) >= offset + WORD, EOUT_OF_RANGE);
+        let i = 0;
+        while (i < 16) {
+            assert!(*vector::borrow(data, offset + i) == 0, EOVERFLOW);
+            i = i + 1;
+        };
+        let val = 0u128;
+        while (i < WORD) {
+            val = (val << 8) | (*vector::borrow(data, offset + i) as u128);
+            i = i + 1;
+        };
+        val
+    }
+
+    fun slice(data: &vector<u8>, offset: u64, len: u64): vector<u8> {
+        assert!(vector::length(data) >= offset + len, EOUT_OF_RANGE);
+        let res = vector::empty();
+        let i = 0;
+        while (i < len) {
+            vector::push_back(&mut res, *vector::borrow(data, offset + i));
+            i = i + 1;
+        };
+        res
+    }
+
+    #[test]
+    fun test_encode_decode() {
+        let encoder = new_encoder(3);
+        encode_u128(&mut encoder, 1000);
+        encode_bytes(&mut encoder, b"hello");
+        encode_bool(&mut encoder, true);
+        let data = finish(encoder);
+        assert!(vector::length(&data) == 5 * WORD, 0);
+        assert!(decode_u128(&data, 0) == 1000, 1);
+        assert!(decode_bytes(&data, 1) == b"hello", 2);
+        assert!(decode_bool(&data, 2), 3);
+    }
+
+    #[test]
+    fun test_encode_decode_address() {
+        let encoder = new_encoder(1);
+        encode_address(&mut encoder, @0x42);
+        let data = finish(encoder);
+        assert!(decode_address(&data, 0) == @0x42, 0);
+    }
+
+    #[test]
+    fun test_encode_decode_word() {
+        let word = u128_word(7);
+        *vector::borrow_mut(&mut word, 0) = 1;
+        let encoder = new_encoder(1);
+        encode_word(&mut encoder, word);
+        let data = finish(encoder);
+        assert!(decode_word(&data, 0) == word, 0);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 1)]
+    fun test_decode_overflow() {
+        let data = u128_word(1);
+        *vector::borrow_mut(&mut data, 0) = 1;
+        decode_u128(&data, 0);
+    }
+}
@@ -0,0 +1,357 @@
+use std::fmt::Write;
+
+use anyhow::{bail, Error};
+use ethabi::{Contract, Event, Function, Param, ParamType};
+use move_core_types::account_address::AccountAddress;
+
+use eth::abi::entry_name;
+use eth::abi::names::{event_name, param_names, snake_case, MOVE_KEYWORDS};
+use eth::Flags;
+use intrinsic::{toml_template, LIBRARY_NAME};
+
+/// Name of the module with the ABI helpers.
+pub const ETH_ABI_MODULE: &str = "eth_abi";
+
+/// Source of the ABI helpers module.
+pub const ETH_ABI_SOURCE: &str = include_str!("eth_abi.move");
+
+/// Locals of the generated wrappers.
+const LOCALS: [&str; 3] = ["account", "encoder", "result"];
+
+/// Generated client module.
+pub struct MoveClient {
+    /// Source of the `<name>_client` module.
+    pub source: String,
+    /// Functions and events the client does not wrap, with the reasons.
+    /// They are listed in the header of `source` as well.
+    pub unsupported: Vec<String>,
+}
+
+/// Typed client of the translated module.
+///
+/// Each Solidity function gets a wrapper that ABI-encodes its arguments, calls the translated module and
+/// decodes the result. Each event gets a struct with its non-indexed fields, a decoder for the event data
+/// and its signature topic. The overloaded items get the selector as the suffix, see [`entry_name`].
+/// Only the eth-ABI interface is wrapped: returns `None` if the module has a native input or output.
+/// With the hidden output the wrappers return nothing.
+///
+/// Integers wider than 128 bits are passed as the `U256` of the shared intrinsic library,
+/// so the items with them are only wrapped if `intrinsic_address` is set.
+pub fn move_client(
+    module_name: &str,
+    abi: &Contract,
+    flags: Flags,
+    intrinsic_address: Option<AccountAddress>,
+) -> Result<Option<MoveClient>, Error> {
+    if flags.native_input || flags.native_output {
+        return Ok(None);
+    }
+    let shared = intrinsic_address.is_some();
+
+    let mut items = String::new();
+    let mut unsupported = Vec::new();
+    for fun in abi.functions() {
+        let name = entry_name(abi, fun);
+        let mut item = String::new();
+        match write_function(
+            &mut item,
+            module_name,
+            &name,
+            fun,
+            flags.hidden_output,
+            shared,
+        ) {
+            Ok(()) => {
+                writeln!(items)?;
+                items.push_str(&item);
+            }
+            Err(err) => unsupported.push(format!("function `{name}`: {err}")),
+        }
+    }
+
+    for event in abi.events() {
+        let name = event_name(abi, event);
+        let mut item = String::new();
+        match write_event(&mut item, &name, event, shared) {
+            Ok(()) => {
+                writeln!(items)?;
+                items.push_str(&item);
+            }
+            Err(err) => unsupported.push(format!("event `{name}`: {err}")),
+        }
+    }
+
+    let mut source = String::new();
+    writeln!(source, "module self::{module_name}_client {{")?;
+    if !unsupported.is_empty() {
+        writeln!(source, "{:width$}// Not wrapped:", "", width = 4)?;
+        for item in &unsupported {
+            writeln!(source, "{:width$}// - {item}", "", width = 4)?;
+        }
+        writeln!(source)?;
+    }
+    writeln!(
+        source,
+        "{:width$}use self::{ETH_ABI_MODULE};",
+        "",
+        width = 4
+    )?;
+    writeln!(source, "{:width$}use self::{module_name};", "", width = 4)?;
+    if let Some(address) = intrinsic_address {
+        writeln!(
+            source,
+            "{:width$}use {}::{LIBRARY_NAME}::{{Self, U256}};",
+            "",
+            address.to_hex_literal(),
+            width = 4
+        )?;
+    }
+    source.push_str(&items);
+    writeln!(source, "}}")?;
+    Ok(Some(MoveClient {
+        source,
+        unsupported,
+    }))
+}
+
+/// Manifest of the client package. The package depends on the interface package in the parent directory.
+pub fn client_manifest(module_name: &str, address: AccountAddress) -> String {
+    package_client_manifest(module_name, module_name, "..", address)
+}
+
+/// Manifest of the client package of the module which is a part of the interface package
+/// `package_name` located at `package_path`.
+pub fn package_client_manifest(
+    module_name: &str,
+    package_name: &str,
+    package_path: &str,
+    address: AccountAddress,
+) -> String {
+    toml_template(&format!("{module_name}_client"), address).replacen(
+        "[dependencies]\n",
+        &format!("[dependencies]\n{package_name} = {{ local = \"{package_path}\" }}\n"),
+        1,
+    )
+}
+
+fn write_function(
+    buff: &mut String,
+    module_name: &str,
+    name: &str,
+    fun: &Function,
+    hidden_output: bool,
+    shared: bool,
+) -> Result<(), Error> {
+    let inputs = names(&fun.inputs);
+    let mut params = vec!["account: &signer".to_string()];
+    for (name, param) in inputs.iter().zip(&fun.inputs) {
+        params.push(format!("{name}: {}", map_type(&param.kind, shared)?));
+    }
+
+    let outputs = if hidden_output { &[][..] } else { &fun.outputs };
+    let ret = outputs
+        .iter()
+        .map(|param| map_type(&param.kind, shared))
+        .collect::<Result<Vec<_>, _>>()?;
+    let ret = match ret.len() {
+        0 => "".to_string(),
+        1 => format!(": {}", ret[0]),
+        _ => format!(": ({})", ret.join(", ")),
+    };
+
+    let mut body = String::new();
+    writeln!(
+        body,
+        "{:width$}let encoder = {ETH_ABI_MODULE}::new_encoder({});",
+        "",
+        fun.inputs.len(),
+        width = 8
+    )?;
+    for (name, param) in inputs.iter().zip(&fun.inputs) {
+        writeln!(
+            body,
+            "{:width$}{}",
+            "",
+            encoder(name, &param.kind)?,
+            width = 8
+        )?;
+    }
+    let call = format!("{module_name}::{name}(account, {ETH_ABI_MODULE}::finish(encoder))");
+    let values = decoders("result", outputs)?;
+    if hidden_output {
+        writeln!(body, "{:width$}{call};", "", width = 8)?;
+    } else {
+        writeln!(body, "{:width$}let result = {call};", "", width = 8)?;
+        if values.is_empty() {
+            writeln!(body, "{:width$}let _ = result;", "", width = 8)?;
+        } else if values.len() == 1 {
+            writeln!(body, "{:width$}{}", "", values[0], width = 8)?;
+        } else {
+            writeln!(body, "{:width$}({})", "", values.join(", "), width = 8)?;
+        }
+    }
+
+    writeln!(
+        buff,
+        "{:width$}public fun {name}({}){ret} {{",
+        "",
+        params.join(", "),
+        width = 4
+    )?;
+    buff.push_str(&body);
+    writeln!(buff, "{:width$}}}", "", width = 4)?;
+    Ok(())
+}
+
+fn write_event(buff: &mut String, name: &str, event: &Event, shared: bool) -> Result<(), Error> {
+    let params = event
+        .inputs
+        .iter()
+        .filter(|param| !param.indexed)
+        .map(|param| Param {
+            name: param.name.clone(),
+            kind: param.kind.clone(),
+            internal_type: None,
+        })
+        .collect::<Vec<_>>();
+    let fields = names(&params);
+    let types = params
+        .iter()
+        .map(|param| map_type(&param.kind, shared))
+        .collect::<Result<Vec<_>, _>>()?;
+    let values = decoders("data", &params)?;
+    let fun_name = snake_case(name);
+
+    writeln!(
+        buff,
+        "{:width$}struct {name} has copy, drop, store {{",
+        "",
+        width = 4
+    )?;
+    for (name, tp) in fields.iter().zip(&types) {
+        writeln!(buff, "{:width$}{name}: {tp},", "", width = 8)?;
+    }
+    writeln!(buff, "{:width$}}}", "", width = 4)?;
+    writeln!(buff)?;
+
+    writeln!(
+        buff,
+        "{:width$}/// Topic of the `{}` event.",
+        "",
+        event.name,
+        width = 4
+    )?;
+    writeln!(
+        buff,
+        "{:width$}public fun {fun_name}_topic(): vector<u8> {{",
+        "",
+        width = 4
+    )?;
+    writeln!(
+        buff,
+        "{:width$}x\"{}\"",
+        "",
+        hex::encode(event.signature()),
+        width = 8
+    )?;
+    writeln!(buff, "{:width$}}}", "", width = 4)?;
+    writeln!(buff)?;
+
+    writeln!(
+        buff,
+        "{:width$}/// Decodes the non-indexed fields of the `{}` event.",
+        "",
+        event.name,
+        width = 4
+    )?;
+    writeln!(
+        buff,
+        "{:width$}public fun decode_{fun_name}(data: vector<u8>): {name} {{",
+        "",
+        width = 4
+    )?;
+    if fields.is_empty() {
+        writeln!(buff, "{:width$}let _ = data;", "", width = 8)?;
+    }
+    writeln!(buff, "{:width$}{name} {{", "", width = 8)?;
+    for (name, value) in fields.iter().zip(&values) {
+        writeln!(buff, "{:width$}{name}: {value},", "", width = 12)?;
+    }
+    writeln!(buff, "{:width$}}}", "", width = 8)?;
+    writeln!(buff, "{:width$}}}", "", width = 4)?;
+    Ok(())
+}
+
+fn decoders(data: &str, params: &[Param]) -> Result<Vec<String>, Error> {
+    params
+        .iter()
+        .enumerate()
+        .map(|(idx, param)| {
+            Ok(match &param.kind {
+                ParamType::Uint(size) if *size > 128 => format!(
+                    "{LIBRARY_NAME}::from_bytes(&{ETH_ABI_MODULE}::decode_word(&{data}, {idx}), \
+                    {LIBRARY_NAME}::zero())"
+                ),
+                ParamType::FixedBytes(size) => {
+                    format!("{ETH_ABI_MODULE}::decode_fixed_bytes(&{data}, {idx}, {size})")
+                }
+                kind => format!("{ETH_ABI_MODULE}::decode_{}(&{data}, {idx})", codec(kind)?),
+            })
+        })
+        .collect()
+}
+
+/// Encodes the `val` local of the `kind` type.
+fn encoder(val: &str, kind: &ParamType) -> Result<String, Error> {
+    Ok(match kind {
+        ParamType::Uint(size) if *size > 128 => format!(
+            "{ETH_ABI_MODULE}::encode_word(&mut encoder, {LIBRARY_NAME}::to_bytes(&{val}));"
+        ),
+        kind => format!(
+            "{ETH_ABI_MODULE}::encode_{}(&mut encoder, {val});",
+            codec(kind)?
+        ),
+    })
+}
+
+/// Move type of the parameter. With the `shared` library the wide integers are `U256`.
+fn map_type(kind: &ParamType, shared: bool) -> Result<&'static str, Error> {
+    Ok(match kind {
+        ParamType::Uint(size) if *size > 128 => {
+            if !shared {
+                bail!(
+                    "uint{size} is only wrapped with the shared intrinsic library (`--intrinsic`)"
+                );
+            }
+            "U256"
+        }
+        ParamType::Uint(_) => "u128",
+        ParamType::Bool => "bool",
+        ParamType::Address => "address",
+        ParamType::FixedBytes(_) | ParamType::Bytes | ParamType::String => "vector<u8>",
+        ParamType::Int(_) => bail!("Signed integers have no Move type: {kind:?}"),
+        _ => bail!("Unsupported type: {kind:?}"),
+    })
+}
+
+fn codec(kind: &ParamType) -> Result<&'static str, Error> {
+    Ok(match kind {
+        ParamType::Uint(_) => "u128",
+        ParamType::Bool => "bool",
+        ParamType::Address => "address",
+        ParamType::FixedBytes(_) => "fixed_bytes",
+        ParamType::Bytes | ParamType::String => "bytes",
+        _ => bail!("Unsupported type: {kind:?}"),
+    })
+}
+
+/// Move names of the parameters.
+/// The keywords and the locals of the wrappers are reserved, see [`param_names`].
+fn names(params: &[Param]) -> Vec<String> {
+    let reserved = MOVE_KEYWORDS
+        .iter()
+        .chain(&LOCALS)
+        .copied()
+        .collect::<Vec<_>>();
+    param_names(params, &reserved)
+}
@@ -2,7 +2,7 @@ use anyhow::Error;
 use move_binary_format::internals::ModuleIndex;
 use move_binary_format::{
     access::ModuleAccess,
-    file_format::{Bytecode, Signature, SignatureToken, Visibility},
+    file_format::{Bytecode, Signature, SignatureToken, StructFieldInformation, Visibility},
     file_format::{
         FieldHandleIndex, FieldInstantiationIndex, FunctionHandleIndex,
         StructDefInstantiationIndex, StructDefinitionIndex, StructHandleIndex, TableIndex,
@@ -144,9 +144,43 @@ pub fn find_all_structs(module: &mut CompiledModule) -> Result<HashSet<StructHan
         }
     }
 
+    // field types of the used structures
+    let used_defs = module
+        .struct_defs
+        .iter()
+        .filter(|def| set.contains(&def.struct_handle))
+        .collect::<Vec<_>>();
+    let mut field_handles = HashSet::new();
+    for def in used_defs {
+        if let StructFieldInformation::Declared(fields) = &def.field_information {
+            for field in fields {
+                collect_handles(&field.signature.0, &mut field_handles);
+            }
+        }
+    }
+    set.extend(field_handles);
+
     Ok(set)
 }
 
+fn collect_handles(token: &SignatureToken, set: &mut HashSet<StructHandleIndex>) {
+    match token {
+        SignatureToken::Struct(handle) => {
+            set.insert(*handle);
+        }
+        SignatureToken::StructInstantiation(handle, params) => {
+            set.insert(*handle);
+            for param in params {
+                collect_handles(param, set);
+            }
+        }
+        SignatureToken::Reference(token)
+        | SignatureToken::MutableReference(token)
+        | SignatureToken::Vector(token) => collect_handles(token, set),
+        _ => {}
+    }
+}
+
 pub enum IndexType<'a> {
     StructDef(&'a mut StructDefinitionIndex),
     StructInsta(&'a mut StructDefInstantiationIndex),
@@ -4,13 +4,11 @@ use anyhow::Error;
 use move_binary_format::access::ModuleAccess;
 use move_binary_format::file_format::{
     Bytecode, CodeUnit, FunctionDefinition, FunctionHandle, FunctionHandleIndex, IdentifierIndex,
-    SignatureIndex, Visibility,
+    SignatureIndex, StructDefinitionIndex, Visibility,
 };
 use move_binary_format::CompiledModule;
 use move_core_types::identifier::Identifier;
 
-use intrinsic::table::Persist;
-
 #[derive(Debug)]
 pub struct Func {
     pub name: Identifier,
@@ -18,6 +16,7 @@ pub struct Func {
     pub input: SignatureIndex,
     pub output: SignatureIndex,
     pub locals: SignatureIndex,
+    pub acquires: Vec<StructDefinitionIndex>,
     pub code: Vec<Bytecode>,
 }
 
@@ -48,7 +47,7 @@ impl Func {
             function: index,
             visibility: self.visibility,
             is_entry: true,
-            acquires_global_resources: vec![Persist::instance()],
+            acquires_global_resources: mem::take(&mut self.acquires),
             code: Some(CodeUnit {
                 locals: self.locals,
                 code: mem::take(&mut self.code),
@@ -1,36 +1,233 @@
+use std::collections::HashSet;
 use std::fmt::Write;
 
-use anyhow::Error;
+use anyhow::{anyhow, bail, Error};
 use ethabi::{Contract, Function};
+use move_binary_format::access::ModuleAccess;
+use move_binary_format::file_format::{
+    Ability, SignatureToken, StructDefinition, StructHandleIndex, Visibility,
+};
 use move_binary_format::CompiledModule;
+use move_core_types::account_address::AccountAddress;
 
+use eth::abi::entry_name;
 use eth::bytecode::types::EthType;
 use eth::Flags;
+use intrinsic::{library, LIBRARY_NAME};
 
+/// Interface of the translated module.
+/// If `intrinsic_address` is set, `U256` is imported from the shared intrinsic library.
 pub fn move_interface(
     module: &CompiledModule,
     abi: &Contract,
     flags: Flags,
+    intrinsic_address: Option<AccountAddress>,
 ) -> Result<String, Error> {
     let mut buff = String::new();
     let id = module.self_id();
 
     writeln!(buff, "module self::{} {{", id.name())?;
+    if let Some(address) = intrinsic_address {
+        writeln!(
+            buff,
+            "{:width$}use {}::{LIBRARY_NAME}::U256;",
+            "",
+            address.to_hex_literal(),
+            width = 4
+        )?;
+        writeln!(buff)?;
+    }
 
     write_constants(&mut buff)?;
     writeln!(buff)?;
 
     abi.functions()
-        .map(|f| write_function(&mut buff, f, module, flags))
+        .map(|f| write_function(&mut buff, &entry_name(abi, f), f, module, flags))
         .collect::<Result<Vec<_>, Error>>()?;
 
-    if (flags.native_input || flags.native_output) && !flags.u128_io {
+    if (flags.native_input || flags.native_output) && !flags.u128_io && intrinsic_address.is_none()
+    {
         write_u256(&mut buff)?;
     }
     writeln!(buff, "}}")?;
     Ok(buff)
 }
 
+/// Interface of the shared intrinsic library published at `address`: the public functions of the
+/// library as natives and the structs of their signatures.
+pub fn intrinsic_interface(address: AccountAddress) -> Result<String, Error> {
+    let module = library(address)?;
+    let functions = module
+        .function_defs
+        .iter()
+        .filter(|def| def.visibility == Visibility::Public)
+        .map(|def| module.function_handle_at(def.function))
+        .collect::<Vec<_>>();
+
+    let mut structs = HashSet::new();
+    let mut tokens = functions
+        .iter()
+        .flat_map(|handle| {
+            let params = &module.signature_at(handle.parameters).0;
+            let returns = &module.signature_at(handle.return_).0;
+            params.iter().chain(returns).cloned()
+        })
+        .collect::<Vec<_>>();
+    while let Some(token) = tokens.pop() {
+        for token in token.preorder_traversal() {
+            if let SignatureToken::Struct(idx) | SignatureToken::StructInstantiation(idx, _) = token
+            {
+                let handle = module.struct_handle_at(*idx);
+                if handle.module == module.self_handle_idx() && structs.insert(*idx) {
+                    let def = module
+                        .struct_defs
+                        .iter()
+                        .find(|def| def.struct_handle == *idx)
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "Struct {} is not defined in the library",
+                                module.identifier_at(handle.name)
+                            )
+                        })?;
+                    tokens.extend(
+                        def.fields()
+                            .into_iter()
+                            .flatten()
+                            .map(|f| f.signature.0.clone()),
+                    );
+                }
+            }
+        }
+    }
+
+    let mut buff = String::new();
+    writeln!(
+        buff,
+        "module {}::{LIBRARY_NAME} {{",
+        address.to_hex_literal()
+    )?;
+    for def in &module.struct_defs {
+        if structs.contains(&def.struct_handle) {
+            write_struct(&mut buff, &module, def)?;
+            writeln!(buff)?;
+        }
+    }
+    for handle in functions {
+        let params = module
+            .signature_at(handle.parameters)
+            .0
+            .iter()
+            .enumerate()
+            .map(|(idx, tp)| Ok(format!("arg_{idx}: {}", token_name(&module, tp)?)))
+            .collect::<Result<Vec<_>, Error>>()?
+            .join(", ");
+        let returns = module
+            .signature_at(handle.return_)
+            .0
+            .iter()
+            .map(|tp| token_name(&module, tp))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let returns = match returns.len() {
+            0 => String::new(),
+            1 => format!(": {}", returns[0]),
+            _ => format!(": ({})", returns.join(", ")),
+        };
+        writeln!(
+            buff,
+            "{:width$}public native fun {}({params}){returns};",
+            "",
+            module.identifier_at(handle.name),
+            width = 4
+        )?;
+    }
+    writeln!(buff, "}}")?;
+    Ok(buff)
+}
+
+fn write_struct(
+    buff: &mut String,
+    module: &CompiledModule,
+    def: &StructDefinition,
+) -> Result<(), Error> {
+    let handle = module.struct_handle_at(def.struct_handle);
+    if !handle.type_parameters.is_empty() {
+        bail!(
+            "Generic struct {} is not supported in the library interface",
+            module.identifier_at(handle.name)
+        );
+    }
+    let abilities = handle
+        .abilities
+        .into_iter()
+        .map(|ability| match ability {
+            Ability::Copy => "copy",
+            Ability::Drop => "drop",
+            Ability::Store => "store",
+            Ability::Key => "key",
+        })
+        .collect::<Vec<_>>();
+    let abilities = if abilities.is_empty() {
+        String::new()
+    } else {
+        format!(" has {}", abilities.join(", "))
+    };
+    writeln!(
+        buff,
+        "{:width$}struct {}{abilities} {{",
+        "",
+        module.identifier_at(handle.name),
+        width = 4
+    )?;
+    for field in def.fields().into_iter().flatten() {
+        writeln!(
+            buff,
+            "{:width$}{}: {},",
+            "",
+            module.identifier_at(field.name),
+            token_name(module, &field.signature.0)?,
+            width = 8
+        )?;
+    }
+    writeln!(buff, "{:width$}}}", "", width = 4)?;
+    Ok(())
+}
+
+fn token_name(module: &CompiledModule, token: &SignatureToken) -> Result<String, Error> {
+    Ok(match token {
+        SignatureToken::Bool => "bool".to_string(),
+        SignatureToken::U8 => "u8".to_string(),
+        SignatureToken::U64 => "u64".to_string(),
+        SignatureToken::U128 => "u128".to_string(),
+        SignatureToken::Address => "address".to_string(),
+        SignatureToken::Signer => "signer".to_string(),
+        SignatureToken::Vector(tp) => format!("vector<{}>", token_name(module, tp)?),
+        SignatureToken::Struct(idx) => struct_name(module, *idx),
+        SignatureToken::StructInstantiation(idx, tps) => format!(
+            "{}<{}>",
+            struct_name(module, *idx),
+            tps.iter()
+                .map(|tp| token_name(module, tp))
+                .collect::<Result<Vec<_>, Error>>()?
+                .join(", ")
+        ),
+        SignatureToken::Reference(tp) => format!("&{}", token_name(module, tp)?),
+        SignatureToken::MutableReference(tp) => format!("&mut {}", token_name(module, tp)?),
+        _ => bail!("Type {token:?} is not supported in the library interface"),
+    })
+}
+
+/// Name of the struct: local structs by name, imported structs by their full path.
+fn struct_name(module: &CompiledModule, idx: StructHandleIndex) -> String {
+    let handle = module.struct_handle_at(idx);
+    let name = module.identifier_at(handle.name);
+    if handle.module == module.self_handle_idx() {
+        name.to_string()
+    } else {
+        let id = module.module_id_for_handle(module.module_handle_at(handle.module));
+        format!("{}::{}::{name}", id.address().to_hex_literal(), id.name())
+    }
+}
+
 fn write_constants(buff: &mut String) -> Result<(), Error> {
     writeln!(
         buff,
@@ -43,6 +240,7 @@ fn write_constants(buff: &mut String) -> Result<(), Error> {
 
 fn write_function(
     buff: &mut String,
+    name: &str,
     fun: &Function,
     _module: &CompiledModule,
     flags: Flags,
@@ -57,23 +255,21 @@ fn write_function(
         "args: vector<u8>".to_string()
     };
 
-    let ret = if flags.native_output {
-        if flags.hidden_output {
-            "".to_string()
+    let ret = if flags.hidden_output {
+        "".to_string()
+    } else if flags.native_output {
+        let params = fun
+            .outputs
+            .iter()
+            .map(|p| map_type(&EthType::try_from(p).unwrap(), &flags))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if fun.outputs.is_empty() {
+            params
+        } else if fun.outputs.len() == 1 {
+            format!(": {}", params)
         } else {
-            let params = fun
-                .outputs
-                .iter()
-                .map(|p| map_type(&EthType::try_from(p).unwrap(), &flags))
-                .collect::<Vec<_>>()
-                .join(", ");
-            if fun.outputs.is_empty() {
-                params
-            } else if fun.outputs.len() == 1 {
-                format!(": {}", params)
-            } else {
-                format!(": ({})", params)
-            }
+            format!(": ({})", params)
         }
     } else {
         ": vector<u8>".to_string()
@@ -83,7 +279,7 @@ fn write_function(
         buff,
         "{:width$}public native fun {}(account_address: &signer, {}){};",
         "",
-        fun.name,
+        name,
         args,
         ret,
         width = 4
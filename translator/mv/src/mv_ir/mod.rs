@@ -12,9 +12,12 @@ use move_ir_types::location::Spanned;
 
 use crate::mv_ir::func::Func;
 
+pub mod client;
 pub mod crop;
 pub mod func;
 pub mod interface;
+pub mod source;
+pub mod state;
 
 #[derive(Debug)]
 pub struct Module {
@@ -70,15 +73,17 @@ pub fn print_move_module(module: &CompiledModule) {
     if !log_enabled!(Level::Trace) {
         return;
     }
+    println!("{}", disassemble(module).unwrap());
+}
 
+/// Disassembly of the externally visible functions of the module.
+pub fn disassemble(module: &CompiledModule) -> Result<String, Error> {
     let source_mapping = SourceMapping::new_from_view(
         BinaryIndexedView::Module(module),
         Spanned::unsafe_no_loc(()).loc,
-    )
-    .unwrap();
+    )?;
     let mut ops = DisassemblerOptions::new();
     ops.only_externally_visible = true;
     let disassembler = Disassembler::new(source_mapping, ops);
-    let dissassemble_string = disassembler.disassemble().unwrap();
-    println!("{}", dissassemble_string);
+    disassembler.disassemble()
 }
@@ -0,0 +1,1036 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use anyhow::{anyhow, bail, Error};
+
+use eth::bytecode::hir::executor::math::{BinaryOp, TernaryOp, UnaryOp};
+use eth::bytecode::hir::ir::Label;
+use eth::bytecode::loc::Loc;
+use eth::bytecode::mir::ir::expression::{key_kinds, Cast, Expression, TypedExpr};
+use eth::bytecode::mir::ir::statement::Statement;
+use eth::bytecode::mir::ir::types::{SType, Value};
+use eth::bytecode::mir::ir::Mir;
+use eth::bytecode::mir::translation::variables::Variable;
+use eth::layout::{FieldKind, KeyType, Layout};
+use eth::program::Program;
+use eth::Flags;
+use intrinsic::table::{Info, Memory as Mem, Persist, U256 as Num};
+use intrinsic::{Function, LIBRARY_NAME, LIBRARY_SOURCES, SOURCES};
+use move_binary_format::file_format::SignatureToken;
+use move_core_types::account_address::AccountAddress;
+
+use crate::mv_ir::interface::intrinsic_interface;
+use crate::mv_ir::state::{field_names, field_types, STATE_NAME};
+use crate::translator::signature::map_signature;
+
+const MODULE_U256: &str = "u256";
+const MODULE_MEMORY: &str = "memory";
+const MODULE_PERSIST: &str = "persist";
+const MODULE_INFO: &str = "info";
+
+/// Renders the program as Move source code.
+///
+/// Every function is emitted from its MIR. The jumps of the MIR follow the structure of the HIR:
+/// a conditional jump skips the code of the false branch, which never falls through to the true
+/// branch, and a backward jump goes to the head of a loop. So the branches are written as
+/// `if`/`else` and the loops as `loop` with `continue`.
+///
+/// If `intrinsic_address` is set, the U256 and the memory helpers are imported from the shared
+/// intrinsic library under their module names.
+pub fn move_source(
+    program: &Program,
+    max_memory: u64,
+    flags: Flags,
+    intrinsic_address: Option<AccountAddress>,
+) -> Result<String, Error> {
+    let mut buff = String::new();
+    writeln!(buff, "module self::{} {{", program.name())?;
+    writeln!(
+        buff,
+        "{:width$}{}",
+        "",
+        use_intrinsic(MODULE_U256, "Self, U256", intrinsic_address),
+        width = 4
+    )?;
+    writeln!(
+        buff,
+        "{:width$}{}",
+        "",
+        use_intrinsic(MODULE_MEMORY, "Self, Memory", intrinsic_address),
+        width = 4
+    )?;
+    writeln!(
+        buff,
+        "{:width$}use self::persist::{{Self, Persist}};",
+        "",
+        width = 4
+    )?;
+    writeln!(buff, "{:width$}use self::info;", "", width = 4)?;
+
+    let fields = field_names(program.layout());
+    if !fields.is_empty() {
+        writeln!(buff)?;
+        writeln!(
+            buff,
+            "{:width$}struct {STATE_NAME} has key {{",
+            "",
+            width = 4
+        )?;
+        for (name, tp) in fields.iter().zip(field_types(program.layout())) {
+            writeln!(buff, "{:width$}{name}: {tp},", "", width = 8)?;
+        }
+        writeln!(buff, "{:width$}}}", "", width = 4)?;
+    }
+
+    for hash in program.functions_hash() {
+        let def = program.function_def(hash).ok_or_else(|| {
+            anyhow!(
+                "Function with hash {} not found in program {}",
+                hash,
+                program.name()
+            )
+        })?;
+        let mir = program.function_mir(hash).ok_or_else(|| {
+            anyhow!(
+                "Function with hash {} not found in program {}",
+                hash,
+                program.name()
+            )
+        })?;
+
+        let params = if flags.native_input {
+            let mut params = vec![SType::Signer];
+            params.extend(
+                def.native_input
+                    .iter()
+                    .map(|tp| SType::from_eth_type(tp, flags.u128_io)),
+            );
+            params
+        } else {
+            vec![SType::Signer, SType::Bytes]
+        };
+
+        let output = if flags.hidden_output {
+            vec![]
+        } else if flags.native_output {
+            map_signature(&def.native_output, true, &flags)
+        } else {
+            map_signature(&def.eth_output, false, &flags)
+        };
+        let output = output
+            .iter()
+            .map(token_name)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        writeln!(buff)?;
+        SourceWriter::new(
+            &mut buff,
+            max_memory,
+            params,
+            mir,
+            program.layout(),
+            &fields,
+            flags.native_mappings,
+        )
+        .write_function(&def.name, output, true)?;
+    }
+
+    writeln!(buff)?;
+    SourceWriter::new(
+        &mut buff,
+        max_memory,
+        vec![SType::Signer],
+        program.constructor_mir(),
+        program.layout(),
+        &fields,
+        flags.native_mappings,
+    )
+    .write_function("constructor", vec![], false)?;
+    writeln!(buff, "}}")?;
+    Ok(buff)
+}
+
+/// Returns the intrinsic modules which the emitted source depends on.
+///
+/// Private functions are opened to the contract module via `public(friend)`, and `persist` gets an
+/// accessor for the contract storage since global storage can only be borrowed by its own module.
+/// If `intrinsic_address` is set, the modules of the shared library are replaced by its interface.
+pub fn intrinsic_sources(
+    name: &str,
+    intrinsic_address: Option<AccountAddress>,
+) -> Result<Vec<(String, String)>, Error> {
+    let mut sources = SOURCES
+        .iter()
+        .filter(|(module, _)| intrinsic_address.is_none() || !LIBRARY_SOURCES.contains(module))
+        .map(|(module, source)| {
+            let mut lines = Vec::new();
+            let mut is_test = false;
+            for line in source.lines() {
+                let trimmed = line.trim_start();
+                if trimmed.starts_with("#[") {
+                    is_test |= trimmed.contains("test");
+                    lines.push(line.to_string());
+                    continue;
+                }
+
+                let library_use = LIBRARY_SOURCES.iter().find_map(|module| {
+                    let path = format!("use self::{module}::");
+                    trimmed.starts_with(&path).then_some((*module, path))
+                });
+                if let (Some(address), Some((module, path))) = (intrinsic_address, library_use) {
+                    let items =
+                        trimmed[path.len()..].replacen("Self", &format!("Self as {module}"), 1);
+                    let indent = &line[..line.len() - trimmed.len()];
+                    lines.push(format!(
+                        "{indent}use {}::{LIBRARY_NAME}::{items}",
+                        address.to_hex_literal()
+                    ));
+                } else if trimmed.starts_with("fun ") && !is_test {
+                    lines.push(line.replacen("fun ", "public(friend) fun ", 1));
+                } else {
+                    lines.push(line.to_string());
+                }
+                if !trimmed.is_empty() {
+                    is_test = false;
+                }
+
+                if trimmed.starts_with("module self::") {
+                    lines.push(format!("{:width$}friend self::{name};", "", width = 4));
+                }
+            }
+
+            if *module == MODULE_PERSIST {
+                if let Some(end) = lines.iter().rposition(|line| line.trim() == "}") {
+                    lines.insert(
+                        end,
+                        format!(
+                            "\n{:width$}public(friend) fun borrow_persist(): &mut Persist acquires Persist {{\n\
+                            {:width2$}borrow_global_mut<Persist>(@self)\n\
+                            {:width$}}}",
+                            "",
+                            "",
+                            "",
+                            width = 4,
+                            width2 = 8
+                        ),
+                    );
+                }
+            }
+
+            let mut source = lines.join("\n");
+            source.push('\n');
+            (module.to_string(), source)
+        })
+        .collect::<Vec<_>>();
+    if let Some(address) = intrinsic_address {
+        sources.push((LIBRARY_NAME.to_string(), intrinsic_interface(address)?));
+    }
+    Ok(sources)
+}
+
+/// Imports the `items` of the intrinsic `module`, from the shared library if its address is set.
+fn use_intrinsic(module: &str, items: &str, intrinsic_address: Option<AccountAddress>) -> String {
+    match intrinsic_address {
+        Some(address) => format!(
+            "use {}::{LIBRARY_NAME}::{{{}}};",
+            address.to_hex_literal(),
+            items.replacen("Self", &format!("Self as {module}"), 1)
+        ),
+        None => format!("use self::{module}::{{{items}}};"),
+    }
+}
+
+struct SourceWriter<'a> {
+    buff: &'a mut String,
+    max_memory: u64,
+    params: Vec<SType>,
+    mir: &'a Mir,
+    /// Heads of the loops, the targets of the unconditional jumps.
+    loop_heads: HashSet<Label>,
+    /// Code of the written loops from their heads.
+    loops: HashMap<Label, &'a [Loc<Statement>]>,
+    /// Enclosing loops of the written code, the innermost is the last. The flag is set if the
+    /// loop is left by `break`.
+    scopes: Vec<(Label, bool)>,
+    /// The function continues an outer loop through the `jump` local.
+    uses_jump: bool,
+    layout: &'a Layout,
+    /// Field names of the typed state.
+    fields: &'a [String],
+    /// The contract keeps the native mappings.
+    native_mappings: bool,
+}
+
+impl<'a> SourceWriter<'a> {
+    fn new(
+        buff: &'a mut String,
+        max_memory: u64,
+        params: Vec<SType>,
+        mir: &'a Mir,
+        layout: &'a Layout,
+        fields: &'a [String],
+        native_mappings: bool,
+    ) -> Self {
+        Self {
+            buff,
+            max_memory,
+            params,
+            mir,
+            loop_heads: Default::default(),
+            loops: Default::default(),
+            scopes: Default::default(),
+            uses_jump: false,
+            layout,
+            fields,
+            native_mappings,
+        }
+    }
+
+    fn write_function(
+        mut self,
+        name: &str,
+        output: Vec<String>,
+        is_entry: bool,
+    ) -> Result<(), Error> {
+        let params = self
+            .params
+            .iter()
+            .enumerate()
+            .map(|(idx, tp)| {
+                let var = Variable::new(idx as u8, *tp);
+                format!("{}: {}", var_name(var), type_name(*tp))
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        // Entry functions can only take primitive arguments and return nothing.
+        let is_entry = is_entry
+            && output.is_empty()
+            && self
+                .params
+                .iter()
+                .all(|tp| !matches!(tp, SType::Num | SType::Storage | SType::Memory));
+        let visibility = if is_entry {
+            "public entry fun"
+        } else {
+            "public fun"
+        };
+        let output = match output.len() {
+            0 => String::new(),
+            1 => format!(": {}", output[0]),
+            _ => format!(": ({})", output.join(", ")),
+        };
+        let acquires = if self.fields.is_empty() {
+            String::new()
+        } else {
+            format!(" acquires {STATE_NAME}")
+        };
+
+        writeln!(
+            self.buff,
+            "{:width$}{visibility} {name}({params}){output}{acquires} {{",
+            "",
+            width = 4
+        )?;
+
+        let statements = self.mir.statements();
+        let prologue_len = statements
+            .iter()
+            .position(|st| {
+                matches!(
+                    st.as_ref(),
+                    Statement::Label(_) | Statement::BrTrue(_, _) | Statement::Br(_)
+                )
+            })
+            .unwrap_or(statements.len());
+        self.declare_locals(&statements[..prologue_len])?;
+        let body = self.buff.len();
+
+        self.loop_heads = statements
+            .iter()
+            .filter_map(|st| match st.as_ref() {
+                Statement::Br(label) => Some(*label),
+                _ => None,
+            })
+            .collect();
+        self.write_block(statements, 2)?;
+        if self.uses_jump {
+            self.buff.insert_str(
+                body,
+                &format!("{:width$}let jump: u64 = 0;\n", "", width = 8),
+            );
+        }
+
+        writeln!(self.buff, "{:width$}}}", "", width = 4)?;
+        Ok(())
+    }
+
+    /// Declares all locals of the function.
+    /// Locals assigned in `prologue` are left uninitialized, the rest get a default value so that
+    /// they are assigned on every path through the branches and the loops.
+    fn declare_locals(&mut self, prologue: &[Loc<Statement>]) -> Result<(), Error> {
+        let assigned = prologue
+            .iter()
+            .filter_map(|st| match st.as_ref() {
+                Statement::Assign(var, _) => Some(*var),
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+
+        let params = self.params.len();
+        for (idx, tp) in self.mir.locals().iter().enumerate() {
+            let var = Variable::new((idx + params) as u8, *tp);
+            let name = var_name(var);
+            let tp_name = type_name(*tp);
+            if assigned.contains(&var) {
+                writeln!(self.buff, "{:width$}let {name}: {tp_name};", "", width = 8)?;
+            } else {
+                let default = self.default_value(*tp)?;
+                writeln!(
+                    self.buff,
+                    "{:width$}let {name}: {tp_name} = {default};",
+                    "",
+                    width = 8
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn default_value(&self, tp: SType) -> Result<String, Error> {
+        Ok(match tp {
+            SType::Num => format!("{MODULE_U256}::zero()"),
+            SType::RawNum => "0".to_string(),
+            SType::Bool => "false".to_string(),
+            SType::Address => "@0x0".to_string(),
+            SType::Bytes => "x\"\"".to_string(),
+            SType::Memory => call(MODULE_MEMORY, Mem::New, vec![self.max_memory.to_string()]),
+            SType::Storage | SType::State | SType::Signer => {
+                bail!("Local of type {tp} must be assigned before the first jump")
+            }
+        })
+    }
+
+    /// Writes the code up to its end or its first terminal statement.
+    fn write_block(
+        &mut self,
+        statements: &'a [Loc<Statement>],
+        indent: usize,
+    ) -> Result<(), Error> {
+        for (idx, st) in statements.iter().enumerate() {
+            match st.as_ref() {
+                Statement::Label(label) if self.loop_heads.contains(label) => {
+                    let body = &statements[idx + 1..];
+                    self.loops.insert(*label, body);
+                    return self.write_loop(*label, body, indent);
+                }
+                Statement::Label(label) => bail!("Unexpected fall through to {label}"),
+                Statement::BrTrue(cnd, label) => {
+                    let rest = &statements[idx + 1..];
+                    let false_len = rest
+                        .iter()
+                        .position(|st| matches!(st.as_ref(), Statement::Label(l) if l == label))
+                        .ok_or_else(|| anyhow!("Unstructured jump to {label}"))?;
+                    let cnd = self.expr(cnd)?;
+                    writeln!(self.buff, "{:width$}if ({cnd}) {{", "", width = indent * 4)?;
+                    self.write_block(&rest[false_len + 1..], indent + 1)?;
+                    writeln!(self.buff, "{:width$}}} else {{", "", width = indent * 4)?;
+                    self.write_block(&rest[..false_len], indent + 1)?;
+                    writeln!(self.buff, "{:width$}}}", "", width = indent * 4)?;
+                    return Ok(());
+                }
+                Statement::Br(label) => return self.write_jump(*label, indent),
+                Statement::Abort(_) | Statement::Result(_) => {
+                    return self.write_statement(st, indent);
+                }
+                _ => self.write_statement(st, indent)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the loop. Its body never falls through: it continues the loop, leaves the function
+    /// or breaks to continue an outer loop.
+    fn write_loop(
+        &mut self,
+        head: Label,
+        body: &'a [Loc<Statement>],
+        indent: usize,
+    ) -> Result<(), Error> {
+        writeln!(self.buff, "{:width$}loop {{", "", width = indent * 4)?;
+        self.scopes.push((head, false));
+        self.write_block(body, indent + 1)?;
+        let (_, is_broken) = self
+            .scopes
+            .pop()
+            .ok_or_else(|| anyhow!("Unbalanced loop {head}"))?;
+        if !is_broken {
+            writeln!(self.buff, "{:width$}}}", "", width = indent * 4)?;
+            return Ok(());
+        }
+
+        // The break continues one of the outer loops.
+        writeln!(self.buff, "{:width$}}};", "", width = indent * 4)?;
+        let outer = self.scopes.len() - 1;
+        if outer == 0 {
+            writeln!(self.buff, "{:width$}continue", "", width = indent * 4)?;
+        } else {
+            writeln!(
+                self.buff,
+                "{:width$}if (jump == {outer}) continue;",
+                "",
+                width = indent * 4
+            )?;
+            writeln!(self.buff, "{:width$}break", "", width = indent * 4)?;
+            self.scopes[outer].1 = true;
+        }
+        Ok(())
+    }
+
+    fn write_jump(&mut self, label: Label, indent: usize) -> Result<(), Error> {
+        match self.scopes.iter().rposition(|(head, _)| *head == label) {
+            Some(scope) if scope + 1 == self.scopes.len() => {
+                writeln!(self.buff, "{:width$}continue", "", width = indent * 4)?;
+            }
+            Some(scope) => {
+                self.uses_jump = true;
+                writeln!(
+                    self.buff,
+                    "{:width$}jump = {scope};",
+                    "",
+                    width = indent * 4
+                )?;
+                writeln!(self.buff, "{:width$}break", "", width = indent * 4)?;
+                if let Some(innermost) = self.scopes.last_mut() {
+                    innermost.1 = true;
+                }
+            }
+            None => {
+                // The loop was entered on another path, its code is written once more.
+                let body = self
+                    .loops
+                    .get(&label)
+                    .copied()
+                    .ok_or_else(|| anyhow!("Unknown label {label}"))?;
+                self.write_loop(label, body, indent)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn field_name(&self, field: usize) -> Result<&str, Error> {
+        self.fields
+            .get(field)
+            .map(|name| name.as_str())
+            .ok_or_else(|| anyhow!("Unknown state field {field}"))
+    }
+
+    /// Arguments of the mapping keys: the `vector<address>` literal of the address keys,
+    /// the `vector<U256>` literal of the number keys and their kinds.
+    fn keys(&self, keys: &[Loc<TypedExpr>]) -> Result<String, Error> {
+        let (addrs, nums): (Vec<_>, Vec<_>) = keys.iter().partition(|key| key.ty == SType::Address);
+        let literal = |keys: Vec<&Loc<TypedExpr>>| -> Result<String, Error> {
+            let keys = keys
+                .into_iter()
+                .map(|key| self.expr(key))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(format!("vector[{}]", keys.join(", ")))
+        };
+        Ok(format!(
+            "{}, {}, {}",
+            literal(addrs)?,
+            literal(nums)?,
+            key_kinds(keys)
+        ))
+    }
+
+    fn write_statement(&mut self, st: &Loc<Statement>, indent: usize) -> Result<(), Error> {
+        let line = match st.as_ref() {
+            Statement::InitStorage(var) => {
+                let mut line = format!(
+                    "{};",
+                    call(MODULE_PERSIST, Persist::InitContract, vec![var_name(*var)])
+                );
+                if self.native_mappings {
+                    write!(
+                        line,
+                        "\n{:width$}{};",
+                        "",
+                        call(MODULE_PERSIST, Persist::InitMappings, vec![var_name(*var)]),
+                        width = indent * 4
+                    )?;
+                }
+                line
+            }
+            Statement::InitState(var) => {
+                let values = self
+                    .fields
+                    .iter()
+                    .zip(self.layout.fields())
+                    .map(|(name, field)| {
+                        let val = match field.kind {
+                            FieldKind::Value => format!("{MODULE_U256}::zero()"),
+                            FieldKind::Mapping(KeyType::Num) => {
+                                call(MODULE_PERSIST, Persist::NewTable, vec![])
+                            }
+                            FieldKind::Mapping(KeyType::Address) => {
+                                call(MODULE_PERSIST, Persist::NewAddressTable, vec![])
+                            }
+                        };
+                        format!("{name}: {val}")
+                    })
+                    .collect::<Vec<_>>();
+                format!(
+                    "move_to({}, {STATE_NAME} {{ {} }});",
+                    var_name(*var),
+                    values.join(", ")
+                )
+            }
+            Statement::StoreStack(ctx) => {
+                if ctx.is_empty() {
+                    return Ok(());
+                }
+                let vars = ctx.keys().map(|var| var_name(*var)).collect::<Vec<_>>();
+                let exprs = ctx
+                    .values()
+                    .map(|expr| self.expr(expr))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if vars.len() == 1 {
+                    format!("{} = {};", vars[0], exprs[0])
+                } else {
+                    format!("({}) = ({});", vars.join(", "), exprs.join(", "))
+                }
+            }
+            Statement::Assign(var, expr) => format!("{} = {};", var_name(*var), self.expr(expr)?),
+            Statement::MStore {
+                memory,
+                offset,
+                val,
+            } => format!(
+                "{};",
+                call(
+                    MODULE_MEMORY,
+                    Mem::Store,
+                    vec![mut_borrow(*memory), self.expr(offset)?, self.expr(val)?],
+                )
+            ),
+            Statement::MStore8 {
+                memory,
+                offset,
+                val,
+            } => format!(
+                "{};",
+                call(
+                    MODULE_MEMORY,
+                    Mem::Store8,
+                    vec![mut_borrow(*memory), self.expr(offset)?, self.expr(val)?],
+                )
+            ),
+            Statement::CodeCopy { memory, dest, data } => format!(
+                "{};",
+                call(
+                    MODULE_MEMORY,
+                    Mem::CodeCopy,
+                    vec![
+                        mut_borrow(*memory),
+                        self.expr(dest)?,
+                        format!("x\"{}\"", hex::encode(data)),
+                    ],
+                )
+            ),
+            Statement::Call {
+                result,
+                signer,
+                module,
+                function,
+                args,
+            } => format!(
+                "{} = {}::{}::{}({}, {});",
+                var_name(*result),
+                module.address().to_hex_literal(),
+                module.name(),
+                function,
+                var_name(*signer),
+                self.expr(args)?
+            ),
+            Statement::WriteResult {
+                memory,
+                dest,
+                data,
+                len,
+            } => format!(
+                "{};",
+                call(
+                    MODULE_MEMORY,
+                    Mem::WriteResult,
+                    vec![
+                        mut_borrow(*memory),
+                        self.expr(dest)?,
+                        format!("&{}", var_name(*data)),
+                        self.expr(len)?,
+                    ],
+                )
+            ),
+            Statement::ReturnDataCopy {
+                memory,
+                dest,
+                data,
+                offset,
+                len,
+            } => format!(
+                "{};",
+                call(
+                    MODULE_MEMORY,
+                    Mem::ReturnDataCopy,
+                    vec![
+                        mut_borrow(*memory),
+                        self.expr(dest)?,
+                        format!("&{}", var_name(*data)),
+                        self.expr(offset)?,
+                        self.expr(len)?,
+                    ],
+                )
+            ),
+            Statement::SStore { storage, key, val } => format!(
+                "{};",
+                call(
+                    MODULE_PERSIST,
+                    Persist::Store,
+                    vec![var_name(*storage), self.expr(key)?, self.expr(val)?],
+                )
+            ),
+            Statement::HashedSStore { storage, key, val } => format!(
+                "{};",
+                call(
+                    MODULE_PERSIST,
+                    Persist::StoreHashed,
+                    vec![var_name(*storage), self.expr(key)?, self.expr(val)?],
+                )
+            ),
+            Statement::MapStore {
+                storage,
+                slot,
+                keys,
+                val,
+            } => format!(
+                "{};",
+                call(
+                    MODULE_PERSIST,
+                    Persist::MappingStore,
+                    vec![
+                        var_name(*storage),
+                        self.expr(slot)?,
+                        self.keys(keys)?,
+                        self.expr(val)?
+                    ],
+                )
+            ),
+            Statement::SetField { state, field, val } => format!(
+                "{}.{} = {};",
+                var_name(*state),
+                self.field_name(*field)?,
+                self.expr(val)?
+            ),
+            Statement::SetEntry {
+                state,
+                field,
+                key,
+                val,
+            } => {
+                let fun = if key.ty == SType::Address {
+                    Persist::StoreAddressEntry
+                } else {
+                    Persist::StoreEntry
+                };
+                let tbl = format!("&mut {}.{}", var_name(*state), self.field_name(*field)?);
+                format!(
+                    "{};",
+                    call(
+                        MODULE_PERSIST,
+                        fun,
+                        vec![tbl, self.expr(key)?, self.expr(val)?]
+                    )
+                )
+            }
+            Statement::Log {
+                storage,
+                memory,
+                offset,
+                len,
+                topics,
+            } => {
+                let fun = match topics.len() {
+                    0 => Persist::Log0,
+                    1 => Persist::Log1,
+                    2 => Persist::Log2,
+                    3 => Persist::Log3,
+                    4 => Persist::Log4,
+                    _ => bail!("too many topics"),
+                };
+                let mut args = vec![
+                    var_name(*storage),
+                    mut_borrow(*memory),
+                    self.expr(offset)?,
+                    self.expr(len)?,
+                ];
+                for topic in topics {
+                    args.push(self.expr(topic)?);
+                }
+                format!("{};", call(MODULE_PERSIST, fun, args))
+            }
+            Statement::Abort(code) => format!("abort {code}"),
+            Statement::Result(vars) => match vars.len() {
+                0 => "return".to_string(),
+                1 => format!("return {}", var_name(vars[0])),
+                _ => format!(
+                    "return ({})",
+                    vars.iter()
+                        .map(|var| var_name(*var))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            },
+            Statement::Label(_) | Statement::BrTrue(_, _) | Statement::Br(_) => {
+                bail!("Unexpected jump in straight-line code")
+            }
+        };
+        writeln!(self.buff, "{:width$}{line}", "", width = indent * 4)?;
+        Ok(())
+    }
+
+    fn expr(&self, expr: &Loc<TypedExpr>) -> Result<String, Error> {
+        Ok(match expr.expr.as_ref() {
+            Expression::Const(Value::Number(val)) => {
+                if val.bits() <= 128 {
+                    call(MODULE_U256, Num::FromU128, vec![val.as_u128().to_string()])
+                } else {
+                    call(
+                        MODULE_U256,
+                        Num::FromU64s,
+                        val.0.iter().map(|part| part.to_string()).collect(),
+                    )
+                }
+            }
+            Expression::Const(Value::Bool(val)) => val.to_string(),
+            Expression::GetMem => call(MODULE_MEMORY, Mem::New, vec![self.max_memory.to_string()]),
+            Expression::GetStore => format!("{MODULE_PERSIST}::borrow_persist()"),
+            Expression::GetState => format!("borrow_global_mut<{STATE_NAME}>(@self)"),
+            Expression::GetField { state, field } => {
+                format!("{}.{}", var_name(*state), self.field_name(*field)?)
+            }
+            Expression::GetEntry {
+                state,
+                storage,
+                field,
+                key,
+                slot,
+            } => {
+                let fun = if key.ty == SType::Address {
+                    Persist::LoadAddressEntry
+                } else {
+                    Persist::LoadEntry
+                };
+                let tbl = format!("&{}.{}", var_name(*state), self.field_name(*field)?);
+                call(
+                    MODULE_PERSIST,
+                    fun,
+                    vec![tbl, self.expr(key)?, var_name(*storage), self.expr(slot)?],
+                )
+            }
+            Expression::MLoad { memory, offset } => call(
+                MODULE_MEMORY,
+                Mem::Load,
+                vec![mut_borrow(*memory), self.expr(offset)?],
+            ),
+            Expression::MSlice {
+                memory,
+                offset,
+                len,
+            } => call(
+                MODULE_MEMORY,
+                Mem::Slice,
+                vec![mut_borrow(*memory), self.expr(offset)?, self.expr(len)?],
+            ),
+            Expression::MapSlot { slot, keys } => call(
+                MODULE_PERSIST,
+                Persist::MappingSlot,
+                vec![self.expr(slot)?, self.keys(keys)?],
+            ),
+            Expression::MapLoad {
+                storage,
+                slot,
+                keys,
+            } => call(
+                MODULE_PERSIST,
+                Persist::MappingLoad,
+                vec![var_name(*storage), self.expr(slot)?, self.keys(keys)?],
+            ),
+            Expression::SLoad { storage, key } => call(
+                MODULE_PERSIST,
+                Persist::Load,
+                vec![var_name(*storage), self.expr(key)?],
+            ),
+            Expression::HashedSLoad { storage, key } => call(
+                MODULE_PERSIST,
+                Persist::LoadHashed,
+                vec![var_name(*storage), self.expr(key)?],
+            ),
+            Expression::MSize { memory } => {
+                call(MODULE_MEMORY, Mem::Size, vec![mut_borrow(*memory)])
+            }
+            Expression::MoveVar(var) | Expression::CopyVar(var) => var_name(*var),
+            Expression::Unary(op, arg) => {
+                let fun = match op {
+                    UnaryOp::IsZero => Num::IsZero,
+                    UnaryOp::Not => Num::BitNot,
+                };
+                call(MODULE_U256, fun, vec![self.expr(arg)?])
+            }
+            Expression::Binary(op, arg, arg1) => {
+                if *op == BinaryOp::Eq || arg.ty == SType::Bool && arg1.ty == SType::Bool {
+                    format!("({} == {})", self.expr(arg)?, self.expr(arg1)?)
+                } else {
+                    let fun = match op {
+                        BinaryOp::Eq => Num::Eq,
+                        BinaryOp::Lt => Num::Lt,
+                        BinaryOp::Gt => Num::Gt,
+                        BinaryOp::Shr => Num::Shr,
+                        BinaryOp::Shl => Num::Shl,
+                        BinaryOp::Sar => Num::Sar,
+                        BinaryOp::Add => Num::Add,
+                        BinaryOp::And => Num::BitAnd,
+                        BinaryOp::Or => Num::BitOr,
+                        BinaryOp::Xor => Num::BitXor,
+                        BinaryOp::Mul => Num::Mul,
+                        BinaryOp::Sub => Num::Sub,
+                        BinaryOp::Div => Num::Div,
+                        BinaryOp::SDiv => Num::SDiv,
+                        BinaryOp::SLt => Num::SLt,
+                        BinaryOp::SGt => Num::SGt,
+                        BinaryOp::Byte => Num::Byte,
+                        BinaryOp::Mod => Num::Mod,
+                        BinaryOp::SMod => Num::SMod,
+                        BinaryOp::Exp => Num::Exp,
+                        BinaryOp::SignExtend => Num::SignExtend,
+                    };
+                    call(MODULE_U256, fun, vec![self.expr(arg)?, self.expr(arg1)?])
+                }
+            }
+            Expression::Ternary(op, arg, arg1, arg2) => {
+                let fun = match op {
+                    TernaryOp::AddMod => Num::AddMod,
+                    TernaryOp::MulMod => Num::MulMod,
+                };
+                call(
+                    MODULE_U256,
+                    fun,
+                    vec![self.expr(arg)?, self.expr(arg1)?, self.expr(arg2)?],
+                )
+            }
+            Expression::Cast(arg, cast) => {
+                let val = self.expr(arg)?;
+                match cast {
+                    Cast::BoolToNum => call(MODULE_U256, Num::FromBool, vec![val]),
+                    Cast::SignerToNum => call(MODULE_U256, Num::FromSigner, vec![val]),
+                    Cast::BytesToNum => call(
+                        MODULE_U256,
+                        Num::FromBytes,
+                        vec![format!("&{val}"), format!("{MODULE_U256}::zero()")],
+                    ),
+                    Cast::NumToBool => call(MODULE_U256, Num::ToBool, vec![val]),
+                    Cast::AddressToNum => call(MODULE_U256, Num::FromAddress, vec![val]),
+                    Cast::NumToAddress => call(MODULE_U256, Num::ToAddress, vec![val]),
+                    Cast::RawNumToNum => call(MODULE_U256, Num::FromU128, vec![val]),
+                    Cast::NumToRawNum => call(MODULE_U256, Num::ToU128, vec![val]),
+                }
+            }
+            Expression::BytesLen(bytes) => call(
+                MODULE_MEMORY,
+                Mem::RequestBufferLen,
+                vec![format!("&{}", var_name(*bytes))],
+            ),
+            Expression::DataLen(data) => call(
+                MODULE_MEMORY,
+                Mem::DataLen,
+                vec![format!("&{}", var_name(*data))],
+            ),
+            Expression::ReadNum { data, offset } => call(
+                MODULE_MEMORY,
+                Mem::ReadRequestBuffer,
+                vec![format!("&{}", var_name(*data)), self.expr(offset)?],
+            ),
+            Expression::Hash { mem, offset, len } => call(
+                MODULE_MEMORY,
+                Mem::Hash,
+                vec![mut_borrow(*mem), self.expr(offset)?, self.expr(len)?],
+            ),
+            Expression::Balance { address } => {
+                call(MODULE_INFO, Info::AptosBalance, vec![self.expr(address)?])
+            }
+            Expression::Gas => call(MODULE_INFO, Info::Gas, vec![]),
+            Expression::GasPrice => call(MODULE_INFO, Info::GasPrice, vec![]),
+            Expression::GasLimit => call(MODULE_INFO, Info::GasLimit, vec![]),
+            Expression::BlockHeight => call(MODULE_INFO, Info::BlockHeight, vec![]),
+            Expression::BlockTimestamp => call(MODULE_INFO, Info::BlockTimestamp, vec![]),
+            Expression::BlockHash { number } => {
+                call(MODULE_INFO, Info::BlockHash, vec![self.expr(number)?])
+            }
+            Expression::BlockDifficulty => call(MODULE_INFO, Info::BlockDifficulty, vec![]),
+            Expression::BlockCoinbase => call(MODULE_INFO, Info::BlockCoinbase, vec![]),
+        })
+    }
+}
+
+fn call(module: &str, fun: impl Function, args: Vec<String>) -> String {
+    format!("{module}::{}({})", fun.name(), args.join(", "))
+}
+
+fn mut_borrow(var: Variable) -> String {
+    format!("&mut {}", var_name(var))
+}
+
+fn var_name(var: Variable) -> String {
+    let prefix = match var.ty() {
+        SType::Storage => "store",
+        SType::State => "state",
+        SType::Memory => "mem",
+        SType::Num => "num",
+        SType::RawNum => "raw",
+        SType::Bool => "flag",
+        SType::Signer => "account",
+        SType::Address => "addr",
+        SType::Bytes => "data",
+    };
+    format!("{prefix}_{}", var.index())
+}
+
+fn type_name(tp: SType) -> &'static str {
+    match tp {
+        SType::Storage => "&mut Persist",
+        SType::State => "&mut State",
+        SType::Memory => "Memory",
+        SType::Num => "U256",
+        SType::RawNum => "u128",
+        SType::Bool => "bool",
+        SType::Signer => "&signer",
+        SType::Address => "address",
+        SType::Bytes => "vector<u8>",
+    }
+}
+
+/// Source name of the type of the function signature.
+fn token_name(token: &SignatureToken) -> Result<String, Error> {
+    Ok(match token {
+        SignatureToken::Bool => "bool".to_string(),
+        SignatureToken::U8 => "u8".to_string(),
+        SignatureToken::U64 => "u64".to_string(),
+        SignatureToken::U128 => "u128".to_string(),
+        SignatureToken::Address => "address".to_string(),
+        SignatureToken::Signer => "signer".to_string(),
+        SignatureToken::Vector(tp) => format!("vector<{}>", token_name(tp)?),
+        SignatureToken::Reference(tp) => format!("&{}", token_name(tp)?),
+        SignatureToken::MutableReference(tp) => format!("&mut {}", token_name(tp)?),
+        tp if *tp == Num::token() => "U256".to_string(),
+        tp => bail!("Unexpected type {tp:?} of the function signature"),
+    })
+}
@@ -0,0 +1,37 @@
+use std::collections::HashSet;
+
+use eth::abi::names::{snake_case, unique, MOVE_KEYWORDS};
+use eth::layout::{FieldKind, KeyType, Layout};
+
+/// Name of the resource with the typed state variables.
+pub const STATE_NAME: &str = "State";
+
+/// Move names of the state fields.
+/// Names that are not valid for Move fields are prefixed, see [`unique`] for the keywords.
+pub fn field_names(layout: &Layout) -> Vec<String> {
+    let mut taken = HashSet::new();
+    layout
+        .fields()
+        .iter()
+        .map(|field| {
+            let mut name = snake_case(field.name.trim_start_matches('_'));
+            if !name.starts_with(|c: char| c.is_ascii_lowercase()) {
+                name = format!("field_{name}");
+            }
+            unique(name, &MOVE_KEYWORDS, &mut taken)
+        })
+        .collect()
+}
+
+/// Move types of the state fields.
+pub fn field_types(layout: &Layout) -> Vec<&'static str> {
+    layout
+        .fields()
+        .iter()
+        .map(|field| match field.kind {
+            FieldKind::Value => "U256",
+            FieldKind::Mapping(KeyType::Num) => "aptos_std::table::Table<U256, U256>",
+            FieldKind::Mapping(KeyType::Address) => "aptos_std::table::Table<address, U256>",
+        })
+        .collect()
+}
@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Error, Result};
 use move_binary_format::file_format::{
-    Bytecode, ConstantPoolIndex, SignatureIndex, SignatureToken, Visibility,
+    Bytecode, ConstantPoolIndex, SignatureIndex, SignatureToken, StructDefinitionIndex, Visibility,
 };
 use move_binary_format::CompiledModule;
 use move_core_types::account_address::AccountAddress;
@@ -11,18 +11,23 @@ use std::mem;
 use eth::abi::call::FunHash;
 use eth::bytecode::hir::executor::math::{BinaryOp, TernaryOp, UnaryOp};
 use eth::bytecode::loc::Loc;
-use eth::bytecode::mir::ir::expression::{Cast, Expression, TypedExpr};
+use eth::bytecode::mir::ir::expression::{key_kinds, Cast, Expression, TypedExpr};
 use eth::bytecode::mir::ir::statement::Statement;
 use eth::bytecode::mir::ir::types::{SType, Value};
 use eth::bytecode::mir::ir::Mir;
 use eth::bytecode::mir::translation::variables::Variable;
 use eth::bytecode::types::EthType;
+use eth::layout::{FieldKind, KeyType};
 use eth::program::Program;
 use eth::Flags;
 use intrinsic::table::{self_address_index, Info, Memory as Mem, Persist, U256 as Num};
-use intrinsic::{template, Function};
+use intrinsic::{
+    add_state, import_function, mappings_def, persist_def, shared_template, template, Function,
+    StateDef,
+};
 
 use crate::mv_ir::func::Func;
+use crate::mv_ir::state::field_names;
 use crate::mv_ir::Module;
 use crate::translator::constants::ConstantPool;
 use crate::translator::signature::{map_signature, signer, SignatureWriter};
@@ -42,16 +47,60 @@ pub struct MvIrTranslator {
     max_memory: u64,
     program: Option<Program>,
     flags: Flags,
+    /// Definition of the contract storage.
+    persist: StructDefinitionIndex,
+    /// Definition of the native mappings. Only set with `Flags::native_mappings`.
+    mappings: Option<StructDefinitionIndex>,
+    /// The translated function calls the intrinsics which borrow the native mappings.
+    uses_mappings: bool,
+    state: Option<StateDef>,
+    state_fields: Vec<FieldKind>,
 }
 
 impl MvIrTranslator {
+    /// Creates a translator for the module at `address`.
+    /// If `intrinsic_address` is set, the intrinsics are imported from the library published at
+    /// that address instead of being copied into the module.
     pub fn new(
         address: AccountAddress,
         max_memory: u64,
         program: Program,
         flags: Flags,
+        intrinsic_address: Option<AccountAddress>,
     ) -> Result<MvIrTranslator> {
-        let mut template = template(address, program.name(), program.identifiers())?;
+        let mut template = if let Some(library) = intrinsic_address {
+            shared_template(address, program.name(), library, program.identifiers())?
+        } else {
+            template(address, program.name(), program.identifiers())?
+        };
+        let persist = persist_def(&template)?;
+        let mappings = if flags.native_mappings {
+            Some(mappings_def(&template)?)
+        } else {
+            None
+        };
+
+        let layout = program.layout();
+        let state = if layout.is_empty() {
+            None
+        } else {
+            let names = field_names(layout);
+            let fields = names
+                .iter()
+                .zip(layout.fields())
+                .map(|(name, field)| {
+                    let key = match field.kind {
+                        FieldKind::Value => None,
+                        FieldKind::Mapping(KeyType::Num) => Some(Num::token()),
+                        FieldKind::Mapping(KeyType::Address) => Some(SignatureToken::Address),
+                    };
+                    (name.as_str(), key)
+                })
+                .collect::<Vec<_>>();
+            Some(add_state(&mut template, &fields)?)
+        };
+        let state_fields = layout.fields().iter().map(|field| field.kind).collect();
+
         Ok(Self {
             sign_writer: SignatureWriter::new(mem::take(&mut template.signatures)),
             constant_pool: ConstantPool::new(mem::take(&mut template.constant_pool)),
@@ -60,6 +109,11 @@ impl MvIrTranslator {
             max_memory,
             program: Some(program),
             flags,
+            persist,
+            mappings,
+            uses_mappings: false,
+            state,
+            state_fields,
         })
     }
 
@@ -86,6 +140,7 @@ impl MvIrTranslator {
         let mir = program.constructor_mir().clone();
 
         self.code.reset();
+        self.uses_mappings = false;
         self.translate_statements(mir.statements());
         let code = self.code.freeze()?;
 
@@ -99,6 +154,7 @@ impl MvIrTranslator {
             input,
             output,
             locals: self.map_locals(&mir),
+            acquires: self.acquires(),
             code,
         })
     }
@@ -143,6 +199,7 @@ impl MvIrTranslator {
 
         let locals = self.map_locals(mir);
         self.code.reset();
+        self.uses_mappings = false;
         self.translate_statements(mir.statements());
         let code = self.code.freeze()?;
 
@@ -152,10 +209,30 @@ impl MvIrTranslator {
             input,
             output,
             locals,
+            acquires: self.acquires(),
             code,
         })
     }
 
+    /// Resources which are borrowed by the translated functions.
+    fn acquires(&self) -> Vec<StructDefinitionIndex> {
+        let mut acquires = vec![self.persist];
+        if let Some(state) = &self.state {
+            acquires.push(state.def);
+        }
+        // The native mappings are borrowed by the intrinsics, so only their callers acquire them.
+        if self.uses_mappings {
+            acquires.extend(self.mappings);
+        }
+        acquires
+    }
+
+    fn state(&self) -> &StateDef {
+        self.state
+            .as_ref()
+            .expect("The typed state is used by a contract without the storage layout")
+    }
+
     fn map_locals(&mut self, mir: &Mir) -> SignatureIndex {
         let types = mir
             .locals()
@@ -164,6 +241,7 @@ impl MvIrTranslator {
                 SType::Num => Num::token(),
                 SType::Bool => SignatureToken::Bool,
                 SType::Storage => Persist::token(),
+                SType::State => self.state().token(),
                 SType::Memory => Mem::token(),
                 SType::Signer => SignatureToken::Reference(Box::new(SignatureToken::Signer)),
                 SType::Bytes => SignatureToken::Vector(Box::new(SignatureToken::U8)),
@@ -229,8 +307,81 @@ impl MvIrTranslator {
                     vec![CallOp::Copy(*storage), CallOp::Expr(key), CallOp::Expr(val)],
                 );
             }
+            Statement::HashedSStore { storage, key, val } => {
+                self.uses_mappings = true;
+                self.call(
+                    Persist::StoreHashed,
+                    vec![CallOp::Copy(*storage), CallOp::Expr(key), CallOp::Expr(val)],
+                );
+            }
             Statement::InitStorage(var) => {
                 self.call(Persist::InitContract, vec![CallOp::Copy(*var)]);
+                if self.mappings.is_some() {
+                    self.call(Persist::InitMappings, vec![CallOp::Copy(*var)]);
+                }
+            }
+            Statement::InitState(var) => {
+                self.code.copy_loc(var.index());
+                for field in self.state_fields.clone() {
+                    match field {
+                        FieldKind::Value => self.call(
+                            Num::FromU64s,
+                            vec![
+                                CallOp::ConstU64(0),
+                                CallOp::ConstU64(0),
+                                CallOp::ConstU64(0),
+                                CallOp::ConstU64(0),
+                            ],
+                        ),
+                        FieldKind::Mapping(KeyType::Num) => self.call(Persist::NewTable, vec![]),
+                        FieldKind::Mapping(KeyType::Address) => {
+                            self.call(Persist::NewAddressTable, vec![])
+                        }
+                    }
+                }
+                let def = self.state().def;
+                self.code.write(Bytecode::Pack(def));
+                self.code.write(Bytecode::MoveTo(def));
+            }
+            Statement::SetField { state, field, val } => {
+                self.translate_expr(val);
+                self.code.copy_loc(state.index());
+                self.code
+                    .write(Bytecode::MutBorrowField(self.state().fields[*field]));
+                self.code.write(Bytecode::WriteRef);
+            }
+            Statement::SetEntry {
+                state,
+                field,
+                key,
+                val,
+            } => {
+                self.code.copy_loc(state.index());
+                self.code
+                    .write(Bytecode::MutBorrowField(self.state().fields[*field]));
+                let fun = if key.ty == SType::Address {
+                    Persist::StoreAddressEntry
+                } else {
+                    Persist::StoreEntry
+                };
+                self.call(fun, vec![CallOp::Expr(key), CallOp::Expr(val)]);
+            }
+            Statement::MapStore {
+                storage,
+                slot,
+                keys,
+                val,
+            } => {
+                self.uses_mappings = true;
+                self.call(
+                    Persist::MappingStore,
+                    vec![
+                        CallOp::Copy(*storage),
+                        CallOp::Expr(slot),
+                        CallOp::MapKeys(keys),
+                        CallOp::Expr(val),
+                    ],
+                );
             }
             Statement::Log {
                 storage,
@@ -266,6 +417,64 @@ impl MvIrTranslator {
                     ],
                 );
             }
+            Statement::Call {
+                result,
+                signer,
+                module,
+                function,
+                args,
+            } => {
+                let input = self.sign_writer.make_signature(map_signature(
+                    &[EthType::Address, EthType::Bytes],
+                    false,
+                    &self.flags,
+                ));
+                let output = self.sign_writer.make_signature(map_signature(
+                    &[EthType::Bytes],
+                    false,
+                    &self.flags,
+                ));
+                let handle = import_function(&mut self.template, module, function, input, output)
+                    .expect("The library function name is a Move identifier");
+                self.code.copy_loc(signer.index());
+                self.translate_expr(args);
+                self.code.write(Bytecode::Call(handle));
+                self.code.assign(result.index());
+            }
+            Statement::WriteResult {
+                memory,
+                dest,
+                data,
+                len,
+            } => {
+                self.call(
+                    Mem::WriteResult,
+                    vec![
+                        CallOp::MutBorrow(*memory),
+                        CallOp::Expr(dest),
+                        CallOp::Borrow(*data),
+                        CallOp::Expr(len),
+                    ],
+                );
+            }
+            Statement::ReturnDataCopy {
+                memory,
+                dest,
+                data,
+                offset,
+                len,
+            } => {
+                self.call(
+                    Mem::ReturnDataCopy,
+                    vec![
+                        CallOp::MutBorrow(*memory),
+                        CallOp::Expr(dest),
+                        CallOp::Borrow(*data),
+                        CallOp::Expr(offset),
+                        CallOp::Expr(len),
+                    ],
+                );
+            }
         }
     }
 
@@ -320,8 +529,62 @@ impl MvIrTranslator {
                 let index = self_address_index();
                 self.code.write(Bytecode::LdConst(index));
 
+                self.code.write(Bytecode::MutBorrowGlobal(self.persist));
+            }
+            Expression::GetState => {
+                self.code.write(Bytecode::LdConst(self_address_index()));
+                self.code.write(Bytecode::MutBorrowGlobal(self.state().def));
+            }
+            Expression::GetField { state, field } => {
+                self.code.copy_loc(state.index());
                 self.code
-                    .write(Bytecode::MutBorrowGlobal(Persist::instance()));
+                    .write(Bytecode::ImmBorrowField(self.state().fields[*field]));
+                self.code.write(Bytecode::ReadRef);
+            }
+            Expression::GetEntry {
+                state,
+                storage,
+                field,
+                key,
+                slot,
+            } => {
+                self.code.copy_loc(state.index());
+                self.code
+                    .write(Bytecode::ImmBorrowField(self.state().fields[*field]));
+                let fun = if key.ty == SType::Address {
+                    Persist::LoadAddressEntry
+                } else {
+                    Persist::LoadEntry
+                };
+                self.call(
+                    fun,
+                    vec![
+                        CallOp::Expr(key),
+                        CallOp::Copy(*storage),
+                        CallOp::Expr(slot),
+                    ],
+                );
+            }
+            Expression::MapSlot { slot, keys } => {
+                self.call(
+                    Persist::MappingSlot,
+                    vec![CallOp::Expr(slot), CallOp::MapKeys(keys)],
+                );
+            }
+            Expression::MapLoad {
+                storage,
+                slot,
+                keys,
+            } => {
+                self.uses_mappings = true;
+                self.call(
+                    Persist::MappingLoad,
+                    vec![
+                        CallOp::Copy(*storage),
+                        CallOp::Expr(slot),
+                        CallOp::MapKeys(keys),
+                    ],
+                );
             }
             Expression::MLoad { memory, offset } => {
                 self.call(
@@ -335,6 +598,13 @@ impl MvIrTranslator {
                     vec![CallOp::Copy(*storage), CallOp::Expr(key)],
                 );
             }
+            Expression::HashedSLoad { storage, key } => {
+                self.uses_mappings = true;
+                self.call(
+                    Persist::LoadHashed,
+                    vec![CallOp::Copy(*storage), CallOp::Expr(key)],
+                );
+            }
             Expression::MSize { memory } => {
                 self.call(Mem::Size, vec![CallOp::MutBorrow(*memory)]);
             }
@@ -356,6 +626,9 @@ impl MvIrTranslator {
             Expression::BytesLen(bytes) => {
                 self.call(Mem::RequestBufferLen, vec![CallOp::Borrow(*bytes)]);
             }
+            Expression::DataLen(data) => {
+                self.call(Mem::DataLen, vec![CallOp::Borrow(*data)]);
+            }
             Expression::ReadNum { data, offset } => {
                 self.call(
                     Mem::ReadRequestBuffer,
@@ -550,6 +823,18 @@ impl MvIrTranslator {
                 CallOp::Constant(idx) => {
                     self.code.write(Bytecode::LdConst(idx));
                 }
+                CallOp::MapKeys(keys) => {
+                    let (addrs, nums): (Vec<_>, Vec<_>) =
+                        keys.iter().partition(|key| key.ty == SType::Address);
+                    for (items, token) in [(addrs, SignatureToken::Address), (nums, Num::token())] {
+                        for item in &items {
+                            self.translate_expr(item);
+                        }
+                        let tp = self.sign_writer.make_signature(vec![token]);
+                        self.code.write(Bytecode::VecPack(tp, items.len() as u64));
+                    }
+                    self.code.write(Bytecode::LdU64(key_kinds(keys)));
+                }
             }
         }
         self.code.write(Bytecode::Call(fun.handler()));
@@ -565,4 +850,7 @@ pub enum CallOp<'a> {
     Borrow(Variable),
     ConstU64(u64),
     Constant(ConstantPoolIndex),
+    /// Keys of the nested mappings: the `vector<address>` of the address keys,
+    /// the `vector<U256>` of the number keys and their kinds.
+    MapKeys(&'a [Loc<TypedExpr>]),
 }
@@ -1,12 +1,24 @@
 use anyhow::Error;
 use ethabi::Contract;
+use log::warn;
+use move_binary_format::CompiledModule;
 use move_core_types::account_address::AccountAddress;
 use primitive_types::U256;
 
-use eth::transpile_program;
-pub use eth::Flags;
-use intrinsic::toml_template;
-use mv::mv_ir::interface::move_interface;
+use eth::compile::EvmPack;
+use eth::layout::Layout;
+pub use eth::link::LibraryLink;
+use eth::{transpile_program, TranspileOptions};
+pub use eth::{Flags, Frontend};
+use intrinsic::library;
+pub use intrinsic::toml_template;
+use mv::gas::estimate;
+pub use mv::gas::{FunctionGas, GasReport, LoopGas};
+pub use mv::mv_ir::client::package_client_manifest;
+use mv::mv_ir::client::{client_manifest, move_client, ETH_ABI_SOURCE};
+use mv::mv_ir::disassemble as disassemble_module;
+use mv::mv_ir::interface::{intrinsic_interface, move_interface};
+use mv::mv_ir::source::{intrinsic_sources, move_source};
 use mv::translator::MvIrTranslator;
 
 pub const MAX_MEMORY: u64 = 1024 * 32;
@@ -17,9 +29,47 @@ pub struct Config<'a> {
     pub name: &'a str,
     pub initialization_args: &'a str,
     pub flags: Flags,
+    /// Address of the shared intrinsic library.
+    /// If `None`, the intrinsics are copied into the translated module.
+    pub intrinsic_address: Option<AccountAddress>,
+    /// solc `storageLayout` of the contract.
+    /// If set, the state variables are kept in a typed `State` resource instead of the raw storage.
+    pub storage_layout: Option<&'a str>,
+    /// External libraries linked into the bytecode.
+    pub libraries: &'a [LibraryLink],
+    /// Source of the function bodies: the bytecode or the Yul IR of the contract.
+    pub frontend: Frontend<'a>,
+    /// Print the HIR and the MIR of the functions into the target. Only the snapshots need them.
+    pub print_stages: bool,
 }
 
 impl<'a> Config<'a> {
+    /// Config of the module `name` with the default options.
+    /// The other options are set by the struct update syntax:
+    /// `Config { flags, ..Config::new(addr, name) }`.
+    pub fn new(contract_addr: AccountAddress, name: &'a str) -> Config<'a> {
+        Config {
+            contract_addr,
+            name,
+            initialization_args: "",
+            flags: Flags::default(),
+            intrinsic_address: None,
+            storage_layout: None,
+            libraries: &[],
+            frontend: Frontend::Bytecode,
+            print_stages: false,
+        }
+    }
+
+    /// Config of the compiled contract with its storage layout and linked libraries.
+    pub fn for_pack(contract_addr: AccountAddress, pack: &'a EvmPack) -> Config<'a> {
+        Config {
+            storage_layout: pack.storage_layout(),
+            libraries: pack.links(),
+            ..Config::new(contract_addr, pack.name())
+        }
+    }
+
     pub fn encoded_address(&self) -> U256 {
         U256::from(self.contract_addr.as_slice())
     }
@@ -27,6 +77,11 @@ impl<'a> Config<'a> {
 
 pub fn translate(bytecode: &str, abi: &str, config: Config) -> Result<Target, Error> {
     let abi: Contract = serde_json::from_str(abi)?;
+    let layout = config
+        .storage_layout
+        .map(Layout::parse)
+        .transpose()?
+        .unwrap_or_default();
 
     let program = transpile_program(
         config.name,
@@ -34,14 +89,60 @@ pub fn translate(bytecode: &str, abi: &str, config: Config) -> Result<Target, Er
         config.initialization_args,
         &abi,
         config.encoded_address(),
-        config.flags,
+        TranspileOptions {
+            flags: config.flags,
+            layout,
+            libraries: config.libraries,
+            frontend: config.frontend,
+            print_hir: config.print_stages,
+        },
     )?;
 
-    let mvir = MvIrTranslator::new(config.contract_addr, MAX_MEMORY, program, config.flags)?;
+    // The source is a by-product of the translation, so its failures never block the binary.
+    let emitted = move_source(&program, MAX_MEMORY, config.flags, config.intrinsic_address)
+        .and_then(|source| {
+            let intrinsics = intrinsic_sources(config.name, config.intrinsic_address)?;
+            Ok((source, intrinsics))
+        });
+    let (source, intrinsics) = match emitted {
+        Ok((source, intrinsics)) => (Some(source), intrinsics),
+        Err(err) => {
+            warn!("The Move source of {} is not emitted: {err}", config.name);
+            (None, vec![])
+        }
+    };
+    let (hir, mir) = if config.print_stages {
+        (Some(program.debug_hir()), Some(format!("{program:?}")))
+    } else {
+        (None, None)
+    };
+
+    let layout = program.layout().clone();
+    let mvir = MvIrTranslator::new(
+        config.contract_addr,
+        MAX_MEMORY,
+        program,
+        config.flags,
+        config.intrinsic_address,
+    )?;
     let module = mvir.translate()?;
     let compiled_module = module.make_move_module()?;
-    let interface = move_interface(&compiled_module, &abi, config.flags)?;
+    let interface = move_interface(
+        &compiled_module,
+        &abi,
+        config.flags,
+        config.intrinsic_address,
+    )?;
     let manifest = toml_template(config.name, config.contract_addr);
+    let client =
+        move_client(config.name, &abi, config.flags, config.intrinsic_address)?.map(|client| {
+            Client {
+                manifest: client_manifest(config.name, config.contract_addr),
+                source: client.source,
+                eth_abi: ETH_ABI_SOURCE.to_string(),
+                unsupported: client.unsupported,
+            }
+        });
 
     let mut bytecode = Vec::new();
     compiled_module.serialize(&mut bytecode)?;
@@ -50,11 +151,75 @@ pub fn translate(bytecode: &str, abi: &str, config: Config) -> Result<Target, Er
         bytecode,
         interface,
         manifest,
+        source,
+        intrinsics,
+        client,
+        layout,
+        hir,
+        mir,
+    })
+}
+
+/// Builds the shared intrinsic library to be published at `address`.
+pub fn translate_intrinsic(address: AccountAddress) -> Result<Library, Error> {
+    let module = library(address)?;
+    let mut bytecode = Vec::new();
+    module.serialize(&mut bytecode)?;
+
+    Ok(Library {
+        bytecode,
+        interface: intrinsic_interface(address)?,
     })
 }
 
+/// Estimates the gas of the entry functions of the translated module.
+/// If the module imports the intrinsics from the library at `intrinsic_address`,
+/// the library functions are analyzed as well.
+pub fn estimate_gas(
+    bytecode: &[u8],
+    intrinsic_address: Option<AccountAddress>,
+) -> Result<GasReport, Error> {
+    let module = CompiledModule::deserialize(bytecode)?;
+    let library = intrinsic_address.map(library).transpose()?;
+    Ok(estimate(&module, &library.iter().collect::<Vec<_>>()))
+}
+
+/// Disassembly of the translated module.
+pub fn disassemble(bytecode: &[u8]) -> Result<String, Error> {
+    disassemble_module(&CompiledModule::deserialize(bytecode)?)
+}
+
+pub struct Library {
+    pub bytecode: Vec<u8>,
+    pub interface: String,
+}
+
 pub struct Target {
     pub bytecode: Vec<u8>,
     pub interface: String,
     pub manifest: String,
+    /// Move source of the translated module. Not set if the module can't be written as source.
+    pub source: Option<String>,
+    /// Sources of the intrinsic modules used by `source` as (module name, source) pairs.
+    pub intrinsics: Vec<(String, String)>,
+    /// Typed Move client of the module. Only generated for the eth-ABI interface.
+    pub client: Option<Client>,
+    /// Typed state of the module. The mappings are dropped from the storage layout if their
+    /// entries can't be matched.
+    pub layout: Layout,
+    /// Printed HIR of the functions. Only set with `Config::print_stages`.
+    pub hir: Option<String>,
+    /// Printed MIR of the constructor and the functions. Only set with `Config::print_stages`.
+    pub mir: Option<String>,
+}
+
+/// Move package with typed wrappers over the eth-ABI interface of the module.
+pub struct Client {
+    pub manifest: String,
+    /// Source of the `<name>_client` module.
+    pub source: String,
+    /// Source of the `eth_abi` helpers module.
+    pub eth_abi: String,
+    /// Functions and events of the ABI without wrappers, with the reasons.
+    pub unsupported: Vec<String>,
 }
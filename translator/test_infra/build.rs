@@ -1,10 +1,17 @@
+use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
 
 use anyhow::{ensure, Result};
+use bindings::rust_bindings;
+use eth::compile::build_sol;
+use eth::Flags;
 
 const TEST_HELPER_MOVE: &str = "./resources/mv";
 
+/// Contracts whose Rust bindings are compiled into the tests, `$OUT_DIR/<contract>.rs`.
+const BINDINGS: [&str; 2] = ["./sol/client/bank.sol", "./sol/client/overloads.sol"];
+
 pub fn main() -> Result<()> {
     println!("cargo:rerun-if-changed=resources/mv/sources");
     println!("cargo:rerun-if-changed=resources/mv/Move.toml");
@@ -29,5 +36,17 @@ pub fn main() -> Result<()> {
 
     ensure!(file_path.exists(), "file not found helper.mv");
 
+    // generating the rust bindings
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR")?);
+    for path in BINDINGS {
+        println!("cargo:rerun-if-changed={path}");
+        let evm = build_sol(path)?;
+        let bindings = rust_bindings(evm.name(), evm.contract().abi(), Flags::default())?;
+        fs::write(
+            out_dir.join(evm.name()).with_extension("rs"),
+            bindings.source,
+        )?;
+    }
+
     Ok(())
 }
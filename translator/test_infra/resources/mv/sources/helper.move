@@ -2,6 +2,8 @@ module self::helper {
     use aptos_framework::aptos_coin::AptosCoin;
     use aptos_framework::coin;
     use aptos_framework::block;
+    use std::string::{Self, String};
+    use std::vector;
 
     #[test_only]
     use aptos_framework::aptos_account::{create_account};
@@ -42,4 +44,15 @@ module self::helper {
     entry fun balance(account: address): u64 {
         coin::balance<AptosCoin>(account)
     }
-}
\ No newline at end of file
+
+    entry fun hello(_: signer, name: String): String {
+        let hello = string::utf8(b"hello, ");
+        string::append(&mut hello, name);
+        hello
+    }
+
+    entry fun reverse(_: signer, vals: vector<vector<u64>>): vector<vector<u64>> {
+        vector::reverse(&mut vals);
+        vals
+    }
+}
@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: Apache-2.0
+
+pragma solidity ^0.8.0;
+
+contract Bank {
+    event Deposit(address indexed owner, uint256 amount, string memo);
+
+    mapping(address => uint256) balances;
+
+    function deposit(uint256 amount, string memory memo) public returns (uint256) {
+        balances[msg.sender] += amount;
+        emit Deposit(msg.sender, amount, memo);
+        return balances[msg.sender];
+    }
+
+    function balanceOf(address account) public view returns (uint256, bool) {
+        return (balances[account], balances[account] != 0);
+    }
+}
@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: Apache-2.0
+
+pragma solidity ^0.8.0;
+
+contract Overloads {
+    event Moved(uint256 amount);
+    event Moved(address to, uint256 amount);
+
+    function get() public pure returns (uint256) {
+        return 1;
+    }
+
+    function get(uint256 val) public pure returns (uint256) {
+        return val + 1;
+    }
+
+    function neg(int256 val) public pure returns (int256) {
+        return -val;
+    }
+}
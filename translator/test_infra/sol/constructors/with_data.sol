@@ -5,6 +5,16 @@ pragma solidity ^0.8.0;
 contract with_data {
     uint256 val;
 
+    // # scenario init_val
+    // # > constructor(1000, true)
+    // # > get_val()
+    // # > storage[0] -> 1000
+
+    // # scenario default_val
+    // # > constructor(1000, false)
+    // # > get_val()
+    // # > storage[0] -> 42
+
     constructor(uint init_val, bool cnd) {
         if (cnd) {
             val = init_val;
@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: Apache-2.0
+
+pragma solidity ^0.8.0;
+
+interface ICounter {
+    function get() external view returns (uint256);
+}
+
+abstract contract Named {
+    function id() public pure virtual returns (uint256);
+}
+
+contract counter is ICounter, Named {
+    uint256 value;
+
+    function inc() public returns (uint256) {
+        value += 1;
+        return value;
+    }
+
+    function get() public view override returns (uint256) {
+        return value;
+    }
+
+    function id() public pure override returns (uint256) {
+        return 1;
+    }
+}
+
+contract adder {
+    function add(uint256 a, uint256 b) public pure returns (uint256) {
+        return a + b;
+    }
+}
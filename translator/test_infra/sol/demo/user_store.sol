@@ -12,6 +12,17 @@ contract UserStore {
     uint256 seq;
     mapping(address => User) user_store;
 
+    // # scenario users
+    // # > constructor(0x42, admin)
+    // # > is_admin()
+    // # > get_name()
+    // # > @0x12 create_user(bob)
+    // # > @0x12 get_name()
+    // # > @0x12 is_admin()
+    // # > @0x13 create_user(alice)
+    // # > @0x13 get_id()
+    // # > storage[0] -> 2
+
     constructor(address admin_address, string memory name) {
         user_store[admin_address] = User(seq, name, true);
     }
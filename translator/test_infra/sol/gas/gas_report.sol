@@ -0,0 +1,24 @@
+// SPDX-License-Identifier: Apache-2.0
+
+pragma solidity ^0.8.0;
+
+contract gas_report {
+    uint256 total;
+
+    function add(uint256 a, uint256 b) public pure returns (uint256) {
+        return a + b;
+    }
+
+    function sum(uint256 n) public pure returns (uint256) {
+        uint256 s = 0;
+        for (uint256 i = 0; i < n; i++) {
+            s += i;
+        }
+        return s;
+    }
+
+    function store(uint256 val) public returns (uint256) {
+        total = val;
+        return total;
+    }
+}
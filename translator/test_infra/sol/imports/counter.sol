@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: Apache-2.0
+
+pragma solidity ^0.8.0;
+
+import "./i_counter.sol";
+import "@math/l_math.sol";
+
+contract counter is ICounter {
+    uint256 value;
+
+    // # inc(2) 2
+    function inc(uint256 step) public override returns (uint256) {
+        value = Math.add(value, step);
+        return value;
+    }
+
+    function get() public view override returns (uint256) {
+        return value;
+    }
+}
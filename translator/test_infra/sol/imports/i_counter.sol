@@ -0,0 +1,9 @@
+// SPDX-License-Identifier: Apache-2.0
+
+pragma solidity ^0.8.0;
+
+interface ICounter {
+    function inc(uint256 step) external returns (uint256);
+
+    function get() external view returns (uint256);
+}
@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: Apache-2.0
+
+pragma solidity ^0.8.0;
+
+library Calc {
+    function double(uint256 a) external pure returns (uint256) {
+        return a * 2;
+    }
+}
+
+contract link_calc {
+    function twice(uint256 a) public pure returns (uint256) {
+        return Calc.double(a);
+    }
+
+    function plus(uint256 a, uint256 b) public pure returns (uint256) {
+        return a + b;
+    }
+}
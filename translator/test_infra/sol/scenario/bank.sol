@@ -0,0 +1,45 @@
+// SPDX-License-Identifier: Apache-2.0
+
+pragma solidity ^0.8.0;
+
+contract Bank {
+    mapping(address => uint256) balances;
+    uint256 total;
+
+    event Deposit(address indexed owner, uint256 amount);
+
+    // # scenario deposit
+    // # > constructor(100)
+    // # > @0x12 deposit(5) -> event Deposit(0x12, 5)
+    // # > @0x12 balance()
+    // # > @0x13 balance()
+    // # > @0x13 withdraw(1) -> revert("insufficient balance")
+    // # > @0x12 withdraw(2)
+    // # > @0x12 withdraw(4) -> revert -> abort(255)
+    // # > storage[1] -> 103
+    // # > share(3)
+    // # > share(0) -> revert(0x12)
+    constructor(uint256 reserve) {
+        total = reserve;
+    }
+
+    function deposit(uint256 amount) public {
+        balances[msg.sender] += amount;
+        total += amount;
+        emit Deposit(msg.sender, amount);
+    }
+
+    function withdraw(uint256 amount) public {
+        require(balances[msg.sender] >= amount, "insufficient balance");
+        balances[msg.sender] -= amount;
+        total -= amount;
+    }
+
+    function balance() public view returns (uint256) {
+        return balances[msg.sender];
+    }
+
+    function share(uint256 parts) public view returns (uint256) {
+        return total / parts;
+    }
+}
@@ -0,0 +1,12 @@
+// SPDX-License-Identifier: Apache-2.0
+
+pragma solidity ^0.8.0;
+
+contract accumulator {
+    uint256 total;
+
+    function add(uint256 val) public returns (uint256) {
+        total += val;
+        return total;
+    }
+}
@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: Apache-2.0
+
+pragma solidity ^0.8.0;
+
+contract native_mappings {
+    mapping(address => uint256) balances;
+    mapping(address => mapping(uint256 => uint256)) allowances;
+    uint256 total;
+
+    constructor() {
+        balances[msg.sender] = 100;
+        allowances[msg.sender][1] = 10;
+        total = 100;
+    }
+
+    function deposit(uint256 amount) public returns (uint256) {
+        balances[msg.sender] += amount;
+        total += amount;
+        return balances[msg.sender];
+    }
+
+    function balanceOf(address account) public view returns (uint256) {
+        return balances[account];
+    }
+
+    function approve(uint256 id, uint256 amount) public returns (uint256) {
+        allowances[msg.sender][id] += amount;
+        return allowances[msg.sender][id];
+    }
+
+    function allowance(address owner, uint256 id) public view returns (uint256) {
+        return allowances[owner][id];
+    }
+
+    function sumAllowances(address owner, uint256 count) public view returns (uint256) {
+        uint256 sum = 0;
+        for (uint256 i = 0; i < count; i++) {
+            sum += allowances[owner][i];
+        }
+        return sum;
+    }
+
+    function rawBalance(address owner, bool bonus) public view returns (uint256 val) {
+        assembly {
+            mstore(0, owner)
+            mstore(32, balances.slot)
+            let slot := keccak256(0, 64)
+            if bonus {
+                val := 1
+            }
+            val := add(val, sload(slot))
+        }
+    }
+}
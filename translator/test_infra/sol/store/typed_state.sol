@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: Apache-2.0
+
+pragma solidity ^0.8.0;
+
+contract typed_state {
+    uint256 total;
+    mapping(address => uint256) balances;
+    mapping(uint256 => uint256) squares;
+
+    constructor() {
+        total = 100;
+    }
+
+    function deposit(uint256 amount) public returns (uint256) {
+        balances[msg.sender] += amount;
+        total += amount;
+        return balances[msg.sender];
+    }
+
+    function balanceOf(address account) public view returns (uint256) {
+        return balances[account];
+    }
+
+    function getTotal() public view returns (uint256) {
+        return total;
+    }
+
+    function square(uint256 val) public returns (uint256) {
+        squares[val] = val * val;
+        return squares[val];
+    }
+
+    function getSquare(uint256 val) public view returns (uint256) {
+        return squares[val];
+    }
+}
@@ -0,0 +1,25 @@
+// SPDX-License-Identifier: Apache-2.0
+
+pragma solidity ^0.8.0;
+
+contract typed_state_raw {
+    uint256 total;
+    mapping(address => uint256) balances;
+
+    constructor() {
+        balances[msg.sender] = 100;
+        total = 100;
+    }
+
+    function deposit(uint256 amount) public returns (uint256) {
+        balances[msg.sender] += amount;
+        total += amount;
+        return balances[msg.sender];
+    }
+
+    function rawLoad(uint256 slot) public view returns (uint256 val) {
+        assembly {
+            val := sload(slot)
+        }
+    }
+}
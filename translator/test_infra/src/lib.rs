@@ -8,6 +8,7 @@ use log::{Metadata, Record};
 
 pub mod color;
 pub mod env;
+pub mod revm;
 
 pub static CUST_LOGGER: CustLogger = CustLogger;
 
@@ -0,0 +1,205 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::rc::Rc;
+
+use anyhow::{bail, Error, Result};
+use evm::backend::{Apply, Log, MemoryAccount, MemoryBackend, MemoryVicinity};
+use evm::executor::stack::{MemoryStackState, StackExecutor, StackSubstateMetadata};
+use evm::{Config, Context, ExitReason, Runtime};
+use primitive_types::{H160, H256, U256};
+
+use eth::compile::EvmPack;
+
+const ADDRESS: &str = "0000000000000000000000000000000000000042";
+
+fn memory_vicinity() -> Result<MemoryVicinity> {
+    Ok(MemoryVicinity {
+        block_base_fee_per_gas: U256::max_value(),
+        gas_price: U256::from(100),
+        origin: H160::random(),
+        chain_id: U256::from(1u8),
+        block_hashes: vec![
+            "00000000000000001ebf88508a03865c71d452e25f4d51194196a1d22b6653dc".parse()?,
+            "00000000000000010ff5414c5cfbe9eae982e8cef7eb2399a39118e1206c8247".parse()?,
+        ],
+        block_number: U256::from(3),
+        block_coinbase: H160::zero(),
+        block_timestamp: U256::from(10_000_123),
+        block_difficulty: U256::zero(),
+        block_gas_limit: U256::from(10_000_000),
+    })
+}
+
+fn context() -> Result<Context> {
+    Ok(Context {
+        // Execution address.
+        address: ADDRESS.parse()?,
+        // The calling EVM.
+        caller: ADDRESS.parse()?,
+        // The apparent value of the EVM. call value, if non-zero, must have payable
+        apparent_value: U256::from(0u8),
+    })
+}
+
+/// The call was reverted with the data.
+#[derive(Debug)]
+pub struct Revert(pub Vec<u8>);
+
+impl Display for Revert {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "ExitReason::Revert 0x")?;
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl std::error::Error for Revert {}
+
+/// EVM harness which runs the original contract.
+/// The storage of the contract is kept between the calls.
+pub struct REvm {
+    code: Rc<Vec<u8>>,
+    config: Config,
+    vicinity: MemoryVicinity,
+    ctx: Context,
+    storage: BTreeMap<H256, H256>,
+    /// Logs of the last call.
+    logs: Vec<Log>,
+}
+
+impl REvm {
+    fn new() -> Result<Self> {
+        Ok(REvm {
+            code: Rc::new(Vec::new()),
+            config: Config::london(),
+            vicinity: memory_vicinity()?,
+            ctx: context()?,
+            storage: BTreeMap::new(),
+            logs: Vec::new(),
+        })
+    }
+
+    /// Runs the contract constructor and keeps the deployed code.
+    /// Returns the gas used by the constructor.
+    pub fn construct(&mut self, code: Vec<u8>) -> Result<u64> {
+        let (res, gas) = self.execute(code)?;
+        self.code = Rc::new(res);
+        Ok(gas)
+    }
+
+    pub fn set_code(&mut self, code: Vec<u8>) -> &mut Self {
+        self.code = Rc::new(code);
+        self
+    }
+
+    /// Sender of the following calls.
+    pub fn set_caller(&mut self, caller: H160) -> &mut Self {
+        self.ctx.caller = caller;
+        self
+    }
+
+    pub fn run_tx(&mut self, call: Vec<u8>) -> Result<Vec<u8>> {
+        self.execute(call).map(|(res, _)| res)
+    }
+
+    /// Storage of the contract. The zero slots are omitted.
+    pub fn storage(&self) -> BTreeMap<U256, U256> {
+        self.storage
+            .iter()
+            .map(|(key, val)| (U256::from(key.as_bytes()), U256::from(val.as_bytes())))
+            .filter(|(_, val)| !val.is_zero())
+            .collect()
+    }
+
+    /// Logs emitted by the last call.
+    pub fn logs(&self) -> &[Log] {
+        &self.logs
+    }
+
+    /// Runs the call and returns its output and the gas used by the execution.
+    /// The intrinsic gas of the transaction is not included.
+    /// The storage changes are kept only if the call succeeds.
+    pub fn execute(&mut self, call: Vec<u8>) -> Result<(Vec<u8>, u64)> {
+        self.logs.clear();
+        let address: H160 = ADDRESS.parse()?;
+        let account = MemoryAccount {
+            nonce: U256::zero(),
+            balance: U256::zero(),
+            storage: self.storage.clone(),
+            code: Vec::new(),
+        };
+        let backend = MemoryBackend::new(&self.vicinity, BTreeMap::from([(address, account)]));
+        let metadata = StackSubstateMetadata::new(u64::MAX, &self.config);
+
+        let precompiles = BTreeMap::new();
+
+        let mut memo = MemoryStackState::new(metadata, &backend);
+        memo.deposit(address, U256::from(1_000_000));
+
+        let mut executor: StackExecutor<MemoryStackState<MemoryBackend>, BTreeMap<_, _>> =
+            StackExecutor::new_with_precompiles(memo, &self.config, &precompiles);
+
+        let mut rt = Runtime::new(
+            self.code.clone(),
+            Rc::new(call),
+            self.ctx.clone(),
+            &self.config,
+        );
+        let exit_reason = executor.execute(&mut rt);
+
+        match exit_reason {
+            ExitReason::Fatal(status) => {
+                bail!("{status:?}")
+            }
+            ExitReason::Error(status) => {
+                bail!("{status:?}")
+            }
+            ExitReason::Revert(status) => {
+                log::trace!("ExitReason::Revert {status:?}");
+                Err(Revert(rt.machine().return_value()).into())
+            }
+            ExitReason::Succeed(status) => {
+                log::trace!("ExitReason::Succeed {status:?}");
+                let gas = executor.used_gas();
+                let (applies, logs) = executor.into_state().deconstruct();
+                for apply in applies {
+                    if let Apply::Modify {
+                        address: modified,
+                        storage,
+                        reset_storage,
+                        ..
+                    } = apply
+                    {
+                        if modified != address {
+                            continue;
+                        }
+                        if reset_storage {
+                            self.storage.clear();
+                        }
+                        self.storage.extend(storage);
+                    }
+                }
+                self.logs = logs.into_iter().collect();
+                Ok((rt.machine().return_value(), gas))
+            }
+        }
+    }
+}
+
+impl TryFrom<Vec<u8>> for REvm {
+    type Error = Error;
+
+    fn try_from(code: Vec<u8>) -> std::result::Result<Self, Self::Error> {
+        let mut evm = REvm::new()?;
+        evm.set_code(code);
+        Ok(evm)
+    }
+}
+
+impl TryFrom<&EvmPack> for REvm {
+    type Error = Error;
+
+    fn try_from(pack: &EvmPack) -> std::result::Result<Self, Self::Error> {
+        let code = pack.code_evm()?;
+        REvm::try_from(code)
+    }
+}
@@ -0,0 +1,66 @@
+use std::sync::{Arc, Mutex};
+
+use libtest_mimic::{Arguments, Outcome, Test};
+
+use test_infra::{init_log, init_log_with_buff_and_name, CustLogger};
+
+mod ethtests;
+use crate::ethtests::{EthTest, Matrix, Verdict};
+
+fn run_test(name: &str, test: &EthTest, matrix: &Mutex<Matrix>) -> Outcome {
+    init_log_with_buff_and_name(name);
+
+    let verdict = test.run();
+    matrix
+        .lock()
+        .unwrap()
+        .0
+        .entry(test.family.clone())
+        .or_default()
+        .add(&verdict);
+    match verdict {
+        Verdict::Pass => Outcome::Passed,
+        Verdict::Fail(err) => {
+            log::error!("{err}");
+            Outcome::Failed {
+                msg: Some(CustLogger::flush_and_get()),
+            }
+        }
+        Verdict::Unsupported(reason) => {
+            log::info!("unsupported: {reason}");
+            Outcome::Ignored
+        }
+    }
+}
+
+fn main() {
+    init_log();
+
+    let matrix = Arc::new(Mutex::new(Matrix::default()));
+    let mut tests = EthTest::from_dir()
+        .unwrap()
+        .into_iter()
+        .map(|test| {
+            let name = test.test_name();
+            let run_name = name.clone();
+            let matrix = matrix.clone();
+
+            let testfn: Box<dyn Fn() -> Outcome + Send + Sync> =
+                Box::new(move || run_test(&run_name, &test, &matrix));
+            Test {
+                name,
+                kind: String::new(),
+                is_ignored: false,
+                is_bench: false,
+                data: testfn,
+            }
+        })
+        .collect::<Vec<_>>();
+    tests.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+    let configs = Arguments::from_args();
+
+    let conclusion = libtest_mimic::run_tests(&configs, tests, |test| (test.data)());
+    println!("\n{}", matrix.lock().unwrap());
+    conclusion.exit()
+}
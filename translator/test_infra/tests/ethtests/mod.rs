@@ -0,0 +1,410 @@
+//! Runner of the [ethereum/tests](https://github.com/ethereum/tests) fixtures.
+//!
+//! The code of a VMTests fixture is translated into a Move module with a synthetic ABI: one
+//! function `run()` whose outputs are the words of the expected return data. The pre-state
+//! storage is written by the constructor. After the call the return data and the storage of
+//! the module are checked against the expected post-state.
+//!
+//! The fixtures are vendored into `ethtests/VMTests` of the crate, in the layout of
+//! `LegacyTests/Constantinople/VMTests` of ethereum/tests. `make ethtests` replaces them with
+//! the VMTests at the pinned revision. `E2M_ETH_TESTS` points to another VMTests directory.
+//! The family of a test is the directory of its fixture.
+//! Only the VMTests format is read: the other fixtures, e.g. GeneralStateTests, fail to load.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, ensure, Result};
+use ethabi::ethereum_types::U256;
+use ethabi::Token;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::ModuleId;
+use serde_json::Value;
+
+use eth::bytecode::ops::InstructionIter;
+use eth::{Flags, OpCode};
+use move_executor::{MoveExecutor, MoveExecutorInstance};
+use translator::{translate, Config};
+
+const ADDRESS: &str = "0x42";
+const MODULE: &str = "VMTest";
+const FUNCTION: &str = "run";
+
+/// Families of VMTests.
+pub const FAMILIES: [&str; 12] = [
+    "vmArithmeticTest",
+    "vmBitwiseLogicOperation",
+    "vmBlockInfoTest",
+    "vmEnvironmentalInfo",
+    "vmIOandFlowOperations",
+    "vmLogTest",
+    "vmPerformance",
+    "vmPushDupSwapTest",
+    "vmRandomTest",
+    "vmSha3Test",
+    "vmSystemOperations",
+    "vmTests",
+];
+
+/// Verdict of a fixture.
+#[derive(Debug, Clone)]
+pub enum Verdict {
+    Pass,
+    Fail(String),
+    /// The fixture depends on what the translated module can't reproduce.
+    Unsupported(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct EthTest {
+    pub family: String,
+    pub name: String,
+    fixture: VmFixture,
+}
+
+#[derive(Debug, Clone)]
+struct VmFixture {
+    code: Vec<u8>,
+    pre_storage: BTreeMap<U256, U256>,
+    /// Return data and storage after the call. `None` if the call fails.
+    post: Option<(Vec<u8>, BTreeMap<U256, U256>)>,
+}
+
+impl EthTest {
+    pub fn from_dir() -> Result<Vec<EthTest>> {
+        let dir = match std::env::var("E2M_ETH_TESTS") {
+            Ok(dir) => PathBuf::from(dir),
+            Err(_) => PathBuf::from(std::env::var("CARGO_MANIFEST_DIR")?).join("ethtests/VMTests"),
+        };
+        ensure!(
+            dir.is_dir(),
+            "The VMTests fixtures were not found in {dir:?}"
+        );
+        let mut tests = Vec::new();
+        collect(&dir, &mut tests)?;
+        Ok(tests)
+    }
+
+    pub fn test_name(&self) -> String {
+        format!("eth::{}::{}", self.family, self.name)
+    }
+
+    pub fn run(&self) -> Verdict {
+        let fixture = &self.fixture;
+        if let Some(reason) = fixture.unsupported() {
+            return Verdict::Unsupported(reason);
+        }
+        match fixture.run() {
+            Ok(()) => Verdict::Pass,
+            Err(err) => Verdict::Fail(format!("{err:?}")),
+        }
+    }
+}
+
+impl VmFixture {
+    fn parse(test: &Value) -> Result<VmFixture> {
+        let exec = &test["exec"];
+        let address = str_field(exec, "address")?.to_lowercase();
+        let account = |state: &str| {
+            test[state]
+                .as_object()
+                .and_then(|accounts| {
+                    accounts
+                        .iter()
+                        .find(|(key, _)| key.to_lowercase() == address)
+                        .map(|(_, account)| account)
+                })
+                .map(|account| parse_storage(&account["storage"]))
+                .transpose()
+        };
+        let post = match test.get("post") {
+            Some(_) => Some((
+                parse_bytes(str_field(test, "out")?)?,
+                account("post")?.unwrap_or_default(),
+            )),
+            None => None,
+        };
+        Ok(VmFixture {
+            code: parse_bytes(str_field(exec, "code")?)?,
+            pre_storage: account("pre")?.unwrap_or_default(),
+            post,
+        })
+    }
+
+    /// The reason why the fixture can't be checked on the translated module.
+    /// The call data and the value of the fixture are seen only by the opcodes of the environment.
+    fn unsupported(&self) -> Option<String> {
+        if let Some((out, _)) = &self.post {
+            if out.len() % 32 != 0 {
+                return Some(format!("return data of {} bytes", out.len()));
+            }
+        }
+        InstructionIter::new(self.code.clone())
+            .find(|inst| depends_on_environment(inst))
+            .map(|inst| format!("{:?}", *inst))
+    }
+
+    fn run(&self) -> Result<()> {
+        let outputs = self
+            .post
+            .as_ref()
+            .map(|(out, _)| out.len() / 32)
+            .unwrap_or_default();
+        let abi = synthetic_abi(outputs);
+        let module = translate(
+            &hex::encode(self.init_code()),
+            &abi,
+            Config::new(AccountAddress::from_hex_literal(ADDRESS)?, MODULE),
+        )?;
+
+        let mut vm = MoveExecutor::new(
+            serde_json::from_str(&abi)?,
+            Flags::default(),
+            MoveExecutorInstance::Aptos,
+        );
+        vm.deploy(ADDRESS, module.bytecode)?;
+        vm.run(&format!("{ADDRESS}::{MODULE}::constructor"), ADDRESS, None)?;
+        let result = vm.run(
+            &format!("{ADDRESS}::{MODULE}::{FUNCTION}"),
+            ADDRESS,
+            Some(""),
+        );
+
+        match &self.post {
+            Some((out, storage)) => {
+                let result = result?;
+                let expected = out
+                    .chunks(32)
+                    .map(|word| Token::Uint(U256::from_big_endian(word)))
+                    .collect::<Vec<_>>();
+                ensure!(
+                    result.returns == expected,
+                    "returned {:?}, expected 0x{}",
+                    result.returns,
+                    hex::encode(out)
+                );
+
+                let module_id = ModuleId::new(
+                    AccountAddress::from_hex_literal(ADDRESS)?,
+                    Identifier::new(MODULE)?,
+                );
+                let actual = vm.storage(&module_id)?;
+                let expected = storage
+                    .iter()
+                    .filter(|(_, val)| !val.is_zero())
+                    .map(|(slot, val)| (*slot, *val))
+                    .collect::<BTreeMap<_, _>>();
+                ensure!(
+                    actual == expected,
+                    "storage {actual:?}, expected {expected:?}"
+                );
+            }
+            None => ensure!(result.is_err(), "expected an exception"),
+        }
+        Ok(())
+    }
+
+    /// Writes the pre-state storage and returns the fixture code.
+    fn init_code(&self) -> Vec<u8> {
+        let mut init = Vec::new();
+        for (slot, val) in &self.pre_storage {
+            init.push(0x7f); // PUSH32
+            init.extend(word(val));
+            init.push(0x7f); // PUSH32
+            init.extend(word(slot));
+            init.push(0x55); // SSTORE
+        }
+        let len = (self.code.len() as u16).to_be_bytes();
+        // PUSH2 len, DUP1, PUSH2 offset, PUSH1 0, CODECOPY, PUSH1 0, RETURN
+        let offset = ((init.len() + 13) as u16).to_be_bytes();
+        init.extend([0x61, len[0], len[1], 0x80, 0x61, offset[0], offset[1]]);
+        init.extend([0x60, 0x00, 0x39, 0x60, 0x00, 0xf3]);
+        init.extend(&self.code);
+        init
+    }
+}
+
+/// Opcodes whose result depends on the transaction, the block or the other accounts.
+fn depends_on_environment(code: &OpCode) -> bool {
+    matches!(
+        code,
+        OpCode::Addr
+            | OpCode::Balance
+            | OpCode::Origin
+            | OpCode::Caller
+            | OpCode::CallValue
+            | OpCode::CallDataLoad
+            | OpCode::CallDataSize
+            | OpCode::CallDataCopy
+            | OpCode::GasPrice
+            | OpCode::ExtCodeSize
+            | OpCode::ExtCodeCopy
+            | OpCode::ExtCodeHash
+            | OpCode::ReturnDataSize
+            | OpCode::ReturnDataCopy
+            | OpCode::Blockhash
+            | OpCode::Coinbase
+            | OpCode::Timestamp
+            | OpCode::Number
+            | OpCode::Difficulty
+            | OpCode::GasLimit
+            | OpCode::Gas
+            | OpCode::Create
+            | OpCode::Create2
+            | OpCode::Call
+            | OpCode::CallCode
+            | OpCode::DelegateCall
+            | OpCode::StaticCall
+            | OpCode::SelfDestruct
+    )
+}
+
+fn synthetic_abi(outputs: usize) -> String {
+    let outputs = (0..outputs)
+        .map(|index| format!(r#"{{"name":"out{index}","type":"uint256"}}"#))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!(
+        r#"[{{"type":"function","name":"{FUNCTION}","inputs":[],"outputs":[{outputs}],"stateMutability":"nonpayable"}}]"#
+    )
+}
+
+/// Reads the fixtures of the directory and its subdirectories. The fillers are skipped.
+fn collect(dir: &Path, tests: &mut Vec<EthTest>) -> Result<()> {
+    for entry in dir.read_dir()? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if path.file_name().and_then(|name| name.to_str()) != Some("src") {
+                collect(&path, tests)?;
+            }
+        } else if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+            let family = dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .filter(|family| FAMILIES.contains(family))
+                .ok_or_else(|| anyhow!("{path:?} is not in a directory of a VMTests family"))?
+                .to_string();
+            let content: Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+            let fixtures = content
+                .as_object()
+                .ok_or_else(|| anyhow!("Invalid fixture file {path:?}"))?;
+            for (name, test) in fixtures {
+                ensure!(
+                    test.get("exec").is_some(),
+                    "{path:?} {name}: not a VMTests fixture"
+                );
+                let fixture =
+                    VmFixture::parse(test).map_err(|err| anyhow!("{path:?} {name}: {err}"))?;
+                tests.push(EthTest {
+                    family: family.clone(),
+                    name: name.clone(),
+                    fixture,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn str_field<'a>(value: &'a Value, name: &str) -> Result<&'a str> {
+    value[name]
+        .as_str()
+        .ok_or_else(|| anyhow!("The field {name} was not found"))
+}
+
+fn parse_storage(storage: &Value) -> Result<BTreeMap<U256, U256>> {
+    storage
+        .as_object()
+        .map(|storage| {
+            storage
+                .iter()
+                .map(|(slot, val)| {
+                    let val = val
+                        .as_str()
+                        .ok_or_else(|| anyhow!("Invalid value of the slot {slot}"))?;
+                    Ok((parse_u256(slot)?, parse_u256(val)?))
+                })
+                .collect()
+        })
+        .unwrap_or_else(|| Ok(BTreeMap::new()))
+}
+
+fn parse_u256(val: &str) -> Result<U256> {
+    match val.strip_prefix("0x") {
+        Some("") => Ok(U256::zero()),
+        Some(hex) => Ok(U256::from_str_radix(hex, 16)?),
+        None => Ok(U256::from_dec_str(val)?),
+    }
+}
+
+fn parse_bytes(val: &str) -> Result<Vec<u8>> {
+    Ok(hex::decode(val.trim_start_matches("0x"))?)
+}
+
+fn word(val: &U256) -> [u8; 32] {
+    let mut word = [0; 32];
+    val.to_big_endian(&mut word);
+    word
+}
+
+/// Number of the verdicts of a family.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Tally {
+    pub pass: usize,
+    pub fail: usize,
+    pub unsupported: usize,
+}
+
+impl Tally {
+    pub fn add(&mut self, verdict: &Verdict) {
+        match verdict {
+            Verdict::Pass => self.pass += 1,
+            Verdict::Fail(_) => self.fail += 1,
+            Verdict::Unsupported(_) => self.unsupported += 1,
+        }
+    }
+}
+
+/// Pass/fail/unsupported matrix by the test families.
+#[derive(Debug)]
+pub struct Matrix(pub BTreeMap<String, Tally>);
+
+impl Default for Matrix {
+    /// Every family is listed, even without the fixtures.
+    fn default() -> Matrix {
+        Matrix(
+            FAMILIES
+                .iter()
+                .map(|family| (family.to_string(), Tally::default()))
+                .collect(),
+        )
+    }
+}
+
+impl Display for Matrix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let width = self
+            .0
+            .keys()
+            .map(String::len)
+            .chain([6])
+            .max()
+            .unwrap_or_default();
+        writeln!(
+            f,
+            "{:width$} {:>6} {:>6} {:>12}",
+            "family", "pass", "fail", "unsupported"
+        )?;
+        for (family, tally) in &self.0 {
+            writeln!(
+                f,
+                "{family:width$} {:>6} {:>6} {:>12}",
+                tally.pass, tally.fail, tally.unsupported
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use anyhow::{anyhow, Result};
+use libtest_mimic::{Arguments, Outcome, Test};
+
+use test_infra::{init_log, init_log_with_buff_and_name, CustLogger};
+
+mod testssol;
+use crate::testssol::fuzz::{FuzzConfig, Fuzzer};
+use crate::testssol::parse::SolFile;
+
+fn run_test(name: &str, fuzzer: &Fuzzer, config: FuzzConfig) -> Result<()> {
+    init_log_with_buff_and_name(name);
+
+    fuzzer.run(config).map_err(|err| {
+        log::error!("{err:?}");
+        anyhow!("{err}\n{}", CustLogger::flush_and_get())
+    })
+}
+
+fn main() {
+    init_log();
+
+    let config = FuzzConfig::from_env().unwrap();
+    let mut tests = SolFile::from_sol_dir()
+        .unwrap()
+        .into_iter()
+        .chain(SolFile::from_vy_dir().unwrap())
+        .filter(|file| !file.tests.is_empty())
+        .filter_map(|file| {
+            Fuzzer::new(&file)
+                .map_err(|err| log::error!("{err:?}"))
+                .ok()
+                .flatten()
+        })
+        .map(|fuzzer| {
+            let name = fuzzer.test_name();
+            let run_name = name.clone();
+
+            let testfn: Box<dyn Fn() -> Outcome + Send + Sync> =
+                Box::new(move || match run_test(&run_name, &fuzzer, config) {
+                    Ok(()) => Outcome::Passed,
+                    Err(err) => Outcome::Failed {
+                        msg: Some(format!("{}", err)),
+                    },
+                });
+            Test {
+                name,
+                kind: String::new(),
+                // Fuzzing takes a while, run with `--ignored`.
+                is_ignored: true,
+                is_bench: false,
+                data: testfn,
+            }
+        })
+        .collect::<Vec<_>>();
+    tests.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+    let configs = Arguments::from_args();
+
+    libtest_mimic::run_tests(&configs, tests, |test| (test.data)()).exit()
+}
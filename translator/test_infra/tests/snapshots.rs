@@ -0,0 +1,52 @@
+use anyhow::{anyhow, Result};
+use libtest_mimic::{Arguments, Outcome, Test};
+
+use test_infra::{init_log, init_log_with_buff_and_name, CustLogger};
+
+mod testssol;
+use crate::testssol::parse::SolFile;
+use crate::testssol::snapshot::Snapshot;
+
+fn run_test(name: &str, snapshot: &Snapshot, update: bool) -> Result<()> {
+    init_log_with_buff_and_name(name);
+
+    snapshot.run(update).map_err(|err| {
+        log::error!("{err:?}");
+        anyhow!("{err}\n{}", CustLogger::flush_and_get())
+    })
+}
+
+fn main() {
+    init_log();
+
+    let update = std::env::var("E2M_UPDATE_SNAPSHOTS").as_deref() == Ok("1");
+    let mut tests = SolFile::from_sol_dir()
+        .unwrap()
+        .iter()
+        .map(Snapshot::new)
+        .map(|snapshot| {
+            let name = snapshot.test_name();
+            let run_name = name.clone();
+
+            let testfn: Box<dyn Fn() -> Outcome + Send + Sync> =
+                Box::new(move || match run_test(&run_name, &snapshot, update) {
+                    Ok(()) => Outcome::Passed,
+                    Err(err) => Outcome::Failed {
+                        msg: Some(format!("{}", err)),
+                    },
+                });
+            Test {
+                name,
+                kind: String::new(),
+                is_ignored: false,
+                is_bench: false,
+                data: testfn,
+            }
+        })
+        .collect::<Vec<_>>();
+    tests.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+    let configs = Arguments::from_args();
+
+    libtest_mimic::run_tests(&configs, tests, |test| (test.data)()).exit()
+}
@@ -21,6 +21,7 @@ fn main() {
     let mut tests = STest::from_sol_dir()
         .unwrap()
         .into_iter()
+        .chain(STest::from_vy_dir().unwrap())
         .enumerate()
         .map(|(index, data)| {
             let name = data.test_name(index);
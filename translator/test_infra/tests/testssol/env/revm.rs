@@ -1,129 +1,4 @@
-use std::collections::BTreeMap;
-use std::rc::Rc;
-
-use anyhow::{bail, Error, Result};
-use evm::backend::{MemoryBackend, MemoryVicinity};
-use evm::executor::stack::{MemoryStackState, StackExecutor, StackSubstateMetadata};
-use evm::{Config, Context, ExitReason, Runtime};
-use primitive_types::{H160, U256};
-
-use eth::compile::EvmPack;
-
-const ADDRESS: &str = "0000000000000000000000000000000000000042";
-
-fn memory_vicinity() -> Result<MemoryVicinity> {
-    Ok(MemoryVicinity {
-        block_base_fee_per_gas: U256::max_value(),
-        gas_price: U256::from(100),
-        origin: H160::random(),
-        chain_id: U256::from(1u8),
-        block_hashes: vec![
-            "00000000000000001ebf88508a03865c71d452e25f4d51194196a1d22b6653dc".parse()?,
-            "00000000000000010ff5414c5cfbe9eae982e8cef7eb2399a39118e1206c8247".parse()?,
-        ],
-        block_number: U256::from(3),
-        block_coinbase: H160::zero(),
-        block_timestamp: U256::from(10_000_123),
-        block_difficulty: U256::zero(),
-        block_gas_limit: U256::from(10_000_000),
-    })
-}
-
-fn context() -> Result<Context> {
-    Ok(Context {
-        // Execution address.
-        address: ADDRESS.parse()?,
-        // The calling EVM.
-        caller: ADDRESS.parse()?,
-        // The apparent value of the EVM. call value, if non-zero, must have payable
-        apparent_value: U256::from(0u8),
-    })
-}
-
-pub struct REvm {
-    code: Rc<Vec<u8>>,
-    config: Config,
-    vicinity: MemoryVicinity,
-    ctx: Context,
-}
-
-impl REvm {
-    fn new() -> Result<Self> {
-        Ok(REvm {
-            code: Rc::new(Vec::new()),
-            config: Config::london(),
-            vicinity: memory_vicinity()?,
-            ctx: context()?,
-        })
-    }
-
-    pub fn construct(&mut self, code: Vec<u8>) -> Result<()> {
-        let res = self.run_tx(code)?;
-        self.code = Rc::new(res);
-        Ok(())
-    }
-
-    pub fn set_code(&mut self, code: Vec<u8>) -> &mut Self {
-        self.code = Rc::new(code);
-        self
-    }
-
-    pub fn run_tx(&self, call: Vec<u8>) -> Result<Vec<u8>> {
-        let backend = MemoryBackend::new(&self.vicinity, BTreeMap::new());
-        let metadata = StackSubstateMetadata::new(u64::MAX, &self.config);
-
-        let precompiles = BTreeMap::new();
-
-        let mut memo = MemoryStackState::new(metadata, &backend);
-        memo.deposit(ADDRESS.parse().unwrap(), U256::from(1_000_000));
-
-        let mut executor: StackExecutor<MemoryStackState<MemoryBackend>, BTreeMap<_, _>> =
-            StackExecutor::new_with_precompiles(memo, &self.config, &precompiles);
-
-        let mut rt = Runtime::new(
-            self.code.clone(),
-            Rc::new(call),
-            self.ctx.clone(),
-            &self.config,
-        );
-        let exit_reason = executor.execute(&mut rt);
-
-        match exit_reason {
-            ExitReason::Fatal(status) => {
-                bail!("{status:?}")
-            }
-            ExitReason::Error(status) => {
-                bail!("{status:?}")
-            }
-            ExitReason::Revert(status) => {
-                bail!("ExitReason::Revert  {status:?}")
-            }
-            ExitReason::Succeed(status) => {
-                log::trace!("ExitReason::Succeed {status:?}");
-                Ok(rt.machine().return_value())
-            }
-        }
-    }
-}
-
-impl TryFrom<Vec<u8>> for REvm {
-    type Error = Error;
-
-    fn try_from(code: Vec<u8>) -> std::result::Result<Self, Self::Error> {
-        let mut evm = REvm::new()?;
-        evm.set_code(code);
-        Ok(evm)
-    }
-}
-
-impl TryFrom<&EvmPack> for REvm {
-    type Error = Error;
-
-    fn try_from(pack: &EvmPack) -> std::result::Result<Self, Self::Error> {
-        let code = pack.code_evm()?;
-        REvm::try_from(code)
-    }
-}
+pub use test_infra::revm::REvm;
 
 #[allow(unused_imports)]
 #[cfg(test)]
@@ -0,0 +1,169 @@
+//! ABI-typed arguments of the fuzzed calls.
+
+use ethabi::ethereum_types::{H160, U256};
+use ethabi::{ParamType, Token};
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::Rng;
+
+/// Maximum length of the generated arrays.
+const MAX_ARRAY_LEN: usize = 4;
+/// Maximum length of the generated bytes and strings.
+const MAX_BYTES_LEN: usize = 64;
+const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Types which can be passed by the test directives. Tuples are not supported.
+pub fn is_supported(tp: &ParamType) -> bool {
+    match tp {
+        ParamType::Array(tp) | ParamType::FixedArray(tp, _) => is_supported(tp),
+        ParamType::Tuple(_) => false,
+        _ => true,
+    }
+}
+
+/// Random value of the type. The bounds of the numbers are preferred.
+pub fn rand_token(tp: &ParamType, rng: &mut StdRng) -> Token {
+    match tp {
+        ParamType::Bool => Token::Bool(rng.gen()),
+        ParamType::Uint(size) => Token::Uint(rand_uint(*size, rng)),
+        ParamType::Int(size) => Token::Int(rand_int(*size, rng)),
+        ParamType::Address => {
+            let address = match rng.gen_range(0..4) {
+                0 => H160::zero(),
+                1 => H160::from_low_u64_be(0x42),
+                _ => H160::from(rng.gen::<[u8; 20]>()),
+            };
+            Token::Address(address)
+        }
+        ParamType::FixedBytes(size) => Token::FixedBytes(rand_bytes(*size, rng)),
+        ParamType::Bytes => {
+            let len = rng.gen_range(0..=MAX_BYTES_LEN);
+            Token::Bytes(rand_bytes(len, rng))
+        }
+        ParamType::String => {
+            let len = rng.gen_range(1..=MAX_BYTES_LEN);
+            let val = (0..len)
+                .map(|_| CHARS[rng.gen_range(0..CHARS.len())] as char)
+                .collect();
+            Token::String(val)
+        }
+        ParamType::Array(tp) => {
+            let len = rng.gen_range(0..=MAX_ARRAY_LEN);
+            Token::Array((0..len).map(|_| rand_token(tp, rng)).collect())
+        }
+        ParamType::FixedArray(tp, len) => {
+            Token::FixedArray((0..*len).map(|_| rand_token(tp, rng)).collect())
+        }
+        ParamType::Tuple(_) => unreachable!("tuples are not fuzzed"),
+    }
+}
+
+fn rand_uint(size: usize, rng: &mut StdRng) -> U256 {
+    let max = max_uint(size);
+    match rng.gen_range(0..8) {
+        0 => U256::zero(),
+        1 => U256::one(),
+        2 => max,
+        3 => max - 1,
+        4 => U256::one() << (size - 1),
+        5 => U256::from(rng.gen_range(0..=u8::MAX)),
+        _ => U256::from_big_endian(&rng.gen::<[u8; 32]>()) & max,
+    }
+}
+
+/// Two's complement of the signed value.
+fn rand_int(size: usize, rng: &mut StdRng) -> U256 {
+    let max = max_uint(size - 1);
+    match rng.gen_range(0..8) {
+        0 => U256::zero(),
+        1 => U256::one(),
+        2 => U256::MAX,
+        3 => max,
+        4 => !max,
+        5 => U256::from(rng.gen_range(0..=u8::MAX)) & max,
+        _ => {
+            let val = U256::from_big_endian(&rng.gen::<[u8; 32]>()) & max_uint(size);
+            if val.bit(size - 1) {
+                val | !max_uint(size)
+            } else {
+                val
+            }
+        }
+    }
+}
+
+fn max_uint(size: usize) -> U256 {
+    if size >= 256 {
+        U256::MAX
+    } else {
+        (U256::one() << size) - 1
+    }
+}
+
+fn rand_bytes(len: usize, rng: &mut StdRng) -> Vec<u8> {
+    (0..len).map(|_| rng.gen()).collect()
+}
+
+/// Simpler values of the same type which the minimization tries instead of the token.
+pub fn simpler_tokens(token: &Token) -> Vec<Token> {
+    let candidates = match token {
+        Token::Bool(_) => vec![Token::Bool(false)],
+        Token::Uint(_) => vec![Token::Uint(U256::zero()), Token::Uint(U256::one())],
+        Token::Int(_) => vec![Token::Int(U256::zero()), Token::Int(U256::one())],
+        Token::Address(_) => vec![Token::Address(H160::zero())],
+        Token::FixedBytes(val) => vec![Token::FixedBytes(vec![0; val.len()])],
+        Token::Bytes(val) => vec![
+            Token::Bytes(vec![]),
+            Token::Bytes(val[..val.len() / 2].to_vec()),
+        ],
+        Token::String(_) => vec![Token::String("a".to_string())],
+        Token::Array(items) => vec![
+            Token::Array(vec![]),
+            Token::Array(items[..items.len() / 2].to_vec()),
+        ],
+        Token::FixedArray(items) => vec![Token::FixedArray(
+            items
+                .iter()
+                .map(|item| {
+                    simpler_tokens(item)
+                        .into_iter()
+                        .next()
+                        .unwrap_or_else(|| item.clone())
+                })
+                .collect(),
+        )],
+        Token::Tuple(_) => vec![],
+    };
+    let mut simpler: Vec<Token> = Vec::new();
+    for candidate in candidates {
+        if &candidate != token && !simpler.contains(&candidate) {
+            simpler.push(candidate);
+        }
+    }
+    simpler
+}
+
+/// Parameters of the call in the form of the test directives.
+pub fn tokens_to_params(tokens: &[Token]) -> String {
+    tokens.iter().map(token_to_str).join(", ")
+}
+
+fn token_to_str(token: &Token) -> String {
+    match token {
+        Token::Bool(val) => val.to_string(),
+        Token::Uint(val) => val.to_string(),
+        Token::Int(val) => {
+            if val.bit(255) {
+                format!("-{}", (!*val).overflowing_add(U256::one()).0)
+            } else {
+                val.to_string()
+            }
+        }
+        Token::Address(val) => format!("0x{}", hex::encode(val.as_bytes())),
+        Token::FixedBytes(val) | Token::Bytes(val) => format!("0x{}", hex::encode(val)),
+        Token::String(val) => val.clone(),
+        Token::Array(items) | Token::FixedArray(items) | Token::Tuple(items) => {
+            format!("[{}]", items.iter().map(token_to_str).join(", "))
+        }
+    }
+}
@@ -0,0 +1,337 @@
+//! Differential fuzzing of the translated contracts.
+//!
+//! Random sequences of the ABI-typed calls run on the original contract in `REvm` and on the
+//! translated module in `MoveExecutor`. After each call the return values, the failure, the
+//! storage and the logs of both are compared. A diverging sequence is minimized into the test
+//! directives of the contract file.
+//!
+//! The fuzz tests are ignored by default:
+//! `cargo test -p test_infra --test fuzz -- --ignored`.
+//! `E2M_FUZZ_SEED`, `E2M_FUZZ_RUNS` and `E2M_FUZZ_CALLS` set the seed, the number of the
+//! sequences per contract and the maximum length of a sequence.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Result};
+use ethabi::ethereum_types::U256;
+use ethabi::{Function, RawLog, Token};
+use itertools::Itertools;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::ModuleId;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use eth::compile::EvmPack;
+use eth::Flags;
+use move_executor::{MoveExecutor, MoveExecutorInstance};
+
+use crate::testssol::env::revm::REvm;
+use crate::testssol::parse::SolFile;
+use crate::testssol::state;
+use crate::testssol::{make_move_module, return_val_to_string};
+
+pub mod input;
+
+const ADDRESS: &str = "0x42";
+const DEFAULT_RUNS: usize = 32;
+const DEFAULT_CALLS: usize = 8;
+
+/// Settings of the fuzzing.
+#[derive(Debug, Clone, Copy)]
+pub struct FuzzConfig {
+    /// Seed of the random calls. If not specified, a random one.
+    pub seed: u64,
+    /// Number of the call sequences per contract.
+    pub runs: usize,
+    /// Maximum number of the calls in a sequence.
+    pub calls: usize,
+}
+
+impl FuzzConfig {
+    pub fn from_env() -> Result<FuzzConfig> {
+        Ok(FuzzConfig {
+            seed: env_var("E2M_FUZZ_SEED")?.unwrap_or_else(rand::random),
+            runs: env_var("E2M_FUZZ_RUNS")?.unwrap_or(DEFAULT_RUNS),
+            calls: env_var("E2M_FUZZ_CALLS")?.unwrap_or(DEFAULT_CALLS).max(1),
+        })
+    }
+}
+
+fn env_var<T: FromStr>(name: &str) -> Result<Option<T>> {
+    std::env::var(name)
+        .ok()
+        .map(|val| {
+            val.parse()
+                .map_err(|_| anyhow!("Invalid value of {name}: {val:?}"))
+        })
+        .transpose()
+}
+
+#[derive(Debug, Clone)]
+struct Call {
+    func: String,
+    tokens: Vec<Token>,
+}
+
+impl Call {
+    fn params(&self) -> String {
+        input::tokens_to_params(&self.tokens)
+    }
+}
+
+impl Display for Call {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({})", self.func, self.params())
+    }
+}
+
+/// Observable result of a call.
+#[derive(Debug, PartialEq)]
+struct Outcome {
+    /// Return values or `!panic` if the call failed.
+    returns: String,
+    /// Storage after the call.
+    storage: BTreeMap<U256, U256>,
+    logs: Vec<RawLog>,
+}
+
+/// The first call of the sequence whose outcomes differ.
+struct Divergence {
+    index: usize,
+    evm: Outcome,
+    mv: Outcome,
+}
+
+pub struct Fuzzer {
+    name: String,
+    contract: EvmPack,
+    vyper: bool,
+    functions: Vec<Function>,
+}
+
+impl Fuzzer {
+    /// Returns `None` if the contract can't be fuzzed: the constructor takes arguments or none
+    /// of the functions takes the supported types.
+    pub fn new(file: &SolFile) -> Result<Option<Fuzzer>> {
+        let abi = file.contract.abi()?;
+        if abi
+            .constructor()
+            .map(|constructor| !constructor.inputs.is_empty())
+            .unwrap_or_default()
+        {
+            return Ok(None);
+        }
+
+        // Overloaded functions can't be called by the name.
+        let functions = abi
+            .functions()
+            .filter(|fun| abi.functions_by_name(&fun.name).map(Vec::len).ok() == Some(1))
+            .filter(|fun| {
+                fun.inputs
+                    .iter()
+                    .all(|param| input::is_supported(&param.kind))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if functions.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Fuzzer {
+            name: file.name.clone(),
+            contract: file.contract.clone(),
+            vyper: file.is_vyper(),
+            functions,
+        }))
+    }
+
+    pub fn test_name(&self) -> String {
+        format!("fuzz::{}::{}", self.name, self.contract.name())
+    }
+
+    pub fn run(&self, config: FuzzConfig) -> Result<()> {
+        let module = make_move_module(
+            &self.module_address(),
+            &hex::encode(self.contract.code()?),
+            "",
+            self.contract.abi_str(),
+            Flags::default(),
+        )?;
+
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        for _ in 0..config.runs {
+            let len = rng.gen_range(1..=config.calls);
+            let calls = (0..len)
+                .map(|_| self.rand_call(&mut rng))
+                .collect::<Vec<_>>();
+            if let Some(divergence) = self.diverges(&module, &calls)? {
+                let (calls, divergence) = self.minimize(&module, calls, divergence);
+                bail!(self.report(config.seed, &calls, &divergence));
+            }
+        }
+        Ok(())
+    }
+
+    fn rand_call(&self, rng: &mut StdRng) -> Call {
+        let fun = self
+            .functions
+            .choose(rng)
+            .expect("The fuzzer has at least one function");
+        Call {
+            func: fun.name.clone(),
+            tokens: fun
+                .inputs
+                .iter()
+                .map(|param| input::rand_token(&param.kind, rng))
+                .collect(),
+        }
+    }
+
+    /// Runs the sequence on a newly deployed contract and its module.
+    fn diverges(&self, module: &[u8], calls: &[Call]) -> Result<Option<Divergence>> {
+        let module_id = self.module_id()?;
+
+        let mut evm = REvm::try_from(&self.contract)?;
+        evm.construct(vec![])?;
+
+        let mut vm = MoveExecutor::new(
+            self.contract.abi()?,
+            Flags::default(),
+            MoveExecutorInstance::Aptos,
+        );
+        vm.deploy(ADDRESS, module.to_vec())?;
+        vm.run(
+            &format!("{}::constructor", self.module_address()),
+            ADDRESS,
+            None,
+        )?;
+
+        for (index, call) in calls.iter().enumerate() {
+            let evm = self.run_evm(&mut evm, call)?;
+            let mv = self.run_mv(&mut vm, &module_id, call)?;
+            if evm != mv {
+                return Ok(Some(Divergence { index, evm, mv }));
+            }
+        }
+        Ok(None)
+    }
+
+    fn run_evm(&self, evm: &mut REvm, call: &Call) -> Result<Outcome> {
+        let abi = self.contract.abi()?;
+        let fun = abi.function(&call.func)?;
+        let returns = evm
+            .run_tx(fun.encode_input(&call.tokens)?)
+            .and_then(|bytes| Ok(fun.decode_output(&bytes)?))
+            .map(|tokens| tokens.iter().map(|token| format!("{token:?}")).join(", "));
+        Ok(Outcome {
+            returns: return_val_to_string(returns),
+            storage: evm.storage(),
+            logs: state::evm_logs(evm.logs()),
+        })
+    }
+
+    fn run_mv(&self, vm: &mut MoveExecutor, module_id: &ModuleId, call: &Call) -> Result<Outcome> {
+        let result = vm.run(
+            &format!("{}::{}", self.module_address(), call.func),
+            ADDRESS,
+            Some(&call.params()),
+        );
+        let (returns, logs) = match result {
+            Ok(result) => (result.to_result_str(), result.logs),
+            Err(err) => (return_val_to_string(Err(err)), vec![]),
+        };
+        Ok(Outcome {
+            returns,
+            storage: vm.storage(module_id)?,
+            logs,
+        })
+    }
+
+    /// Drops the calls and simplifies the arguments while the sequence still diverges.
+    fn minimize(
+        &self,
+        module: &[u8],
+        mut calls: Vec<Call>,
+        mut divergence: Divergence,
+    ) -> (Vec<Call>, Divergence) {
+        calls.truncate(divergence.index + 1);
+
+        let mut index = 0;
+        while index < calls.len() && calls.len() > 1 {
+            let mut candidate = calls.clone();
+            candidate.remove(index);
+            match self.diverges(module, &candidate) {
+                Ok(Some(found)) => {
+                    candidate.truncate(found.index + 1);
+                    calls = candidate;
+                    divergence = found;
+                }
+                _ => index += 1,
+            }
+        }
+
+        let mut index = 0;
+        while index < calls.len() {
+            let mut arg = 0;
+            while arg < calls[index].tokens.len() {
+                for simpler in input::simpler_tokens(&calls[index].tokens[arg]) {
+                    let mut candidate = calls.clone();
+                    candidate[index].tokens[arg] = simpler;
+                    if let Ok(Some(found)) = self.diverges(module, &candidate) {
+                        candidate.truncate(found.index + 1);
+                        calls = candidate;
+                        divergence = found;
+                        break;
+                    }
+                }
+                if index >= calls.len() {
+                    break;
+                }
+                arg += 1;
+            }
+            index += 1;
+        }
+
+        (calls, divergence)
+    }
+
+    fn report(&self, seed: u64, calls: &[Call], divergence: &Divergence) -> String {
+        let Divergence { index, evm, mv } = divergence;
+        let mut report = format!(
+            "Seed {seed}: the call {index} `{}` diverged.\n",
+            calls[*index]
+        );
+        if evm.returns != mv.returns {
+            report += &format!("returned: evm `{}`, move `{}`\n", evm.returns, mv.returns);
+        }
+        for line in state::storage_diff(&evm.storage, &mv.storage)
+            .into_iter()
+            .chain(state::logs_diff(&evm.logs, &mv.logs))
+        {
+            report += &line;
+            report += "\n";
+        }
+
+        let comment = if self.vyper { "#" } else { "//" };
+        report += "Minimized calls, in the order of the execution on one contract:\n";
+        for call in calls {
+            report += &format!("{comment} # {call}\n");
+        }
+        report
+    }
+
+    fn module_address(&self) -> String {
+        format!("{ADDRESS}::{}", self.contract.name())
+    }
+
+    fn module_id(&self) -> Result<ModuleId> {
+        Ok(ModuleId::new(
+            AccountAddress::from_hex_literal(ADDRESS)?,
+            Identifier::new(self.contract.name())?,
+        ))
+    }
+}
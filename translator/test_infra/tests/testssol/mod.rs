@@ -1,33 +1,49 @@
 #![allow(dead_code)]
 
+use std::collections::BTreeMap;
 use std::fmt::Debug;
-use std::fs;
 use std::path::PathBuf;
 
 use anyhow::{anyhow, ensure, Error, Result};
+use ethabi::ethereum_types::{H160, U256};
+use ethabi::{ParamType, RawLog, Token};
 use itertools::Itertools;
 use lazy_static::lazy_static;
+use move_binary_format::errors::VMError;
 use move_core_types::account_address::AccountAddress;
+use move_core_types::vm_status::StatusCode;
 use regex::Regex;
 
+use eth::abi::call::{fn_params_str_split, to_token, FunHash};
+use eth::bytecode::mir::interpreter::{self, Env, Val};
+use eth::program::Program;
 use eth::{abi::call::EthEncodeByString, compile::EvmPack, Flags};
-use move_executor::{ExecutionResult, MoveExecutor, MoveExecutorInstance};
+use eth::{transpile_program, TranspileOptions};
+use move_executor::{MoveExecutor, MoveExecutorInstance};
 use test_infra::color;
-use translator::translate;
+use test_infra::revm::Revert;
+use translator::{translate, Frontend};
 
 pub mod convert;
 pub mod env;
+pub mod fuzz;
 pub mod parse;
+pub mod snapshot;
+pub mod state;
 
-use crate::testssol::parse::PreInit;
+use crate::testssol::env::revm::REvm;
+use crate::testssol::parse::{Call, Expect, PreInit, RevertReason, Step};
 use parse::{SolFile, SolTest};
 
 const TEST_NAME: &str = "sol";
+const VY_TEST_NAME: &str = "vy";
+/// Prefix of the tests which are translated through the Yul frontend.
+const YUL_TEST_NAME: &str = "yul";
+/// The contract address and the default signer of the calls.
+const SIGNER: &str = "0x42";
 
 lazy_static! {
     pub static ref REG_PARAMS: Regex = Regex::new("[^a-z0-9]+").unwrap();
-    static ref HELPER_MV: Vec<u8> =
-        fs::read("./resources/mv/build/test_helper/bytecode_modules/helper.mv").unwrap();
 }
 
 #[derive(Debug)]
@@ -35,83 +51,305 @@ pub struct STest {
     prename: String,
     contract: EvmPack,
     test: SolTest,
+    /// Builds the functions from the Yul IR instead of the bytecode.
+    yul: bool,
+    vyper: bool,
 }
 
 impl STest {
+    /// Each test runs through the bytecode frontend and, if the compiler reported the IR,
+    /// through the Yul frontend.
     pub fn from_file(file: SolFile) -> Vec<STest> {
+        let frontends: &[bool] = if file.contract.ir().is_some() {
+            &[false, true]
+        } else {
+            &[false]
+        };
+        let vyper = file.is_vyper();
         file.tests
             .into_iter()
-            .map(|test| STest {
+            .flat_map(|test| frontends.iter().map(move |yul| (test.clone(), *yul)))
+            .map(|(test, yul)| STest {
                 prename: file.name.clone(),
                 contract: file.contract.clone(),
                 test,
+                yul,
+                vyper,
             })
             .collect()
     }
 
     pub fn from_sol_dir() -> Result<Vec<STest>> {
-        let list = SolFile::from_sol_dir()?
+        Ok(STest::from_files(SolFile::from_sol_dir()?))
+    }
+
+    pub fn from_vy_dir() -> Result<Vec<STest>> {
+        Ok(STest::from_files(SolFile::from_vy_dir()?))
+    }
+
+    fn from_files(files: Vec<SolFile>) -> Vec<STest> {
+        files
             .into_iter()
             .filter(|file| !file.tests.is_empty())
             .flat_map(STest::from_file)
-            .collect();
-        Ok(list)
+            .collect()
     }
 }
 
 impl STest {
     pub fn test_name(&self, index: usize) -> String {
-        let mut sub = REG_PARAMS.replace_all(&self.test.params, "_").to_string();
+        let mut sub = REG_PARAMS.replace_all(self.test.params(), "_").to_string();
         if sub.len() > 15 {
             sub = index.to_string();
         }
         if !sub.is_empty() {
             sub = format!("::{sub}");
         }
+        let prefix = if self.yul {
+            YUL_TEST_NAME
+        } else if self.vyper {
+            VY_TEST_NAME
+        } else {
+            TEST_NAME
+        };
         format!(
-            "{TEST_NAME}::{file}::{module}::{function}{sub}",
+            "{prefix}::{file}::{module}::{function}{sub}",
             file = self.prename,
             module = self.contract.name(),
-            function = &self.test.func
+            function = &self.test.name
         )
     }
 
+    /// Runs the steps of the test on the EVM and in the Move VM.
+    /// A failed deployment fails each call on that side.
+    /// After each call the return values, the storage and the logs of both sides are compared.
+    /// The calls also run in the MIR interpreter, which must agree with both sides. If the sides
+    /// diverge, the interpreter tells the stage where they diverge.
     pub fn run(&self) -> Result<()> {
+        let mut mv = self.deploy_mv();
+        let mut evm = self.deploy_evm();
+        let mut mir = self.deploy_mir();
+
+        for step in &self.test.steps {
+            match step {
+                Step::Call(call) => self.run_call(&mut evm, &mut mir, &mut mv, call)?,
+                Step::Storage { slot, val } => self.check_storage(&evm, &mv, slot, val)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn run_call(
+        &self,
+        evm: &mut Result<REvm>,
+        mir: &mut Result<MirContract>,
+        mv: &mut Result<MvContract>,
+        call: &Call,
+    ) -> Result<()> {
         let module_address = self.module_address();
-        let test = &self.test;
 
         // move result
-        let result_mv = return_val_to_string(self.run_mv());
+        let output_mv = match mv {
+            Ok(mv) => self.call_mv(mv, call),
+            Err(err) => Err(anyhow!("{err}")),
+        };
+        let result_mv = returned(&output_mv);
 
         // sol result
-        let result_evm = return_val_to_string(self.run_evm());
+        let output_evm = match evm {
+            Ok(evm) => self.call_evm(evm, call),
+            Err(err) => Err(anyhow!("{err}")),
+        };
+        let result_evm = returned(&output_evm);
+
+        // mir result
+        let output_mir = match mir {
+            Ok(mir) => self.call_mir(mir, call),
+            Err(err) => Err(anyhow!("{err}")),
+        };
 
         log::info!(
-            "{wait}: {module_address}::{test:?} {result_evm}",
+            "{wait}: {module_address}::{call:?} {result_evm}",
             wait = color::font_blue("WAIT")
         );
-        ensure!(result_evm == result_mv, "returned: {result_mv}");
 
+        let mut diff = vec![];
+        if result_evm != result_mv {
+            diff.push(format!("returned: evm `{result_evm}`, move `{result_mv}`"));
+        }
+        if let (Ok(output_evm), Ok(output_mv)) = (&output_evm, &output_mv) {
+            diff.extend(state::logs_diff(&output_evm.logs, &output_mv.logs));
+        }
+        if let (Ok(evm), Ok(mv)) = (evm.as_ref(), mv.as_ref()) {
+            diff.extend(state::storage_diff(&evm.storage(), &mv.storage));
+        }
+        let evm_storage = evm.as_ref().ok().map(REvm::storage);
+        let mir_slots = mir.as_ref().ok().map(MirContract::slots);
+        let observed_evm = Observed::new(&output_evm, evm_storage.as_ref());
+        let observed_mir = Observed::new(&output_mir, mir_slots.as_ref());
+        if !diff.is_empty() {
+            diff.push(diverged_stage(
+                &observed_evm,
+                mir.as_ref().map(|_| observed_mir),
+                &Observed::new(&output_mv, mv.as_ref().ok().map(|mv| &mv.storage)),
+            ));
+        } else if !observed_mir.agrees(&observed_evm) {
+            let reason = match &output_mir {
+                Err(err) => format!(": {err}"),
+                Ok(_) => String::new(),
+            };
+            diff.push(format!(
+                "the MIR interpreter diverges from the EVM and the Move VM{reason}"
+            ));
+        }
+        ensure!(diff.is_empty(), "{call:?} diverged:\n{}", diff.join("\n"));
+
+        for expect in &call.expects {
+            self.check_expect(expect, call, &output_evm, &output_mv)?;
+        }
         Ok(())
     }
 
-    pub fn run_mv(&self) -> Result<String> {
-        let result = self.vm_run().map_err(|err| anyhow!("{err}"))?;
-        Ok(result.to_result_str())
+    fn check_expect(
+        &self,
+        expect: &Expect,
+        call: &Call,
+        output_evm: &Result<Output>,
+        output_mv: &Result<Output>,
+    ) -> Result<()> {
+        match expect {
+            Expect::Revert(reason) => {
+                let err = output_evm
+                    .as_ref()
+                    .err()
+                    .ok_or_else(|| anyhow!("{call:?} didn't revert in the EVM"))?;
+                ensure!(output_mv.is_err(), "{call:?} didn't abort in the Move VM");
+                if let Some(reason) = reason {
+                    let data = err
+                        .downcast_ref::<Revert>()
+                        .map(|revert| revert.0.as_slice())
+                        .unwrap_or_default();
+                    ensure!(
+                        data == revert_data(reason),
+                        "{call:?} reverted with 0x{}, expected {reason:?}",
+                        hex::encode(data)
+                    );
+                }
+            }
+            Expect::Abort(code) => {
+                ensure!(output_evm.is_err(), "{call:?} didn't revert in the EVM");
+                let err = output_mv
+                    .as_ref()
+                    .err()
+                    .ok_or_else(|| anyhow!("{call:?} didn't abort in the Move VM"))?;
+                let abort_code = err
+                    .downcast_ref::<VMError>()
+                    .filter(|err| err.major_status() == StatusCode::ABORTED)
+                    .and_then(VMError::sub_status);
+                ensure!(
+                    abort_code == Some(*code),
+                    "{call:?} aborted with {abort_code:?}, expected {code}: {err}"
+                );
+            }
+            Expect::Event { name, params } => {
+                let abi = self.contract.abi()?;
+                let event = abi.event(name)?;
+                let kinds = event
+                    .inputs
+                    .iter()
+                    .map(|param| param.kind.clone())
+                    .collect::<Vec<_>>();
+                let expected = tokenize(&kinds, params)?;
+                for (vm, output) in [("EVM", output_evm), ("Move VM", output_mv)] {
+                    let emitted = output.as_ref().map_err(|err| anyhow!("{err}"))?;
+                    ensure!(
+                        emitted.logs.iter().any(|log| {
+                            event.parse_log(log.clone()).ok().map(|log| {
+                                log.params.into_iter().map(|param| param.value).collect()
+                            }) == Some(expected.clone())
+                        }),
+                        "{call:?} didn't emit {name}({params}) in the {vm}"
+                    );
+                }
+            }
+        }
+        Ok(())
     }
 
-    pub fn run_evm(&self) -> Result<String> {
-        use crate::testssol::env::revm::REvm;
+    fn check_storage(
+        &self,
+        evm: &Result<REvm>,
+        mv: &Result<MvContract>,
+        slot: &U256,
+        val: &U256,
+    ) -> Result<()> {
+        let evm = evm.as_ref().map_err(|err| anyhow!("{err}"))?;
+        let mv = mv.as_ref().map_err(|err| anyhow!("{err}"))?;
+
+        let evm_val = evm.storage().get(slot).copied().unwrap_or_default();
+        ensure!(
+            evm_val == *val,
+            "storage[{slot:#x}] is {evm_val:#x} in the EVM, expected {val:#x}"
+        );
+        let mv_val = mv.storage.get(slot).copied().unwrap_or_default();
+        ensure!(
+            mv_val == *val,
+            "storage[{slot:#x}] is {mv_val:#x} in the Move VM, expected {val:#x}"
+        );
+        Ok(())
+    }
 
+    fn call_mv(&self, mv: &mut MvContract, call: &Call) -> Result<Output> {
+        let func_address = format!("{}::{}", self.module_address(), &call.func);
+        let result = mv
+            .vm
+            .run(&func_address, call.signer(), Some(&call.params))?;
+        state::apply_writes(&mut mv.storage, &result.storage);
+        Ok(Output {
+            returns: result.to_result_str(),
+            logs: result.logs,
+        })
+    }
+
+    fn call_mir(&self, mir: &mut MirContract, call: &Call) -> Result<Output> {
         let abi = self.contract.abi()?;
         let func = abi
-            .functions_by_name(&self.test.func)?
+            .functions_by_name(&call.func)?
             .first()
             .ok_or_else(|| anyhow!("function not found in abi"))?;
-        let tx = func.call_by_str(&self.test.params)?;
+        let tx = func.call_by_str(&call.params)?;
+        let hash = FunHash::from(<[u8; 4]>::try_from(&tx[..4])?);
+        let fun = mir
+            .program
+            .function_mir(hash)
+            .ok_or_else(|| anyhow!("The MIR of {} was not found", call.func))?;
+
+        let args = vec![signer_val(call.signer())?, Val::Bytes(tx[4..].to_vec())];
+        let output = interpreter::run(fun, args, &mut mir.state, &mir_env(self.test.preinit))?;
+        let returns = match output.returns.first() {
+            Some(Val::Bytes(data)) => func
+                .decode_output(data)?
+                .iter()
+                .map(|data| format!("{data:?}"))
+                .join(", "),
+            Some(val) => return Err(anyhow!("Unexpected result {val:?}")),
+            None => String::new(),
+        };
+        Ok(Output {
+            returns,
+            logs: state::mir_logs(&output.logs),
+        })
+    }
 
-        let mut evm = REvm::try_from(&self.contract)?;
-        evm.construct(vec![])?;
+    fn call_evm(&self, evm: &mut REvm, call: &Call) -> Result<Output> {
+        let abi = self.contract.abi()?;
+        let func = abi
+            .functions_by_name(&call.func)?
+            .first()
+            .ok_or_else(|| anyhow!("function not found in abi"))?;
+        let tx = func.call_by_str(&call.params)?;
+
+        evm.set_caller(sender_address(call.signer())?);
         let result_bytes = evm.run_tx(tx)?;
         log::trace!("emv result_bytes: {result_bytes:?}");
 
@@ -122,22 +360,58 @@ impl STest {
             .join(", ");
         log::trace!("emv result_string: {return_value:?}");
 
-        Ok(return_value)
+        Ok(Output {
+            returns: return_value,
+            logs: state::evm_logs(evm.logs()),
+        })
+    }
+
+    fn deploy_evm(&self) -> Result<REvm> {
+        let mut code = self.contract.code_evm()?;
+        if !self.test.init_args.is_empty() {
+            let abi = self.contract.abi()?;
+            let constructor = abi
+                .constructor()
+                .ok_or_else(|| anyhow!("constructor not found in abi"))?;
+            let kinds = constructor
+                .inputs
+                .iter()
+                .map(|param| param.kind.clone())
+                .collect::<Vec<_>>();
+            code = constructor.encode_input(code, &tokenize(&kinds, &self.test.init_args)?)?;
+        }
+
+        let mut evm = REvm::try_from(code)?;
+        evm.construct(vec![])?;
+        Ok(evm)
     }
 
     fn module_address(&self) -> String {
-        format!("0x42::{}", &self.contract.name())
+        format!("{SIGNER}::{}", &self.contract.name())
+    }
+
+    fn frontend(&self) -> Result<Frontend> {
+        Ok(if self.yul {
+            Frontend::Yul(
+                self.contract
+                    .ir()
+                    .ok_or_else(|| anyhow!("The Yul IR was not found"))?,
+            )
+        } else {
+            Frontend::Bytecode
+        })
     }
 
-    fn vm_run(&self) -> Result<ExecutionResult> {
+    fn deploy_mv(&self) -> Result<MvContract> {
         let module_address = self.module_address();
 
-        let bytecode = make_move_module(
+        let bytecode = make_move_module_with(
             &module_address,
             &hex::encode(self.bin()?),
-            "",
+            &self.test.init_args,
             self.abi_str(),
             Flags::default(),
+            self.frontend()?,
         )?;
         let mut vm = MoveExecutor::new(
             self.contract.abi()?,
@@ -149,11 +423,35 @@ impl STest {
         preinit(&mut vm, self.test.preinit)?;
 
         // deploy contract
-        vm.deploy("0x42", bytecode)?;
-        vm.run(&format!("{}::constructor", module_address), "0x42", None)?;
+        vm.deploy(SIGNER, bytecode)?;
+        let result = vm.run(&format!("{}::constructor", module_address), SIGNER, None)?;
+        let mut storage = BTreeMap::new();
+        state::apply_writes(&mut storage, &result.storage);
+        Ok(MvContract { vm, storage })
+    }
+
+    fn deploy_mir(&self) -> Result<MirContract> {
+        let address = AccountAddress::from_hex_literal(SIGNER)?;
+        let program = transpile_program(
+            self.contract.name(),
+            &hex::encode(self.bin()?),
+            &self.test.init_args,
+            &self.contract.abi()?,
+            U256::from(address.as_slice()),
+            TranspileOptions {
+                frontend: self.frontend()?,
+                ..Default::default()
+            },
+        )?;
 
-        let func_address = format!("{module_address}::{}", &self.test.func);
-        vm.run(&func_address, "0x42", Some(&self.test.params))
+        let mut state = interpreter::State::default();
+        interpreter::run(
+            program.constructor_mir(),
+            vec![signer_val(SIGNER)?],
+            &mut state,
+            &mir_env(self.test.preinit),
+        )?;
+        Ok(MirContract { program, state })
     }
 
     fn abi_str(&self) -> &str {
@@ -165,21 +463,164 @@ impl STest {
     }
 }
 
+/// The module in the Move VM and its slots, kept by the writes of the calls.
+struct MvContract {
+    vm: MoveExecutor,
+    storage: BTreeMap<U256, U256>,
+}
+
+/// The functions of the contract in the MIR interpreter and their state.
+struct MirContract {
+    program: Program,
+    state: interpreter::State,
+}
+
+impl MirContract {
+    /// The raw slots and the typed fields as the EVM slots.
+    fn slots(&self) -> BTreeMap<U256, U256> {
+        self.state.slots(self.program.layout())
+    }
+}
+
+/// Return values and logs of a call.
+struct Output {
+    returns: String,
+    logs: Vec<RawLog>,
+}
+
+impl Call {
+    fn signer(&self) -> &str {
+        self.sender.as_deref().unwrap_or(SIGNER)
+    }
+}
+
+/// Result of a call and the whole state after it as the EVM slots. `None` if the contract wasn't
+/// deployed.
+struct Observed<'a> {
+    output: &'a Result<Output>,
+    storage: Option<&'a BTreeMap<U256, U256>>,
+}
+
+impl<'a> Observed<'a> {
+    fn new(output: &'a Result<Output>, storage: Option<&'a BTreeMap<U256, U256>>) -> Self {
+        Observed { output, storage }
+    }
+
+    fn agrees(&self, other: &Observed) -> bool {
+        let logs = match (self.output, other.output) {
+            (Ok(output), Ok(other)) => output.logs == other.logs,
+            _ => true,
+        };
+        let storage = match (self.storage, other.storage) {
+            (Some(storage), Some(other)) => storage == other,
+            _ => true,
+        };
+        returned(self.output) == returned(other.output) && logs && storage
+    }
+}
+
+/// The first stage of the translation which diverges from the EVM.
+/// The MIR interpreter agrees with the EVM unless the HIR or the MIR is wrong,
+/// and with the Move VM unless the Move code is wrong.
+fn diverged_stage(evm: &Observed, mir: Result<Observed, &Error>, mv: &Observed) -> String {
+    let stage = match mir {
+        Err(err) => format!("unknown, the MIR interpreter failed: {err}"),
+        Ok(mir) if !mir.agrees(evm) => {
+            let reason = match mir.output {
+                Err(err) => format!(", the MIR interpreter failed: {err}"),
+                Ok(_) => String::new(),
+            };
+            format!("HIR or MIR translation, the MIR diverges from the EVM{reason}")
+        }
+        Ok(mir) if !mir.agrees(mv) => {
+            "Move code generation, the MIR agrees with the EVM".to_string()
+        }
+        Ok(_) => "unknown, the MIR agrees with the EVM and the Move VM".to_string(),
+    };
+    format!("first diverging stage: {stage}")
+}
+
+/// The Move signer as the MIR value.
+fn signer_val(signer: &str) -> Result<Val> {
+    let address = AccountAddress::from_hex_literal(signer)?;
+    Ok(Val::Signer(U256::from(address.as_slice())))
+}
+
+/// The EVM address of the Move signer.
+fn sender_address(signer: &str) -> Result<H160> {
+    let address = AccountAddress::from_hex_literal(signer)?;
+    let bytes = address.as_slice();
+    ensure!(
+        bytes[..bytes.len() - H160::len_bytes()]
+            .iter()
+            .all(|byte| *byte == 0),
+        "The sender {signer} doesn't fit the EVM address"
+    );
+    Ok(H160::from_slice(&bytes[bytes.len() - H160::len_bytes()..]))
+}
+
+fn tokenize(kinds: &[ParamType], params: &str) -> Result<Vec<Token>> {
+    let params = fn_params_str_split(params)?;
+    ensure!(
+        params.len() == kinds.len(),
+        "Expected {} parameters: {params:?}",
+        kinds.len()
+    );
+    kinds
+        .iter()
+        .cloned()
+        .zip(params)
+        .map(|param| to_token(&param).map_err(|err| anyhow!("{err:?}")))
+        .collect()
+}
+
+/// Revert data of the solc `Panic(uint256)` and `Error(string)`.
+fn revert_data(reason: &RevertReason) -> Vec<u8> {
+    let (name, kind, token) = match reason {
+        RevertReason::Panic(code) => ("Panic", ParamType::Uint(256), Token::Uint(*code)),
+        RevertReason::Error(msg) => ("Error", ParamType::String, Token::String(msg.clone())),
+    };
+    let mut data = ethabi::short_signature(name, &[kind]).to_vec();
+    data.extend(ethabi::encode(&[token]));
+    data
+}
+
+fn returned(output: &Result<Output>) -> String {
+    match output {
+        Ok(output) => output.returns.clone(),
+        Err(err) => {
+            log::trace!("{err}");
+            "!panic".to_string()
+        }
+    }
+}
+
 pub fn make_move_module(
     name: &str,
     eth: &str,
     init_args: &str,
     abi: &str,
     flags: Flags,
+) -> Result<Vec<u8>, Error> {
+    make_move_module_with(name, eth, init_args, abi, flags, Frontend::Bytecode)
+}
+
+pub fn make_move_module_with(
+    name: &str,
+    eth: &str,
+    init_args: &str,
+    abi: &str,
+    flags: Flags,
+    frontend: Frontend,
 ) -> Result<Vec<u8>, Error> {
     let mut split = name.split("::");
     let addr = AccountAddress::from_hex_literal(split.next().unwrap())?;
     let name = split.next().unwrap();
     let cfg = translator::Config {
-        contract_addr: addr,
-        name,
         initialization_args: init_args,
         flags,
+        frontend,
+        ..translator::Config::new(addr, name)
     };
     let target = translate(eth, abi, cfg)?;
     Ok(target.bytecode)
@@ -204,36 +645,18 @@ fn log_initialization(text: &str) {
     log::trace!("[{}] {text}", color::font_blue("INITIALIZATION"));
 }
 
-#[inline]
-fn log_run(text: &str) {
-    log::trace!("[{}] {text}", color::font_blue("RUN"));
-}
-
 fn preinit(vm: &mut MoveExecutor, preinit: PreInit) -> Result<()> {
     log::trace!("preinic");
 
     match preinit {
         PreInit::Block => {
-            log_initialization("test helper");
-            log::trace!("preinit block");
-
-            vm.deploy("0x1", HELPER_MV.clone())?;
-
-            log_run("0x1::helper::genesis_inic");
-            vm.run("0x1::helper::genesis_inic", "0x1", None)?;
-
-            log_run("0x1::helper::fake_block()");
-            vm.run("0x1::helper::fake_block", "0x0", None)?;
+            log_initialization("block height 3");
+            vm.set_block_height(3)?;
         }
         PreInit::Balance => {
-            log_initialization("test helper");
-            log::trace!("preinit balance");
-
-            vm.deploy("0x1", HELPER_MV.clone())?;
-
             // Topping up the balance on account 0x42
-            log_run("0x1::helper::x42_1_000_000");
-            vm.run("0x1::helper::x42_1_000_000", "0x1", None)?;
+            log_initialization("balance of 0x42: 1000000");
+            vm.set_balance("0x42", 1_000_000)?;
         }
         PreInit::None => {
             log::trace!("preinit: None");
@@ -242,3 +665,16 @@ fn preinit(vm: &mut MoveExecutor, preinit: PreInit) -> Result<()> {
 
     Ok(())
 }
+
+/// Environment of the MIR interpreter with the same initialization as the Move VM.
+fn mir_env(preinit: PreInit) -> Env {
+    let mut env = Env::default();
+    match preinit {
+        PreInit::Block => env.block_height = U256::from(3),
+        PreInit::Balance => {
+            env.balances.insert(U256::from(0x42), U256::from(1_000_000));
+        }
+        PreInit::None => {}
+    }
+    env
+}
@@ -4,14 +4,17 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::{fmt, fs, usize};
 
-use anyhow::{anyhow, bail, Error, Result};
+use anyhow::{anyhow, bail, ensure, Error, Result};
+use ethabi::ethereum_types::U256;
 use ethabi::{Contract, ParamType};
 use rand::Rng;
 
 use eth::abi::call::fn_params_str_split;
 use eth::compile::{build_sol, EvmPack};
+use eth::vyper::build_vy;
 
 const SOL_DIRECTORY: &str = "./sol";
+const VY_DIRECTORY: &str = "./vy";
 
 #[derive(Debug)]
 pub struct SolFile {
@@ -27,10 +30,15 @@ impl SolFile {
         SolFile::from_dir(dir)
     }
 
+    pub fn from_vy_dir() -> Result<Vec<SolFile>> {
+        let dir = PathBuf::from(VY_DIRECTORY).canonicalize()?;
+        SolFile::from_dir(dir)
+    }
+
     /// The search is carried out by nested folders inclusive
     fn from_dir(sol_dir: PathBuf) -> Result<Vec<SolFile>> {
         let mut result = Vec::new();
-        if path_is_sol(&sol_dir) {
+        if path_is_source(&sol_dir) {
             if let Some(mut solfile) = pathsol_to_solfile(sol_dir) {
                 solfile.check_paths()?;
                 solfile.inic_tests()?;
@@ -60,12 +68,16 @@ impl SolFile {
 }
 
 impl SolFile {
+    pub fn is_vyper(&self) -> bool {
+        path_to_ext(&self.sol_path) == "vy"
+    }
+
     fn check_paths(&self) -> Result<()> {
         if !self.sol_path.exists() {
             bail!("sol file not found: {:?}", self.sol_path);
         }
-        if path_to_ext(&self.sol_path) != "sol" {
-            bail!("expected extension sol -> {:?}", self.sol_path);
+        if !path_is_source(&self.sol_path) {
+            bail!("expected extension sol or vy -> {:?}", self.sol_path);
         }
 
         Ok(())
@@ -74,26 +86,63 @@ impl SolFile {
     fn inic_tests(&mut self) -> Result<()> {
         let content = fs::read_to_string(&self.sol_path)?;
         let abi = self.contract.abi()?;
-        self.tests = content
+        let comment = if self.is_vyper() { "#" } else { "//" };
+        let directives = content
             .lines()
             .map(|line| line.trim())
-            .filter(|line| line.starts_with("//"))
-            .map(|line| line.trim_start_matches("//").trim())
+            .filter(|line| line.starts_with(comment))
+            .map(|line| line.trim_start_matches(comment).trim())
             .filter(|line| line.starts_with('#'))
-            .map(|line| line.trim_start_matches('#').trim())
-            .filter_map(|line| SolTest::try_from_with_fuzzing(line, &abi).ok())
-            .flatten()
-            .collect::<Vec<SolTest>>();
+            .map(|line| line.trim_start_matches('#').trim());
+
+        let mut tests = Vec::new();
+        // The steps of a scenario follow its header.
+        let mut scenario: Option<Result<SolTest>> = None;
+        for line in directives {
+            if let Some(step) = line.strip_prefix('>') {
+                let pushed = match scenario.as_mut() {
+                    Some(Ok(test)) => test.push_step(step),
+                    Some(Err(_)) => Ok(()),
+                    None => Err(anyhow!("The step is out of a scenario: {line:?}")),
+                };
+                if let Err(err) = pushed {
+                    if scenario.is_none() {
+                        log::error!("{err:?}");
+                    } else {
+                        scenario = Some(Err(err));
+                    }
+                }
+                continue;
+            }
+
+            push_scenario(&mut tests, scenario.take());
+            scenario = SolTest::scenario(line);
+            if scenario.is_none() {
+                if let Ok(line_tests) = SolTest::try_from_with_fuzzing(line, &abi) {
+                    tests.extend(line_tests);
+                }
+            }
+        }
+        push_scenario(&mut tests, scenario);
+        self.tests = tests;
         Ok(())
     }
 }
 
+fn push_scenario(tests: &mut Vec<SolTest>, scenario: Option<Result<SolTest>>) {
+    match scenario {
+        Some(Ok(test)) => tests.push(test),
+        Some(Err(err)) => log::error!("{err:?}"),
+        None => {}
+    }
+}
+
 fn path_to_ext(path: &Path) -> &str {
     path.extension().and_then(OsStr::to_str).unwrap_or_default()
 }
 
-fn path_is_sol(path: &Path) -> bool {
-    path.is_file() && path_to_ext(path) == "sol"
+fn path_is_source(path: &Path) -> bool {
+    path.is_file() && matches!(path_to_ext(path), "sol" | "vy")
 }
 
 fn pathsol_to_solfile(sol_path: PathBuf) -> Option<SolFile> {
@@ -105,14 +154,17 @@ fn pathsol_to_solfile(sol_path: PathBuf) -> Option<SolFile> {
         }
     }
 
-    let contract = build_sol(&sol_path)
-        .map_err(|err| log::error!("{err:?}"))
-        .ok()?;
+    let (contract, directory) = if path_to_ext(&sol_path) == "vy" {
+        (build_vy(&sol_path), VY_DIRECTORY)
+    } else {
+        (build_sol(&sol_path), SOL_DIRECTORY)
+    };
+    let contract = contract.map_err(|err| log::error!("{err:?}")).ok()?;
 
     let name: String = sol_path
         .to_string_lossy()
         .to_string()
-        .split_once(&SOL_DIRECTORY[2..])?
+        .split_once(&directory[2..])?
         .1
         .split('/')
         .filter(|p| !p.is_empty())
@@ -127,11 +179,64 @@ fn pathsol_to_solfile(sol_path: PathBuf) -> Option<SolFile> {
     })
 }
 
+/// Test of the directives of the contract file.
+///
+/// A line is a test of one call: `// # [preinit#] [@sender] fn(args) [-> expectation]..`.
+/// A scenario runs its steps on one contract in order:
+/// ```text
+/// // # [preinit#] scenario <name>
+/// // # > constructor(<args>)
+/// // # > @0x12 fn(args) -> revert(0x11)
+/// // # > fn(args) -> event Name(args)
+/// // # > storage[<slot>] -> <value>
+/// ```
 #[derive(Clone)]
 pub struct SolTest {
+    /// The name of the scenario or the called function.
+    pub name: String,
+    pub preinit: PreInit,
+    /// Arguments of the constructor.
+    pub init_args: String,
+    pub steps: Vec<Step>,
+    scenario: bool,
+}
+
+#[derive(Clone, Debug)]
+pub enum Step {
+    Call(Call),
+    /// The slot of the storage keeps the value.
+    Storage {
+        slot: U256,
+        val: U256,
+    },
+}
+
+#[derive(Clone)]
+pub struct Call {
+    /// The signer of the call. If not specified, the contract address.
+    pub sender: Option<String>,
     pub func: String,
     pub params: String,
-    pub preinit: PreInit,
+    pub expects: Vec<Expect>,
+}
+
+/// Expected result of the call. The results of the EVM and of the Move VM must be equal anyway.
+#[derive(Clone, Debug)]
+pub enum Expect {
+    /// The call fails. The EVM reverts with the reason if it is specified.
+    Revert(Option<RevertReason>),
+    /// The call fails, and the Move VM aborts with the code.
+    Abort(u64),
+    /// The call emits the event with the parameters.
+    Event { name: String, params: String },
+}
+
+#[derive(Clone, Debug)]
+pub enum RevertReason {
+    /// `Panic(uint256)` of the solc checks: `revert(0x11)`.
+    Panic(U256),
+    /// `Error(string)` of `require` and `revert`: `revert("reason")`.
+    Error(String),
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -158,24 +263,148 @@ impl TryFrom<&str> for SolTest {
     type Error = anyhow::Error;
     fn try_from(instruction: &str) -> Result<Self> {
         let (preinic, part) = instruction.split_once('#').unwrap_or(("", instruction));
+        let call = Call::from_str(part)?;
+
+        Ok(SolTest {
+            name: call.func.clone(),
+            preinit: PreInit::from_str(preinic.trim())?,
+            init_args: String::new(),
+            steps: vec![Step::Call(call)],
+            scenario: false,
+        })
+    }
+}
+
+impl FromStr for Call {
+    type Err = Error;
+    fn from_str(instruction: &str) -> Result<Self> {
+        let instruction = instruction.trim();
+        let (sender, part) = match instruction.strip_prefix('@') {
+            Some(part) => {
+                let (sender, part) = part
+                    .split_once(char::is_whitespace)
+                    .ok_or_else(|| anyhow!("Function not found: {}", instruction))?;
+                (Some(sender.to_string()), part)
+            }
+            None => (None, instruction),
+        };
 
         let (name, part) = part
             .split_once('(')
             .ok_or_else(|| anyhow!("Function name and parameters not found: {}", instruction))?;
-        let (params, ..) = part
+        let (params, rest) = part
             .split_once(')')
             .ok_or_else(|| anyhow!("Function parameters not found: {}", instruction))?;
-
-        Ok(SolTest {
+        // The text between the call and the first expectation is a comment.
+        let expects = rest
+            .split("->")
+            .skip(1)
+            .map(Expect::from_str)
+            .collect::<Result<_>>()?;
+
+        Ok(Call {
+            sender,
             func: name.trim().to_string(),
             params: params.trim().to_string(),
-            preinit: PreInit::from_str(preinic.trim())?,
+            expects,
         })
     }
 }
 
+impl FromStr for Expect {
+    type Err = Error;
+    fn from_str(expect: &str) -> Result<Self> {
+        let expect = expect.trim();
+        if expect == "revert" {
+            return Ok(Expect::Revert(None));
+        }
+        if let Some(reason) = args_of(expect, "revert") {
+            let reason = if reason.starts_with('"') {
+                RevertReason::Error(reason.trim_matches('"').to_string())
+            } else {
+                RevertReason::Panic(parse_u256(reason)?)
+            };
+            return Ok(Expect::Revert(Some(reason)));
+        }
+        if let Some(code) = args_of(expect, "abort") {
+            return Ok(Expect::Abort(code.parse()?));
+        }
+        if let Some(event) = expect.strip_prefix("event ") {
+            let (name, params) = event
+                .split_once('(')
+                .ok_or_else(|| anyhow!("Event parameters not found: {expect}"))?;
+            let params = params
+                .trim()
+                .strip_suffix(')')
+                .ok_or_else(|| anyhow!("Event parameters not found: {expect}"))?;
+            return Ok(Expect::Event {
+                name: name.trim().to_string(),
+                params: params.trim().to_string(),
+            });
+        }
+        bail!("Unknown expectation: {expect:?}")
+    }
+}
+
+impl FromStr for Step {
+    type Err = Error;
+    fn from_str(step: &str) -> Result<Self> {
+        let step = step.trim();
+        if let Some(part) = step.strip_prefix("storage[") {
+            let (slot, val) = part
+                .split_once(']')
+                .ok_or_else(|| anyhow!("Storage slot not found: {step}"))?;
+            let val = val
+                .trim()
+                .strip_prefix("->")
+                .ok_or_else(|| anyhow!("Storage value not found: {step}"))?;
+            return Ok(Step::Storage {
+                slot: parse_u256(slot)?,
+                val: parse_u256(val)?,
+            });
+        }
+        Ok(Step::Call(Call::from_str(step)?))
+    }
+}
+
+/// Arguments of `name(args)`.
+fn args_of<'a>(expr: &'a str, name: &str) -> Option<&'a str> {
+    expr.strip_prefix(name)?
+        .trim()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+        .map(str::trim)
+}
+
+fn parse_u256(val: &str) -> Result<U256> {
+    let val = val.trim();
+    match val.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16),
+        None => U256::from_dec_str(val),
+    }
+    .map_err(|err| anyhow!("Invalid number {val:?}: {err:?}"))
+}
+
 impl fmt::Debug for SolTest {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.scenario {
+            write!(f, "scenario {}", &self.name)
+        } else {
+            write!(
+                f,
+                "{name}({params})",
+                name = &self.name,
+                params = self.params()
+            )
+        }
+    }
+}
+
+impl fmt::Debug for Call {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(sender) = &self.sender {
+            write!(f, "@{sender} ")?;
+        }
         write!(
             f,
             "{name}({params})",
@@ -185,18 +414,61 @@ impl fmt::Debug for SolTest {
     }
 }
 
+impl SolTest {
+    /// Scenario of the header `[preinit#] scenario <name>`.
+    pub fn scenario(instruction: &str) -> Option<Result<SolTest>> {
+        let (preinic, part) = instruction.split_once('#').unwrap_or(("", instruction));
+        let name = part.trim().strip_prefix("scenario")?;
+        if !name.starts_with(char::is_whitespace) || name.contains('(') {
+            return None;
+        }
+
+        Some(PreInit::from_str(preinic.trim()).map(|preinit| SolTest {
+            name: name.trim().to_string(),
+            preinit,
+            init_args: String::new(),
+            steps: Vec::new(),
+            scenario: true,
+        }))
+    }
+
+    /// Adds the step `constructor(args)`, `storage[slot] -> val` or a call to the scenario.
+    pub fn push_step(&mut self, step: &str) -> Result<()> {
+        let step = step.trim();
+        if let Some(args) = args_of(step, "constructor") {
+            ensure!(
+                self.steps.is_empty(),
+                "The constructor must be the first step of the scenario {}",
+                self.name
+            );
+            self.init_args = args.to_string();
+        } else {
+            self.steps.push(Step::from_str(step)?);
+        }
+        Ok(())
+    }
+
+    /// Parameters of the call of a one-line test.
+    pub fn params(&self) -> &str {
+        match self.steps.as_slice() {
+            [Step::Call(call)] if !self.scenario => &call.params,
+            _ => "",
+        }
+    }
+}
+
 impl SolTest {
     pub fn try_from_with_fuzzing(instruction: &str, abi: &Contract) -> Result<Vec<SolTest>> {
         let ins = SolTest::try_from(instruction)?;
-        let params = fn_params_str_split(&ins.params)?;
+        let params = fn_params_str_split(ins.params())?;
         if params.is_empty() || !params.contains(&"*") {
             return Ok(vec![ins]);
         }
 
         let call = abi
-            .functions_by_name(&ins.func)?
+            .functions_by_name(&ins.name)?
             .first()
-            .ok_or_else(|| anyhow!("Not found fn {}", &ins.func))?;
+            .ok_or_else(|| anyhow!("Not found fn {}", &ins.name))?;
 
         let variants = params
             .iter()
@@ -238,7 +510,9 @@ impl SolTest {
             .map(|params| params.join(","))
             .map(|param| {
                 let mut test = ins.clone();
-                test.params = param;
+                if let [Step::Call(call)] = test.steps.as_mut_slice() {
+                    call.params = param;
+                }
                 test
             })
             .collect();
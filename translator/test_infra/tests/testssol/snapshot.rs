@@ -0,0 +1,143 @@
+//! Golden snapshots of the pipeline stages.
+//!
+//! The HIR, the MIR and the Move disassembly of each sol contract are kept in
+//! `snapshots/<file>/<contract>.<stage>.snap`. A test fails if a stage differs from its snapshot.
+//! A missing snapshot fails the test as well. The snapshots depend on the solc version.
+//!
+//! The snapshots are written only on request. Record the new snapshots or update them after
+//! an intended change: `E2M_UPDATE_SNAPSHOTS=1 cargo test -p test_infra --test snapshots`.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, Result};
+use move_core_types::account_address::AccountAddress;
+
+use eth::compile::EvmPack;
+use translator::{disassemble, translate, Config};
+
+use crate::testssol::parse::SolFile;
+
+const ADDRESS: &str = "0x42";
+/// Number of the differing lines shown by the diff.
+const MAX_DIFF_LINES: usize = 40;
+
+pub struct Snapshot {
+    name: String,
+    contract: EvmPack,
+    init_args: String,
+}
+
+impl Snapshot {
+    pub fn new(file: &SolFile) -> Snapshot {
+        // A constructor with arguments takes them from the first scenario.
+        let init_args = file
+            .tests
+            .iter()
+            .map(|test| test.init_args.clone())
+            .find(|args| !args.is_empty())
+            .unwrap_or_default();
+        Snapshot {
+            name: file.name.clone(),
+            contract: file.contract.clone(),
+            init_args,
+        }
+    }
+
+    pub fn test_name(&self) -> String {
+        format!("snapshot::{}::{}", self.name, self.contract.name())
+    }
+
+    pub fn run(&self, update: bool) -> Result<()> {
+        let target = translate(
+            &hex::encode(self.contract.code()?),
+            self.contract.abi_str(),
+            Config {
+                initialization_args: &self.init_args,
+                print_stages: true,
+                ..Config::new(
+                    AccountAddress::from_hex_literal(ADDRESS)?,
+                    self.contract.name(),
+                )
+            },
+        )?;
+
+        let stages = [
+            ("hir", target.hir.unwrap_or_default()),
+            ("mir", target.mir.unwrap_or_default()),
+            ("mv", disassemble(&target.bytecode)?),
+        ];
+        let mut diffs = Vec::new();
+        for (stage, actual) in stages {
+            let path = self.path(stage);
+            match fs::read_to_string(&path) {
+                Ok(expected) if expected == actual => {}
+                Ok(expected) if !update => {
+                    diffs.push(format!("{path:?}:\n{}", diff(&expected, &actual)));
+                }
+                Err(_) if !update => {
+                    diffs.push(format!("{path:?}: the snapshot is missing"));
+                }
+                _ => {
+                    log::info!("write the snapshot {path:?}");
+                    let dir = path
+                        .parent()
+                        .ok_or_else(|| anyhow!("Invalid snapshot path {path:?}"))?;
+                    fs::create_dir_all(dir)?;
+                    fs::write(&path, actual)?;
+                }
+            }
+        }
+        if !diffs.is_empty() {
+            bail!(
+                "{}\nUpdate the snapshots with `E2M_UPDATE_SNAPSHOTS=1 cargo test -p test_infra --test snapshots`",
+                diffs.join("\n")
+            );
+        }
+        Ok(())
+    }
+
+    fn path(&self, stage: &str) -> PathBuf {
+        let file = self.name.replace("::", "/");
+        let file = file.rsplit_once('.').map(|(file, _)| file).unwrap_or(&file);
+        PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+            .join("snapshots")
+            .join(file)
+            .join(format!("{}.{stage}.snap", self.contract.name()))
+    }
+}
+
+/// Lines between the common beginning and the common end of the texts.
+fn diff(expected: &str, actual: &str) -> String {
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+    let prefix = expected
+        .iter()
+        .zip(&actual)
+        .take_while(|(expected, actual)| expected == actual)
+        .count();
+    let suffix = expected[prefix..]
+        .iter()
+        .rev()
+        .zip(actual[prefix..].iter().rev())
+        .take_while(|(expected, actual)| expected == actual)
+        .count();
+
+    let mut lines = vec![format!("@@ line {} @@", prefix + 1)];
+    lines.extend(
+        expected[prefix..expected.len() - suffix]
+            .iter()
+            .map(|line| format!("-{line}")),
+    );
+    lines.extend(
+        actual[prefix..actual.len() - suffix]
+            .iter()
+            .map(|line| format!("+{line}")),
+    );
+    if lines.len() > MAX_DIFF_LINES {
+        let more = lines.len() - MAX_DIFF_LINES;
+        lines.truncate(MAX_DIFF_LINES);
+        lines.push(format!("... {more} more lines"));
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,90 @@
+//! Storage and logs of the contracts under test.
+
+use std::collections::BTreeMap;
+
+use ethabi::ethereum_types::{H256, U256};
+use ethabi::RawLog;
+use evm::backend::Log;
+use itertools::Itertools;
+
+use eth::bytecode::mir::interpreter;
+
+pub fn evm_logs(logs: &[Log]) -> Vec<RawLog> {
+    logs.iter()
+        .map(|log| RawLog {
+            topics: log.topics.clone(),
+            data: log.data.clone(),
+        })
+        .collect()
+}
+
+pub fn mir_logs(logs: &[interpreter::Log]) -> Vec<RawLog> {
+    logs.iter()
+        .map(|log| RawLog {
+            topics: log
+                .topics
+                .iter()
+                .map(|topic| {
+                    let mut buf = [0u8; 32];
+                    topic.to_big_endian(&mut buf);
+                    H256::from(buf)
+                })
+                .collect(),
+            data: log.data.clone(),
+        })
+        .collect()
+}
+
+/// Applies the slots written by a call. A zero slot is removed.
+pub fn apply_writes(storage: &mut BTreeMap<U256, U256>, writes: &BTreeMap<U256, U256>) {
+    for (slot, val) in writes {
+        if val.is_zero() {
+            storage.remove(slot);
+        } else {
+            storage.insert(*slot, *val);
+        }
+    }
+}
+
+/// Lines of the slots which differ.
+pub fn storage_diff(evm: &BTreeMap<U256, U256>, mv: &BTreeMap<U256, U256>) -> Vec<String> {
+    evm.keys()
+        .chain(mv.keys())
+        .sorted()
+        .dedup()
+        .filter_map(|slot| {
+            let evm_val = evm.get(slot).copied().unwrap_or_default();
+            let mv_val = mv.get(slot).copied().unwrap_or_default();
+            (evm_val != mv_val)
+                .then(|| format!("storage[{slot:#x}]: evm {evm_val:#x}, move {mv_val:#x}"))
+        })
+        .collect()
+}
+
+/// Lines of the logs which differ, in the order of the emission.
+pub fn logs_diff(evm: &[RawLog], mv: &[RawLog]) -> Vec<String> {
+    (0..evm.len().max(mv.len()))
+        .filter(|index| evm.get(*index) != mv.get(*index))
+        .map(|index| {
+            format!(
+                "log {index}: evm {}, move {}",
+                log_to_string(evm.get(index)),
+                log_to_string(mv.get(index))
+            )
+        })
+        .collect()
+}
+
+fn log_to_string(log: Option<&RawLog>) -> String {
+    match log {
+        Some(log) => format!(
+            "topics [{}] data 0x{}",
+            log.topics
+                .iter()
+                .map(|topic| format!("{topic:#x}"))
+                .join(", "),
+            hex::encode(&log.data)
+        ),
+        None => "none".to_string(),
+    }
+}
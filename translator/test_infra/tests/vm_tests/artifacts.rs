@@ -0,0 +1,152 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::testssol::sol_path;
+use eth::artifact::load_artifacts;
+use eth::compile::{build_sol, Evm};
+use eth::Flags;
+use move_core_types::account_address::AccountAddress;
+use move_executor::{MoveExecutor, MoveExecutorInstance};
+use test_infra::init_log;
+use translator::{translate, Config};
+
+fn artifacts_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("e2m_{name}_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_json(path: &Path, json: &Value) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, json.to_string()).unwrap();
+}
+
+fn abi_json(evm: &Evm) -> Value {
+    if evm.abi().is_empty() {
+        json!([])
+    } else {
+        serde_json::from_str(evm.abi()).unwrap()
+    }
+}
+
+fn hardhat_artifact(evm: &Evm, source: &str) -> Value {
+    json!({
+        "_format": "hh-sol-artifact-1",
+        "contractName": evm.name(),
+        "sourceName": source,
+        "abi": abi_json(evm),
+        "bytecode": format!("0x{}", evm.bin()),
+        "deployedBytecode": "0x",
+        "linkReferences": {},
+        "deployedLinkReferences": {},
+    })
+}
+
+fn foundry_artifact(evm: &Evm, source: &str, link_references: Value) -> Value {
+    let storage_layout = if evm.storage_layout().is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_str(evm.storage_layout()).unwrap()
+    };
+    json!({
+        "abi": abi_json(evm),
+        "bytecode": {
+            "object": format!("0x{}", evm.bin()),
+            "sourceMap": evm.source_map(),
+            "linkReferences": link_references,
+        },
+        "deployedBytecode": {
+            "object": "0x",
+            "sourceMap": "",
+            "linkReferences": {},
+        },
+        "storageLayout": storage_layout,
+        "metadata": {
+            "settings": {
+                "compilationTarget": { source: evm.name() },
+            },
+        },
+    })
+}
+
+#[test]
+pub fn test_hardhat_artifact() {
+    init_log();
+    let pack = build_sol(sol_path().join("a_plus_b.sol")).unwrap();
+    let dir = artifacts_dir("hardhat");
+    let artifact = dir.join("a_plus_b.sol/APlusB.json");
+    write_json(
+        &artifact,
+        &hardhat_artifact(pack.contract(), "a_plus_b.sol"),
+    );
+    // The debug files and the build info are not contracts.
+    write_json(&dir.join("a_plus_b.sol/APlusB.dbg.json"), &json!({}));
+    write_json(&dir.join("build-info/0.json"), &json!({ "output": {} }));
+
+    for path in [artifact, dir] {
+        let loaded = load_artifacts(&path).unwrap();
+        assert_eq!(loaded.contract_names(), vec!["APlusB"]);
+        assert_eq!(loaded.bin_contract(), pack.bin_contract());
+        assert_eq!(
+            serde_json::from_str::<Value>(loaded.abi_str()).unwrap(),
+            serde_json::from_str::<Value>(pack.abi_str()).unwrap()
+        );
+
+        let cfg = Config::for_pack(AccountAddress::from_hex_literal("0x42").unwrap(), &loaded);
+        let target = translate(loaded.bin_contract(), loaded.abi_str(), cfg).unwrap();
+        let mut vm = MoveExecutor::new(
+            loaded.abi().unwrap(),
+            Flags::default(),
+            MoveExecutorInstance::Aptos,
+        );
+        vm.deploy("0x42", target.bytecode).unwrap();
+        vm.run("0x42::APlusB::constructor", "0x42", None).unwrap();
+        let res = vm
+            .run("0x42::APlusB::plus", "0x42", None)
+            .unwrap()
+            .to_result_str();
+        assert_eq!("Uint(27)", res);
+    }
+}
+
+#[test]
+pub fn test_foundry_artifacts() {
+    init_log();
+    let pack = build_sol(sol_path().join("link/link_calc.sol")).unwrap();
+    let library = &pack.libraries()[0];
+    let dir = artifacts_dir("foundry");
+    let artifact = dir.join("link_calc.sol/link_calc.json");
+    write_json(
+        &artifact,
+        &foundry_artifact(
+            pack.contract(),
+            "link_calc.sol",
+            json!({ "link_calc.sol": { "Calc": [{ "start": 0, "length": 20 }] } }),
+        ),
+    );
+    write_json(
+        &dir.join("link_calc.sol/Calc.json"),
+        &foundry_artifact(&library.evm, "link_calc.sol", json!({})),
+    );
+    // The interfaces have no bytecode.
+    write_json(
+        &dir.join("ICalc.sol/ICalc.json"),
+        &json!({ "abi": [], "bytecode": { "object": "0x" } }),
+    );
+
+    for path in [artifact, dir] {
+        let loaded = load_artifacts(&path).unwrap();
+        assert_eq!(loaded.contract_names(), vec!["link_calc"]);
+        assert_eq!(loaded.contract().source_map(), pack.contract().source_map());
+        assert!(!loaded.contract().source_map().is_empty());
+
+        // The library is found by the link references.
+        let libraries = loaded.used_libraries();
+        assert_eq!(libraries.len(), 1);
+        assert_eq!(libraries[0].path, "link_calc.sol:Calc");
+        assert_eq!(libraries[0].evm.bin(), library.evm.bin());
+    }
+}
@@ -0,0 +1,66 @@
+use move_core_types::account_address::AccountAddress;
+
+use eth::compile::build_sol;
+use eth::Flags;
+use move_executor::{MoveExecutor, MoveExecutorInstance};
+use test_infra::init_log;
+use translator::{translate, Config};
+
+use crate::testssol::sol_path;
+
+fn deployed() -> MoveExecutor {
+    let evm = build_sol(sol_path().join("info.sol")).unwrap();
+    let cfg = Config::new(
+        AccountAddress::from_hex_literal("0x42").unwrap(),
+        evm.name(),
+    );
+    let target = translate(evm.contract().bin(), evm.contract().abi(), cfg).unwrap();
+    let mut vm = MoveExecutor::new(
+        evm.abi().unwrap(),
+        Flags::default(),
+        MoveExecutorInstance::Aptos,
+    );
+    vm.deploy("0x42", target.bytecode).unwrap();
+    vm.run("0x42::Info::constructor", "0x42", None).unwrap();
+    vm
+}
+
+fn call(vm: &mut MoveExecutor, fun: &str, signer: &str) -> String {
+    vm.run(&format!("0x42::Info::{fun}"), signer, None)
+        .unwrap()
+        .to_result_str()
+}
+
+#[test]
+pub fn test_block_and_time() {
+    init_log();
+    let mut vm = deployed();
+
+    vm.set_block_height(77).unwrap();
+    vm.set_timestamp(1_700_000_000).unwrap();
+    assert_eq!("Uint(77)", call(&mut vm, "block_height", "0x42"));
+    assert_eq!(
+        "Uint(1700000000)",
+        call(&mut vm, "epoch_interval_secs", "0x42")
+    );
+
+    vm.set_block_height(78).unwrap();
+    assert_eq!("Uint(78)", call(&mut vm, "block_height", "0x42"));
+    assert!(vm.set_timestamp(u64::MAX).is_err());
+}
+
+#[test]
+pub fn test_balance() {
+    init_log();
+    let mut vm = deployed();
+    vm.set_chain_id(4).unwrap();
+
+    vm.set_balance("0x42", 500).unwrap();
+    assert_eq!("Uint(500)", call(&mut vm, "x42_balance", "0x42"));
+
+    vm.set_balance("0x42", 700).unwrap();
+    vm.set_balance("0x43", 10).unwrap();
+    assert_eq!("Uint(700)", call(&mut vm, "sender_balance", "0x42"));
+    assert_eq!("Uint(10)", call(&mut vm, "sender_balance", "0x43"));
+    assert_eq!("Uint(0)", call(&mut vm, "non_existent_address", "0x42"));
+}
@@ -0,0 +1,162 @@
+use std::fs;
+
+use ethabi::ethereum_types::U256;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::ModuleId;
+
+use eth::compile::build_sol;
+use eth::Flags;
+use move_executor::{MoveExecutor, MoveExecutorInstance};
+use test_infra::init_log;
+use translator::{translate, Config};
+
+use crate::testssol::sol_path;
+
+/// The `gas_report` contract translated into the module `name` at `0x42`.
+fn module(name: &str) -> Vec<u8> {
+    let evm = build_sol(sol_path().join("gas/gas_report.sol")).unwrap();
+    let cfg = Config::new(AccountAddress::from_hex_literal("0x42").unwrap(), name);
+    translate(evm.contract().bin(), evm.contract().abi(), cfg)
+        .unwrap()
+        .bytecode
+}
+
+fn deployed() -> MoveExecutor {
+    let evm = build_sol(sol_path().join("gas/gas_report.sol")).unwrap();
+    let mut vm = MoveExecutor::new(
+        evm.abi().unwrap(),
+        Flags::default(),
+        MoveExecutorInstance::Aptos,
+    );
+    vm.deploy("0x42", module("gas_report")).unwrap();
+    vm.run("0x42::gas_report::constructor", "0x42", None)
+        .unwrap();
+    vm
+}
+
+fn total(vm: &MoveExecutor) -> U256 {
+    total_of(vm, "gas_report")
+}
+
+fn total_of(vm: &MoveExecutor, name: &str) -> U256 {
+    let module = ModuleId::new(
+        AccountAddress::from_hex_literal("0x42").unwrap(),
+        Identifier::new(name).unwrap(),
+    );
+    vm.storage(&module)
+        .unwrap()
+        .get(&U256::zero())
+        .copied()
+        .unwrap_or_default()
+}
+
+#[test]
+pub fn test_snapshot_revert() {
+    init_log();
+    let mut vm = deployed();
+    vm.run("0x42::gas_report::store", "0x42", Some("5"))
+        .unwrap();
+    vm.snapshot("five");
+
+    vm.run("0x42::gas_report::store", "0x42", Some("7"))
+        .unwrap();
+    assert_eq!(total(&vm), U256::from(7));
+
+    vm.revert("five").unwrap();
+    assert_eq!(total(&vm), U256::from(5));
+    vm.run("0x42::gas_report::store", "0x42", Some("9"))
+        .unwrap();
+    vm.revert("five").unwrap();
+    assert_eq!(total(&vm), U256::from(5));
+
+    assert!(vm.revert("unknown").is_err());
+}
+
+#[test]
+pub fn test_fork() {
+    init_log();
+    let mut vm = deployed();
+    vm.run("0x42::gas_report::store", "0x42", Some("5"))
+        .unwrap();
+
+    let mut fork = vm.fork();
+    fork.run("0x42::gas_report::store", "0x42", Some("7"))
+        .unwrap();
+    assert_eq!(total(&fork), U256::from(7));
+    assert_eq!(total(&vm), U256::from(5));
+
+    // A new executor starts from the genesis.
+    let empty = MoveExecutor::new(
+        serde_json::from_str("[]").unwrap(),
+        Flags::default(),
+        MoveExecutorInstance::None,
+    );
+    assert!(empty.resolver.state_data.is_empty());
+}
+
+#[test]
+pub fn test_save_load_state() {
+    init_log();
+    let path = std::env::temp_dir().join(format!("e2m_state_{}.bcs", std::process::id()));
+
+    let mut vm = deployed();
+    vm.run("0x42::gas_report::store", "0x42", Some("5"))
+        .unwrap();
+    vm.save_state(&path).unwrap();
+    let saved = fs::read(&path).unwrap();
+    vm.save_state(&path).unwrap();
+    assert_eq!(saved, fs::read(&path).unwrap());
+
+    let mut loaded = MoveExecutor::new(
+        serde_json::from_str("[]").unwrap(),
+        Flags::default(),
+        MoveExecutorInstance::None,
+    );
+    loaded.load_state(&path).unwrap();
+    assert_eq!(loaded.resolver.state_data, vm.resolver.state_data);
+    assert_eq!(total(&loaded), U256::from(5));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+pub fn test_new_table_after_load_state() {
+    init_log();
+    let path = std::env::temp_dir().join(format!("e2m_tables_{}.bcs", std::process::id()));
+
+    let mut vm = deployed();
+    vm.run("0x42::gas_report::store", "0x42", Some("5"))
+        .unwrap();
+    vm.save_state(&path).unwrap();
+
+    // The constructor of the new module creates its table in a session of the same sender and
+    // arguments as the constructor of the saved one.
+    let evm = build_sol(sol_path().join("gas/gas_report.sol")).unwrap();
+    let mut loaded = MoveExecutor::new(
+        evm.abi().unwrap(),
+        Flags::default(),
+        MoveExecutorInstance::None,
+    );
+    loaded.load_state(&path).unwrap();
+    loaded.deploy("0x42", module("gas_report_copy")).unwrap();
+    loaded
+        .run("0x42::gas_report_copy::constructor", "0x42", None)
+        .unwrap();
+    loaded
+        .run("0x42::gas_report_copy::store", "0x42", Some("7"))
+        .unwrap();
+    assert_eq!(total_of(&loaded, "gas_report_copy"), U256::from(7));
+    assert_eq!(total(&loaded), U256::from(5));
+
+    // A reverted snapshot creates the table again.
+    vm.snapshot("five");
+    vm.deploy("0x42", module("gas_report_copy")).unwrap();
+    vm.revert("five").unwrap();
+    vm.deploy("0x42", module("gas_report_copy")).unwrap();
+    vm.run("0x42::gas_report_copy::constructor", "0x42", None)
+        .unwrap();
+    vm.run("0x42::gas_report_copy::store", "0x42", Some("7"))
+        .unwrap();
+    assert_eq!(total(&vm), U256::from(5));
+    fs::remove_file(&path).unwrap();
+}
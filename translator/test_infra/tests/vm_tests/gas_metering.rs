@@ -0,0 +1,83 @@
+use eth::compile::build_sol;
+use eth::Flags;
+use move_core_types::account_address::AccountAddress;
+use move_executor::gas::{GasSchedule, DEFAULT_MAX_GAS};
+use move_executor::{MoveExecutor, MoveExecutorInstance};
+use test_infra::init_log;
+use translator::{translate, Config};
+
+use crate::testssol::sol_path;
+
+fn executor() -> (MoveExecutor, Vec<u8>) {
+    let evm = build_sol(sol_path().join("gas/gas_report.sol")).unwrap();
+    let cfg = Config::new(
+        AccountAddress::from_hex_literal("0x42").unwrap(),
+        evm.name(),
+    );
+    let target = translate(evm.contract().bin(), evm.contract().abi(), cfg).unwrap();
+    let vm = MoveExecutor::new(
+        evm.abi().unwrap(),
+        Flags::default(),
+        MoveExecutorInstance::Aptos,
+    );
+    (vm, target.bytecode)
+}
+
+#[test]
+pub fn test_standard_gas_schedule() {
+    init_log();
+    let (mut vm, bytecode) = executor();
+    vm.set_gas_schedule(GasSchedule::Standard, DEFAULT_MAX_GAS);
+
+    let gas = vm.deploy("0x42", bytecode).unwrap();
+    assert!(gas.storage > 0);
+    assert!(gas.intrinsic > 0);
+
+    vm.run("0x42::gas_report::constructor", "0x42", None)
+        .unwrap();
+    let res = vm
+        .run("0x42::gas_report::store", "0x42", Some("5"))
+        .unwrap();
+    assert_eq!("Uint(5)", res.to_result_str());
+    assert!(res.gas.instructions > 0);
+    assert!(res.gas.storage > 0);
+    assert!(res.gas.intrinsic > 0);
+    assert_eq!(
+        res.gas.total(),
+        res.gas.instructions + res.gas.storage + res.gas.intrinsic
+    );
+}
+
+#[test]
+pub fn test_unmetered_by_default() {
+    init_log();
+    let (mut vm, bytecode) = executor();
+
+    assert_eq!(vm.deploy("0x42", bytecode).unwrap().total(), 0);
+    vm.run("0x42::gas_report::constructor", "0x42", None)
+        .unwrap();
+    let res = vm
+        .run("0x42::gas_report::add", "0x42", Some("1,2"))
+        .unwrap();
+    assert_eq!(res.gas.total(), 0);
+}
+
+#[test]
+pub fn test_out_of_gas() {
+    init_log();
+    let (mut vm, bytecode) = executor();
+    vm.deploy("0x42", bytecode).unwrap();
+    vm.run("0x42::gas_report::constructor", "0x42", None)
+        .unwrap();
+
+    vm.set_gas_schedule(GasSchedule::Standard, 1);
+    assert!(vm
+        .run("0x42::gas_report::sum", "0x42", Some("100"))
+        .is_err());
+
+    vm.set_gas_schedule(GasSchedule::Standard, DEFAULT_MAX_GAS);
+    let res = vm
+        .run("0x42::gas_report::sum", "0x42", Some("100"))
+        .unwrap();
+    assert_eq!("Uint(4950)", res.to_result_str());
+}
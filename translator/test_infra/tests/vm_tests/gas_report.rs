@@ -0,0 +1,94 @@
+use crate::testssol::sol_path;
+use eth::compile::build_sol;
+use eth::Flags;
+use ethabi::Token;
+use move_core_types::account_address::AccountAddress;
+use move_executor::gas::{GasSchedule, DEFAULT_MAX_GAS};
+use move_executor::{MoveExecutor, MoveExecutorInstance};
+use primitive_types::U256;
+use test_infra::init_log;
+use test_infra::revm::REvm;
+use translator::{estimate_gas, translate, Config};
+
+#[test]
+pub fn test_gas_report() {
+    init_log();
+    let evm = build_sol(sol_path().join("gas/gas_report.sol")).unwrap();
+    let cfg = Config::new(
+        AccountAddress::from_hex_literal("0x42").unwrap(),
+        evm.name(),
+    );
+    let target = translate(evm.contract().bin(), evm.contract().abi(), cfg).unwrap();
+    let report = estimate_gas(&target.bytecode, None).unwrap();
+
+    let add = report.function("add").unwrap();
+    assert!(add.loops.is_empty());
+    assert!(add.typical <= add.worst);
+    let sum = report.function("sum").unwrap();
+    assert!(!sum.loops.is_empty());
+    assert!(report.function("store").is_some());
+    assert!(report.function("constructor").is_some());
+
+    let mut vm = MoveExecutor::new(
+        evm.abi().unwrap(),
+        Flags::default(),
+        MoveExecutorInstance::Aptos,
+    );
+    vm.deploy("0x42", target.bytecode).unwrap();
+    vm.set_gas_schedule(GasSchedule::Instructions, DEFAULT_MAX_GAS);
+    vm.run("0x42::gas_report::constructor", "0x42", None)
+        .unwrap();
+
+    // The metered run of the loop-free function is close to the estimate.
+    let res = vm
+        .run("0x42::gas_report::add", "0x42", Some("1,2"))
+        .unwrap();
+    assert_eq!(res.returns, vec![Token::Uint(U256::from(3))]);
+    assert!(res.gas.instructions > 0);
+    assert!(
+        res.gas.instructions <= add.worst * 2,
+        "{} > 2 * {}",
+        res.gas.instructions,
+        add.worst
+    );
+    assert!(
+        res.gas.instructions * 2 >= add.typical,
+        "2 * {} < {}",
+        res.gas.instructions,
+        add.typical
+    );
+
+    // Every extra iteration costs about the per-iteration estimate.
+    let short = vm
+        .run("0x42::gas_report::sum", "0x42", Some("5"))
+        .unwrap()
+        .gas
+        .instructions;
+    let long = vm
+        .run("0x42::gas_report::sum", "0x42", Some("15"))
+        .unwrap()
+        .gas
+        .instructions;
+    let per_iteration = (long - short) / 10;
+    let estimate = sum.loops.iter().map(|lp| lp.per_iteration).max().unwrap();
+    assert!(
+        per_iteration <= estimate * 2,
+        "{per_iteration} > 2 * {estimate}"
+    );
+    assert!(
+        per_iteration * 2 >= estimate,
+        "2 * {per_iteration} < {estimate}"
+    );
+
+    // The EVM gas of the same function.
+    let abi = evm.abi().unwrap();
+    let mut revm = REvm::try_from(&evm).unwrap();
+    assert!(revm.construct(vec![]).unwrap() > 0);
+    let call = abi
+        .function("add")
+        .unwrap()
+        .encode_input(&[Token::Uint(U256::from(1)), Token::Uint(U256::from(2))])
+        .unwrap();
+    let (_, evm_gas) = revm.execute(call).unwrap();
+    assert!(evm_gas > 0);
+}
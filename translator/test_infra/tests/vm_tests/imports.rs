@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use crate::testssol::sol_path;
+use eth::compile::{build_sol, build_sol_with, EvmPack, SolcConfig};
+use eth::Flags;
+use move_core_types::account_address::AccountAddress;
+use move_executor::{MoveExecutor, MoveExecutorInstance};
+use test_infra::init_log;
+use translator::{translate, Config};
+
+fn run_counter(pack: &EvmPack) {
+    let cfg = Config::for_pack(AccountAddress::from_hex_literal("0x42").unwrap(), pack);
+    let target = translate(pack.bin_contract(), pack.abi_str(), cfg).unwrap();
+
+    let mut vm = MoveExecutor::new(
+        pack.abi().unwrap(),
+        Flags::default(),
+        MoveExecutorInstance::Aptos,
+    );
+    vm.deploy("0x42", target.bytecode).unwrap();
+    vm.run("0x42::counter::constructor", "0x42", None).unwrap();
+
+    vm.run("0x42::counter::inc", "0x42", Some("2")).unwrap();
+    let res = vm
+        .run("0x42::counter::inc", "0x42", Some("3"))
+        .unwrap()
+        .to_result_str();
+    assert_eq!("Uint(5)", res);
+    let res = vm
+        .run("0x42::counter::get", "0x42", None)
+        .unwrap()
+        .to_result_str();
+    assert_eq!("Uint(5)", res);
+}
+
+#[test]
+pub fn test_imports() {
+    init_log();
+    // The `@math/` prefix is resolved by `remappings.txt` next to the file.
+    let pack = build_sol(sol_path().join("imports/counter.sol")).unwrap();
+    assert_eq!(pack.contract_names(), vec!["counter"]);
+    run_counter(&pack);
+}
+
+#[test]
+pub fn test_solc_config() {
+    init_log();
+    let path = sol_path().join("imports/counter.sol");
+
+    // `remappings.txt` is only read from the base path.
+    let config = SolcConfig {
+        base_path: Some(sol_path()),
+        ..SolcConfig::default()
+    };
+    assert!(build_sol_with(&path, &config).is_err());
+
+    let config = SolcConfig {
+        base_path: Some(sol_path()),
+        remappings: vec!["@math/=imports/lib/".to_string()],
+        optimizer_runs: Some(1000),
+        ..SolcConfig::default()
+    };
+    let pack = build_sol_with(&path, &config).unwrap();
+    run_counter(&pack);
+
+    let config = SolcConfig {
+        via_ir: true,
+        ..config
+    };
+    let pack = build_sol_with(&path, &config).unwrap();
+    assert!(!pack.bin_contract().is_empty());
+
+    let config = SolcConfig {
+        solc: Some(PathBuf::from("solc-not-installed")),
+        ..SolcConfig::default()
+    };
+    assert!(build_sol_with(&path, &config).is_err());
+}
@@ -0,0 +1,65 @@
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::ModuleId;
+
+use crate::testssol::sol_path;
+use eth::compile::{build_sol, EvmPack};
+use eth::link::placeholders;
+use eth::Flags;
+use move_executor::{MoveExecutor, MoveExecutorInstance};
+use test_infra::init_log;
+use translator::{translate, Config, LibraryLink, Target};
+
+fn translate_pack(pack: &EvmPack) -> anyhow::Result<Target> {
+    let cfg = Config::for_pack(AccountAddress::from_hex_literal("0x42").unwrap(), pack);
+    translate(pack.bin_contract(), pack.abi_str(), cfg)
+}
+
+#[test]
+pub fn test_link_library() {
+    init_log();
+    let pack = build_sol(sol_path().join("link/link_calc.sol")).unwrap();
+    assert_eq!(pack.contract_names(), vec!["link_calc"]);
+    assert_eq!(pack.libraries().len(), 1);
+    let library = &pack.libraries()[0];
+    assert!(library.path.ends_with(":Calc"));
+    assert_eq!(library.name(), "Calc");
+    assert!(!placeholders(pack.bin_contract()).is_empty());
+
+    // The placeholders must be linked before the translation.
+    let err = translate_pack(&pack).unwrap_err();
+    assert!(err.to_string().contains("unlinked libraries"), "{err:?}");
+
+    let module = ModuleId::new(
+        AccountAddress::from_hex_literal("0x42").unwrap(),
+        Identifier::new("Calc").unwrap(),
+    );
+    let linked = pack
+        .link(&[LibraryLink::new(library.path.clone(), module)])
+        .unwrap();
+    assert!(placeholders(linked.bin_contract()).is_empty());
+    assert_eq!(linked.links().len(), 1);
+    // The functions of the library are known from its ABI.
+    assert_eq!(linked.links()[0].functions.len(), 1);
+
+    // The library is published before the contracts which call it.
+    let calc = translate_pack(&library.pack(linked.links()).unwrap()).unwrap();
+    let target = translate_pack(&linked).unwrap();
+    let mut vm = MoveExecutor::new(
+        linked.abi().unwrap(),
+        Flags::default(),
+        MoveExecutorInstance::Aptos,
+    );
+    vm.deploy("0x42", calc.bytecode).unwrap();
+    vm.run("0x42::Calc::constructor", "0x42", None).unwrap();
+    vm.deploy("0x42", target.bytecode).unwrap();
+    vm.run("0x42::link_calc::constructor", "0x42", None)
+        .unwrap();
+
+    // twice(0x15) is Calc.double(0x15) = 0x2a.
+    let res = vm
+        .run("0x42::link_calc::twice", "0x42", Some("21"))
+        .unwrap()
+        .to_result_str();
+    assert_eq!("Uint(42)", res);
+}
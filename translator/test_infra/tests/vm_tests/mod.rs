@@ -1,7 +1,24 @@
 mod address;
+mod artifacts;
+mod executor_env;
+mod executor_state;
+mod gas_metering;
+mod gas_report;
+mod imports;
+mod link;
 mod loops;
+mod move_client;
+mod move_source;
+mod multiple_contracts;
 mod mv_balance;
+mod native_mappings;
+mod native_values;
+mod remote_replay;
+mod reproducible;
+mod rust_bindings;
+mod shared_intrinsic;
 mod store;
 mod strings;
 mod template_crop;
+mod typed_state;
 mod users;
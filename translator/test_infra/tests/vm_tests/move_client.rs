@@ -0,0 +1,331 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use aptos_types::transaction::EntryFunction;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::ModuleId;
+
+use crate::testssol::sol_path;
+use eth::compile::build_sol;
+use eth::Flags;
+use move_executor::{MoveExecutor, MoveExecutorInstance};
+use test_infra::init_log;
+use translator::{translate, translate_intrinsic, Config, Target};
+
+const LIBRARY: &str = "0x41";
+
+fn library() -> Option<AccountAddress> {
+    Some(AccountAddress::from_hex_literal(LIBRARY).unwrap())
+}
+
+#[test]
+pub fn test_move_client() {
+    init_log();
+    let evm = build_sol(sol_path().join("client/bank.sol")).unwrap();
+    let cfg = Config {
+        intrinsic_address: library(),
+        ..Config::new(
+            AccountAddress::from_hex_literal("0x42").unwrap(),
+            evm.name(),
+        )
+    };
+    let target = translate(evm.contract().bin(), evm.contract().abi(), cfg).unwrap();
+    let client = target.client.as_ref().unwrap();
+
+    assert!(client
+        .source
+        .starts_with(&format!("module self::{}_client {{", evm.name())));
+    assert!(client.source.contains("use 0x41::intrinsic::{Self, U256};"));
+    assert!(client
+        .source
+        .contains("public fun deposit(account: &signer, amount: U256, memo: vector<u8>): U256 {"));
+    assert!(client
+        .source
+        .contains("let encoder = eth_abi::new_encoder(2);"));
+    assert!(client
+        .source
+        .contains("eth_abi::encode_word(&mut encoder, intrinsic::to_bytes(&amount));"));
+    assert!(client
+        .source
+        .contains("eth_abi::encode_bytes(&mut encoder, memo);"));
+    assert!(client
+        .source
+        .contains("public fun balanceOf(account: &signer, account_: address): (U256, bool) {"));
+    assert!(client.source.contains(
+        "(intrinsic::from_bytes(&eth_abi::decode_word(&result, 0), intrinsic::zero()), \
+        eth_abi::decode_bool(&result, 1))"
+    ));
+    assert!(client
+        .source
+        .contains("struct Deposit has copy, drop, store {"));
+    assert!(client
+        .source
+        .contains("public fun decode_deposit(data: vector<u8>): Deposit {"));
+    assert!(client
+        .source
+        .contains("public fun deposit_topic(): vector<u8> {"));
+    assert!(client.eth_abi.starts_with("/// Ethereum ABI"));
+    assert!(client
+        .manifest
+        .contains(&format!("{} = {{ local = \"..\" }}", evm.name())));
+    assert!(client.unsupported.is_empty());
+    test_client_package(&target, evm.name(), library());
+
+    // Without the shared library the wide integers have no Move type.
+    let cfg = Config::new(
+        AccountAddress::from_hex_literal("0x42").unwrap(),
+        evm.name(),
+    );
+    let target = translate(evm.contract().bin(), evm.contract().abi(), cfg).unwrap();
+    let client = target.client.as_ref().unwrap();
+    assert!(!client.source.contains("public fun deposit("));
+    assert_eq!(client.unsupported.len(), 3);
+    assert!(client.unsupported.iter().any(|item| item.starts_with(
+        "function `deposit`: uint256 is only wrapped with the shared intrinsic library"
+    )));
+
+    let cfg = Config {
+        flags: Flags {
+            hidden_output: true,
+            ..Flags::default()
+        },
+        intrinsic_address: library(),
+        ..Config::new(
+            AccountAddress::from_hex_literal("0x42").unwrap(),
+            evm.name(),
+        )
+    };
+    let target = translate(evm.contract().bin(), evm.contract().abi(), cfg).unwrap();
+    let client = target.client.as_ref().unwrap();
+    assert!(client
+        .source
+        .contains("public fun deposit(account: &signer, amount: U256, memo: vector<u8>) {"));
+    assert!(client
+        .source
+        .contains("        Bank::deposit(account, eth_abi::finish(encoder));\n"));
+    assert!(!client.source.contains("let result"));
+    test_client_package(&target, evm.name(), library());
+
+    let cfg = Config {
+        flags: Flags::native_interface(),
+        ..Config::new(
+            AccountAddress::from_hex_literal("0x42").unwrap(),
+            evm.name(),
+        )
+    };
+    let target = translate(evm.contract().bin(), evm.contract().abi(), cfg).unwrap();
+    assert!(target.client.is_none());
+}
+
+#[test]
+pub fn test_move_client_overloads() {
+    init_log();
+    let evm = build_sol(sol_path().join("client/overloads.sol")).unwrap();
+    let cfg = Config {
+        intrinsic_address: library(),
+        ..Config::new(
+            AccountAddress::from_hex_literal("0x42").unwrap(),
+            evm.name(),
+        )
+    };
+    let target = translate(evm.contract().bin(), evm.contract().abi(), cfg).unwrap();
+    let client = target.client.as_ref().unwrap();
+
+    assert!(client
+        .source
+        .contains("public fun get_6d4ce63c(account: &signer): U256 {"));
+    assert!(client
+        .source
+        .contains("let result = Overloads::get_6d4ce63c(account, eth_abi::finish(encoder));"));
+    assert!(client
+        .source
+        .contains("public fun get_9507d39a(account: &signer, val: U256): U256 {"));
+    assert!(target.interface.contains("get_6d4ce63c"));
+    assert!(target.interface.contains("get_9507d39a"));
+    assert!(client
+        .source
+        .contains("struct Moved_1dab0657 has copy, drop, store {"));
+    assert!(client
+        .source
+        .contains("public fun decode_moved_5f8c326f(data: vector<u8>): Moved_5f8c326f {"));
+
+    assert!(!client.source.contains("public fun neg("));
+    assert_eq!(client.unsupported.len(), 1);
+    assert!(client.unsupported[0].starts_with("function `neg`: Signed integers"));
+    assert!(client
+        .source
+        .contains(&format!("    // - {}", client.unsupported[0])));
+    test_client_package(&target, evm.name(), library());
+}
+
+/// Calls the wrappers of the client from a Move module against the translated module.
+#[test]
+pub fn test_move_client_call() {
+    init_log();
+    if !has_aptos() {
+        eprintln!("test_move_client_call is skipped: the aptos binary is not installed");
+        return;
+    }
+
+    let evm = build_sol(sol_path().join("client/bank.sol")).unwrap();
+    let cfg = Config {
+        intrinsic_address: library(),
+        ..Config::new(
+            AccountAddress::from_hex_literal("0x42").unwrap(),
+            evm.name(),
+        )
+    };
+    let target = translate(evm.contract().bin(), evm.contract().abi(), cfg).unwrap();
+
+    let dir = write_client_package(&target, evm.name(), library());
+    let client_dir = dir.join("client");
+    fs::write(
+        client_dir.join("sources").join("check.move"),
+        format!(
+            "module self::check {{
+    use {LIBRARY}::intrinsic;
+    use self::{name}_client;
+
+    public entry fun check(account: &signer) {{
+        let balance = {name}_client::deposit(account, intrinsic::from_u128(100), b\"memo\");
+        assert!(intrinsic::as_u128(balance) == 100, 1);
+        let balance = {name}_client::deposit(account, intrinsic::from_u128(20), b\"\");
+        assert!(intrinsic::as_u128(balance) == 120, 2);
+        let (balance, has_balance) = {name}_client::balanceOf(account, @0x42);
+        assert!(intrinsic::as_u128(balance) == 120 && has_balance, 3);
+        let (balance, has_balance) = {name}_client::balanceOf(account, @0x43);
+        assert!(intrinsic::as_u128(balance) == 0 && !has_balance, 4);
+    }}
+}}
+",
+            name = evm.name()
+        ),
+    )
+    .unwrap();
+    run_aptos(
+        &client_dir,
+        "compile",
+        &target.client.as_ref().unwrap().source,
+    );
+    let modules = client_dir
+        .join("build")
+        .join(format!("{}_client", evm.name()))
+        .join("bytecode_modules");
+    let client_modules = [
+        "eth_abi".to_string(),
+        format!("{}_client", evm.name()),
+        "check".to_string(),
+    ]
+    .iter()
+    .map(|module| fs::read(modules.join(format!("{module}.mv"))).unwrap())
+    .collect::<Vec<_>>();
+    fs::remove_dir_all(&dir).unwrap();
+
+    // The check module returns nothing and is not a part of the ABI.
+    let mut vm = MoveExecutor::new(
+        evm.abi().unwrap(),
+        Flags {
+            hidden_output: true,
+            ..Flags::default()
+        },
+        MoveExecutorInstance::Aptos,
+    );
+    vm.deploy(
+        LIBRARY,
+        translate_intrinsic(library().unwrap()).unwrap().bytecode,
+    )
+    .unwrap();
+    vm.deploy("0x42", target.bytecode).unwrap();
+    for module in client_modules {
+        vm.deploy("0x42", module).unwrap();
+    }
+
+    let address = AccountAddress::from_hex_literal("0x42").unwrap();
+    let entry = |module: &str, fun: &str| {
+        EntryFunction::new(
+            ModuleId::new(address, Identifier::new(module).unwrap()),
+            Identifier::new(fun).unwrap(),
+            vec![],
+            vec![],
+        )
+    };
+    vm.run_entry("0x42", entry(evm.name(), "constructor"))
+        .unwrap();
+    vm.run_entry("0x42", entry("check", "check")).unwrap();
+}
+
+fn has_aptos() -> bool {
+    Command::new("aptos").arg("--version").output().is_ok()
+}
+
+/// Writes the interface and the client packages like `e2m convert` does and runs the Move unit
+/// tests of the client package.
+fn test_client_package(target: &Target, name: &str, library: Option<AccountAddress>) {
+    if !has_aptos() {
+        return;
+    }
+    let dir = write_client_package(target, name, library);
+    run_aptos(
+        &dir.join("client"),
+        "test",
+        &target.client.as_ref().unwrap().source,
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Writes the interface package with the client package in its `client` subdirectory.
+/// The interface of the shared `library` is a part of the interface package.
+fn write_client_package(target: &Target, name: &str, library: Option<AccountAddress>) -> PathBuf {
+    let client = target.client.as_ref().unwrap();
+    let dir = std::env::temp_dir().join(format!("e2m_client_{name}_{}", std::process::id()));
+    if dir.exists() {
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    fs::create_dir_all(dir.join("sources")).unwrap();
+    fs::write(dir.join("Move.toml"), &target.manifest).unwrap();
+    fs::write(
+        dir.join("sources").join(format!("{name}.move")),
+        &target.interface,
+    )
+    .unwrap();
+    if let Some(address) = library {
+        fs::write(
+            dir.join("sources").join("intrinsic.move"),
+            translate_intrinsic(address).unwrap().interface,
+        )
+        .unwrap();
+    }
+
+    let client_dir = dir.join("client");
+    fs::create_dir_all(client_dir.join("sources")).unwrap();
+    fs::write(client_dir.join("Move.toml"), &client.manifest).unwrap();
+    fs::write(
+        client_dir
+            .join("sources")
+            .join(format!("{name}_client.move")),
+        &client.source,
+    )
+    .unwrap();
+    fs::write(
+        client_dir.join("sources").join("eth_abi.move"),
+        &client.eth_abi,
+    )
+    .unwrap();
+    dir
+}
+
+fn run_aptos(dir: &Path, command: &str, source: &str) {
+    let out = Command::new("aptos")
+        .current_dir(dir)
+        .args(["move", command])
+        .stderr(Stdio::inherit())
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}\n{source}",
+        String::from_utf8_lossy(&out.stdout),
+    );
+}
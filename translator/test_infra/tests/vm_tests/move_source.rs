@@ -0,0 +1,265 @@
+use std::fs;
+use std::process::{Command, Stdio};
+
+use crate::testssol::sol_path;
+use eth::compile::build_sol;
+use eth::Flags;
+use move_core_types::account_address::AccountAddress;
+use move_executor::{MoveExecutor, MoveExecutorInstance};
+use test_infra::init_log;
+use translator::{translate, translate_intrinsic, Config, Target};
+
+/// Intrinsic modules of the emitted source in the order of their dependencies.
+const INTRINSICS: [&str; 6] = ["utiles", "u512", "u256", "memory", "persist", "info"];
+
+/// Intrinsic modules of the emitted source with the shared library.
+const SHARED_INTRINSICS: [&str; 2] = ["persist", "info"];
+
+const LIBRARY: &str = "0x41";
+
+#[test]
+pub fn test_move_source() {
+    init_log();
+    let evm = build_sol(sol_path().join("mod/add.sol")).unwrap();
+    let cfg = Config {
+        flags: Flags::native_interface(),
+        ..Config::new(
+            AccountAddress::from_hex_literal("0x42").unwrap(),
+            evm.name(),
+        )
+    };
+    let target = translate(evm.contract().bin(), evm.contract().abi(), cfg).unwrap();
+
+    let source = target.source.unwrap();
+    assert!(source.starts_with(&format!("module self::{} {{", evm.name())));
+    assert!(source.contains("use self::u256::{Self, U256};"));
+    assert!(source.contains("public fun add_mod_u256(account_0: &signer"));
+    assert!(source.contains("u256::add_mod("));
+    assert!(source.contains("public fun constructor(account_0: &signer)"));
+
+    let modules = target
+        .intrinsics
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        modules,
+        vec!["info", "memory", "persist", "u256", "u512", "utiles"]
+    );
+    for (_, source) in &target.intrinsics {
+        assert!(source.contains(&format!("friend self::{};", evm.name())));
+    }
+    let (_, persist) = &target.intrinsics[2];
+    assert!(persist.contains("fun borrow_persist(): &mut Persist acquires Persist"));
+}
+
+#[test]
+pub fn test_shared_move_source() {
+    init_log();
+    let evm = build_sol(sol_path().join("mod/add.sol")).unwrap();
+    let cfg = Config {
+        flags: Flags::native_interface(),
+        intrinsic_address: Some(AccountAddress::from_hex_literal(LIBRARY).unwrap()),
+        ..Config::new(
+            AccountAddress::from_hex_literal("0x42").unwrap(),
+            evm.name(),
+        )
+    };
+    let target = translate(evm.contract().bin(), evm.contract().abi(), cfg).unwrap();
+
+    let source = target.source.unwrap();
+    assert!(source.contains("use 0x41::intrinsic::{Self as u256, U256};"));
+    assert!(source.contains("use 0x41::intrinsic::{Self as memory, Memory};"));
+    assert!(source.contains("u256::add_mod("));
+
+    let modules = target
+        .intrinsics
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(modules, vec!["info", "persist", "intrinsic"]);
+    let (_, persist) = &target.intrinsics[1];
+    assert!(persist.contains("use 0x41::intrinsic::{U256, zero, to_bytes, from_bytes};"));
+    assert!(!persist.contains("use self::u256"));
+    let (_, library) = &target.intrinsics[2];
+    assert!(library.starts_with("module 0x41::intrinsic {"));
+    assert!(library.contains("struct U256 has copy, drop, store {"));
+    assert!(library.contains("public native fun add_mod("));
+}
+
+fn has_aptos() -> bool {
+    Command::new("aptos").arg("--version").output().is_ok()
+}
+
+/// Builds the emitted source with the Move compiler. Returns the `modules` in the order of their
+/// dependencies, the contract is the last.
+fn compile_source(target: &Target, name: &str, modules: &[&str]) -> Vec<Vec<u8>> {
+    let dir = std::env::temp_dir().join(format!("e2m_source_{name}_{}", std::process::id()));
+    let sources = dir.join("sources");
+    fs::create_dir_all(&sources).unwrap();
+    let source = target.source.as_ref().unwrap();
+    fs::write(dir.join("Move.toml"), &target.manifest).unwrap();
+    fs::write(sources.join(format!("{name}.move")), source).unwrap();
+    for (module, source) in &target.intrinsics {
+        fs::write(sources.join(format!("{module}.move")), source).unwrap();
+    }
+
+    let out = Command::new("aptos")
+        .current_dir(&dir)
+        .args(["move", "compile"])
+        .stderr(Stdio::inherit())
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}\n{source}",
+        String::from_utf8_lossy(&out.stdout),
+    );
+
+    let build = dir.join("build").join(name).join("bytecode_modules");
+    let bytecode = modules
+        .iter()
+        .chain([&name])
+        .map(|module| fs::read(build.join(format!("{module}.mv"))).unwrap())
+        .collect();
+    fs::remove_dir_all(&dir).unwrap();
+    bytecode
+}
+
+#[test]
+pub fn test_compiled_source() {
+    init_log();
+    if !has_aptos() {
+        eprintln!("test_compiled_source is skipped: the aptos binary is not installed");
+        return;
+    }
+
+    let cases = [
+        (
+            "loop/for.sol",
+            vec![
+                ("for_loop", "13, 1000"),
+                ("for_loop", "49, 0"),
+                ("for_static", ""),
+            ],
+        ),
+        ("loop/while.sol", vec![("sum", "2, 10"), ("sum", "3, 0")]),
+        (
+            "if_fn.sol",
+            vec![
+                ("test_1", "true, true, true"),
+                ("test_1", "true, true, false"),
+                ("test_1", "true, false, true"),
+                ("test_1", "false, true, true"),
+                ("test_2", "true, 1, 2"),
+                ("test_2", "false, 1, 2"),
+            ],
+        ),
+        (
+            "operators/bitwise/simple.sol",
+            vec![
+                ("or_uint", "20, 10"),
+                ("xor_uint", "20, 10"),
+                ("leftshift_uint", "20, 10"),
+                ("rightshift_uint", "20, 10"),
+                ("and_uint", "20, 20"),
+                ("not_uint", "20"),
+            ],
+        ),
+        (
+            "operators/arithmetic/plusminus.sol",
+            vec![
+                ("plusminus_params", "1, 2, 3"),
+                // Overflows, both modules abort.
+                (
+                    "plusminus_params",
+                    "0, 340282366920938463463374607431768211455, 0",
+                ),
+            ],
+        ),
+        (
+            "mod/add.sol",
+            vec![
+                ("add_mod_u256", "100, 5, 2000"),
+                ("add_mod_u256", "42971, 8723, 793"),
+                ("add_mod_u256_max", ""),
+            ],
+        ),
+        (
+            "store/load_store.sol",
+            vec![
+                ("get_all", ""),
+                ("set_all", "1, 2, false, false"),
+                ("get_all", ""),
+                ("set_b", "7"),
+                ("get_b", ""),
+                ("get_flag", ""),
+            ],
+        ),
+    ];
+
+    for (path, calls) in cases {
+        assert_source_equivalence(path, &calls, None);
+    }
+    assert_source_equivalence(
+        "store/load_store.sol",
+        &[("set_a", "5"), ("get_all", "")],
+        Some(LIBRARY),
+    );
+}
+
+/// Runs the `calls` on the translated module and on the module compiled from its emitted source,
+/// and compares the results. Both modules are linked to the shared library at `library` if set.
+fn assert_source_equivalence(path: &str, calls: &[(&str, &str)], library: Option<&str>) {
+    let evm = build_sol(sol_path().join(path)).unwrap();
+    let library = library.map(|address| AccountAddress::from_hex_literal(address).unwrap());
+    let cfg = Config {
+        flags: Flags::native_interface(),
+        intrinsic_address: library,
+        ..Config::new(
+            AccountAddress::from_hex_literal("0x42").unwrap(),
+            evm.name(),
+        )
+    };
+    let target = translate(evm.contract().bin(), evm.contract().abi(), cfg).unwrap();
+    assert!(!target.source.as_ref().unwrap().contains("pc = "));
+
+    let mut module_vm = MoveExecutor::new(
+        evm.abi().unwrap(),
+        Flags::native_interface(),
+        MoveExecutorInstance::Aptos,
+    );
+    let mut source_vm = MoveExecutor::new(
+        evm.abi().unwrap(),
+        Flags::native_interface(),
+        MoveExecutorInstance::Aptos,
+    );
+    let intrinsics = match library {
+        Some(address) => {
+            let bytecode = translate_intrinsic(address).unwrap().bytecode;
+            let address = address.to_hex_literal();
+            module_vm.deploy(&address, bytecode.clone()).unwrap();
+            source_vm.deploy(&address, bytecode).unwrap();
+            &SHARED_INTRINSICS[..]
+        }
+        None => &INTRINSICS[..],
+    };
+    module_vm.deploy("0x42", target.bytecode.clone()).unwrap();
+    for module in compile_source(&target, evm.name(), intrinsics) {
+        source_vm.deploy("0x42", module).unwrap();
+    }
+
+    let constructor = format!("0x42::{}::constructor", evm.name());
+    module_vm.run(&constructor, "0x42", None).unwrap();
+    source_vm.run(&constructor, "0x42", None).unwrap();
+    for (fun, args) in calls {
+        let ident = format!("0x42::{}::{fun}", evm.name());
+        let expected = module_vm
+            .run(&ident, "0x42", Some(*args))
+            .map(|res| res.to_result_str());
+        let actual = source_vm
+            .run(&ident, "0x42", Some(*args))
+            .map(|res| res.to_result_str());
+        assert_eq!(expected.ok(), actual.ok(), "{path} {fun}({args})");
+    }
+}
@@ -0,0 +1,77 @@
+use crate::testssol::sol_path;
+use eth::compile::build_sol;
+use eth::Flags;
+use move_core_types::account_address::AccountAddress;
+use move_executor::{MoveExecutor, MoveExecutorInstance};
+use test_infra::init_log;
+use translator::{translate, Config};
+
+#[test]
+pub fn test_select_contract() {
+    init_log();
+    let pack = build_sol(sol_path().join("contracts/multiple_contracts.sol")).unwrap();
+
+    // The interface and the abstract contract are not deployable.
+    let mut names = pack.contract_names();
+    names.sort();
+    assert_eq!(names, vec!["adder", "counter"]);
+
+    let counter = pack.select("counter").unwrap();
+    assert_eq!(counter.name(), "counter");
+    assert!(counter.abi().unwrap().function("inc").is_ok());
+    assert!(counter.abi().unwrap().function("add").is_err());
+
+    assert!(pack.select("ICounter").is_err());
+    assert!(pack.select("Named").is_err());
+}
+
+#[test]
+pub fn test_all_contracts() {
+    init_log();
+    let pack = build_sol(sol_path().join("contracts/multiple_contracts.sol")).unwrap();
+    let packs = pack.split();
+    assert_eq!(packs.len(), 2);
+
+    for pack in packs {
+        let cfg = Config::for_pack(AccountAddress::from_hex_literal("0x42").unwrap(), &pack);
+        let target = translate(pack.bin_contract(), pack.abi_str(), cfg).unwrap();
+        assert!(target
+            .source
+            .as_ref()
+            .unwrap()
+            .starts_with(&format!("module self::{} {{", pack.name())));
+
+        let mut vm = MoveExecutor::new(
+            pack.abi().unwrap(),
+            Flags::default(),
+            MoveExecutorInstance::Aptos,
+        );
+        vm.deploy("0x42", target.bytecode).unwrap();
+        vm.run(&format!("0x42::{}::constructor", pack.name()), "0x42", None)
+            .unwrap();
+
+        match pack.name() {
+            "counter" => {
+                vm.run("0x42::counter::inc", "0x42", None).unwrap();
+                let res = vm
+                    .run("0x42::counter::inc", "0x42", None)
+                    .unwrap()
+                    .to_result_str();
+                assert_eq!("Uint(2)", res);
+                let res = vm
+                    .run("0x42::counter::id", "0x42", None)
+                    .unwrap()
+                    .to_result_str();
+                assert_eq!("Uint(1)", res);
+            }
+            "adder" => {
+                let res = vm
+                    .run("0x42::adder::add", "0x42", Some("2,3"))
+                    .unwrap()
+                    .to_result_str();
+                assert_eq!("Uint(5)", res);
+            }
+            name => panic!("Unexpected contract {name}"),
+        }
+    }
+}
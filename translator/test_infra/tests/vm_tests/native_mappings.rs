@@ -0,0 +1,148 @@
+use crate::testssol::sol_path;
+use eth::compile::build_sol;
+use eth::layout::entry_slot;
+use eth::Flags;
+use ethabi::ethereum_types::U256;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::ModuleId;
+use move_executor::{solidity::FromSolidity, MoveExecutor};
+use primitive_types::U256 as PU256;
+use test_infra::init_log;
+use translator::{translate, Config};
+
+fn flags() -> Flags {
+    Flags {
+        native_mappings: true,
+        ..Flags::default()
+    }
+}
+
+#[test]
+pub fn test_native_mappings_source() {
+    init_log();
+    let evm = build_sol(sol_path().join("store/native_mappings.sol")).unwrap();
+    let cfg = Config {
+        flags: flags(),
+        ..Config::new(
+            AccountAddress::from_hex_literal("0x42").unwrap(),
+            evm.name(),
+        )
+    };
+    let source = translate(evm.contract().bin(), evm.contract().abi(), cfg)
+        .unwrap()
+        .source
+        .unwrap();
+
+    assert!(source.contains("persist::mapping_load("));
+    assert!(source.contains("persist::mapping_store("));
+    // Only the hashed slots of the loop look up the entries kept by the keys.
+    assert!(source.contains("persist::sload_hashed("));
+    assert!(source.contains("persist::sload("));
+}
+
+#[test]
+pub fn test_native_mappings_address_keys() {
+    init_log();
+    let evm = build_sol(sol_path().join("store/native_mappings.sol")).unwrap();
+    let cfg = Config {
+        flags: flags(),
+        ..Config::for_pack(AccountAddress::from_hex_literal("0x42").unwrap(), &evm)
+    };
+    let source = translate(evm.contract().bin(), evm.contract().abi(), cfg)
+        .unwrap()
+        .source
+        .unwrap();
+
+    // The storage layout types the keys: `balances` and the outer key of `allowances` are addresses.
+    assert!(source.contains("vector[u256::to_address("));
+    assert!(!source.contains("vector[], vector["));
+}
+
+#[test]
+pub fn test_native_mappings() {
+    init_log();
+
+    let mut vm = MoveExecutor::from_sol(
+        "sol/store/native_mappings.sol",
+        AccountAddress::from_hex_literal("0x42").unwrap(),
+        "",
+        flags(),
+    )
+    .unwrap();
+
+    vm.run("0x42::native_mappings::constructor", "0x42", None)
+        .unwrap();
+
+    let res = vm
+        .run("0x42::native_mappings::balanceOf", "0x42", Some("0x42"))
+        .unwrap()
+        .to_result_str();
+    assert_eq!("Uint(100)", res);
+    let res = vm
+        .run("0x42::native_mappings::deposit", "0x42", Some("50"))
+        .unwrap();
+    assert_eq!("Uint(150)", res.to_result_str());
+    // The entries kept by the keys and the typed fields are reported by their raw slots.
+    let balance = U256(entry_slot(PU256::from(0x42), PU256::zero()).0);
+    assert_eq!(Some(&U256::from(150)), res.storage.get(&balance));
+    assert_eq!(Some(&U256::from(150)), res.storage.get(&U256::from(2)));
+    let module = ModuleId::new(
+        AccountAddress::from_hex_literal("0x42").unwrap(),
+        Identifier::new("native_mappings").unwrap(),
+    );
+    let storage = vm.storage(&module).unwrap();
+    assert_eq!(Some(&U256::from(150)), storage.get(&balance));
+    assert_eq!(Some(&U256::from(150)), storage.get(&U256::from(2)));
+    // The slot is hashed in another block than the one which reads it.
+    let res = vm
+        .run(
+            "0x42::native_mappings::rawBalance",
+            "0x42",
+            Some("0x42,true"),
+        )
+        .unwrap()
+        .to_result_str();
+    assert_eq!("Uint(151)", res);
+    let res = vm
+        .run(
+            "0x42::native_mappings::rawBalance",
+            "0x42",
+            Some("0x42,false"),
+        )
+        .unwrap()
+        .to_result_str();
+    assert_eq!("Uint(150)", res);
+    let res = vm
+        .run("0x42::native_mappings::balanceOf", "0x42", Some("0x43"))
+        .unwrap()
+        .to_result_str();
+    assert_eq!("Uint(0)", res);
+
+    let res = vm
+        .run("0x42::native_mappings::allowance", "0x42", Some("0x42,1"))
+        .unwrap()
+        .to_result_str();
+    assert_eq!("Uint(10)", res);
+    let res = vm
+        .run("0x42::native_mappings::approve", "0x42", Some("2,5"))
+        .unwrap()
+        .to_result_str();
+    assert_eq!("Uint(5)", res);
+    let res = vm
+        .run("0x42::native_mappings::approve", "0x42", Some("1,5"))
+        .unwrap()
+        .to_result_str();
+    assert_eq!("Uint(15)", res);
+
+    // The loop reads the entries through the raw slots.
+    let res = vm
+        .run(
+            "0x42::native_mappings::sumAllowances",
+            "0x42",
+            Some("0x42,3"),
+        )
+        .unwrap()
+        .to_result_str();
+    assert_eq!("Uint(20)", res);
+}
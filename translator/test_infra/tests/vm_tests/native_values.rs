@@ -0,0 +1,50 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use ethabi::Contract;
+
+use eth::Flags;
+use move_executor::{MoveExecutor, MoveExecutorInstance};
+use test_infra::init_log;
+
+fn me() -> Result<MoveExecutor> {
+    let path = PathBuf::from("./resources/mv/build/test_helper/bytecode_modules/helper.mv")
+        .canonicalize()?;
+    let bytecode = fs::read(path)?;
+    let abi: Contract = serde_json::from_str("[]")?;
+
+    let mut vm = MoveExecutor::new(abi, Flags::native_interface(), MoveExecutorInstance::Aptos);
+    vm.deploy("0x1", bytecode)?;
+
+    Ok(vm)
+}
+
+#[test]
+pub fn test_string() {
+    init_log();
+
+    let mut vm = me().unwrap();
+    let result = vm
+        .run_native("0x1::helper::hello", "0x42", Some("\"world, again\""))
+        .unwrap();
+    assert_eq!(r#"String("hello, world, again")"#, result.to_result_str());
+}
+
+#[test]
+pub fn test_nested_vectors() {
+    init_log();
+
+    let mut vm = me().unwrap();
+    let result = vm
+        .run_native("0x1::helper::reverse", "0x42", Some("[[1, 2], [], [3]]"))
+        .unwrap();
+    assert_eq!(
+        "Array([Array([Uint(3)]), Array([]), Array([Uint(1), Uint(2)])])",
+        result.to_result_str()
+    );
+
+    assert!(vm
+        .run_native("0x1::helper::reverse", "0x42", Some("[1, 2]"))
+        .is_err());
+}
@@ -0,0 +1,202 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::thread;
+
+use aptos_types::state_store::state_key::StateKey;
+use ethabi::Contract;
+use move_core_types::account_address::AccountAddress;
+
+use eth::Flags;
+use move_executor::load::fixture::Remote;
+use move_executor::load::{load_table_handle_u256, LoadRemoteData};
+use move_executor::profile::ProfileConfig;
+use move_executor::{MoveExecutor, MoveExecutorInstance};
+use test_infra::init_log;
+
+const HANDLE: &str = "0xa1";
+const BCS: &str = "application/x-bcs";
+const JSON: &str = "application/json";
+
+/// Local stand-in of the node. Serves the `routes` by the request path and the accepted format.
+fn serve(routes: HashMap<(String, String), Vec<u8>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let path = line
+                .split_whitespace()
+                .nth(1)
+                .unwrap_or_default()
+                .to_string();
+            let mut len = 0;
+            let mut accept = String::new();
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, val)) = header.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        len = val.trim().parse().unwrap();
+                    } else if name.eq_ignore_ascii_case("accept") {
+                        accept = val.trim().to_string();
+                    }
+                }
+            }
+            let mut body = vec![0; len];
+            reader.read_exact(&mut body).unwrap();
+
+            let (status, body) = match routes.get(&(path, accept)) {
+                Some(body) => ("200 OK", body.clone()),
+                None => ("404 Not Found", vec![]),
+            };
+            write!(
+                stream,
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            )
+            .unwrap();
+            stream.write_all(&body).unwrap();
+        }
+    });
+    format!("http://{address}")
+}
+
+fn routes() -> HashMap<(String, String), Vec<u8>> {
+    let modules = BTreeMap::from([("0x42::demo".to_string(), vec![1, 2, 3])]);
+    let resources = serde_json::json!([{
+        "type": "0x42::demo::Persist",
+        "data": {
+            "events": {"counter": "0"},
+            "tbl": {"handle": HANDLE},
+        }
+    }]);
+    [
+        ("/v1/accounts/0x42/modules", BCS, bcs_map(&modules)),
+        (
+            "/v1/accounts/0x42/resources",
+            JSON,
+            serde_json::to_vec(&resources).unwrap(),
+        ),
+        // The node has no resources besides the table handle.
+        (
+            "/v1/accounts/0x42/resources",
+            BCS,
+            bcs_map(&BTreeMap::new()),
+        ),
+        ("/v1/tables/0xa1/item", BCS, vec![7; 32]),
+    ]
+    .into_iter()
+    .map(|(path, accept, body)| ((path.to_string(), accept.to_string()), body))
+    .collect()
+}
+
+/// BCS of the map of the strings to the bytes.
+fn bcs_map(map: &BTreeMap<String, Vec<u8>>) -> Vec<u8> {
+    fn uleb(mut val: usize, buf: &mut Vec<u8>) {
+        while val >= 0x80 {
+            buf.push((val as u8 & 0x7f) | 0x80);
+            val >>= 7;
+        }
+        buf.push(val as u8);
+    }
+
+    let mut buf = vec![];
+    uleb(map.len(), &mut buf);
+    for (key, val) in map {
+        uleb(key.len(), &mut buf);
+        buf.extend(key.as_bytes());
+        uleb(val.len(), &mut buf);
+        buf.extend(val);
+    }
+    buf
+}
+
+/// Fixtures recorded from the node of [`routes`].
+fn fixtures() -> PathBuf {
+    PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap()).join("fixtures/remote")
+}
+
+fn executor(remote: Remote) -> MoveExecutor {
+    MoveExecutor::with_remote(
+        Contract::default(),
+        Flags::default(),
+        MoveExecutorInstance::None,
+        remote,
+    )
+}
+
+/// Loads the modules, the resources and a table item of `0x42` from the node at `rest_url`.
+fn load(rest_url: &str, remote: Remote) -> (HashMap<StateKey, Vec<u8>>, Option<Vec<u8>>) {
+    let address = AccountAddress::from_hex_literal("0x42").unwrap();
+    let mut profile = ProfileConfig::vm(address);
+    profile.rest_url = Some(rest_url.to_string());
+
+    let mut vm = executor(remote);
+    vm.load_all(&profile, &address).unwrap();
+
+    let handle = AccountAddress::from_str(HANDLE).unwrap();
+    let item = load_table_handle_u256(
+        &vm.resolver.handler_data[&handle],
+        &vec![5; 32],
+        &vm.resolver.remote,
+    )
+    .unwrap();
+    (vm.resolver.state_data, item)
+}
+
+fn file_names(dir: &Path) -> BTreeSet<String> {
+    fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect()
+}
+
+#[test]
+pub fn test_replay() {
+    init_log();
+    // Nothing listens on the port, so each response comes from the fixtures.
+    let (state, item) = load("http://127.0.0.1:1", Remote::Replay(fixtures()));
+    assert_eq!(state.len(), 1);
+    assert_eq!(item, Some(vec![7; 32]));
+
+    let address = AccountAddress::from_hex_literal("0x42").unwrap();
+    let mut profile = ProfileConfig::vm(address);
+    profile.rest_url = Some("http://127.0.0.1:1".to_string());
+    let mut vm = executor(Remote::Replay(fixtures().join("empty")));
+    assert!(vm.load_modules(&profile, &address).is_err());
+}
+
+#[test]
+pub fn test_record() {
+    init_log();
+    let dir = std::env::temp_dir().join(format!("e2m_fixtures_{}", std::process::id()));
+    let node = serve(routes());
+
+    let recorded = load(&node, Remote::Record(dir.clone()));
+    let live = load(&node, Remote::Live);
+    assert_eq!(recorded, live);
+
+    // The same requests are recorded as the fixtures of the replay.
+    assert_eq!(file_names(&dir), file_names(&fixtures()));
+    assert_eq!(
+        recorded,
+        load("http://127.0.0.1:1", Remote::Replay(dir.clone()))
+    );
+    assert_eq!(
+        recorded,
+        load("http://127.0.0.1:1", Remote::Replay(fixtures()))
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
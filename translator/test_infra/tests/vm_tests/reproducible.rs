@@ -0,0 +1,76 @@
+use std::fs;
+use std::process::Command;
+
+use crate::testssol::{make_move_module, sol_path};
+use eth::compile::build_sol;
+use eth::Flags;
+use test_infra::init_log;
+
+/// File the child process writes the translated module to.
+const OUT_VAR: &str = "E2M_REPRODUCIBLE_OUT";
+
+fn translate() -> Vec<u8> {
+    let evm = build_sol(sol_path().join("operators/arithmetic/simple.sol")).unwrap();
+    make_move_module(
+        &format!("0x42::{}", evm.name()),
+        evm.contract().bin(),
+        "",
+        evm.contract().abi(),
+        Flags::default(),
+    )
+    .unwrap()
+}
+
+#[test]
+pub fn test_reproducible_translation() {
+    init_log();
+
+    let bytecode = translate();
+    for _ in 0..5 {
+        assert_eq!(bytecode, translate());
+    }
+}
+
+/// Each process seeds the hash maps anew, so an order which depends on the hashes differs
+/// between the processes.
+#[test]
+pub fn test_reproducible_across_processes() {
+    init_log();
+
+    let bytecode = translate();
+    let exe = std::env::current_exe().unwrap();
+    for idx in 0..3 {
+        let path =
+            std::env::temp_dir().join(format!("e2m_reproducible_{}_{idx}.mv", std::process::id()));
+        let output = Command::new(&exe)
+            .args([
+                "--ignored",
+                "--exact",
+                "vm_tests::reproducible::translate_to_file",
+            ])
+            .env(OUT_VAR, &path)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+        let child = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            bytecode, child,
+            "The translation differs in the process {idx}"
+        );
+    }
+}
+
+/// Writes the translated module to the file of `E2M_REPRODUCIBLE_OUT`.
+/// Run by `test_reproducible_across_processes` in the child processes.
+#[test]
+#[ignore]
+pub fn translate_to_file() {
+    if let Ok(path) = std::env::var(OUT_VAR) {
+        fs::write(path, translate()).unwrap();
+    }
+}
@@ -0,0 +1,280 @@
+use aptos_types::transaction::EntryFunction;
+use ethabi::ethereum_types::{H160, U256};
+use ethabi::{Contract, Token};
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::ModuleId;
+
+use bindings::rust_bindings;
+use eth::abi::codec;
+use eth::compile::build_sol;
+use eth::Flags;
+use move_executor::{MoveExecutor, MoveExecutorInstance};
+use test_infra::init_log;
+use translator::{translate, Config};
+
+use crate::testssol::sol_path;
+
+/// Bindings generated by the build script.
+#[allow(dead_code)]
+mod bank {
+    include!(concat!(env!("OUT_DIR"), "/Bank.rs"));
+}
+
+#[allow(dead_code)]
+mod overloads {
+    include!(concat!(env!("OUT_DIR"), "/Overloads.rs"));
+}
+
+#[test]
+pub fn test_rust_bindings() {
+    init_log();
+    let evm = build_sol(sol_path().join("client/bank.sol")).unwrap();
+
+    let bindings = rust_bindings(evm.name(), evm.contract().abi(), Flags::default()).unwrap();
+    assert!(bindings.unsupported.is_empty());
+    let bindings = bindings.source;
+    assert!(bindings.starts_with("// Rust bindings of the `Bank` Move module."));
+    assert!(bindings.contains("const ABI: &str = r#\"["));
+    assert!(bindings.contains("pub struct Bank {"));
+    assert!(bindings
+        .contains("pub fn deposit(&self, amount: U256, memo: String) -> Result<EntryFunction> {"));
+    assert!(bindings.contains("pub fn balance_of(&self, account: H160) -> Result<EntryFunction> {"));
+    assert!(bindings.contains("codec::encode_eth_args(self.function([0x"));
+    assert!(bindings.contains("self.entry(\"balanceOf\", vec![args])"));
+    assert!(bindings.contains(
+        "pub fn decode_balance_of(&self, returns: &[(Vec<u8>, MoveTypeLayout)]) -> Result<(U256, bool)> {"
+    ));
+    assert!(bindings.contains("pub struct DepositEvent {"));
+    assert!(bindings
+        .contains("pub fn decode_deposit_event(&self, event: &[u8]) -> Result<DepositEvent> {"));
+
+    let bindings = rust_bindings(
+        evm.name(),
+        evm.contract().abi(),
+        Flags {
+            native_input: true,
+            native_output: true,
+            hidden_output: false,
+            u128_io: true,
+            native_mappings: false,
+        },
+    )
+    .unwrap()
+    .source;
+    assert!(bindings
+        .contains("pub fn deposit(&self, amount: u128, memo: String) -> Result<EntryFunction> {"));
+    assert!(bindings.contains("codec::encode_move_arg(token, true)"));
+    assert!(bindings.contains("codec::decode_move_values(returns, &self.module)"));
+
+    // The raw string of the abi is closed by more hashes than it contains.
+    let abi = evm.contract().abi().replace(
+        "\"internalType\":\"string\"",
+        "\"internalType\":\"string\\\"###\"",
+    );
+    let bindings = rust_bindings(evm.name(), &abi, Flags::default()).unwrap();
+    assert!(bindings.source.contains("const ABI: &str = r####\"["));
+    assert!(bindings.source.contains("\"####;"));
+}
+
+#[test]
+pub fn test_rust_bindings_overloads() {
+    init_log();
+    let evm = build_sol(sol_path().join("client/overloads.sol")).unwrap();
+
+    let bindings = rust_bindings(evm.name(), evm.contract().abi(), Flags::default()).unwrap();
+    assert!(bindings.unsupported.is_empty());
+    let source = bindings.source;
+    assert!(source.contains("pub fn get_6d4ce63c(&self) -> Result<EntryFunction> {"));
+    assert!(source.contains("self.entry(\"get_6d4ce63c\", vec![args])"));
+    assert!(source.contains("pub fn get_9507d39a(&self, val: U256) -> Result<EntryFunction> {"));
+    assert!(source.contains("pub fn neg(&self, val: U256) -> Result<EntryFunction> {"));
+    assert!(source.contains("Token::Int(val)"));
+    assert!(source.contains("/// The `int` arguments are the two's complement `U256`."));
+    assert!(source.contains("pub struct Moved1dab0657Event {"));
+    assert!(source.contains(
+        "pub fn decode_moved_5f8c326f_event(&self, event: &[u8]) -> Result<Moved5f8c326fEvent> {"
+    ));
+
+    let bindings = rust_bindings(
+        evm.name(),
+        evm.contract().abi(),
+        Flags {
+            native_input: true,
+            native_output: true,
+            hidden_output: false,
+            u128_io: true,
+            native_mappings: false,
+        },
+    )
+    .unwrap();
+    assert_eq!(bindings.unsupported.len(), 1);
+    assert!(bindings.unsupported[0].starts_with("function `neg`: Signed integers"));
+    assert!(bindings
+        .source
+        .contains(&format!("// - {}", bindings.unsupported[0])));
+    assert!(!bindings.source.contains("pub fn neg("));
+}
+
+#[test]
+pub fn test_rust_bindings_decoders() {
+    init_log();
+    // The decoder of `get` is named as the payload builder of `decodeGet`.
+    let abi = r#"[
+        {"type":"function","name":"decodeGet","inputs":[],"outputs":[],"stateMutability":"view"},
+        {"type":"function","name":"get","inputs":[],"outputs":[],"stateMutability":"view"}
+    ]"#;
+    let source = rust_bindings("Getter", abi, Flags::default())
+        .unwrap()
+        .source;
+    assert!(source.contains("pub fn decode_get(&self) -> Result<EntryFunction> {"));
+    assert!(source.contains("pub fn decode_decode_get(&self, returns"));
+    assert!(source.contains("pub fn get_1(&self) -> Result<EntryFunction> {"));
+    assert!(source.contains("pub fn decode_get_1(&self, returns"));
+    assert!(source.contains("self.entry(\"get\", vec![args])"));
+}
+
+#[test]
+pub fn test_compiled_bindings() {
+    init_log();
+    let address = AccountAddress::from_hex_literal("0x42").unwrap();
+    let sender = H160::from_low_u64_be(0x42);
+
+    let evm = build_sol(sol_path().join("client/bank.sol")).unwrap();
+    let target = translate(
+        evm.contract().bin(),
+        evm.contract().abi(),
+        Config::new(address, evm.name()),
+    )
+    .unwrap();
+    let mut vm = MoveExecutor::new(
+        evm.abi().unwrap(),
+        Flags::default(),
+        MoveExecutorInstance::Aptos,
+    );
+    vm.deploy("0x42", target.bytecode).unwrap();
+
+    let bank = bank::Bank::new(address).unwrap();
+    vm.run_entry("0x42", bank.constructor().unwrap()).unwrap();
+    let res = vm
+        .run_entry(
+            "0x42",
+            bank.deposit(U256::from(100), "hi".to_string()).unwrap(),
+        )
+        .unwrap();
+    assert_eq!(
+        bank.decode_deposit(&res.raw_returns).unwrap(),
+        U256::from(100)
+    );
+    let event = res.events.last().unwrap();
+    assert_eq!(
+        bank.decode_deposit_event(&event.3).unwrap(),
+        bank::DepositEvent {
+            owner: sender,
+            amount: U256::from(100),
+            memo: "hi".to_string(),
+        }
+    );
+    let res = vm
+        .run_entry("0x42", bank.balance_of(sender).unwrap())
+        .unwrap();
+    assert_eq!(
+        bank.decode_balance_of(&res.raw_returns).unwrap(),
+        (U256::from(100), true)
+    );
+
+    let evm = build_sol(sol_path().join("client/overloads.sol")).unwrap();
+    let target = translate(
+        evm.contract().bin(),
+        evm.contract().abi(),
+        Config::new(address, evm.name()),
+    )
+    .unwrap();
+    let mut vm = MoveExecutor::new(
+        evm.abi().unwrap(),
+        Flags::default(),
+        MoveExecutorInstance::Aptos,
+    );
+    vm.deploy("0x42", target.bytecode).unwrap();
+
+    let client = overloads::Overloads::new(address).unwrap();
+    vm.run_entry("0x42", client.constructor().unwrap()).unwrap();
+    let res = vm
+        .run_entry("0x42", client.get_6d4ce63c().unwrap())
+        .unwrap();
+    assert_eq!(
+        client.decode_get_6d4ce63c(&res.raw_returns).unwrap(),
+        U256::one()
+    );
+    let res = vm
+        .run_entry("0x42", client.get_9507d39a(U256::from(41)).unwrap())
+        .unwrap();
+    assert_eq!(
+        client.decode_get_9507d39a(&res.raw_returns).unwrap(),
+        U256::from(42)
+    );
+
+    // -5 and 5 in two's complement.
+    let minus_five = U256::MAX - U256::from(4);
+    let res = vm
+        .run_entry("0x42", client.neg(minus_five).unwrap())
+        .unwrap();
+    assert_eq!(client.decode_neg(&res.raw_returns).unwrap(), U256::from(5));
+    let res = vm
+        .run_entry("0x42", client.neg(U256::from(5)).unwrap())
+        .unwrap();
+    assert_eq!(client.decode_neg(&res.raw_returns).unwrap(), minus_five);
+}
+
+#[test]
+pub fn test_shared_codecs() {
+    init_log();
+    let address = AccountAddress::from_hex_literal("0x42").unwrap();
+    let evm = build_sol(sol_path().join("client/bank.sol")).unwrap();
+    let cfg = Config::new(address, evm.name());
+    let target = translate(evm.contract().bin(), evm.contract().abi(), cfg).unwrap();
+    let abi: Contract = serde_json::from_str(evm.contract().abi()).unwrap();
+
+    let mut vm = MoveExecutor::new(abi.clone(), Flags::default(), MoveExecutorInstance::Aptos);
+    vm.deploy("0x42", target.bytecode).unwrap();
+    vm.run(&format!("0x42::{}::constructor", evm.name()), "0x42", None)
+        .unwrap();
+
+    // The payload is built the same way as by the generated bindings.
+    let tokens = [
+        Token::Uint(U256::from(100)),
+        Token::String("hi".to_string()),
+    ];
+    let args = codec::encode_eth_args(abi.function("deposit").unwrap(), &tokens).unwrap();
+    let entry = EntryFunction::new(
+        ModuleId::new(address, Identifier::new(evm.name()).unwrap()),
+        Identifier::new("deposit").unwrap(),
+        vec![],
+        vec![args],
+    );
+    let res = vm.run_entry("0x42", entry).unwrap();
+    assert_eq!(res.returns, vec![Token::Uint(U256::from(100))]);
+
+    let event = res.events.last().unwrap();
+    let tokens = codec::decode_event(abi.event("Deposit").unwrap(), &event.3).unwrap();
+    assert_eq!(
+        tokens,
+        vec![
+            Token::Address(H160::from_low_u64_be(0x42)),
+            Token::Uint(U256::from(100)),
+            Token::String("hi".to_string()),
+        ]
+    );
+
+    let res = vm
+        .run(
+            &format!("0x42::{}::balanceOf", evm.name()),
+            "0x42",
+            Some("0x42"),
+        )
+        .unwrap();
+    assert_eq!(
+        res.returns,
+        vec![Token::Uint(U256::from(100)), Token::Bool(true)]
+    );
+}
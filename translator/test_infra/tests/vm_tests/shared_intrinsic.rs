@@ -0,0 +1,182 @@
+use aptos_types::transaction::EntryFunction;
+use ethabi::ethereum_types::U256;
+use ethabi::Token;
+use move_binary_format::file_format::{
+    empty_module, Ability, AbilitySet, AddressIdentifierIndex, Bytecode, CodeUnit,
+    FunctionDefinition, FunctionHandle, FunctionHandleIndex, IdentifierIndex, ModuleHandle,
+    ModuleHandleIndex, Signature, SignatureIndex, SignatureToken, StructHandle, StructHandleIndex,
+    Visibility,
+};
+use move_binary_format::CompiledModule;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::ModuleId;
+
+use eth::compile::{build_sol, EvmPack};
+use eth::Flags;
+use move_executor::{MoveExecutor, MoveExecutorInstance};
+use test_infra::init_log;
+use translator::{translate, translate_intrinsic, Config, Target};
+
+use crate::testssol::sol_path;
+
+const LIBRARY: &str = "0x42";
+
+fn translate_shared(evm: &EvmPack, address: &str, initialization_args: &str) -> Target {
+    let cfg = Config {
+        initialization_args,
+        flags: Flags::native_interface(),
+        intrinsic_address: Some(AccountAddress::from_hex_literal(LIBRARY).unwrap()),
+        ..Config::new(
+            AccountAddress::from_hex_literal(address).unwrap(),
+            evm.name(),
+        )
+    };
+    translate(evm.contract().bin(), evm.contract().abi(), cfg).unwrap()
+}
+
+/// `0x45::relay` with a single function, which passes the `U256` returned by
+/// `0x43::with_data::get_val` to `0x44::accumulator::add`:
+///
+/// ```move
+/// public entry fun relay(account: &signer): U256 {
+///     let val = 0x43::with_data::get_val(account);
+///     0x44::accumulator::add(account, val)
+/// }
+/// ```
+fn relay_module(version: u32) -> Vec<u8> {
+    let address = |addr: &str| AccountAddress::from_hex_literal(addr).unwrap();
+    let u256 = SignatureToken::Struct(StructHandleIndex(0));
+    let signer = SignatureToken::Reference(Box::new(SignatureToken::Signer));
+
+    let mut module = empty_module();
+    module.version = version;
+    module.identifiers = [
+        "relay",
+        "with_data",
+        "accumulator",
+        "intrinsic",
+        "U256",
+        "get_val",
+        "add",
+    ]
+    .into_iter()
+    .map(|name| Identifier::new(name).unwrap())
+    .collect();
+    module.address_identifiers = vec![
+        address("0x45"),
+        address("0x43"),
+        address("0x44"),
+        address(LIBRARY),
+    ];
+    module.module_handles = (0..4)
+        .map(|idx| ModuleHandle {
+            address: AddressIdentifierIndex(idx),
+            name: IdentifierIndex(idx),
+        })
+        .collect();
+    module.self_module_handle_idx = ModuleHandleIndex(0);
+    module.struct_handles = vec![StructHandle {
+        module: ModuleHandleIndex(3),
+        name: IdentifierIndex(4),
+        abilities: AbilitySet::EMPTY | Ability::Copy | Ability::Drop | Ability::Store,
+        type_parameters: vec![],
+    }];
+    module.signatures = vec![
+        Signature(vec![signer.clone()]),
+        Signature(vec![u256.clone()]),
+        Signature(vec![signer, u256]),
+    ];
+    let handle = |module: u16, name: u16, parameters: u16| FunctionHandle {
+        module: ModuleHandleIndex(module),
+        name: IdentifierIndex(name),
+        parameters: SignatureIndex(parameters),
+        return_: SignatureIndex(1),
+        type_parameters: vec![],
+    };
+    module.function_handles = vec![handle(1, 5, 0), handle(2, 6, 2), handle(0, 0, 0)];
+    module.function_defs = vec![FunctionDefinition {
+        function: FunctionHandleIndex(2),
+        visibility: Visibility::Public,
+        is_entry: true,
+        acquires_global_resources: vec![],
+        code: Some(CodeUnit {
+            locals: SignatureIndex(1),
+            code: vec![
+                Bytecode::CopyLoc(0),
+                Bytecode::Call(FunctionHandleIndex(0)),
+                Bytecode::StLoc(1),
+                Bytecode::MoveLoc(0),
+                Bytecode::MoveLoc(1),
+                Bytecode::Call(FunctionHandleIndex(1)),
+                Bytecode::Ret,
+            ],
+        }),
+    }];
+
+    let mut bytecode = Vec::new();
+    module.serialize(&mut bytecode).unwrap();
+    bytecode
+}
+
+#[test]
+pub fn test_shared_intrinsic() {
+    init_log();
+
+    let source = build_sol(sol_path().join("constructors/with_data.sol")).unwrap();
+    let sink = build_sol(sol_path().join("shared/accumulator.sol")).unwrap();
+    let source_target = translate_shared(&source, "0x43", "1000, true");
+    let sink_target = translate_shared(&sink, "0x44", "");
+    let library = translate_intrinsic(AccountAddress::from_hex_literal(LIBRARY).unwrap()).unwrap();
+
+    let mut vm = MoveExecutor::new(
+        serde_json::from_str(source.contract().abi()).unwrap(),
+        Flags::native_interface(),
+        MoveExecutorInstance::Aptos,
+    );
+    vm.deploy(LIBRARY, library.bytecode).unwrap();
+    vm.deploy("0x43", source_target.bytecode).unwrap();
+    let version = CompiledModule::deserialize(&sink_target.bytecode)
+        .unwrap()
+        .version;
+    vm.deploy("0x44", sink_target.bytecode).unwrap();
+    vm.deploy("0x45", relay_module(version)).unwrap();
+
+    vm.run("0x43::with_data::constructor", "0x43", None)
+        .unwrap();
+    vm.run("0x44::accumulator::constructor", "0x44", None)
+        .unwrap();
+
+    // Both modules take the `U256` of the library, so a third module passes the value between them.
+    let res = vm.run("0x45::relay::relay", "0x45", None).unwrap();
+    assert_eq!(res.returns, vec![Token::Uint(U256::from(1000))]);
+
+    // The value is passed as is by the client as well.
+    let val = vm
+        .run("0x43::with_data::get_val", "0x43", None)
+        .unwrap()
+        .raw_returns
+        .remove(0)
+        .0;
+    let accumulator = ModuleId::new(
+        AccountAddress::from_hex_literal("0x44").unwrap(),
+        Identifier::new("accumulator").unwrap(),
+    );
+    let add = |val: Vec<u8>| {
+        EntryFunction::new(
+            accumulator.clone(),
+            Identifier::new("add").unwrap(),
+            vec![],
+            vec![val],
+        )
+    };
+    let res = vm.run_entry("0x44", add(val.clone())).unwrap();
+    assert_eq!(res.returns, vec![Token::Uint(U256::from(2000))]);
+    let res = vm.run_entry("0x44", add(val)).unwrap();
+    assert_eq!(res.returns, vec![Token::Uint(U256::from(3000))]);
+
+    // The slots are kept in the `Persist` resource of the module.
+    assert_eq!(res.storage.get(&U256::zero()), Some(&U256::from(3000)));
+    let storage = vm.storage(&accumulator).unwrap();
+    assert_eq!(storage.get(&U256::zero()), Some(&U256::from(3000)));
+}
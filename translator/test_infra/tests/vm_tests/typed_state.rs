@@ -0,0 +1,116 @@
+use crate::testssol::sol_path;
+use eth::compile::build_sol;
+use eth::layout::entry_slot;
+use eth::Flags;
+use move_core_types::account_address::AccountAddress;
+use move_executor::{solidity::FromSolidity, MoveExecutor};
+use primitive_types::U256;
+use test_infra::init_log;
+use translator::{translate, Config};
+
+#[test]
+pub fn test_typed_state_source() {
+    init_log();
+    let evm = build_sol(sol_path().join("store/typed_state.sol")).unwrap();
+    let cfg = Config {
+        storage_layout: evm.storage_layout(),
+        ..Config::new(
+            AccountAddress::from_hex_literal("0x42").unwrap(),
+            evm.name(),
+        )
+    };
+    let source = translate(evm.contract().bin(), evm.contract().abi(), cfg)
+        .unwrap()
+        .source
+        .unwrap();
+
+    assert!(source.contains("struct State has key {"));
+    assert!(source.contains("total: U256,"));
+    assert!(source.contains("balances: aptos_std::table::Table<address, U256>,"));
+    assert!(source.contains("squares: aptos_std::table::Table<U256, U256>,"));
+    assert!(source.contains("acquires State"));
+}
+
+#[test]
+pub fn test_typed_state() {
+    init_log();
+
+    let mut vm = MoveExecutor::from_sol(
+        "sol/store/typed_state.sol",
+        AccountAddress::from_hex_literal("0x42").unwrap(),
+        "",
+        Flags::default(),
+    )
+    .unwrap();
+
+    vm.run("0x42::typed_state::constructor", "0x42", None)
+        .unwrap();
+
+    let res = vm
+        .run("0x42::typed_state::balanceOf", "0x42", Some("0x42"))
+        .unwrap()
+        .to_result_str();
+    assert_eq!("Uint(0)", res);
+    let res = vm
+        .run("0x42::typed_state::deposit", "0x42", Some("50"))
+        .unwrap()
+        .to_result_str();
+    assert_eq!("Uint(50)", res);
+    let res = vm
+        .run("0x42::typed_state::getTotal", "0x42", Some(""))
+        .unwrap()
+        .to_result_str();
+    assert_eq!("Uint(150)", res);
+
+    let res = vm
+        .run("0x42::typed_state::square", "0x42", Some("4"))
+        .unwrap()
+        .to_result_str();
+    assert_eq!("Uint(16)", res);
+    let res = vm
+        .run("0x42::typed_state::getSquare", "0x42", Some("4"))
+        .unwrap()
+        .to_result_str();
+    assert_eq!("Uint(16)", res);
+}
+
+#[test]
+pub fn test_raw_mappings() {
+    init_log();
+    let evm = build_sol(sol_path().join("store/typed_state_raw.sol")).unwrap();
+    let cfg = Config::for_pack(AccountAddress::from_hex_literal("0x42").unwrap(), &evm);
+    let target = translate(evm.contract().bin(), evm.contract().abi(), cfg).unwrap();
+
+    // The keys of the constructor entries are unknown and `rawLoad` may read any entry,
+    // so only the value is typed.
+    assert_eq!(1, target.layout.fields().len());
+    let source = target.source.unwrap();
+    assert!(source.contains("total: U256,"));
+    assert!(!source.contains("balances: aptos_std::table::Table<address, U256>,"));
+
+    let mut vm = MoveExecutor::from_sol(
+        "sol/store/typed_state_raw.sol",
+        AccountAddress::from_hex_literal("0x42").unwrap(),
+        "",
+        Flags::default(),
+    )
+    .unwrap();
+    vm.run("0x42::typed_state_raw::constructor", "0x42", None)
+        .unwrap();
+    let res = vm
+        .run("0x42::typed_state_raw::deposit", "0x42", Some("50"))
+        .unwrap()
+        .to_result_str();
+    assert_eq!("Uint(150)", res);
+
+    let balance = entry_slot(U256::from(0x42), U256::one());
+    let res = vm
+        .run(
+            "0x42::typed_state_raw::rawLoad",
+            "0x42",
+            Some(&balance.to_string()),
+        )
+        .unwrap()
+        .to_result_str();
+    assert_eq!("Uint(150)", res);
+}
@@ -135,6 +135,11 @@ pub fn test_for_users() {
         native_output: true,
         hidden_output: false,
         u128_io: true,
+        native_mappings: false,
+    });
+    test(Flags {
+        native_mappings: true,
+        ..Flags::default()
     });
     test_for_users_with_hidden_result();
 }
@@ -145,6 +150,7 @@ pub fn test_for_users_with_hidden_result() {
         native_output: false,
         hidden_output: true,
         u128_io: false,
+        native_mappings: false,
     };
     let evm = build_sol("../../examples/users.sol").unwrap();
     let bytecode = make_move_module(
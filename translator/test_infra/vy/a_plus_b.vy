@@ -0,0 +1,36 @@
+# @version ^0.3.7
+
+# # plus()
+@external
+@pure
+def plus() -> uint128:
+    return self._plus(13, 14)
+
+
+@internal
+@pure
+def _plus(a: uint128, b: uint128) -> uint128:
+    return a + b
+
+
+# # minus()
+@external
+@pure
+def minus() -> uint128:
+    return 14 - 13
+
+
+# # add_64(1, 2)
+# # add_64(*, *)
+@external
+@pure
+def add_64(a: uint64, b: uint64) -> uint256:
+    return convert(a, uint256) + convert(b, uint256)
+
+
+# # safe_div(10, 3)
+# # safe_div(10, 0)
+@external
+@pure
+def safe_div(a: uint256, b: uint256) -> uint256:
+    return a / b
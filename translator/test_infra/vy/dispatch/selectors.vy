@@ -0,0 +1,70 @@
+# @version ^0.3.7
+
+# Enough functions for vyper to dispatch the selectors through a jump table.
+
+# # f_0()
+@external
+@pure
+def f_0() -> uint256:
+    return 0
+
+
+# # f_1(1)
+@external
+@pure
+def f_1(a: uint256) -> uint256:
+    return a + 1
+
+
+# # f_2(1, 2)
+@external
+@pure
+def f_2(a: uint256, b: uint256) -> uint256:
+    return a * b + 2
+
+
+@external
+@pure
+def f_3() -> uint256:
+    return 3
+
+
+@external
+@pure
+def f_4() -> uint256:
+    return 4
+
+
+@external
+@pure
+def f_5() -> uint256:
+    return 5
+
+
+@external
+@pure
+def f_6() -> uint256:
+    return 6
+
+
+@external
+@pure
+def f_7() -> uint256:
+    return 7
+
+
+# # f_8()
+@external
+@pure
+def f_8() -> uint256:
+    return 8
+
+
+# # f_9(true)
+# # f_9(false)
+@external
+@pure
+def f_9(flag: bool) -> uint256:
+    if flag:
+        return 9
+    return 90
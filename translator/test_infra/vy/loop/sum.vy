@@ -0,0 +1,24 @@
+# @version ^0.3.7
+
+# # sum_ten(0)
+# # sum_ten(*)
+@external
+@pure
+def sum_ten(start: uint64) -> uint256:
+    total: uint256 = 0
+    first: uint256 = convert(start, uint256)
+    for i in range(first, first + 10):
+        total += i
+    return total
+
+
+# # first_square_above(0)
+# # first_square_above(50)
+# # first_square_above(10000)
+@external
+@pure
+def first_square_above(limit: uint256) -> uint256:
+    for i in range(100):
+        if i * i > limit:
+            return i
+    return 100
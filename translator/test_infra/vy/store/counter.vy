@@ -0,0 +1,34 @@
+# @version ^0.3.7
+
+counter: public(uint256)
+balances: public(HashMap[uint256, uint256])
+
+
+@external
+def __init__():
+    self.counter = 10
+
+
+# # inc()
+@external
+def inc() -> uint256:
+    self.counter += 1
+    return self.counter
+
+
+# # add(5)
+# # add(*)
+@external
+def add(value: uint128) -> uint256:
+    self.counter += convert(value, uint256)
+    return self.counter
+
+
+# # deposit(1, 100)
+# # deposit(*, *)
+@external
+def deposit(account: uint64, amount: uint64) -> uint256:
+    key: uint256 = convert(account, uint256)
+    self.balances[key] += convert(amount, uint256)
+    self.balances[key] += convert(amount, uint256)
+    return self.balances[key]